@@ -0,0 +1,133 @@
+//! Adjusting computed ehash amounts to what the mint can issue.
+//!
+//! [`calculate_ehash_amount`](crate::calculate_ehash_amount) may return amounts that the
+//! mint's keyset cannot represent, or amounts too small to be worth a quote. An
+//! [`AmountPolicy`] rounds the raw amount down to a sum of keyset denominations and applies
+//! a configured minimum before the quote is dispatched.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Number of keys in the mint's HASH keyset, with denominations `2^0` to `2^63`
+pub const HASH_KEYSET_DENOMINATIONS: u32 = 64;
+
+/// What to do with an amount that is below the configured minimum after rounding
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BelowMinimum {
+    /// Dispatch no quote for the share
+    #[default]
+    Skip,
+    /// Quote the minimum instead
+    RaiseToMinimum,
+}
+
+impl fmt::Display for BelowMinimum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Skip => write!(f, "skip"),
+            Self::RaiseToMinimum => write!(f, "raise_to_minimum"),
+        }
+    }
+}
+
+/// Rounding and minimum rules applied to every computed ehash amount
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmountPolicy {
+    /// Keyset denominations, largest first
+    denominations: Vec<u64>,
+    minimum: u64,
+    below_minimum: BelowMinimum,
+}
+
+impl Default for AmountPolicy {
+    fn default() -> Self {
+        Self {
+            denominations: (0..HASH_KEYSET_DENOMINATIONS)
+                .rev()
+                .map(|i| 1u64 << i)
+                .collect(),
+            minimum: 0,
+            below_minimum: BelowMinimum::Skip,
+        }
+    }
+}
+
+impl AmountPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Round to sums of `denominations` instead of the HASH keyset's powers of two
+    pub fn with_denominations(mut self, denominations: impl IntoIterator<Item = u64>) -> Self {
+        let mut denominations: Vec<u64> = denominations.into_iter().filter(|&d| d > 0).collect();
+        denominations.sort_unstable_by(|a, b| b.cmp(a));
+        denominations.dedup();
+        self.denominations = denominations;
+        self
+    }
+
+    /// Treat rounded amounts under `minimum` according to `below_minimum`. The minimum is
+    /// itself rounded down to a representable amount.
+    pub fn with_minimum(mut self, minimum: u64, below_minimum: BelowMinimum) -> Self {
+        self.minimum = minimum;
+        self.below_minimum = below_minimum;
+        self
+    }
+
+    /// Largest sum of distinct denominations not above `amount`.
+    ///
+    /// Picks denominations greedily from the largest, which finds the largest such sum for
+    /// power-of-two keysets like the mint's.
+    pub fn round_down(&self, amount: u64) -> u64 {
+        self.denominations.iter().fold(0u64, |sum, &denomination| {
+            match sum.checked_add(denomination) {
+                Some(next) if next <= amount => next,
+                _ => sum,
+            }
+        })
+    }
+
+    /// The amount to quote for a raw `amount`, or `None` if no quote should be dispatched.
+    pub fn apply(&self, amount: u64) -> Option<u64> {
+        let rounded = self.round_down(amount);
+        let minimum = self.round_down(self.minimum);
+        if rounded >= minimum {
+            return Some(rounded);
+        }
+        match self.below_minimum {
+            BelowMinimum::Skip => None,
+            BelowMinimum::RaiseToMinimum => Some(minimum),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amounts_round_down_to_denominations_and_respect_minimum() {
+        let policy = AmountPolicy::new();
+        // Every amount is a sum of distinct powers of two
+        assert_eq!(policy.apply(0), Some(0));
+        assert_eq!(policy.apply(1000), Some(1000));
+        assert_eq!(policy.apply(u64::MAX), Some(u64::MAX));
+
+        // A keyset missing the small denominations can't represent 1000 exactly
+        let coarse = AmountPolicy::new().with_denominations([64, 256, 1024, 512, 128, 512]);
+        assert_eq!(coarse.round_down(1000), 960);
+        assert_eq!(coarse.apply(1000), Some(960));
+        assert_eq!(coarse.round_down(100_000), 1984);
+
+        // A minimum of 100 becomes 64, the largest representable amount under it
+        let skip = coarse.clone().with_minimum(100, BelowMinimum::Skip);
+        assert_eq!(skip.apply(63), None);
+        assert_eq!(skip.apply(127), Some(64));
+
+        let raise = coarse.with_minimum(100, BelowMinimum::RaiseToMinimum);
+        assert_eq!(raise.apply(10), Some(64));
+        assert_eq!(raise.apply(200), Some(192));
+    }
+}
@@ -6,6 +6,7 @@
 
 use std::fmt;
 
+pub mod amount;
 pub mod keyset;
 pub mod locking_key;
 pub mod message_type;
@@ -14,6 +15,7 @@ pub mod share;
 pub mod sv2;
 pub mod work;
 
+pub use amount::{AmountPolicy, BelowMinimum, HASH_KEYSET_DENOMINATIONS};
 pub use keyset::{
     build_cdk_keyset, calculate_keyset_id, keyset_from_sv2_bytes, signing_keys_from_cdk,
     signing_keys_to_cdk, KeysetConversionError, KeysetId, SigningKey,
@@ -22,8 +24,8 @@ pub use locking_key::{parse_locking_key, LockingKeyError};
 pub use message_type::{MessageType, MessageTypeError, MintQuoteMessage};
 pub use quote::{
     build_mint_quote_request, mint_quote_response_from_cdk, parse_mint_quote_request,
-    validate_header_hash, HeaderHashError, ParsedMintQuoteRequest, QuoteBuildError,
-    QuoteConversionError, QuoteParseError,
+    validate_header_hash, validate_header_hash_with, HeaderHashError, HeaderHashValidation,
+    ParsedMintQuoteRequest, QuoteBuildError, QuoteConversionError, QuoteParseError,
 };
 pub use share::{ShareHash, ShareHashError};
 pub use sv2::{Sv2KeySet, Sv2KeySetWire, Sv2SigningKey};
@@ -4,8 +4,8 @@
 //! mint-quote messages, independent of any messaging infrastructure.
 
 use const_sv2::{
-    MESSAGE_TYPE_MINT_QUOTE_ERROR, MESSAGE_TYPE_MINT_QUOTE_REQUEST,
-    MESSAGE_TYPE_MINT_QUOTE_RESPONSE,
+    MESSAGE_TYPE_MINT_PING, MESSAGE_TYPE_MINT_PONG, MESSAGE_TYPE_MINT_QUOTE_ERROR,
+    MESSAGE_TYPE_MINT_QUOTE_REQUEST, MESSAGE_TYPE_MINT_QUOTE_RESPONSE,
 };
 use mint_quote_sv2::{MintQuoteError, MintQuoteRequest, MintQuoteResponse};
 
@@ -22,6 +22,9 @@ pub enum MessageType {
     MintQuoteRequest = MESSAGE_TYPE_MINT_QUOTE_REQUEST as isize,
     MintQuoteResponse = MESSAGE_TYPE_MINT_QUOTE_RESPONSE as isize,
     MintQuoteError = MESSAGE_TYPE_MINT_QUOTE_ERROR as isize,
+    /// Heartbeat sent by the pool; the mint answers with a [`MessageType::Pong`]
+    Ping = MESSAGE_TYPE_MINT_PING as isize,
+    Pong = MESSAGE_TYPE_MINT_PONG as isize,
 }
 
 impl MessageType {
@@ -31,6 +34,8 @@ impl MessageType {
             MESSAGE_TYPE_MINT_QUOTE_REQUEST => Ok(MessageType::MintQuoteRequest),
             MESSAGE_TYPE_MINT_QUOTE_RESPONSE => Ok(MessageType::MintQuoteResponse),
             MESSAGE_TYPE_MINT_QUOTE_ERROR => Ok(MessageType::MintQuoteError),
+            MESSAGE_TYPE_MINT_PING => Ok(MessageType::Ping),
+            MESSAGE_TYPE_MINT_PONG => Ok(MessageType::Pong),
             _ => Err(MessageTypeError::InvalidMessageType(value)),
         }
     }
@@ -28,6 +28,29 @@ pub enum QuoteBuildError {
 pub enum HeaderHashError {
     #[error("invalid header hash length: expected 32 bytes, got {0}")]
     InvalidLength(usize),
+    #[error("header hash is all zeros")]
+    AllZero,
+}
+
+/// How strictly [`validate_header_hash_with`] checks a header hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderHashValidation {
+    /// Accept the all-zero hash. No real share hashes to zero, so this is only meant for test
+    /// vectors and synthetic chains.
+    pub allow_zero: bool,
+}
+
+impl HeaderHashValidation {
+    /// Production checks: the hash must be 32 bytes and not all zeros.
+    pub const STRICT: Self = Self { allow_zero: false };
+    /// Only the length is checked.
+    pub const RELAXED: Self = Self { allow_zero: true };
+}
+
+impl Default for HeaderHashValidation {
+    fn default() -> Self {
+        Self::STRICT
+    }
 }
 
 /// Build a `MintQuoteRequest` using the canonical "HASH" unit and the provided
@@ -123,23 +146,35 @@ impl ParsedMintQuoteRequest {
     }
 }
 
-/// Validates that a header hash is exactly 32 bytes
+/// Validates a header hash with [`HeaderHashValidation::STRICT`]
 ///
 /// Header hashes in ehash are always SHA-256 hashes, which are 32 bytes.
-/// This function validates that the provided bytes meet this requirement.
+/// This function validates that the provided bytes meet this requirement
+/// and are not all zeros.
 ///
 /// # Arguments
 /// * `header_hash` - The header hash bytes to validate
 ///
 /// # Returns
 /// * `Ok([u8; 32])` - Valid header hash as fixed-size array
-/// * `Err(HeaderHashError)` - Invalid header hash length
+/// * `Err(HeaderHashError)` - Invalid header hash
 pub fn validate_header_hash(header_hash: &[u8]) -> Result<[u8; 32], HeaderHashError> {
+    validate_header_hash_with(header_hash, HeaderHashValidation::STRICT)
+}
+
+/// Validates a header hash with explicit strictness, see [`validate_header_hash`]
+pub fn validate_header_hash_with(
+    header_hash: &[u8],
+    validation: HeaderHashValidation,
+) -> Result<[u8; 32], HeaderHashError> {
     if header_hash.len() != 32 {
         return Err(HeaderHashError::InvalidLength(header_hash.len()));
     }
     let mut hash = [0u8; 32];
     hash.copy_from_slice(header_hash);
+    if !validation.allow_zero && hash == [0u8; 32] {
+        return Err(HeaderHashError::AllZero);
+    }
     Ok(hash)
 }
 
@@ -273,4 +308,28 @@ mod tests {
         assert_eq!(cdk_request.unit, CurrencyUnit::Custom("HASH".to_string()));
         assert_eq!(cdk_request.pubkey, expected_pubkey);
     }
+
+    #[test]
+    fn all_zero_header_hash_depends_on_validation() {
+        let zero = [0u8; 32];
+        assert!(matches!(
+            validate_header_hash(&zero),
+            Err(HeaderHashError::AllZero)
+        ));
+        assert!(matches!(
+            validate_header_hash_with(&zero, HeaderHashValidation::default()),
+            Err(HeaderHashError::AllZero)
+        ));
+        assert_eq!(
+            validate_header_hash_with(&zero, HeaderHashValidation::RELAXED).unwrap(),
+            zero
+        );
+
+        // Length is checked in either mode
+        assert!(matches!(
+            validate_header_hash_with(&[0u8; 31], HeaderHashValidation::RELAXED),
+            Err(HeaderHashError::InvalidLength(31))
+        ));
+        assert_eq!(validate_header_hash(&[0x11u8; 32]).unwrap(), [0x11u8; 32]);
+    }
 }
@@ -16,7 +16,10 @@
 //! Use the [`JobStore`] trait for custom job store implementations, or the [`DefaultJobStore`]
 //! for standard job lifecycle management in mining channel abstractions.
 
-use std::{collections::HashMap, fmt::Debug};
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+};
 
 use super::Job;
 
@@ -60,6 +63,10 @@ pub trait JobStore<T: Job>: Send + Sync + Debug {
 ///
 /// Maintains collections for future, active, past, and stale jobs, and tracks template-to-job ID
 /// mappings for future job activation.
+///
+/// By default every job issued since the last chain tip stays valid. A store built with
+/// [`DefaultJobStore::with_stale_job_grace`] only keeps the given number of superseded jobs
+/// valid; older ones are moved to the stale set as soon as a newer job replaces them.
 #[derive(Debug)]
 pub struct DefaultJobStore<T: Job + Clone> {
     future_template_to_job_id: HashMap<u64, u32>,
@@ -70,6 +77,12 @@ pub struct DefaultJobStore<T: Job + Clone> {
     past_jobs: HashMap<u32, T>,
     // Stale jobs are indexed with job_id (u32)
     stale_jobs: HashMap<u32, T>,
+    // Past job ids, oldest first
+    past_job_order: VecDeque<u32>,
+    // Ids of jobs that went stale under the grace limit since the last chain tip
+    expired_job_ids: Vec<u32>,
+    // How many superseded jobs remain valid (unlimited if unset)
+    stale_job_grace: Option<usize>,
 }
 
 impl<T: Job + Clone> DefaultJobStore<T> {
@@ -81,6 +94,41 @@ impl<T: Job + Clone> DefaultJobStore<T> {
             active_job: None,
             past_jobs: HashMap::new(),
             stale_jobs: HashMap::new(),
+            past_job_order: VecDeque::new(),
+            expired_job_ids: Vec::new(),
+            stale_job_grace: None,
+        }
+    }
+
+    /// Creates a new empty job store where only the `grace` most recently superseded jobs
+    /// remain valid for share submission.
+    pub fn with_stale_job_grace(grace: usize) -> Self {
+        Self {
+            stale_job_grace: Some(grace),
+            ..Self::new()
+        }
+    }
+
+    /// Moves the active job (if any) to past jobs, then expires past jobs beyond the grace
+    /// limit.
+    fn retire_active_job(&mut self) {
+        if let Some(active_job) = self.active_job.take() {
+            let job_id = active_job.get_job_id();
+            self.past_jobs.insert(job_id, active_job);
+            self.past_job_order.push_back(job_id);
+        }
+
+        let Some(grace) = self.stale_job_grace else {
+            return;
+        };
+        while self.past_job_order.len() > grace {
+            let Some(job_id) = self.past_job_order.pop_front() else {
+                break;
+            };
+            if let Some(job) = self.past_jobs.remove(&job_id) {
+                self.stale_jobs.insert(job_id, job);
+                self.expired_job_ids.push(job_id);
+            }
         }
     }
 }
@@ -102,9 +150,7 @@ impl<T: Job + Clone + Debug> JobStore<T> for DefaultJobStore<T> {
 
     fn add_active_job(&mut self, job: T) {
         // Move currently active job to past jobs (so it can be marked as stale)
-        if let Some(active_job) = self.active_job.take() {
-            self.past_jobs.insert(active_job.get_job_id(), active_job);
-        }
+        self.retire_active_job();
         // Set the new active job
         self.active_job = Some(job);
     }
@@ -122,9 +168,7 @@ impl<T: Job + Clone + Debug> JobStore<T> for DefaultJobStore<T> {
             };
 
         // Move currently active job to past jobs (so it can be marked as stale)
-        if let Some(active_job) = self.active_job.take() {
-            self.past_jobs.insert(active_job.get_job_id(), active_job);
-        }
+        self.retire_active_job();
 
         // Activate the future job
         future_job.activate(prev_hash_header_timestamp);
@@ -139,11 +183,17 @@ impl<T: Job + Clone + Debug> JobStore<T> for DefaultJobStore<T> {
 
     fn mark_past_jobs_as_stale(&mut self) {
         // Mark all past jobs as stale, so that shares can be rejected with the appropriate error
-        // code
-        self.stale_jobs = self.past_jobs.clone();
+        // code. Jobs that already expired under the grace limit on this tip stay stale too.
+        let mut stale_jobs: HashMap<u32, T> = self
+            .expired_job_ids
+            .drain(..)
+            .filter_map(|job_id| self.stale_jobs.remove(&job_id).map(|job| (job_id, job)))
+            .collect();
+        stale_jobs.extend(self.past_jobs.drain());
+        self.stale_jobs = stale_jobs;
 
-        // Clear past jobs, as we're no longer going to validate shares for them
-        self.past_jobs.clear();
+        // Past jobs are cleared, as we're no longer going to validate shares for them
+        self.past_job_order.clear();
     }
 
     fn get_future_template_to_job_id(&self) -> &HashMap<u64, u32> {
@@ -166,3 +216,74 @@ impl<T: Job + Clone + Debug> JobStore<T> for DefaultJobStore<T> {
         &self.stale_jobs
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    struct TestJob(u32);
+
+    impl Job for TestJob {
+        fn get_job_id(&self) -> u32 {
+            self.0
+        }
+
+        fn activate(&mut self, _prev_hash_header_timestamp: u32) {}
+    }
+
+    fn is_valid(store: &DefaultJobStore<TestJob>, job_id: u32) -> bool {
+        store.get_active_job().map(|job| job.get_job_id()) == Some(job_id)
+            || store.get_past_jobs().contains_key(&job_id)
+    }
+
+    #[test]
+    fn test_stale_job_grace_limits_valid_past_jobs() {
+        let mut store = DefaultJobStore::with_stale_job_grace(1);
+        for job_id in 1..=3 {
+            store.add_active_job(TestJob(job_id));
+        }
+
+        // Current job and the one just superseded are accepted
+        assert!(is_valid(&store, 3));
+        assert!(is_valid(&store, 2));
+        // Anything older is stale
+        assert!(!is_valid(&store, 1));
+        assert!(store.get_stale_jobs().contains_key(&1));
+    }
+
+    #[test]
+    fn test_without_grace_all_jobs_on_tip_stay_valid() {
+        let mut store = DefaultJobStore::new();
+        for job_id in 1..=5 {
+            store.add_active_job(TestJob(job_id));
+        }
+
+        assert!((1..=5).all(|job_id| is_valid(&store, job_id)));
+        assert!(store.get_stale_jobs().is_empty());
+    }
+
+    #[test]
+    fn test_new_chain_tip_marks_all_previous_jobs_stale() {
+        let mut store = DefaultJobStore::with_stale_job_grace(1);
+        for job_id in 1..=3 {
+            store.add_active_job(TestJob(job_id));
+        }
+        store.add_future_job(10, TestJob(4));
+        assert!(store.activate_future_job(10, 0));
+
+        assert!(is_valid(&store, 4));
+        assert!(store.get_past_jobs().is_empty());
+        let mut stale: Vec<u32> = store.get_stale_jobs().keys().copied().collect();
+        stale.sort();
+        assert_eq!(stale, vec![1, 2, 3]);
+
+        // Jobs from the previous tip are dropped once the next tip arrives
+        store.add_active_job(TestJob(5));
+        store.add_future_job(11, TestJob(6));
+        assert!(store.activate_future_job(11, 0));
+        let mut stale: Vec<u32> = store.get_stale_jobs().keys().copied().collect();
+        stale.sort();
+        assert_eq!(stale, vec![4, 5]);
+    }
+}
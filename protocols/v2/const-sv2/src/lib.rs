@@ -196,6 +196,9 @@ pub const MESSAGE_TYPE_SUBMIT_SOLUTION: u8 = 0x76;
 pub const MESSAGE_TYPE_MINT_QUOTE_REQUEST: u8 = 0x80;
 pub const MESSAGE_TYPE_MINT_QUOTE_RESPONSE: u8 = 0x81;
 pub const MESSAGE_TYPE_MINT_QUOTE_ERROR: u8 = 0x82;
+// Liveness check on the mint-pool connection; the payload is a u64 nonce echoed in the pong
+pub const MESSAGE_TYPE_MINT_PING: u8 = 0x83;
+pub const MESSAGE_TYPE_MINT_PONG: u8 = 0x84;
 
 // Extension message types (vendor range: 0xC0-0xFF)
 pub const MESSAGE_TYPE_MINT_QUOTE_NOTIFICATION: u8 = 0xC0;
@@ -263,6 +266,8 @@ pub const CHANNEL_BIT_UPDATE_CHANNEL_ERROR: bool = true;
 pub const CHANNEL_BIT_MINT_QUOTE_REQUEST: bool = true;
 pub const CHANNEL_BIT_MINT_QUOTE_RESPONSE: bool = true;
 pub const CHANNEL_BIT_MINT_QUOTE_ERROR: bool = true;
+pub const CHANNEL_BIT_MINT_PING: bool = false;
+pub const CHANNEL_BIT_MINT_PONG: bool = false;
 
 // Extension messages are channel-based
 pub const CHANNEL_BIT_MINT_QUOTE_NOTIFICATION: bool = true;
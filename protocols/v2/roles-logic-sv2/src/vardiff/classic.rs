@@ -5,6 +5,9 @@ use tracing::debug;
 /// Default minimum hashrate (H/s) if not specified.
 const DEFAULT_MIN_HASHRATE: f32 = 1.0;
 
+/// Default number of seconds that must pass after an adjustment before the next is considered.
+pub const DEFAULT_MIN_UPDATE_INTERVAL_SECS: u64 = 15;
+
 use super::{error::VardiffError, Vardiff};
 
 /// Represents the dynamic state for a variable difficulty (Vardiff) connection.
@@ -18,6 +21,10 @@ pub struct VardiffState {
     pub timestamp_of_last_update: u64,
     /// The lowest hashrate (H/s) the system will allow; values below this are clamped.
     pub min_allowed_hashrate: f32,
+    /// More than this many seconds must pass after an adjustment before the next one.
+    pub min_update_interval_secs: u64,
+    /// Largest factor a single adjustment may raise or lower the hashrate by, if bounded.
+    pub max_step_factor: Option<f32>,
 }
 
 impl VardiffState {
@@ -42,9 +49,26 @@ impl VardiffState {
             shares_since_last_update: 0,
             timestamp_of_last_update: timestamp_secs,
             min_allowed_hashrate,
+            min_update_interval_secs: DEFAULT_MIN_UPDATE_INTERVAL_SECS,
+            max_step_factor: None,
         })
     }
 
+    /// Waits more than `secs` seconds after each adjustment before retargeting again, so noisy
+    /// share timing cannot cause rapid back-and-forth changes.
+    pub fn with_min_update_interval(mut self, secs: u64) -> Self {
+        self.min_update_interval_secs = secs;
+        self
+    }
+
+    /// Bounds each adjustment to at most `factor` times (or `1 / factor` of) the current
+    /// hashrate. Repeated adjustments still converge, just over several steps. Factors below
+    /// 1.0 are treated as 1.0.
+    pub fn with_max_step(mut self, factor: f32) -> Self {
+        self.max_step_factor = Some(factor.max(1.0));
+        self
+    }
+
     /// Sets the count of shares since the last update.
     pub fn set_shares_since_last_update(&mut self, shares_since_last_update: u32) {
         self.shares_since_last_update = shares_since_last_update;
@@ -106,7 +130,7 @@ impl Vardiff for VardiffState {
 
         let delta_time = now - self.timestamp_of_last_update;
 
-        if delta_time <= 15 {
+        if delta_time <= self.min_update_interval_secs {
             return Ok(None);
         }
 
@@ -180,6 +204,20 @@ impl Vardiff for VardiffState {
                 _ => hashrate * 3.0,
             };
         }
+        if let Some(factor) = self.max_step_factor {
+            let bounded = new_hashrate.clamp(hashrate / factor, hashrate * factor);
+            if bounded != new_hashrate {
+                debug!(
+                    target: "vardiff",
+                    "New hashrate {:.2} H/s exceeds max step {}x from {:.2} H/s — clamping to {:.2} H/s",
+                    new_hashrate,
+                    factor,
+                    hashrate,
+                    bounded
+                );
+                new_hashrate = bounded;
+            }
+        }
         if new_hashrate < self.min_allowed_hashrate {
             debug!(
                 target: "vardiff",
@@ -1,6 +1,7 @@
 /// Classic implementation test suite
 use crate::vardiff::test::{
-    simulate_shares_and_wait, TEST_MIN_ALLOWED_HASHRATE, TEST_SHARES_PER_MINUTE,
+    simulate_shares_and_wait, TEST_INITIAL_HASHRATE, TEST_MIN_ALLOWED_HASHRATE,
+    TEST_SHARES_PER_MINUTE,
 };
 use crate::{utils::hash_rate_to_target, vardiff::VardiffError, VardiffState};
 
@@ -115,3 +116,44 @@ fn test_try_vardiff_hashrate_clamps_to_minimum() {
     );
     assert_eq!(vardiff.shares_since_last_update(), 0);
 }
+
+#[test]
+fn test_try_vardiff_respects_min_interval_and_max_step() {
+    let hashrate = TEST_INITIAL_HASHRATE;
+    let target = hash_rate_to_target(hashrate.into(), TEST_SHARES_PER_MINUTE.into())
+        .unwrap()
+        .into();
+
+    let mut vardiff = new_test_vardiff_state()
+        .expect("Failed to create VardiffState")
+        .with_min_update_interval(60)
+        .with_max_step(2.0);
+
+    // Inside the interval nothing changes, however far off the share rate is
+    simulate_shares_and_wait(&mut vardiff, 100, 30);
+    let result = vardiff
+        .try_vardiff(hashrate, &target, TEST_SHARES_PER_MINUTE)
+        .expect("try_vardiff failed");
+    assert_eq!(
+        result, None,
+        "No update expected inside the minimum interval"
+    );
+
+    // Once it has passed, a ~10x jump is bounded to the max step
+    simulate_shares_and_wait(&mut vardiff, 0, 61);
+    let new_hashrate = vardiff
+        .try_vardiff(hashrate, &target, TEST_SHARES_PER_MINUTE)
+        .expect("try_vardiff failed")
+        .expect("Hashrate should update");
+    assert_eq!(new_hashrate, hashrate * 2.0, "Step should be clamped");
+
+    // A second change right after the adjustment is held back again
+    let target = hash_rate_to_target(new_hashrate.into(), TEST_SHARES_PER_MINUTE.into())
+        .unwrap()
+        .into();
+    simulate_shares_and_wait(&mut vardiff, 0, 30);
+    let result = vardiff
+        .try_vardiff(new_hashrate, &target, TEST_SHARES_PER_MINUTE)
+        .expect("try_vardiff failed");
+    assert_eq!(result, None, "Only one adjustment per interval");
+}
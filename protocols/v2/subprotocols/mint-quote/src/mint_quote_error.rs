@@ -7,4 +7,6 @@ pub struct MintQuoteError<'decoder> {
     pub error_code: u32,
     /// Error message
     pub error_message: Str0255<'decoder>,
+    /// Header hash of the share whose quote request failed
+    pub header_hash: U256<'decoder>,
 }
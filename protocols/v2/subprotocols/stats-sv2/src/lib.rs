@@ -10,8 +10,8 @@ mod proxy_stats;
 
 // Pool stats messages
 pub use pool_stats::{
-    ChannelClosed, ChannelOpened, DownstreamConnected, DownstreamDisconnected, QuoteCreated,
-    ShareSubmitted,
+    BlockFound, ChannelClosed, ChannelOpened, DownstreamConnected, DownstreamDisconnected,
+    QuoteCreated, ShareSubmitted,
 };
 
 // Proxy stats messages
@@ -1,10 +1,24 @@
 use super::*;
+use binary_sv2::U256;
 use core::convert::TryInto;
 
 /// Share submitted by a downstream connection
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct ShareSubmitted {
+pub struct ShareSubmitted<'decoder> {
     pub downstream_id: u32,
+    pub channel_id: u32,
+    /// Difficulty as integer (multiply float by 1000 to preserve precision)
+    pub difficulty_millis: u64,
+    /// Channel target the share was validated against
+    pub target: U256<'decoder>,
+    pub timestamp: u64,
+}
+
+/// Accepted share that also solved a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockFound {
+    pub downstream_id: u32,
+    pub channel_id: u32,
     pub timestamp: u64,
 }
 
@@ -6,7 +6,12 @@ use std::{
     path::{Path, PathBuf},
     str::FromStr,
 };
-use stratum_common::roles_logic_sv2::bitcoin::{Amount, TxOut};
+use stratum_common::{
+    network_helpers_sv2::resolve::resolve_address,
+    roles_logic_sv2::bitcoin::{Amount, TxOut},
+};
+
+use crate::error::JDCError;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct JobDeclaratorClientConfig {
@@ -36,6 +41,9 @@ pub struct JobDeclaratorClientConfig {
     jdc_signature: String,
     /// The path to the log file where JDC will write logs.
     log_file: Option<PathBuf>,
+    /// Tracing filter directives used when `RUST_LOG` is unset.
+    #[serde(default)]
+    log_filter: Option<String>,
     /// User Identity
     user_identity: String,
     /// Shares per minute
@@ -77,6 +85,7 @@ impl JobDeclaratorClientConfig {
             coinbase_reward_script: protocol_config.coinbase_reward_script,
             jdc_signature,
             log_file: None,
+            log_filter: None,
             user_identity,
             shares_per_minute,
             share_batch_size,
@@ -154,6 +163,9 @@ impl JobDeclaratorClientConfig {
             self.log_file = Some(log_file);
         }
     }
+    pub fn log_filter(&self) -> Option<&str> {
+        self.log_filter.as_deref()
+    }
     pub fn user_identity(&self) -> &str {
         &self.user_identity
     }
@@ -297,4 +309,61 @@ impl Upstream {
             jds_port,
         }
     }
+
+    /// Resolves the pool and JDS addresses, each an IP literal or a hostname, into socket
+    /// addresses.
+    pub async fn socket_addrs(&self) -> Result<(SocketAddr, SocketAddr), JDCError> {
+        let pool = resolve_address(&self.pool_address, self.pool_port)
+            .await
+            .map_err(|e| {
+                JDCError::InvalidSocketAddress(format!("pool_address `{}`: {e}", self.pool_address))
+            })?;
+        let jds = resolve_address(&self.jds_address, self.jds_port)
+            .await
+            .map_err(|e| {
+                JDCError::InvalidSocketAddress(format!("jds_address `{}`: {e}", self.jds_address))
+            })?;
+        Ok((pool, jds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upstream(pool_address: &str, jds_address: &str) -> Upstream {
+        Upstream::new(
+            Secp256k1PublicKey::from_str("9auqWEzQDVyd2oe1JVGFLMLHZtCo2FFqZwtKA5gd9xbuEu7PH72")
+                .unwrap(),
+            pool_address.to_string(),
+            34254,
+            jds_address.to_string(),
+            34264,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_socket_addrs_reports_the_invalid_field() {
+        let (pool, jds) = upstream("127.0.0.1", "::1").socket_addrs().await.unwrap();
+        assert_eq!(pool, "127.0.0.1:34254".parse().unwrap());
+        assert_eq!(jds, "[::1]:34264".parse().unwrap());
+
+        let err = upstream("not an address", "127.0.0.1")
+            .socket_addrs()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("pool_address `not an address`"),
+            "{err}"
+        );
+
+        let err = upstream("127.0.0.1", "10.0.0.1:34264")
+            .socket_addrs()
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("jds_address `10.0.0.1:34264`"),
+            "{err}"
+        );
+    }
 }
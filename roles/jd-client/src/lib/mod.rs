@@ -55,6 +55,19 @@ impl JobDeclaratorClient {
             self.config.user_identity()
         );
 
+        let mut upstream_addresses = Vec::with_capacity(self.config.upstreams().len());
+        for upstream in self.config.upstreams() {
+            match upstream.socket_addrs().await {
+                Ok((pool_addr, jd_addr)) => {
+                    upstream_addresses.push((pool_addr, jd_addr, upstream.authority_pubkey, false))
+                }
+                Err(e) => {
+                    tracing::error!("{e}");
+                    return;
+                }
+            }
+        }
+
         let miner_coinbase_outputs = vec![self.config.get_txout()];
         let mut encoded_outputs = vec![];
 
@@ -140,23 +153,6 @@ impl JobDeclaratorClient {
             )
             .await;
 
-        let mut upstream_addresses: Vec<_> = self
-            .config
-            .upstreams()
-            .iter()
-            .map(|u| {
-                let pool_addr = SocketAddr::new(
-                    u.pool_address.parse().expect("Invalid pool address"),
-                    u.pool_port,
-                );
-                let jd_addr = SocketAddr::new(
-                    u.jds_address.parse().expect("Invalid JD address"),
-                    u.jds_port,
-                );
-                (pool_addr, jd_addr, u.authority_pubkey, false)
-            })
-            .collect();
-
         channel_manager
             .start(
                 notify_shutdown.clone(),
@@ -1,4 +1,4 @@
-use config_helpers_sv2::logging::init_logging;
+use config_helpers_sv2::logging::init_logging_with_filter;
 use jd_client_sv2::JobDeclaratorClient;
 
 use crate::args::process_cli_args;
@@ -12,6 +12,6 @@ async fn main() {
         std::process::exit(1);
     });
 
-    init_logging(jdc_config.log_file());
+    init_logging_with_filter(jdc_config.log_file(), jdc_config.log_filter());
     JobDeclaratorClient::new(jdc_config).start().await;
 }
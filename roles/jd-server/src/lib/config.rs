@@ -35,6 +35,8 @@ pub struct JobDeclaratorServerConfig {
     #[serde(deserialize_with = "config_helpers_sv2::duration_from_toml")]
     mempool_update_interval: Duration,
     log_file: Option<PathBuf>,
+    #[serde(default)]
+    log_filter: Option<String>,
 }
 
 impl JobDeclaratorServerConfig {
@@ -65,6 +67,7 @@ impl JobDeclaratorServerConfig {
             core_rpc_pass: core_rpc.pass,
             mempool_update_interval,
             log_file: None,
+            log_filter: None,
         }
     }
 
@@ -145,6 +148,9 @@ impl JobDeclaratorServerConfig {
             self.log_file = Some(path);
         }
     }
+    pub fn log_filter(&self) -> Option<&str> {
+        self.log_filter.as_deref()
+    }
 }
 
 fn default_true() -> bool {
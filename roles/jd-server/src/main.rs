@@ -6,7 +6,7 @@
 //! The actual task orchestration and shutdown logic are managed in `lib/mod.rs`.
 mod args;
 use args::process_cli_args;
-use config_helpers_sv2::logging::init_logging;
+use config_helpers_sv2::logging::init_logging_with_filter;
 use jd_server::JobDeclaratorServer;
 use tracing::error;
 
@@ -23,7 +23,7 @@ async fn main() {
             return;
         }
     };
-    init_logging(config.log_file());
+    init_logging_with_filter(config.log_file(), config.log_filter());
     match JobDeclaratorServer::new(config).start().await {
         Ok(_) => {}
         Err(e) => {
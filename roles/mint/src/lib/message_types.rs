@@ -15,6 +15,12 @@ pub mod message_types {
 
     /// MintQuoteError message type
     pub const MINT_QUOTE_ERROR: u8 = 0x82;
+
+    /// Heartbeat ping message type
+    pub const MINT_PING: u8 = 0x83;
+
+    /// Heartbeat pong message type
+    pub const MINT_PONG: u8 = 0x84;
 }
 
 /// Identifies the type of a received mint protocol message
@@ -29,6 +35,12 @@ pub enum MintMessageType {
     /// MintQuoteError - error response
     MintQuoteError,
 
+    /// Ping - heartbeat from pool, answered with a pong
+    Ping,
+
+    /// Pong - heartbeat reply
+    Pong,
+
     /// Unknown message type
     Unknown(u8),
 }
@@ -40,6 +52,8 @@ impl MintMessageType {
             message_types::MINT_QUOTE_REQUEST => MintMessageType::MintQuoteRequest,
             message_types::MINT_QUOTE_RESPONSE => MintMessageType::MintQuoteResponse,
             message_types::MINT_QUOTE_ERROR => MintMessageType::MintQuoteError,
+            message_types::MINT_PING => MintMessageType::Ping,
+            message_types::MINT_PONG => MintMessageType::Pong,
             other => MintMessageType::Unknown(other),
         }
     }
@@ -50,6 +64,8 @@ impl MintMessageType {
             MintMessageType::MintQuoteRequest => Some(message_types::MINT_QUOTE_REQUEST),
             MintMessageType::MintQuoteResponse => Some(message_types::MINT_QUOTE_RESPONSE),
             MintMessageType::MintQuoteError => Some(message_types::MINT_QUOTE_ERROR),
+            MintMessageType::Ping => Some(message_types::MINT_PING),
+            MintMessageType::Pong => Some(message_types::MINT_PONG),
             MintMessageType::Unknown(_) => None,
         }
     }
@@ -73,6 +89,8 @@ mod tests {
             MintMessageType::from_code(0x82),
             MintMessageType::MintQuoteError
         );
+        assert_eq!(MintMessageType::from_code(0x83), MintMessageType::Ping);
+        assert_eq!(MintMessageType::from_code(0x84), MintMessageType::Pong);
         assert_eq!(
             MintMessageType::from_code(0xFF),
             MintMessageType::Unknown(0xFF)
@@ -84,6 +102,8 @@ mod tests {
         assert_eq!(MintMessageType::MintQuoteRequest.to_code(), Some(0x80));
         assert_eq!(MintMessageType::MintQuoteResponse.to_code(), Some(0x81));
         assert_eq!(MintMessageType::MintQuoteError.to_code(), Some(0x82));
+        assert_eq!(MintMessageType::Ping.to_code(), Some(0x83));
+        assert_eq!(MintMessageType::Pong.to_code(), Some(0x84));
         assert_eq!(MintMessageType::Unknown(0xFF).to_code(), None);
     }
 }
@@ -1,3 +1,3 @@
 pub mod setup;
 
-pub use setup::{resolve_and_prepare_db_path, setup_mint};
+pub use setup::{resolve_and_prepare_db_path, resolve_listen_addr, setup_mint};
@@ -1,4 +1,10 @@
-use std::{collections::HashMap, path::PathBuf, str::FromStr, sync::Arc};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+};
 
 use anyhow::{bail, Result};
 use bip39::Mnemonic;
@@ -14,6 +20,8 @@ use cdk_mintd::config::{self, LnBackend};
 use cdk_signatory::db_signatory::DbSignatory;
 use cdk_sqlite::MintSqliteDatabase;
 
+use crate::lib::error::MintError;
+
 /// Setup and initialize the mint with all required components  
 pub async fn setup_mint(mint_settings: config::Settings, db_path: String) -> Result<Arc<Mint>> {
     // TODO add to config
@@ -105,3 +113,70 @@ pub fn resolve_and_prepare_db_path(config_path: &str) -> PathBuf {
 
     full_path
 }
+
+/// Resolve the configured HTTP listen host and port before binding.
+///
+/// IP literals are used as is; wildcard addresses such as `0.0.0.0` and `::`
+/// (optionally written as `[::]`) bind on every interface. Anything else is
+/// looked up as a hostname, e.g. `localhost`, and the first address is used.
+pub async fn resolve_listen_addr(
+    listen_host: &str,
+    listen_port: u16,
+) -> Result<SocketAddr, MintError> {
+    let host = listen_host
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(listen_host);
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, listen_port));
+    }
+
+    let invalid = |reason: String| {
+        MintError::Config(format!("invalid listen_host `{listen_host}`: {reason}"))
+    };
+    // A colon outside an IPv6 literal can't be part of a hostname
+    if host.is_empty() || host.contains(':') {
+        return Err(invalid(
+            "expected an IP address such as 0.0.0.0 or :: or a hostname".to_string(),
+        ));
+    }
+    tokio::net::lookup_host((host, listen_port))
+        .await
+        .map_err(|e| invalid(format!("failed to resolve: {e}")))?
+        .next()
+        .ok_or_else(|| invalid("resolved to no addresses".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_listen_addr_accepts_wildcards() {
+        let v4 = resolve_listen_addr("0.0.0.0", 3338).await.unwrap();
+        assert!(v4.ip().is_unspecified() && v4.is_ipv4());
+        assert_eq!(v4.port(), 3338);
+
+        let v6 = resolve_listen_addr("::", 3338).await.unwrap();
+        assert!(v6.ip().is_unspecified() && v6.is_ipv6());
+        assert_eq!(resolve_listen_addr("[::]", 3338).await.unwrap(), v6);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_listen_addr_resolves_localhost() {
+        // The shipped mint configs listen on `localhost`
+        let addr = resolve_listen_addr("localhost", 3338).await.unwrap();
+        assert!(addr.ip().is_loopback());
+        assert_eq!(addr.port(), 3338);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_listen_addr_rejects_invalid_host() {
+        let err = resolve_listen_addr("mint.local:bad", 3338)
+            .await
+            .unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("listen_host"));
+        assert!(message.contains("mint.local:bad"));
+    }
+}
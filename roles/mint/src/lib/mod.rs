@@ -7,5 +7,5 @@ pub use error::MintError;
 #[allow(unused_imports)]
 pub use message_types::MintMessageType;
 #[allow(unused_imports)]
-pub use mint_manager::{resolve_and_prepare_db_path, setup_mint};
+pub use mint_manager::{resolve_and_prepare_db_path, resolve_listen_addr, setup_mint};
 pub use sv2_connection::connect_to_pool_sv2;
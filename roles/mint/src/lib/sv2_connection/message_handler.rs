@@ -1,6 +1,7 @@
 use anyhow::Result;
 use cdk::mint::Mint;
 use codec_sv2::{StandardEitherFrame, StandardSv2Frame};
+use mint_pool_messaging::{decode_heartbeat_nonce, pong_frame_bytes};
 use roles_logic_sv2::parsers_sv2::AnyMessage;
 use std::sync::Arc;
 use tracing::info;
@@ -78,7 +79,23 @@ async fn process_sv2_message(
             tracing::info!("Received MintQuoteRequest from pool, processing quote");
             process_mint_quote_message(mint.clone(), message_type, &payload, sender).await
         }
-        MintMessageType::MintQuoteResponse | MintMessageType::MintQuoteError => {
+        // Heartbeat from pool; it drops the connection if the pong doesn't come back in time
+        MintMessageType::Ping => {
+            let nonce = decode_heartbeat_nonce(&payload)
+                .map_err(|e| anyhow::anyhow!("Invalid ping from pool: {e}"))?;
+            let frame_bytes = pong_frame_bytes(nonce)
+                .map_err(|e| anyhow::anyhow!("Failed to encode pong frame: {e}"))?;
+            let frame = StandardEitherFrame::Sv2(StandardSv2Frame::from_bytes_unchecked(
+                frame_bytes.into(),
+            ));
+            sender
+                .send(frame)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to send pong frame: {}", e))
+        }
+        MintMessageType::MintQuoteResponse
+        | MintMessageType::MintQuoteError
+        | MintMessageType::Pong => {
             tracing::warn!(
                 "Received unexpected response message from pool: {:?}",
                 msg_type
@@ -6,7 +6,7 @@ use const_sv2::MESSAGE_TYPE_MINT_QUOTE_REQUEST;
 use hex;
 use mint_pool_messaging::{
     mint_quote_response_from_cdk, parse_mint_quote_request, quote_error_frame_bytes,
-    quote_response_frame_bytes, MintQuoteError,
+    quote_response_frame_bytes, MintQuoteError, ShareHash,
 };
 use mint_quote_sv2::MintQuoteResponse;
 use roles_logic_sv2::parsers_sv2::AnyMessage;
@@ -105,7 +105,7 @@ pub async fn process_mint_quote_message(
                     };
 
                     // Send error response back to pool
-                    send_quote_error_to_pool(error_message.clone(), sender).await?;
+                    send_quote_error_to_pool(error_message.clone(), share_hash, sender).await?;
 
                     Err(anyhow::anyhow!(
                         "Mint quote creation failed: {}",
@@ -152,9 +152,10 @@ async fn send_quote_response_to_pool(
     Ok(())
 }
 
-/// Send MintQuoteError back to pool
+/// Send MintQuoteError back to pool, tagged with the share it failed for
 async fn send_quote_error_to_pool(
     error_message: String,
+    share_hash: ShareHash,
     sender: &async_channel::Sender<MintFrame>,
 ) -> Result<()> {
     // Create error code (generic error = 1)
@@ -171,6 +172,9 @@ async fn send_quote_error_to_pool(
         error_message: Str0255::try_from(error_message.clone())
             .map_err(|e| anyhow::anyhow!("Failed to encode error message: {e:?}"))?
             .into_static(),
+        header_hash: share_hash
+            .into_u256()
+            .map_err(|e| anyhow::anyhow!("Failed to encode share hash: {e}"))?,
     };
 
     let frame_bytes = quote_error_frame_bytes(&error)
@@ -3,12 +3,12 @@ mod lib;
 use anyhow::Result;
 use cdk_axum::cache::HttpCache;
 use cdk_mintd::config;
+use config_helpers_sv2::logging::init_logging_with_filter;
 use serde::{Deserialize, Serialize};
 use shared_config::PoolGlobalConfig;
-use std::fs;
+use std::{fs, path::Path};
 use tokio::net::TcpListener;
 use tracing::info;
-use tracing_subscriber::EnvFilter;
 
 /// Extended config for hashpool-specific mint settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,22 +21,17 @@ struct MintConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct HashpoolMintConfig {
     db_path: Option<String>,
+    /// Tracing filter directives used when `RUST_LOG` is unset
+    log_filter: Option<String>,
 }
 
-use lib::{connect_to_pool_sv2, setup_mint};
+use lib::{connect_to_pool_sv2, resolve_listen_addr, setup_mint};
+
+/// Filter used when neither `RUST_LOG` nor `[hashpool_mint] log_filter` is set
+const DEFAULT_LOG_FILTER: &str = "info,sqlx=warn,hyper=warn,h2=warn";
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Respect RUST_LOG env var, defaulting to info level with dependency filtering
-    // Note: CDK mint uses CDK's tracing configuration. To log to file, redirect stdout/stderr
-    // in the systemd service or use RUST_LOG with external log capture.
-    let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new("info,sqlx=warn,hyper=warn,h2=warn"));
-
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
-        .init();
-
     let args: Vec<String> = std::env::args().skip(1).collect();
 
     // Simple argument parser: extract values by flag
@@ -50,12 +45,21 @@ async fn main() -> Result<()> {
         .ok_or_else(|| anyhow::anyhow!("Missing required argument: -c <mint_config_path>"))?;
     let global_config_path = get_arg(&args, "-g")
         .ok_or_else(|| anyhow::anyhow!("Missing required argument: -g <global_config_path>"))?;
-    let _log_file = get_arg(&args, "-f").or_else(|| get_arg(&args, "--log-file"));
+    let log_file = get_arg(&args, "-f").or_else(|| get_arg(&args, "--log-file"));
 
     // Parse mint config
     let mint_config_str = fs::read_to_string(&mint_config_path)?;
     let mint_config: MintConfig = toml::from_str(&mint_config_str)?;
 
+    // Respect RUST_LOG env var, then [hashpool_mint] log_filter, defaulting to info level with
+    // dependency filtering
+    let log_filter = mint_config
+        .hashpool_mint
+        .as_ref()
+        .and_then(|hm| hm.log_filter.as_deref())
+        .unwrap_or(DEFAULT_LOG_FILTER);
+    init_logging_with_filter(log_file.as_deref().map(Path::new), Some(log_filter));
+
     let global_config: PoolGlobalConfig = toml::from_str(&fs::read_to_string(global_config_path)?)?;
 
     // Setup mint with all required components - determine database path
@@ -87,12 +91,13 @@ async fn main() -> Result<()> {
     }
 
     // Start HTTP server
-    let addr = format!(
-        "{}:{}",
-        mint_config.cdk_settings.info.listen_host, mint_config.cdk_settings.info.listen_port
-    );
+    let addr = resolve_listen_addr(
+        &mint_config.cdk_settings.info.listen_host,
+        mint_config.cdk_settings.info.listen_port,
+    )
+    .await?;
     info!("Mint listening on {}", addr);
-    let listener = TcpListener::bind(&addr).await?;
+    let listener = TcpListener::bind(addr).await?;
 
     axum::serve(listener, router).await?;
 
@@ -5,7 +5,7 @@
 use clap::Parser;
 use ext_config::{Config, File, FileFormat};
 use pool_sv2::config::PoolConfig;
-use shared_config::PoolGlobalConfig;
+use shared_config::{PoolGlobalConfig, RoleConfig};
 use std::path::PathBuf;
 
 /// Holds the parsed CLI arguments for the Pool binary.
@@ -37,11 +37,8 @@ pub struct Args {
 pub fn process_cli_args() -> PoolConfig {
     let args = Args::parse();
     let config_path = args.config_path.to_str().expect("Invalid config path");
-    let mut config: PoolConfig = Config::builder()
-        .add_source(File::new(config_path, FileFormat::Toml))
-        .build()
-        .and_then(|settings| settings.try_deserialize::<PoolConfig>())
-        .expect("Failed to load or deserialize config");
+    let mut config =
+        PoolConfig::from_path(config_path).expect("Failed to load or deserialize config");
 
     // Load locking_pubkey from global config if provided
     if let Some(global_config_path) = args.global_config_path {
@@ -74,8 +71,12 @@ pub fn process_cli_args() -> PoolConfig {
                 config.set_sv2_messaging(shared.sv2_messaging.clone());
                 config.set_minimum_difficulty(shared.ehash.map(|e| e.minimum_difficulty));
                 config.set_minimum_share_difficulty_bits(
-                    shared.validation.and_then(|v| v.minimum_share_difficulty_bits),
+                    shared
+                        .validation
+                        .as_ref()
+                        .and_then(|v| v.minimum_share_difficulty_bits),
                 );
+                config.set_stale_job_grace(shared.validation.and_then(|v| v.stale_job_grace));
                 config.set_min_downstream_hashrate(shared.pool.min_downstream_hashrate);
                 config.set_mint_http_url(Some(shared.mint.url));
             }
@@ -8,11 +8,21 @@
 //! - Managing [`TemplateProviderConfig`], [`AuthorityConfig`], [`CoinbaseOutput`], and
 //!   [`ConnectionConfig`]
 //! - Validating and converting coinbase outputs
-use std::path::{Path, PathBuf};
-
-use config_helpers_sv2::CoinbaseRewardScript;
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use config_helpers_sv2::{logging::REDACTED, CoinbaseRewardScript};
+use ehash::BelowMinimum;
 use key_utils::{Secp256k1PublicKey, Secp256k1SecretKey};
-use shared_config::Sv2MessagingConfig;
+use shared_config::{RoleConfig, Sv2MessagingConfig};
+
+use crate::error::PoolError;
+
+/// Address the mint listener binds to when no SV2 messaging config is provided.
+const DEFAULT_MINT_LISTEN_ADDRESS: &str = "127.0.0.1:34260";
 
 /// Configuration for the Pool, including connection, authority, and coinbase settings.
 #[derive(Clone, Debug, serde::Deserialize)]
@@ -28,6 +38,8 @@ pub struct PoolConfig {
     shares_per_minute: f32,
     share_batch_size: usize,
     log_file: Option<PathBuf>,
+    #[serde(default)]
+    log_filter: Option<String>,
     server_id: u16,
     #[serde(default)]
     locking_pubkey: Option<String>,
@@ -37,6 +49,44 @@ pub struct PoolConfig {
     snapshot_poll_interval_secs: u64,
     #[serde(default)]
     jd_server_address: Option<String>,
+    #[serde(default)]
+    emit_share_events: bool,
+    #[serde(default)]
+    no_mint: bool,
+    #[serde(default)]
+    debug_dump_address: Option<String>,
+    #[serde(default)]
+    quote_poll_timeout_secs: Option<u64>,
+    #[serde(default)]
+    quote_poll_concurrency: Option<usize>,
+    #[serde(default)]
+    quote_status_path: Option<String>,
+    #[serde(default)]
+    quote_store_path: Option<String>,
+    #[serde(default)]
+    quote_notify_attempts: Option<u32>,
+    #[serde(default)]
+    quote_queue_capacity: Option<usize>,
+    #[serde(default)]
+    quote_queue_workers: Option<usize>,
+    #[serde(default)]
+    share_hook_timeout_ms: Option<u64>,
+    #[serde(default)]
+    quote_batch_size: Option<usize>,
+    #[serde(default)]
+    quote_batch_flush_ms: Option<u64>,
+    #[serde(default)]
+    lifetime_stats_path: Option<String>,
+    #[serde(default)]
+    quote_min_amount: Option<u64>,
+    #[serde(default)]
+    quote_below_min: Option<BelowMinimum>,
+    #[serde(default)]
+    quote_min_difficulty_extended: Option<u32>,
+    #[serde(default)]
+    max_reconnect_attempts: Option<u32>,
+    #[serde(default)]
+    coinbase_rotation: CoinbaseRotation,
     #[serde(skip)]
     sv2_messaging: Option<Sv2MessagingConfig>,
     #[serde(skip)]
@@ -44,6 +94,8 @@ pub struct PoolConfig {
     #[serde(skip)]
     minimum_share_difficulty_bits: Option<u32>,
     #[serde(skip)]
+    stale_job_grace: Option<usize>,
+    #[serde(skip)]
     mint_http_url: Option<String>,
     #[serde(skip)]
     min_downstream_hashrate: Option<f32>,
@@ -76,14 +128,35 @@ impl PoolConfig {
             shares_per_minute,
             share_batch_size,
             log_file: None,
+            log_filter: None,
             server_id,
             locking_pubkey: None,
             stats_server_address: None,
             snapshot_poll_interval_secs: 5,
             jd_server_address: None,
+            emit_share_events: false,
+            no_mint: false,
+            debug_dump_address: None,
+            quote_poll_timeout_secs: None,
+            quote_poll_concurrency: None,
+            quote_status_path: None,
+            quote_store_path: None,
+            quote_notify_attempts: None,
+            quote_queue_capacity: None,
+            quote_queue_workers: None,
+            share_hook_timeout_ms: None,
+            quote_batch_size: None,
+            quote_batch_flush_ms: None,
+            lifetime_stats_path: None,
+            quote_min_amount: None,
+            quote_below_min: None,
+            quote_min_difficulty_extended: None,
+            max_reconnect_attempts: None,
+            coinbase_rotation: CoinbaseRotation::Static,
             sv2_messaging: None,
             minimum_difficulty: None,
             minimum_share_difficulty_bits: None,
+            stale_job_grace: None,
             mint_http_url: None,
             min_downstream_hashrate: None,
         }
@@ -160,6 +233,11 @@ impl PoolConfig {
         self.log_file.as_deref()
     }
 
+    /// Returns the tracing filter directives used when `RUST_LOG` is unset.
+    pub fn log_filter(&self) -> Option<&str> {
+        self.log_filter.as_deref()
+    }
+
     /// Returns the server id.
     pub fn server_id(&self) -> u16 {
         self.server_id
@@ -185,6 +263,23 @@ impl PoolConfig {
         self.sv2_messaging = messaging;
     }
 
+    /// Returns the address the mint connection listener binds to.
+    pub fn mint_listen_address(&self) -> &str {
+        self.sv2_messaging
+            .as_ref()
+            .map(|cfg| cfg.mint_listen_address.as_str())
+            .unwrap_or(DEFAULT_MINT_LISTEN_ADDRESS)
+    }
+
+    /// Parses the mint listen address, naming the config field on failure.
+    pub fn mint_listen_socket_addr(&self) -> Result<SocketAddr, PoolError> {
+        let value = self.mint_listen_address();
+        value.parse().map_err(|_| PoolError::InvalidAddress {
+            field: "sv2_messaging.mint_listen_address",
+            value: value.to_string(),
+        })
+    }
+
     /// Returns the optional minimum ehash difficulty override.
     pub fn minimum_difficulty(&self) -> Option<u32> {
         self.minimum_difficulty
@@ -205,6 +300,17 @@ impl PoolConfig {
         self.minimum_share_difficulty_bits = bits;
     }
 
+    /// Returns how many superseded jobs keep accepting shares (all jobs on the current chain
+    /// tip if unset).
+    pub fn stale_job_grace(&self) -> Option<usize> {
+        self.stale_job_grace
+    }
+
+    /// Sets the stale job grace (from shared config).
+    pub fn set_stale_job_grace(&mut self, grace: Option<usize>) {
+        self.stale_job_grace = grace;
+    }
+
     /// Returns the optional mint HTTP endpoint used by the quote poller.
     pub fn mint_http_url(&self) -> Option<&str> {
         self.mint_http_url.as_deref()
@@ -230,6 +336,59 @@ impl PoolConfig {
         self.stats_server_address.as_deref()
     }
 
+    /// Returns the timeout for a single mint quote status request, if overridden.
+    pub fn quote_poll_timeout_secs(&self) -> Option<u64> {
+        self.quote_poll_timeout_secs
+    }
+
+    /// Returns the maximum number of mint quote status requests in flight, if overridden.
+    pub fn quote_poll_concurrency(&self) -> Option<usize> {
+        self.quote_poll_concurrency
+    }
+
+    /// Returns the mint quote status path template, if overridden.
+    pub fn quote_status_path(&self) -> Option<&str> {
+        self.quote_status_path.as_deref()
+    }
+
+    /// Returns the SQLite database pending quotes are persisted to, if configured.
+    pub fn quote_store_path(&self) -> Option<&str> {
+        self.quote_store_path.as_deref()
+    }
+
+    /// Returns the number of failed notifications after which a paid quote is abandoned, if
+    /// overridden.
+    pub fn quote_notify_attempts(&self) -> Option<u32> {
+        self.quote_notify_attempts
+    }
+
+    /// Returns the SQLite database per-identity lifetime totals are kept in, if configured.
+    pub fn lifetime_stats_path(&self) -> Option<&str> {
+        self.lifetime_stats_path.as_deref()
+    }
+
+    /// Returns the smallest ehash amount to quote, if configured.
+    pub fn quote_min_amount(&self) -> Option<u64> {
+        self.quote_min_amount
+    }
+
+    /// Returns how amounts below [`Self::quote_min_amount`] are handled, if overridden.
+    pub fn quote_below_min(&self) -> Option<BelowMinimum> {
+        self.quote_below_min
+    }
+
+    /// Returns the ehash minimum difficulty for shares on extended channels, if it differs
+    /// from the shared `minimum_difficulty`.
+    pub fn quote_min_difficulty_extended(&self) -> Option<u32> {
+        self.quote_min_difficulty_extended
+    }
+
+    /// Returns how many times the template provider connection is attempted before the pool
+    /// exits, if limited. `0` retries forever, as does leaving it unset.
+    pub fn max_reconnect_attempts(&self) -> Option<u32> {
+        self.max_reconnect_attempts
+    }
+
     /// Returns the snapshot poll interval in seconds.
     pub fn snapshot_poll_interval_secs(&self) -> u64 {
         self.snapshot_poll_interval_secs
@@ -239,6 +398,221 @@ impl PoolConfig {
     pub fn jd_server_address(&self) -> Option<&str> {
         self.jd_server_address.as_deref()
     }
+
+    /// Returns how many accepted shares may wait for a quote worker before quotes are shed,
+    /// if overridden.
+    pub fn quote_queue_capacity(&self) -> Option<usize> {
+        self.quote_queue_capacity
+    }
+
+    /// Returns the number of workers sending quotes to the mint, if overridden.
+    pub fn quote_queue_workers(&self) -> Option<usize> {
+        self.quote_queue_workers
+    }
+
+    /// How long each share hook may run for an accepted share before it is abandoned, if
+    /// overridden.
+    pub fn share_hook_timeout(&self) -> Option<Duration> {
+        self.share_hook_timeout_ms.map(Duration::from_millis)
+    }
+
+    /// Returns the most quotes sent to the mint per batch, if batching is enabled.
+    pub fn quote_batch_size(&self) -> Option<usize> {
+        self.quote_batch_size
+    }
+
+    /// Returns how long a partial batch of quotes waits before it is sent, in milliseconds,
+    /// if overridden.
+    pub fn quote_batch_flush_ms(&self) -> Option<u64> {
+        self.quote_batch_flush_ms
+    }
+
+    /// Whether a ShareSubmitted event is sent to the stats service for every accepted share.
+    pub fn emit_share_events(&self) -> bool {
+        self.emit_share_events
+    }
+
+    /// Whether shares are accepted and counted without requesting ehash quotes. The mint
+    /// listener and quote poller are not started in this mode.
+    pub fn no_mint(&self) -> bool {
+        self.no_mint
+    }
+
+    /// Address serving `GET /debug/dump` with the pool's quote and channel state, each
+    /// downstream's pending quotes and the stats reset routes, if enabled.
+    pub fn debug_dump_address(&self) -> Option<&str> {
+        self.debug_dump_address.as_deref()
+    }
+
+    /// How long the mint connection may stay silent before it is closed, if limited.
+    pub fn mint_idle_timeout(&self) -> Option<Duration> {
+        self.sv2_messaging
+            .as_ref()
+            .and_then(|cfg| cfg.idle_timeout_ms)
+            .map(Duration::from_millis)
+    }
+
+    /// How long a quote may wait for the mint's response before it is failed, if limited.
+    /// A response arriving later would be dropped as stale anyway.
+    pub fn pending_quote_max_age(&self) -> Option<Duration> {
+        self.sv2_messaging
+            .as_ref()
+            .and_then(|cfg| cfg.max_response_age_ms)
+            .map(Duration::from_millis)
+    }
+
+    /// How long a mint connection may stay open before it is recycled, if limited.
+    pub fn mint_max_connection_lifetime(&self) -> Option<Duration> {
+        self.sv2_messaging
+            .as_ref()
+            .and_then(|cfg| cfg.max_connection_lifetime_ms)
+            .map(Duration::from_millis)
+    }
+
+    /// Most quotes the pool keeps pending on the mint before evicting the oldest, if limited.
+    pub fn max_pending_quotes(&self) -> Option<usize> {
+        self.sv2_messaging
+            .as_ref()
+            .and_then(|cfg| cfg.max_pending_quotes)
+    }
+
+    /// How long a heartbeat ping to the mint may go unanswered, if messaging is configured.
+    pub fn mint_heartbeat_timeout(&self) -> Option<Duration> {
+        self.sv2_messaging
+            .as_ref()
+            .map(|cfg| Duration::from_millis(cfg.timeout_ms))
+    }
+
+    /// Returns how the coinbase payout script is rotated between blocks.
+    pub fn coinbase_rotation(&self) -> &CoinbaseRotation {
+        &self.coinbase_rotation
+    }
+}
+
+impl RoleConfig for PoolConfig {
+    const SERVICE: &'static str = "pool";
+
+    fn validate(&self) -> Result<(), String> {
+        if !(self.shares_per_minute.is_finite() && self.shares_per_minute > 0.0) {
+            return Err(format!(
+                "shares_per_minute must be a positive number, got {}",
+                self.shares_per_minute
+            ));
+        }
+        if self.share_batch_size == 0 {
+            return Err("share_batch_size must be at least 1".to_string());
+        }
+        if self.quote_queue_capacity == Some(0) {
+            return Err("quote_queue_capacity must be at least 1".to_string());
+        }
+        if self.quote_queue_workers == Some(0) {
+            return Err("quote_queue_workers must be at least 1".to_string());
+        }
+        if self.quote_batch_size == Some(0) {
+            return Err("quote_batch_size must be at least 1".to_string());
+        }
+        if self.quote_batch_flush_ms == Some(0) {
+            return Err("quote_batch_flush_ms must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    /// Resolved configuration as `(key, value)` pairs, with the authority secret key redacted.
+    fn effective_config(&self) -> Vec<(&'static str, String)> {
+        fn opt<T: std::fmt::Display>(value: Option<T>) -> String {
+            value.map_or_else(|| "none".to_string(), |v| v.to_string())
+        }
+        let coinbase_rotation = match &self.coinbase_rotation {
+            CoinbaseRotation::Static => "static".to_string(),
+            CoinbaseRotation::RoundRobin { scripts } => format!("round_robin({})", scripts.len()),
+            CoinbaseRotation::Derived { start_index, .. } => format!("derived(from {start_index})"),
+        };
+        vec![
+            ("listen_address", self.listen_address.clone()),
+            ("tp_address", self.tp_address.clone()),
+            (
+                "tp_authority_public_key",
+                opt(self.tp_authority_public_key.as_ref()),
+            ),
+            (
+                "authority_public_key",
+                self.authority_public_key.to_string(),
+            ),
+            ("authority_secret_key", REDACTED.to_string()),
+            ("cert_validity_sec", self.cert_validity_sec.to_string()),
+            (
+                "coinbase_reward_script",
+                self.coinbase_reward_script.script_pubkey().to_hex_string(),
+            ),
+            ("coinbase_rotation", coinbase_rotation),
+            ("pool_signature", self.pool_signature.clone()),
+            ("shares_per_minute", self.shares_per_minute.to_string()),
+            ("share_batch_size", self.share_batch_size.to_string()),
+            ("server_id", self.server_id.to_string()),
+            ("locking_pubkey", opt(self.locking_pubkey.as_ref())),
+            (
+                "stats_server_address",
+                opt(self.stats_server_address.as_ref()),
+            ),
+            (
+                "snapshot_poll_interval_secs",
+                self.snapshot_poll_interval_secs.to_string(),
+            ),
+            ("jd_server_address", opt(self.jd_server_address.as_ref())),
+            ("emit_share_events", self.emit_share_events.to_string()),
+            ("no_mint", self.no_mint.to_string()),
+            ("debug_dump_address", opt(self.debug_dump_address.as_ref())),
+            ("quote_poll_timeout_secs", opt(self.quote_poll_timeout_secs)),
+            ("quote_poll_concurrency", opt(self.quote_poll_concurrency)),
+            ("quote_status_path", opt(self.quote_status_path.as_ref())),
+            ("quote_store_path", opt(self.quote_store_path.as_ref())),
+            ("quote_notify_attempts", opt(self.quote_notify_attempts)),
+            ("quote_queue_capacity", opt(self.quote_queue_capacity)),
+            ("quote_queue_workers", opt(self.quote_queue_workers)),
+            ("share_hook_timeout_ms", opt(self.share_hook_timeout_ms)),
+            ("quote_batch_size", opt(self.quote_batch_size)),
+            ("quote_batch_flush_ms", opt(self.quote_batch_flush_ms)),
+            (
+                "lifetime_stats_path",
+                opt(self.lifetime_stats_path.as_ref()),
+            ),
+            ("quote_min_amount", opt(self.quote_min_amount)),
+            ("quote_below_min", opt(self.quote_below_min)),
+            (
+                "quote_min_difficulty_extended",
+                opt(self.quote_min_difficulty_extended),
+            ),
+            ("max_reconnect_attempts", opt(self.max_reconnect_attempts)),
+            (
+                "mint_listen_address",
+                self.mint_listen_address().to_string(),
+            ),
+            ("minimum_difficulty", opt(self.minimum_difficulty)),
+            (
+                "minimum_share_difficulty_bits",
+                opt(self.minimum_share_difficulty_bits),
+            ),
+            ("stale_job_grace", opt(self.stale_job_grace)),
+            ("min_downstream_hashrate", opt(self.min_downstream_hashrate)),
+        ]
+    }
+}
+
+/// How the pool picks the coinbase payout script for each new block.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum CoinbaseRotation {
+    /// Always pay to `coinbase_reward_script`.
+    #[default]
+    Static,
+    /// Cycle through a fixed list of scripts.
+    RoundRobin { scripts: Vec<CoinbaseRewardScript> },
+    /// Derive a new script per block from a ranged descriptor, e.g. `wpkh(xpub.../0/*)`.
+    Derived {
+        descriptor: String,
+        #[serde(default)]
+        start_index: u32,
+    },
 }
 
 /// Default snapshot poll interval (5 seconds)
@@ -292,3 +666,124 @@ impl ConnectionConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ext_config::{Config, File, FileFormat};
+
+    fn example_config() -> PoolConfig {
+        Config::builder()
+            .add_source(File::new(
+                "config-examples/pool-config-local-tp-example.toml",
+                FileFormat::Toml,
+            ))
+            .build()
+            .expect("Failed to build config")
+            .try_deserialize()
+            .expect("Failed to deserialize config")
+    }
+
+    #[test]
+    fn test_mint_listen_socket_addr_defaults() {
+        let config = example_config();
+        assert_eq!(
+            config.mint_listen_socket_addr().unwrap(),
+            "127.0.0.1:34260".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_mint_listen_socket_addr_rejects_invalid_address() {
+        let mut config = example_config();
+        config.set_sv2_messaging(Some(Sv2MessagingConfig {
+            mint_listen_address: "not-an-address".to_string(),
+            ..Default::default()
+        }));
+
+        let message = config.mint_listen_socket_addr().unwrap_err().to_string();
+        assert!(message.contains("sv2_messaging.mint_listen_address"));
+        assert!(message.contains("not-an-address"));
+    }
+
+    #[test]
+    fn test_coinbase_rotation_defaults_to_static() {
+        let config = example_config();
+        assert!(matches!(
+            config.coinbase_rotation(),
+            CoinbaseRotation::Static
+        ));
+    }
+
+    #[test]
+    fn test_no_mint_defaults_to_off() {
+        let config = example_config();
+        assert!(!config.no_mint());
+    }
+
+    #[test]
+    fn test_effective_config_redacts_secret_key() {
+        let config = example_config();
+        let entries = config.effective_config();
+        let value = |key: &str| {
+            entries
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.clone())
+                .unwrap_or_else(|| panic!("missing {key}"))
+        };
+
+        assert_eq!(value("listen_address"), config.listen_address().to_string());
+        assert_eq!(value("tp_address"), config.tp_address().to_string());
+        assert_eq!(value("mint_listen_address"), "127.0.0.1:34260");
+        assert_eq!(value("authority_secret_key"), REDACTED);
+
+        let secret = config.authority_secret_key().to_string();
+        assert!(entries.iter().all(|(_, v)| !v.contains(&secret)));
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_quote_queue() {
+        let mut config = example_config();
+        config.quote_queue_workers = Some(0);
+        let message = config.validate().unwrap_err();
+        assert!(message.contains("quote_queue_workers"));
+
+        config.quote_queue_workers = Some(2);
+        config.quote_queue_capacity = Some(0);
+        let message = config.validate().unwrap_err();
+        assert!(message.contains("quote_queue_capacity"));
+    }
+
+    #[test]
+    fn test_validate_rejects_empty_quote_batches() {
+        let mut config = example_config();
+        assert_eq!(config.quote_batch_size(), None);
+        config.quote_batch_size = Some(0);
+        let message = config.validate().unwrap_err();
+        assert!(message.contains("quote_batch_size"));
+
+        config.quote_batch_size = Some(32);
+        config.quote_batch_flush_ms = Some(0);
+        let message = config.validate().unwrap_err();
+        assert!(message.contains("quote_batch_flush_ms"));
+
+        config.quote_batch_flush_ms = Some(50);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_role_config_loads_and_validates_example() {
+        let config =
+            PoolConfig::from_path("config-examples/pool-config-local-tp-example.toml").unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.effective_config(),
+            example_config().effective_config()
+        );
+
+        let mut invalid = config;
+        invalid.share_batch_size = 0;
+        assert!(invalid.validate().unwrap_err().contains("share_batch_size"));
+    }
+}
@@ -48,6 +48,12 @@ pub enum PoolError {
     PoisonLock(String),
     /// Error indicating that a component has shut down unexpectedly.
     ComponentShutdown(String),
+    /// A configured address could not be parsed into a socket address.
+    InvalidAddress { field: &'static str, value: String },
+    /// The coinbase payout configuration cannot be used to build coinbase outputs.
+    InvalidCoinbaseConfig { field: &'static str, reason: String },
+    /// Every allowed attempt to connect to the template provider failed.
+    ReconnectAttemptsExhausted { attempts: u32, last_error: String },
     /// Custom error message.
     Custom(String),
     /// Error related to the SV2 protocol, including an error code and a `Mining` message.
@@ -83,6 +89,19 @@ impl std::fmt::Display for PoolError {
             RolesLogic(ref e) => write!(f, "Roles Logic SV2 error: `{e:?}`"),
             PoisonLock(ref e) => write!(f, "Poison lock: {e:?}"),
             ComponentShutdown(ref e) => write!(f, "Component shutdown: {e:?}"),
+            InvalidAddress { field, value } => {
+                write!(f, "Invalid address in config field `{field}`: `{value}`")
+            }
+            InvalidCoinbaseConfig { field, reason } => {
+                write!(f, "Invalid coinbase config in field `{field}`: {reason}")
+            }
+            ReconnectAttemptsExhausted {
+                attempts,
+                last_error,
+            } => write!(
+                f,
+                "Gave up on the template provider after {attempts} attempts: {last_error}"
+            ),
             Custom(ref e) => write!(f, "Custom SV2 error: `{e:?}`"),
             Sv2ProtocolError(ref e) => {
                 write!(f, "Received Sv2 Protocol Error from upstream: `{e:?}`")
@@ -0,0 +1,328 @@
+//! Coinbase payout script selection
+//!
+//! By default every block template pays to `coinbase_reward_script`. With
+//! `coinbase_rotation` configured the pool moves to a fresh script for each new
+//! block, either cycling through a fixed list or deriving the next address from a
+//! ranged descriptor, so payouts are not all linked to one address on-chain.
+//!
+//! The script only changes when a future template arrives; non-future templates
+//! and newly opened channels reuse the current one. Custom jobs from JD clients are
+//! accepted if they pay to the configured script or to any recently issued one.
+
+use std::{
+    collections::VecDeque,
+    sync::{Mutex, PoisonError},
+};
+
+use config_helpers_sv2::{CoinbaseRewardDescriptor, CoinbaseRewardScript};
+use stratum_common::roles_logic_sv2::bitcoin::{Amount, Script, ScriptBuf, TxOut};
+use tracing::error;
+
+use crate::{
+    config::{CoinbaseRotation, PoolConfig},
+    error::PoolError,
+};
+
+/// Number of previously issued scripts still accepted in custom jobs
+pub const RECENT_SCRIPTS: usize = 16;
+
+/// Consensus limit on script size. Outputs paying to a longer script can never be spent.
+pub const MAX_SCRIPT_SIZE: usize = 10_000;
+
+enum Source {
+    Static,
+    RoundRobin(Vec<ScriptBuf>),
+    Derived(CoinbaseRewardDescriptor),
+}
+
+struct State {
+    current: ScriptBuf,
+    next_index: u32,
+    recent: VecDeque<ScriptBuf>,
+}
+
+/// Hands out the pool payout script for each new block
+pub struct CoinbaseOutputProvider {
+    configured: ScriptBuf,
+    source: Source,
+    state: Mutex<State>,
+}
+
+impl CoinbaseOutputProvider {
+    /// Build the provider described by the pool config
+    pub fn from_config(config: &PoolConfig) -> Result<Self, PoolError> {
+        let configured = config.coinbase_reward_script().script_pubkey();
+        match config.coinbase_rotation() {
+            CoinbaseRotation::Static => Ok(Self::new(configured, Source::Static, 0)),
+            CoinbaseRotation::RoundRobin { scripts } => {
+                if scripts.is_empty() {
+                    return Err(PoolError::InvalidCoinbaseConfig {
+                        field: "coinbase_rotation.scripts",
+                        reason: "the list of scripts must not be empty".to_string(),
+                    });
+                }
+                let scripts = scripts
+                    .iter()
+                    .map(CoinbaseRewardScript::script_pubkey)
+                    .collect();
+                Ok(Self::new(configured, Source::RoundRobin(scripts), 0))
+            }
+            CoinbaseRotation::Derived {
+                descriptor,
+                start_index,
+            } => {
+                let descriptor =
+                    CoinbaseRewardDescriptor::from_descriptor(descriptor).map_err(|e| {
+                        PoolError::InvalidCoinbaseConfig {
+                            field: "coinbase_rotation.descriptor",
+                            reason: format!(
+                                "`{}` is not a usable ranged descriptor: {}",
+                                descriptor, e
+                            ),
+                        }
+                    })?;
+                // Fail now rather than silently falling back to the configured script later
+                descriptor.script_pubkey_at(*start_index).map_err(|e| {
+                    PoolError::InvalidCoinbaseConfig {
+                        field: "coinbase_rotation.start_index",
+                        reason: format!("cannot derive a script at index {}: {}", start_index, e),
+                    }
+                })?;
+                Ok(Self::new(
+                    configured,
+                    Source::Derived(descriptor),
+                    *start_index,
+                ))
+            }
+        }
+    }
+
+    fn new(configured: ScriptBuf, source: Source, start_index: u32) -> Self {
+        let provider = Self {
+            configured,
+            source,
+            state: Mutex::new(State {
+                current: ScriptBuf::new(),
+                next_index: start_index,
+                recent: VecDeque::with_capacity(RECENT_SCRIPTS),
+            }),
+        };
+        provider.advance();
+        provider
+    }
+
+    /// A provider that always pays to `script`
+    #[cfg(test)]
+    pub(crate) fn fixed(script: ScriptBuf) -> Self {
+        Self::new(script, Source::Static, 0)
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        // The state is replaced wholesale on every update, so a poisoned lock
+        // still holds a usable value
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn script_at(&self, index: u32) -> ScriptBuf {
+        match &self.source {
+            Source::Static => self.configured.clone(),
+            Source::RoundRobin(scripts) => scripts[index as usize % scripts.len()].clone(),
+            Source::Derived(descriptor) => match descriptor.script_pubkey_at(index) {
+                Ok(script) => script,
+                Err(e) => {
+                    error!(
+                        "Failed to derive coinbase script at index {}: {}; using configured one",
+                        index, e
+                    );
+                    self.configured.clone()
+                }
+            },
+        }
+    }
+
+    /// Move to the next payout script and return it. Called once per future template.
+    pub fn advance(&self) -> ScriptBuf {
+        let mut state = self.state();
+        let index = state.next_index;
+        let script = self.script_at(index);
+        state.next_index = index.wrapping_add(1);
+        if state.recent.len() == RECENT_SCRIPTS {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(script.clone());
+        state.current = script.clone();
+        script
+    }
+
+    /// The payout script for the block currently being built
+    pub fn current(&self) -> ScriptBuf {
+        self.state().current.clone()
+    }
+
+    /// Whether `script` is the configured payout script or one issued recently
+    pub fn recognizes(&self, script: &Script) -> bool {
+        *script == *self.configured || self.state().recent.iter().any(|s| **s == *script)
+    }
+
+    /// A zero-value output paying to the longest script this provider can hand out, used to
+    /// tell the template provider how much room the coinbase output needs.
+    ///
+    /// Fails if the configured or a rotated script is empty or over [`MAX_SCRIPT_SIZE`].
+    pub fn sizing_output(&self) -> Result<TxOut, PoolError> {
+        check_script_len("coinbase_reward_script", &self.configured)?;
+        let script_pubkey = self.longest_script();
+        check_script_len("coinbase_rotation", &script_pubkey)?;
+        Ok(TxOut {
+            value: Amount::from_sat(0),
+            script_pubkey,
+        })
+    }
+
+    /// The longest script this provider can hand out, used to size the coinbase output
+    pub fn longest_script(&self) -> ScriptBuf {
+        let rotated = match &self.source {
+            Source::Static => None,
+            Source::RoundRobin(scripts) => scripts.iter().max_by_key(|s| s.len()).cloned(),
+            // Every index of a descriptor derives a script of the same shape
+            Source::Derived(_) => Some(self.current()),
+        };
+        match rotated {
+            Some(script) if script.len() > self.configured.len() => script,
+            _ => self.configured.clone(),
+        }
+    }
+}
+
+fn check_script_len(field: &'static str, script: &Script) -> Result<(), PoolError> {
+    let reason = if script.is_empty() {
+        "the payout script is empty, so anyone could spend the block reward".to_string()
+    } else if script.len() > MAX_SCRIPT_SIZE {
+        format!(
+            "the payout script is {} bytes, over the {} byte limit",
+            script.len(),
+            MAX_SCRIPT_SIZE
+        )
+    } else {
+        return Ok(());
+    };
+    Err(PoolError::InvalidCoinbaseConfig { field, reason })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ext_config::{Config, File, FileFormat};
+
+    const CONFIGURED: &str = "addr(bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4)";
+
+    fn script(s: &str) -> ScriptBuf {
+        CoinbaseRewardScript::from_descriptor(s)
+            .unwrap()
+            .script_pubkey()
+    }
+
+    fn round_robin(scripts: &[&str]) -> CoinbaseOutputProvider {
+        let scripts = scripts.iter().map(|s| script(s)).collect();
+        CoinbaseOutputProvider::new(script(CONFIGURED), Source::RoundRobin(scripts), 0)
+    }
+
+    #[test]
+    fn test_round_robin_rotates_per_advance() {
+        let a = "addr(1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2)";
+        let b = "addr(3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy)";
+        let provider = round_robin(&[a, b]);
+
+        assert_eq!(provider.current(), script(a));
+        assert_eq!(provider.advance(), script(b));
+        assert_eq!(provider.current(), script(b));
+        assert_eq!(provider.advance(), script(a));
+    }
+
+    #[test]
+    fn test_recognizes_configured_and_recent_scripts() {
+        let a = "addr(1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2)";
+        let b = "addr(3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy)";
+        let provider = round_robin(&[a]);
+
+        assert!(provider.recognizes(&script(a)));
+        assert!(provider.recognizes(&script(CONFIGURED)));
+        assert!(!provider.recognizes(&script(b)));
+    }
+
+    fn config_with_rotation(rotation: &str) -> PoolConfig {
+        Config::builder()
+            .add_source(File::new(
+                "config-examples/pool-config-local-tp-example.toml",
+                FileFormat::Toml,
+            ))
+            .add_source(File::from_str(rotation, FileFormat::Toml))
+            .build()
+            .expect("Failed to build config")
+            .try_deserialize()
+            .expect("Failed to deserialize config")
+    }
+
+    fn config_error(config: &PoolConfig) -> PoolError {
+        CoinbaseOutputProvider::from_config(config)
+            .err()
+            .expect("config should be rejected")
+    }
+
+    #[test]
+    fn test_invalid_rotation_config_names_the_field() {
+        let config = config_with_rotation(
+            r#"
+            [coinbase_rotation]
+            mode = "derived"
+            descriptor = "wpkh(not-a-key/0/*)"
+            "#,
+        );
+        let err = config_error(&config);
+        assert!(matches!(
+            err,
+            PoolError::InvalidCoinbaseConfig {
+                field: "coinbase_rotation.descriptor",
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("wpkh(not-a-key/0/*)"));
+
+        let config = config_with_rotation(
+            r#"
+            [coinbase_rotation]
+            mode = "round_robin"
+            scripts = []
+            "#,
+        );
+        assert!(config_error(&config)
+            .to_string()
+            .contains("coinbase_rotation.scripts"));
+    }
+
+    #[test]
+    fn test_sizing_output_rejects_out_of_bounds_scripts() {
+        let provider = CoinbaseOutputProvider::new(ScriptBuf::new(), Source::Static, 0);
+        assert!(matches!(
+            provider.sizing_output(),
+            Err(PoolError::InvalidCoinbaseConfig {
+                field: "coinbase_reward_script",
+                ..
+            })
+        ));
+
+        let huge = ScriptBuf::from(vec![0x6a; MAX_SCRIPT_SIZE + 1]);
+        let provider =
+            CoinbaseOutputProvider::new(script(CONFIGURED), Source::RoundRobin(vec![huge]), 0);
+        assert!(matches!(
+            provider.sizing_output(),
+            Err(PoolError::InvalidCoinbaseConfig {
+                field: "coinbase_rotation",
+                ..
+            })
+        ));
+
+        let provider = round_robin(&[CONFIGURED]);
+        let output = provider.sizing_output().unwrap();
+        assert_eq!(output.script_pubkey, script(CONFIGURED));
+    }
+}
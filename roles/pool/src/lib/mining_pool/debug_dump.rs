@@ -0,0 +1,409 @@
+//! Opt-in diagnostic dump of the pool's mint messaging state
+//!
+//! When `debug_dump_address` is set the pool serves `GET /debug/dump` on that address,
+//! returning the message hub statistics and pending quotes, the quote poller's pending
+//! quotes and the channels registered with the mint manager as one JSON document.
+//! `GET /debug/downstream/{id}/pending-quotes` lists only the quote poller's pending
+//! quotes on that downstream's channels.
+//!
+//! `POST /debug/stats/reset` zeroes the per-downstream stats counters of every downstream,
+//! and `POST /debug/stats/reset/{id}` those of a single downstream, e.g. at the start of an
+//! accounting period.
+//!
+//! A dump holds the pool lock only long enough to clone the component handles. Each
+//! component is then read under its own short read lock, so share handling never waits
+//! on a dump.
+
+use std::{convert::Infallible, sync::Arc};
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::{
+    body::Incoming, server::conn::http1, service::service_fn, Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use mint_pool_messaging::MintPoolMessageHub;
+use pool_stats::PoolStatsRegistry;
+use serde_json::json;
+use stats_sv2::types::unix_timestamp;
+use stratum_common::roles_logic_sv2::utils::Mutex;
+use tokio::net::TcpListener;
+use tracing::{error, info, warn};
+
+use super::{mint_integration::MintIntegrationManager, quote_poller::QuotePoller, Pool};
+use crate::error::PoolError;
+
+/// Handles to the components included in a dump
+pub struct DumpSources {
+    pub hub: Arc<MintPoolMessageHub>,
+    pub quote_poller: Option<Arc<QuotePoller>>,
+    pub mint_manager: Arc<MintIntegrationManager>,
+    pub stats_registry: Arc<PoolStatsRegistry>,
+}
+
+impl DumpSources {
+    pub fn from_pool(pool: &Arc<Mutex<Pool>>) -> Result<Self, PoolError> {
+        Ok(pool.safe_lock(|p| Self {
+            hub: p.mint_hub.clone(),
+            quote_poller: p.quote_poller.clone(),
+            mint_manager: p.mint_manager.clone(),
+            stats_registry: p.stats_registry.clone(),
+        })?)
+    }
+
+    /// Collect the dump as JSON
+    pub async fn collect(&self) -> serde_json::Value {
+        let hub = self.hub.get_diagnostics().await;
+        let hub_pending: Vec<_> = hub
+            .pending_quotes
+            .iter()
+            .map(|q| {
+                json!({
+                    "share_hash": q.share_hash.to_string(),
+                    "channel_id": q.channel_id,
+                    "sequence_number": q.sequence_number,
+                    "amount": q.amount,
+                    "age_ms": q.age_ms,
+                })
+            })
+            .collect();
+
+        let quote_poller = match &self.quote_poller {
+            Some(poller) => {
+                let mut pending = poller.pending_quote_details().await;
+                pending.sort_by(|a, b| a.1.created_at.cmp(&b.1.created_at));
+                let pending: Vec<_> = pending
+                    .into_iter()
+                    .map(|(quote_id, quote)| {
+                        json!({
+                            "quote_id": quote_id,
+                            "channel_id": quote.channel_id,
+                            "amount": quote.amount,
+                            "age_ms": quote.created_at.elapsed().as_millis() as u64,
+                        })
+                    })
+                    .collect();
+                json!({ "pending_quotes": pending })
+            }
+            None => serde_json::Value::Null,
+        };
+
+        let mut channels = self.mint_manager.channel_contexts().await;
+        channels.sort_by_key(|ctx| ctx.channel_id);
+        let channels: Vec<_> = channels
+            .into_iter()
+            .map(|ctx| {
+                json!({
+                    "channel_id": ctx.channel_id,
+                    "downstream_id": ctx.downstream_id,
+                    "has_locking_key": ctx.locking_key_bytes.is_some(),
+                    "age_secs": ctx.created_at.elapsed().as_secs(),
+                })
+            })
+            .collect();
+
+        json!({
+            "timestamp": unix_timestamp(),
+            "hub": {
+                "total_connections": hub.stats.total_connections,
+                "pool_connections": hub.stats.pool_connections,
+                "mint_connections": hub.stats.mint_connections,
+                "mint_available": hub.stats.mint_available,
+                "quote_request_subscribers": hub.stats.quote_request_subscribers,
+                "quote_response_subscribers": hub.stats.quote_response_subscribers,
+                "quote_error_subscribers": hub.stats.quote_error_subscribers,
+                "pending_quotes": hub.stats.pending_quotes,
+                "oldest_pending_ms": hub.stats.oldest_pending_ms,
+            },
+            "hub_pending_quotes": hub_pending,
+            "quote_poller": quote_poller,
+            "channels": channels,
+        })
+    }
+
+    /// Collect the quote poller's pending quotes for one downstream as JSON
+    pub async fn collect_downstream_pending(&self, downstream_id: u32) -> serde_json::Value {
+        let mut pending = match &self.quote_poller {
+            Some(poller) => {
+                poller
+                    .pending_quotes_for_downstream(downstream_id, &self.mint_manager)
+                    .await
+            }
+            None => Vec::new(),
+        };
+        pending.sort();
+        let pending: Vec<_> = pending
+            .into_iter()
+            .map(|(quote_id, channel_id, amount)| {
+                json!({
+                    "quote_id": quote_id,
+                    "channel_id": channel_id,
+                    "amount": amount,
+                })
+            })
+            .collect();
+
+        json!({
+            "timestamp": unix_timestamp(),
+            "downstream_id": downstream_id,
+            "pending_quotes": pending,
+        })
+    }
+}
+
+/// Serve the debug routes on `address` until the listener fails
+pub async fn run_debug_server(address: String, pool: Arc<Mutex<Pool>>) -> Result<(), PoolError> {
+    let listener = TcpListener::bind(&address).await?;
+    info!("Debug dump listening on http://{}/debug/dump", address);
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let io = TokioIo::new(stream);
+        let pool = pool.clone();
+
+        tokio::task::spawn(async move {
+            let service = service_fn(move |req| {
+                let pool = pool.clone();
+                async move { handle_request(req, pool).await }
+            });
+
+            if let Err(err) = http1::Builder::new().serve_connection(io, service).await {
+                error!("Error serving debug dump connection: {:?}", err);
+            }
+        });
+    }
+}
+
+async fn handle_request(
+    req: Request<Incoming>,
+    pool: Arc<Mutex<Pool>>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let sources = match DumpSources::from_pool(&pool) {
+        Ok(sources) => sources,
+        Err(e) => {
+            warn!("Debug dump unavailable: {}", e);
+            return Ok(Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Full::new(Bytes::from("Pool state unavailable")))
+                .unwrap());
+        }
+    };
+    Ok(route(req.method(), req.uri().path(), &sources).await)
+}
+
+async fn route(method: &Method, path: &str, sources: &DumpSources) -> Response<Full<Bytes>> {
+    let downstream = path
+        .strip_prefix("/debug/downstream/")
+        .and_then(|rest| rest.strip_suffix("/pending-quotes"));
+    let reset = path.strip_prefix("/debug/stats/reset/");
+    match (method, path, downstream, reset) {
+        (&Method::GET, "/debug/dump", _, _) => json_response(sources.collect().await),
+        (&Method::GET, _, Some(downstream_id), _) => match downstream_id.parse::<u32>() {
+            Ok(downstream_id) => {
+                json_response(sources.collect_downstream_pending(downstream_id).await)
+            }
+            Err(_) => invalid_downstream_id(),
+        },
+        (&Method::POST, "/debug/stats/reset", _, _) => {
+            sources.stats_registry.reset_all();
+            info!("Reset stats counters of all downstreams");
+            json_response(json!({ "reset": "all" }))
+        }
+        (&Method::POST, _, _, Some(downstream_id)) => match downstream_id.parse::<u32>() {
+            Ok(downstream_id) if sources.stats_registry.reset_downstream(downstream_id) => {
+                info!("Reset stats counters of downstream {}", downstream_id);
+                json_response(json!({ "reset": downstream_id }))
+            }
+            Ok(_) => not_found(),
+            Err(_) => invalid_downstream_id(),
+        },
+        _ => not_found(),
+    }
+}
+
+fn invalid_downstream_id() -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .body(Full::new(Bytes::from("Invalid downstream ID")))
+        .unwrap()
+}
+
+fn not_found() -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Full::new(Bytes::from("Not Found")))
+        .unwrap()
+}
+
+fn json_response(body: serde_json::Value) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+    use mint_pool_messaging::{
+        build_parsed_quote_request, CompressedPubKey, MessagingConfig, PendingQuoteContext,
+    };
+
+    fn locking_key() -> CompressedPubKey<'static> {
+        let mut encoded = [0u8; 34];
+        encoded[0] = 33;
+        encoded[1] = 0x02;
+        CompressedPubKey::from_bytes(&mut encoded[..])
+            .expect("valid compressed key")
+            .into_static()
+    }
+
+    #[tokio::test]
+    async fn test_dump_includes_pending_quotes_and_channels() {
+        let hub = MintPoolMessageHub::new(MessagingConfig::default());
+        let parsed = build_parsed_quote_request(64, &[0xAB; 32], locking_key()).unwrap();
+        let share_hash = parsed.share_hash.to_string();
+        hub.send_quote_request(
+            parsed,
+            PendingQuoteContext {
+                channel_id: 7,
+                sequence_number: 3,
+                amount: 64,
+            },
+        )
+        .await
+        .unwrap();
+
+        let quote_poller = Arc::new(QuotePoller::new(None));
+        quote_poller
+            .register_quote("quote-1".to_string(), 7, 64, None)
+            .await;
+
+        let mint_manager = Arc::new(MintIntegrationManager::new("127.0.0.1:34260".to_string()));
+        mint_manager.register_channel(7, Some(vec![2; 33]), 1).await;
+        mint_manager.register_channel(8, None, 2).await;
+
+        let dump = DumpSources {
+            hub,
+            quote_poller: Some(quote_poller),
+            mint_manager,
+            stats_registry: PoolStatsRegistry::new(),
+        }
+        .collect()
+        .await;
+
+        assert_eq!(dump["hub"]["pending_quotes"], 1);
+        assert_eq!(dump["hub_pending_quotes"][0]["share_hash"], share_hash);
+        assert_eq!(dump["hub_pending_quotes"][0]["channel_id"], 7);
+        assert_eq!(dump["hub_pending_quotes"][0]["sequence_number"], 3);
+        assert_eq!(
+            dump["quote_poller"]["pending_quotes"][0]["quote_id"],
+            "quote-1"
+        );
+
+        let channels = dump["channels"].as_array().unwrap();
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels[0]["channel_id"], 7);
+        assert_eq!(channels[0]["has_locking_key"], true);
+        assert_eq!(channels[1]["downstream_id"], 2);
+        assert_eq!(channels[1]["has_locking_key"], false);
+    }
+
+    async fn get(sources: &DumpSources, path: &str) -> (StatusCode, serde_json::Value) {
+        request(sources, &Method::GET, path).await
+    }
+
+    async fn request(
+        sources: &DumpSources,
+        method: &Method,
+        path: &str,
+    ) -> (StatusCode, serde_json::Value) {
+        let response = route(method, path, sources).await;
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn test_downstream_pending_quotes_route() {
+        let quote_poller = Arc::new(QuotePoller::new(None));
+        quote_poller
+            .register_quote("quote-a".to_string(), 7, 64, None)
+            .await;
+        quote_poller
+            .register_quote("quote-b".to_string(), 9, 32, None)
+            .await;
+        quote_poller
+            .register_quote("quote-c".to_string(), 8, 16, None)
+            .await;
+
+        let mint_manager = Arc::new(MintIntegrationManager::new("127.0.0.1:34260".to_string()));
+        mint_manager.register_channel(7, None, 1).await;
+        mint_manager.register_channel(9, None, 1).await;
+        mint_manager.register_channel(8, None, 2).await;
+
+        let sources = DumpSources {
+            hub: MintPoolMessageHub::new(MessagingConfig::default()),
+            quote_poller: Some(quote_poller),
+            mint_manager,
+            stats_registry: PoolStatsRegistry::new(),
+        };
+
+        let (status, body) = get(&sources, "/debug/downstream/1/pending-quotes").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["downstream_id"], 1);
+        assert_eq!(
+            body["pending_quotes"],
+            json!([
+                { "quote_id": "quote-a", "channel_id": 7, "amount": 64 },
+                { "quote_id": "quote-b", "channel_id": 9, "amount": 32 },
+            ])
+        );
+
+        let (status, body) = get(&sources, "/debug/downstream/3/pending-quotes").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["pending_quotes"], json!([]));
+
+        let (status, _) = get(&sources, "/debug/downstream/abc/pending-quotes").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get(&sources, "/debug/unknown").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_stats_reset_routes() {
+        let stats_registry = PoolStatsRegistry::new();
+        let first = stats_registry.register_downstream(1);
+        let second = stats_registry.register_downstream(2);
+        first.record_share_with_difficulty(64.0);
+        second.record_share_with_difficulty(64.0);
+
+        let sources = DumpSources {
+            hub: MintPoolMessageHub::new(MessagingConfig::default()),
+            quote_poller: None,
+            mint_manager: Arc::new(MintIntegrationManager::new("127.0.0.1:34260".to_string())),
+            stats_registry: stats_registry.clone(),
+        };
+
+        let (status, body) = request(&sources, &Method::POST, "/debug/stats/reset/1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["reset"], 1);
+        assert_eq!(stats_registry.snapshot()[&1].shares_submitted, 0);
+        assert_eq!(stats_registry.snapshot()[&2].shares_submitted, 1);
+
+        let (status, _) = request(&sources, &Method::POST, "/debug/stats/reset/3").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = request(&sources, &Method::POST, "/debug/stats/reset/abc").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get(&sources, "/debug/stats/reset").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = request(&sources, &Method::POST, "/debug/stats/reset").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["reset"], "all");
+        assert_eq!(stats_registry.snapshot()[&2].shares_submitted, 0);
+        assert_eq!(stats_registry.snapshot().len(), 2);
+    }
+}
@@ -0,0 +1,159 @@
+//! SQLite persistence for per-identity lifetime totals
+//!
+//! The stats registry only counts since startup, so a restart used to wipe the all-time
+//! shares, ehash and blocks of every miner. With `lifetime_stats_path` configured the
+//! totals are loaded into the registry on startup and written back every
+//! [`FLUSH_INTERVAL`], so at most one interval of counts is lost on a crash.
+//!
+//! Totals are keyed by the user identity the registry counts them under, since downstream
+//! ids restart with the pool.
+
+use std::{collections::HashMap, path::Path, str::FromStr, sync::Arc, time::Duration};
+
+use pool_stats::{LifetimeTotals, PoolStatsRegistry};
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Pool, Row, Sqlite,
+};
+use tracing::warn;
+
+/// How often the registry's lifetime totals are written to disk
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Lifetime totals table in a SQLite database
+pub struct LifetimeStore {
+    pool: Pool<Sqlite>,
+}
+
+impl LifetimeStore {
+    /// Open the database at `path`, creating it and its parent directories if needed
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, sqlx::Error> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path.display()))?
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS lifetime_totals (
+                identity TEXT PRIMARY KEY,
+                shares INTEGER NOT NULL,
+                ehash INTEGER NOT NULL,
+                blocks INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(Self { pool })
+    }
+
+    /// Store `totals`, replacing the stored entries of the same identities
+    pub async fn save(&self, totals: &HashMap<String, LifetimeTotals>) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for (identity, total) in totals {
+            sqlx::query(
+                "INSERT OR REPLACE INTO lifetime_totals (identity, shares, ehash, blocks) \
+                 VALUES (?, ?, ?, ?)",
+            )
+            .bind(identity)
+            .bind(total.shares as i64)
+            .bind(total.ehash as i64)
+            .bind(total.blocks as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    /// Every stored identity's totals
+    pub async fn load(&self) -> Result<HashMap<String, LifetimeTotals>, sqlx::Error> {
+        let rows = sqlx::query("SELECT identity, shares, ehash, blocks FROM lifetime_totals")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| {
+                let totals = LifetimeTotals {
+                    shares: row.try_get::<i64, _>("shares")? as u64,
+                    ehash: row.try_get::<i64, _>("ehash")? as u64,
+                    blocks: row.try_get::<i64, _>("blocks")? as u64,
+                };
+                Ok((row.try_get("identity")?, totals))
+            })
+            .collect()
+    }
+
+    /// Write the registry's lifetime totals every `interval`, forever
+    pub async fn run_flush_loop(self, registry: Arc<PoolStatsRegistry>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = self.save(&registry.lifetime_totals()).await {
+                warn!("Failed to persist lifetime stats: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote_dispatcher::QuoteEventCallback;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[tokio::test]
+    async fn test_totals_reload_and_keep_accumulating_after_restart() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let db_path = std::env::temp_dir().join(format!(
+            "hashpool-lifetime-{}-{}.sqlite",
+            std::process::id(),
+            nanos
+        ));
+
+        // Before the restart
+        let registry = PoolStatsRegistry::new();
+        let stats = registry.register_downstream(3);
+        registry.set_identity(3, "alice");
+        stats.record_share();
+        pool_stats::StatsCallback::new(stats.clone()).on_quote_created(0, 64);
+        stats.record_block_found();
+        LifetimeStore::open(&db_path)
+            .await
+            .unwrap()
+            .save(&registry.lifetime_totals())
+            .await
+            .unwrap();
+        drop(registry);
+
+        // After the restart the same identity keeps counting on top, whatever its new id
+        let store = LifetimeStore::open(&db_path).await.unwrap();
+        let registry = PoolStatsRegistry::new();
+        registry.restore_lifetime_totals(store.load().await.unwrap());
+        registry.register_downstream(1).record_share();
+        registry.set_identity(1, "alice");
+
+        let expected = LifetimeTotals {
+            shares: 2,
+            ehash: 64,
+            blocks: 1,
+        };
+        assert_eq!(registry.lifetime_totals()["alice"], expected);
+        store.save(&registry.lifetime_totals()).await.unwrap();
+        assert_eq!(store.load().await.unwrap()["alice"], expected);
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
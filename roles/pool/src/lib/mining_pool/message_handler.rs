@@ -7,6 +7,7 @@
 
 use super::super::mining_pool::Downstream;
 use super::super::share_validation;
+use super::quote_queue::{QuoteJob, QuoteQueue};
 use binary_sv2::Deserialize;
 use ehash::QuoteDispatchError;
 use mint_quote_sv2::CompressedPubKey;
@@ -16,16 +17,15 @@ use std::{
     sync::{Arc, RwLock},
 };
 use stratum_common::roles_logic_sv2::{
-    bitcoin::{consensus::Decodable, transaction::TxOut, Amount},
+    bitcoin::{consensus::Decodable, transaction::TxOut, Amount, Transaction},
     channels_sv2::server::{
         error::{ExtendedChannelError, StandardChannelError},
         extended::ExtendedChannel,
         group::GroupChannel,
-        jobs::job_store::DefaultJobStore,
         share_accounting::{ShareValidationError, ShareValidationResult},
         standard::StandardChannel,
     },
-    codec_sv2::binary_sv2::Str0255,
+    codec_sv2::binary_sv2::{Str0255, U256},
     errors::Error,
     handlers::mining::{ParseMiningMessagesFromDownstream, SendTo, SupportedChannelTypes},
     mining_sv2::*,
@@ -42,16 +42,18 @@ use tracing::{debug, error, info, warn};
 /// 1. Retrieves or uses the provided locking key for attribution
 /// 2. Validates the key format (must be 33 bytes)
 /// 3. Parses the key as a compressed public key
-/// 4. Submits the quote to the dispatcher
+/// 4. Sends the quote through the dispatcher, waiting until the hub has taken it, with the
+///    amount computed against `minimum_difficulty` if given
 ///
 /// Returns `Ok(())` on success or a `QuoteDispatchError` if any step fails.
-async fn dispatch_quote(
+pub(super) async fn dispatch_quote(
     dispatcher: Arc<QuoteDispatcher>,
     mint_manager: Arc<super::mint_integration::MintIntegrationManager>,
     channel_id: u32,
     sequence_number: u32,
     header_hash: [u8; 32],
     locking_key_hint: Option<Vec<u8>>,
+    minimum_difficulty: Option<u32>,
 ) -> Result<(), QuoteDispatchError> {
     let header_hash_bytes = header_hash;
 
@@ -90,17 +92,34 @@ async fn dispatch_quote(
         }
     })?;
 
-    // Submit quote to dispatcher
-    dispatcher
-        .submit_quote(&header_hash_bytes, pubkey.into_static(), channel_id, sequence_number)
-        .map_err(|e| QuoteDispatchError::QuoteDispatchFailed(e.to_string()))?;
+    // Send the quote, so a worker stays busy until the hub has it
+    let pubkey = pubkey.into_static();
+    let sent = match minimum_difficulty {
+        Some(minimum_difficulty) => {
+            dispatcher
+                .send_quote_with_difficulty(
+                    &header_hash_bytes,
+                    pubkey,
+                    channel_id,
+                    sequence_number,
+                    minimum_difficulty,
+                )
+                .await
+        }
+        None => {
+            dispatcher
+                .send_quote(&header_hash_bytes, pubkey, channel_id, sequence_number)
+                .await
+        }
+    };
+    sent.map_err(|e| QuoteDispatchError::QuoteDispatchFailed(e.to_string()))?;
 
     Ok(())
 }
 
 /// Helper function to send a quote request for an accepted share.
 ///
-/// Spawns an async task to dispatch a quote for an accepted share. The task:
+/// Queues the share on the bounded quote work queue. A worker then:
 /// 1. Dispatches the quote to the mint service if dispatcher is configured
 /// 2. Logs errors that occur (e.g., missing/invalid locking key, dispatch failure)
 /// 3. Does NOT fail the share validation if dispatch fails (non-fatal)
@@ -142,47 +161,14 @@ fn send_share_quote_request(
     header_hash: [u8; 32],
     _m: &SubmitSharesStandard,
 ) {
-    if let Some(dispatcher) = downstream.quote_dispatcher.clone() {
-        let mint_manager = downstream.mint_manager.clone();
-        let locking_key_bytes = downstream.locking_key_bytes.clone();
-
-        tokio::spawn(async move {
-            match dispatch_quote(
-                dispatcher,
-                mint_manager,
-                channel_id,
-                sequence_number,
-                header_hash,
-                locking_key_bytes,
-            )
-            .await
-            {
-                Ok(()) => {
-                    debug!(
-                        "Successfully queued mint quote via dispatcher: channel={}, seq={}",
-                        channel_id, sequence_number
-                    );
-                }
-                Err(e) => {
-                    warn!(
-                        "Quote dispatch error for channel {} (seq {}): {}",
-                        channel_id, sequence_number, e
-                    );
-                }
-            }
-        });
-    } else {
-        debug!(
-            "Quote dispatcher not configured; skipping quote for channel {}",
-            channel_id
-        );
-    }
+    enqueue_quote(downstream, channel_id, sequence_number, header_hash, None);
 }
 
 /// Helper function to send a quote request for an extended share.
 ///
-/// Spawns an async task to dispatch a quote for an extended share. Similar to
-/// `send_share_quote_request` but for extended mining channels.
+/// Queues a quote for an extended share. Similar to `send_share_quote_request`
+/// but for extended mining channels, whose ehash amount is computed against
+/// `quote_min_difficulty_extended` when that is configured.
 ///
 /// Quote dispatch errors are logged but don't affect share validation.
 fn send_extended_share_quote_request(
@@ -192,43 +178,155 @@ fn send_extended_share_quote_request(
     header_hash: [u8; 32],
     _m: &SubmitSharesExtended,
 ) {
-    if let Some(dispatcher) = downstream.quote_dispatcher.clone() {
-        let mint_manager = downstream.mint_manager.clone();
-        let locking_key_bytes = downstream.locking_key_bytes.clone();
-
-        tokio::spawn(async move {
-            match dispatch_quote(
-                dispatcher,
-                mint_manager,
-                channel_id,
-                sequence_number,
-                header_hash,
-                locking_key_bytes,
-            )
-            .await
-            {
-                Ok(()) => {
-                    debug!(
-                        "Successfully queued mint quote via dispatcher (extended): channel={}, seq={}",
-                        channel_id, sequence_number
-                    );
-                }
-                Err(e) => {
-                    warn!(
-                        "Extended quote dispatch error for channel {} (seq {}): {}",
-                        channel_id, sequence_number, e
-                    );
-                }
-            }
+    enqueue_quote(
+        downstream,
+        channel_id,
+        sequence_number,
+        header_hash,
+        downstream.quote_min_difficulty_extended,
+    );
+}
+
+/// Pushes an accepted share onto the bounded quote queue.
+///
+/// If the queue is full the quote is shed and counted as failed in the
+/// downstream's stats rather than spawning an unbounded number of tasks.
+fn enqueue_quote(
+    downstream: &Downstream,
+    channel_id: u32,
+    sequence_number: u32,
+    header_hash: [u8; 32],
+    minimum_difficulty: Option<u32>,
+) {
+    let job = downstream
+        .quote_dispatcher
+        .clone()
+        .map(|dispatcher| QuoteJob {
+            dispatcher,
+            mint_manager: downstream.mint_manager.clone(),
+            stats: downstream.stats_registry.get_stats(downstream.id),
+            channel_id,
+            sequence_number,
+            header_hash,
+            locking_key_hint: downstream.locking_key_bytes.clone(),
+            minimum_difficulty,
         });
-    } else {
+    queue_quote(downstream.quote_queue.as_ref(), job, channel_id);
+}
+
+/// Queues `job`, returning whether it was accepted by the queue.
+///
+/// Without a dispatcher or queue (quoting disabled, or `no_mint` mode) the share stays
+/// accepted and counted; nothing is queued and no quote failure is recorded.
+fn queue_quote(queue: Option<&QuoteQueue>, job: Option<QuoteJob>, channel_id: u32) -> bool {
+    let (Some(queue), Some(job)) = (queue, job) else {
         debug!(
-            "Quote dispatcher not configured; skipping extended quote for channel {}",
+            "Quote dispatcher not configured; skipping quote for channel {}",
             channel_id
         );
+        return false;
+    };
+    queue.enqueue(job)
+}
+
+/// Sends a `ShareSubmitted` stats event for an accepted share, if share events are enabled.
+fn emit_share_event(downstream: &Downstream, channel_id: u32, difficulty: f64) {
+    if let Some(emitter) = downstream.share_events.as_ref() {
+        emitter.emit(downstream.id, channel_id, difficulty);
     }
 }
 
+/// Runs the pool's share hooks for an accepted share in the background.
+///
+/// The downstream's [`share_hooks::HookRegistry`] runs the hooks concurrently, each in its
+/// own task, so a failing or panicking hook is logged and never reaches share validation.
+#[allow(clippy::too_many_arguments)]
+fn run_share_hooks(
+    downstream: &Downstream,
+    channel_id: u32,
+    sequence_number: u32,
+    nonce: u32,
+    header_hash: [u8; 32],
+    difficulty: f64,
+    target: [u8; 32],
+    is_block: bool,
+) {
+    if downstream.share_hooks.is_empty() {
+        return;
+    }
+    let hooks = downstream.share_hooks.clone();
+    let event = share_hooks::ShareAcceptedEvent {
+        sequence_number,
+        channel_id,
+        downstream_id: downstream.id,
+        prev_hash: header_hash.to_vec(),
+        nonce,
+        difficulty,
+        target,
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        is_block,
+        extra: Default::default(),
+    };
+    tokio::spawn(async move {
+        hooks.dispatch(event).await;
+    });
+}
+
+/// Little-endian bytes of a channel target, as carried by [`share_hooks::ShareAcceptedEvent`].
+fn target_bytes(target: &Target) -> [u8; 32] {
+    let target: U256<'static> = target.clone().into();
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(target.inner_as_ref());
+    bytes
+}
+
+/// Runs the pool's block hooks for a found block in the background, like [`run_share_hooks`].
+fn run_block_hooks(
+    downstream: &Downstream,
+    channel_id: u32,
+    header_hash: [u8; 32],
+    coinbase: &[u8],
+) {
+    if downstream.block_hooks.is_empty() {
+        return;
+    }
+    let hooks = downstream.block_hooks.clone();
+    let (height, reward_sats) = coinbase_height_and_reward(coinbase);
+    let event = share_hooks::BlockFoundEvent {
+        block_hash: header_hash.to_vec(),
+        height,
+        channel_id,
+        downstream_id: downstream.id,
+        reward_sats,
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+    tokio::spawn(async move {
+        share_hooks::dispatch_block_found(&hooks, event).await;
+    });
+}
+
+/// Reads the block height (BIP34 push at the start of the coinbase input script) and the
+/// total output value from a serialized coinbase. Either is `None` if it can't be read.
+fn coinbase_height_and_reward(coinbase: &[u8]) -> (Option<u64>, Option<u64>) {
+    let Ok(tx) = Transaction::consensus_decode(&mut &coinbase[..]) else {
+        return (None, None);
+    };
+    let reward = tx.output.iter().map(|out| out.value.to_sat()).sum();
+    let height = tx
+        .input
+        .first()
+        .and_then(|input| input.script_sig.instructions().next())
+        .and_then(|instruction| instruction.ok()?.script_num())
+        .and_then(|height| u64::try_from(height).ok());
+    (height, Some(reward))
+}
+
 /// Helper function to spawn a channel registration task with proper logging.
 ///
 /// Spawns an async task to register a channel with the mint manager. The task:
@@ -318,14 +416,14 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
 
         let pool_coinbase_output = TxOut {
             value: Amount::from_sat(last_future_template.coinbase_tx_value_remaining),
-            script_pubkey: self.coinbase_reward_script.script_pubkey(),
+            script_pubkey: self.coinbase_outputs.current(),
         };
 
         if !self.requires_standard_jobs && self.group_channel.is_none() {
             // we only create one group channel for all standard channels
 
             let group_channel_id = self.channel_id_factory.next();
-            let job_store = DefaultJobStore::new();
+            let job_store = self.new_job_store();
 
             let mut group_channel = GroupChannel::new_for_pool(
                 group_channel_id,
@@ -367,7 +465,7 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
             .to_vec();
 
         let channel_id = self.channel_id_factory.next();
-        let job_store = DefaultJobStore::new();
+        let job_store = self.new_job_store();
         let mut standard_channel = match StandardChannel::new_for_pool(
             channel_id,
             user_identity,
@@ -414,6 +512,9 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
             },
         };
 
+        self.stats_registry
+            .set_identity(self.id, standard_channel.get_user_identity());
+
         let mut messages = vec![];
 
         let group_channel_id = if let Some(group_channel_guard) = &self.group_channel {
@@ -570,7 +671,7 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
         };
 
         let channel_id = self.channel_id_factory.next();
-        let job_store = DefaultJobStore::new();
+        let job_store = self.new_job_store();
         let mut extended_channel = match ExtendedChannel::new_for_pool(
             channel_id,
             user_identity,
@@ -632,6 +733,9 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
             },
         };
 
+        self.stats_registry
+            .set_identity(self.id, extended_channel.get_user_identity());
+
         let mut messages = vec![];
 
         let open_extended_mining_channel_success = OpenExtendedMiningChannelSuccess {
@@ -676,7 +780,7 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
 
             let pool_coinbase_output = TxOut {
                 value: Amount::from_sat(last_future_template.coinbase_tx_value_remaining),
-                script_pubkey: self.coinbase_reward_script.script_pubkey(),
+                script_pubkey: self.coinbase_outputs.current(),
             };
 
             // create a future extended job based on the last future template
@@ -916,11 +1020,16 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
         let res = standard_channel.validate_share(m.clone());
         vardiff.increment_shares_since_last_update();
 
+        let difficulty = target_to_difficulty(standard_channel.get_target().clone());
+        let target = target_bytes(standard_channel.get_target());
+
         // Record share with difficulty for time-series metrics
-        if let Some(stats) = self.stats_registry.get_stats(self.id) {
-            let target = standard_channel.get_target().clone();
-            let difficulty = target_to_difficulty(target.clone());
+        let stats = self.stats_registry.get_stats(self.id);
+        if let Some(stats) = &stats {
             stats.record_share_with_difficulty(difficulty);
+            if res.is_err() {
+                stats.record_rejected_share_with_difficulty(difficulty);
+            }
         }
 
         match res {
@@ -928,6 +1037,9 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
                 let header_hash = accepted_share.header_hash_bytes();
 
                 if let Some(error_response) = validate_minimum_share_difficulty(&header_hash, self.minimum_share_difficulty_bits, channel_id, m.sequence_number) {
+                    if let Some(stats) = &stats {
+                        stats.record_rejected_share_with_difficulty(difficulty);
+                    }
                     return Ok(error_response);
                 }
 
@@ -936,6 +1048,9 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
                     channel_id, m.sequence_number
                 );
                 send_share_quote_request(self, channel_id, m.sequence_number, header_hash, &m);
+                emit_share_event(self, channel_id, difficulty);
+                run_share_hooks(self, channel_id, m.sequence_number, m.nonce, header_hash, difficulty, target, false);
+                self.stats_registry.record_accepted_share();
                 Ok(SendTo::None(None))
             }
             Ok(ShareValidationResult::ValidWithAcknowledgement(
@@ -947,10 +1062,16 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
                 let header_hash = accepted_share.header_hash_bytes();
 
                 if let Some(error_response) = validate_minimum_share_difficulty(&header_hash, self.minimum_share_difficulty_bits, channel_id, m.sequence_number) {
+                    if let Some(stats) = &stats {
+                        stats.record_rejected_share_with_difficulty(difficulty);
+                    }
                     return Ok(error_response);
                 }
 
                 send_share_quote_request(self, channel_id, m.sequence_number, header_hash, &m);
+                emit_share_event(self, channel_id, difficulty);
+                run_share_hooks(self, channel_id, m.sequence_number, m.nonce, header_hash, difficulty, target, false);
+                self.stats_registry.record_accepted_share();
                 let success = SubmitSharesSuccess {
                     channel_id,
                     last_sequence_number,
@@ -964,6 +1085,13 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
                 info!("SubmitSharesStandard: 💰 Block Found!!! 💰");
                 let header_hash = accepted_share.header_hash_bytes();
                 send_share_quote_request(self, channel_id, m.sequence_number, header_hash, &m);
+                emit_share_event(self, channel_id, difficulty);
+                run_share_hooks(self, channel_id, m.sequence_number, m.nonce, header_hash, difficulty, target, true);
+                run_block_hooks(self, channel_id, header_hash, &coinbase);
+                if let Some(stats) = &stats {
+                    stats.record_block_found();
+                }
+                self.stats_registry.record_block_found();
                 // if we have a template id (i.e.: this was not a custom job)
                 // we can propagate the solution to the TP
                 if let Some(template_id) = template_id {
@@ -1101,11 +1229,16 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
         let res = extended_channel.validate_share(m.clone());
         vardiff.increment_shares_since_last_update();
 
+        let difficulty = target_to_difficulty(extended_channel.get_target().clone());
+        let target = target_bytes(extended_channel.get_target());
+
         // Record share with difficulty for time-series metrics
-        if let Some(stats) = self.stats_registry.get_stats(self.id) {
-            let target = extended_channel.get_target().clone();
-            let difficulty = target_to_difficulty(target);
+        let stats = self.stats_registry.get_stats(self.id);
+        if let Some(stats) = &stats {
             stats.record_share_with_difficulty(difficulty);
+            if res.is_err() {
+                stats.record_rejected_share_with_difficulty(difficulty);
+            }
         }
 
         match res {
@@ -1113,6 +1246,9 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
                 let header_hash = accepted_share.header_hash_bytes();
 
                 if let Some(error_response) = validate_minimum_share_difficulty(&header_hash, self.minimum_share_difficulty_bits, channel_id, m.sequence_number) {
+                    if let Some(stats) = &stats {
+                        stats.record_rejected_share_with_difficulty(difficulty);
+                    }
                     return Ok(error_response);
                 }
 
@@ -1127,6 +1263,9 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
                     header_hash,
                     &m,
                 );
+                emit_share_event(self, channel_id, difficulty);
+                run_share_hooks(self, channel_id, m.sequence_number, m.nonce, header_hash, difficulty, target, false);
+                self.stats_registry.record_accepted_share();
                 Ok(SendTo::None(None))
             }
             Ok(ShareValidationResult::ValidWithAcknowledgement(
@@ -1138,6 +1277,9 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
                 let header_hash = accepted_share.header_hash_bytes();
 
                 if let Some(error_response) = validate_minimum_share_difficulty(&header_hash, self.minimum_share_difficulty_bits, channel_id, m.sequence_number) {
+                    if let Some(stats) = &stats {
+                        stats.record_rejected_share_with_difficulty(difficulty);
+                    }
                     return Ok(error_response);
                 }
 
@@ -1148,6 +1290,9 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
                     header_hash,
                     &m,
                 );
+                emit_share_event(self, channel_id, difficulty);
+                run_share_hooks(self, channel_id, m.sequence_number, m.nonce, header_hash, difficulty, target, false);
+                self.stats_registry.record_accepted_share();
                 let success = SubmitSharesSuccess {
                     channel_id,
                     last_sequence_number,
@@ -1167,6 +1312,13 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
                     header_hash,
                     &m,
                 );
+                emit_share_event(self, channel_id, difficulty);
+                run_share_hooks(self, channel_id, m.sequence_number, m.nonce, header_hash, difficulty, target, true);
+                run_block_hooks(self, channel_id, header_hash, &coinbase);
+                if let Some(stats) = &stats {
+                    stats.record_block_found();
+                }
+                self.stats_registry.record_block_found();
                 // if we have a template id (i.e.: this was not a custom job)
                 // we can propagate the solution to the TP
                 if let Some(template_id) = template_id {
@@ -1280,11 +1432,11 @@ impl ParseMiningMessagesFromDownstream<()> for Downstream {
         )
         .map_err(|_| Error::FailedToDeserializeCoinbaseOutputs)?;
 
-        // check that the script_pubkey from self.coinbase_reward_script
-        // is present in the custom job coinbase outputs
-        let missing_script = !custom_job_coinbase_outputs.iter().any(|pool_output| {
-            *pool_output.script_pubkey == *self.coinbase_reward_script.script_pubkey()
-        });
+        // check that a payout script issued by the pool is present in the custom job
+        // coinbase outputs
+        let missing_script = !custom_job_coinbase_outputs
+            .iter()
+            .any(|pool_output| self.coinbase_outputs.recognizes(&pool_output.script_pubkey));
 
         if missing_script {
             error!("SetCustomMiningJobError: pool-payout-script-missing");
@@ -1392,4 +1544,252 @@ mod tests {
         let error = QuoteDispatchError::MissingLockingKey(1);
         let _: &dyn std::error::Error = &error;
     }
+
+    /// Reports the channel of every accepted share it sees
+    struct NotifyingHook(tokio::sync::mpsc::UnboundedSender<u32>);
+
+    #[async_trait::async_trait]
+    impl share_hooks::ShareAcceptanceHook for NotifyingHook {
+        async fn on_share_accepted(
+            &self,
+            event: share_hooks::ShareAcceptedEvent,
+        ) -> Result<(), share_hooks::HookError> {
+            let _ = self.0.send(event.channel_id);
+            Ok(())
+        }
+    }
+
+    const HEADER_TIMESTAMP: u32 = 1_700_000_000;
+
+    /// A downstream as the pool builds it in `no_mint` mode, with neither quote dispatcher
+    /// nor quote queue
+    fn no_mint_downstream(share_hooks: share_hooks::HookRegistry) -> Downstream {
+        use super::super::coinbase_outputs::CoinbaseOutputProvider;
+        use std::collections::HashMap;
+        use stratum_common::roles_logic_sv2::{
+            bitcoin::ScriptBuf,
+            template_distribution_sv2::{NewTemplate, SetNewPrevHash as SetNewPrevHashTdp},
+            utils::Id as IdFactory,
+        };
+
+        let (sender, receiver) = async_channel::unbounded();
+        let (solution_sender, _) = async_channel::bounded(1);
+        let extranonce_factory = || {
+            let factory =
+                ExtendedExtranonce::new(0..0, 0..8, 8..MAX_EXTRANONCE_LEN, Some(vec![0, 1]))
+                    .unwrap();
+            Arc::new(Mutex::new(factory))
+        };
+        let payout = ScriptBuf::new_op_return([0u8; 4]);
+        let stats_registry = pool_stats::PoolStatsRegistry::new();
+        stats_registry.register_downstream(1);
+
+        Downstream {
+            id: 1,
+            address: "127.0.0.1:34255".parse().unwrap(),
+            receiver,
+            sender,
+            requires_standard_jobs: true,
+            requires_custom_work: false,
+            solution_sender,
+            quote_dispatcher: None,
+            quote_queue: None,
+            share_events: None,
+            share_hooks,
+            block_hooks: Vec::new(),
+            mint_manager: Arc::new(super::super::mint_integration::MintIntegrationManager::new(
+                "127.0.0.1:34260".to_string(),
+            )),
+            stats_registry,
+            locking_key_bytes: None,
+            channel_id_factory: IdFactory::new(),
+            extranonce_prefix_factory_extended: extranonce_factory(),
+            extranonce_prefix_factory_standard: extranonce_factory(),
+            extended_channels: HashMap::new(),
+            standard_channels: HashMap::new(),
+            vardiff: HashMap::new(),
+            group_channel: None,
+            share_batch_size: 1,
+            // 600 shares a minute from a 1 H/s channel puts its target at the maximum, so
+            // every share is accepted
+            shares_per_minute: 600.0,
+            last_future_template: NewTemplate {
+                template_id: 1,
+                future_template: true,
+                version: 0x2000_0000,
+                coinbase_tx_version: 2,
+                coinbase_prefix: vec![0x51].try_into().unwrap(),
+                coinbase_tx_input_sequence: u32::MAX,
+                coinbase_tx_value_remaining: 5_000_000_000,
+                coinbase_tx_outputs_count: 0,
+                coinbase_tx_outputs: vec![].try_into().unwrap(),
+                coinbase_tx_locktime: 0,
+                merkle_path: vec![].try_into().unwrap(),
+            },
+            last_new_prev_hash: SetNewPrevHashTdp {
+                template_id: 1,
+                prev_hash: [0u8; 32].into(),
+                header_timestamp: HEADER_TIMESTAMP,
+                n_bits: 0x1d00_ffff,
+                target: [0u8; 32].into(),
+            },
+            coinbase_outputs: Arc::new(CoinbaseOutputProvider::fixed(payout)),
+            pool_tag_string: "hashpool".to_string(),
+            minimum_share_difficulty_bits: None,
+            quote_min_difficulty_extended: None,
+            stale_job_grace: None,
+            min_downstream_hashrate: None,
+        }
+    }
+
+    /// Opens a standard channel on `downstream` and submits one share on it, asserting it is
+    /// accepted. Returns the channel id.
+    fn submit_first_share(downstream: &mut Downstream) -> u32 {
+        let opened = downstream
+            .handle_open_standard_mining_channel(OpenStandardMiningChannel {
+                request_id: 1.into(),
+                user_identity: "miner".to_string().try_into().unwrap(),
+                nominal_hash_rate: 1.0,
+                max_target: vec![0xff_u8; 32].try_into().unwrap(),
+            })
+            .unwrap();
+        let SendTo::Multiple(messages) = opened else {
+            panic!("expected channel setup messages");
+        };
+        let (channel_id, job_id, version) = messages
+            .iter()
+            .find_map(|message| match message {
+                SendTo::Respond(Mining::NewMiningJob(job)) => {
+                    Some((job.channel_id, job.job_id, job.version))
+                }
+                _ => None,
+            })
+            .expect("channel opens with a job");
+
+        let response = downstream
+            .handle_submit_shares_standard(SubmitSharesStandard {
+                channel_id,
+                sequence_number: 1,
+                job_id,
+                nonce: 0,
+                ntime: HEADER_TIMESTAMP,
+                version,
+            })
+            .unwrap();
+        assert!(matches!(
+            response,
+            SendTo::Respond(Mining::SubmitSharesSuccess(_))
+        ));
+        channel_id
+    }
+
+    #[tokio::test]
+    async fn test_no_mint_records_shares_without_quotes() {
+        use std::sync::atomic::Ordering;
+
+        let (hook_tx, mut hook_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut hooks = share_hooks::HookRegistry::new();
+        hooks.register(Arc::new(NotifyingHook(hook_tx)));
+        let mut downstream = no_mint_downstream(hooks);
+
+        let channel_id = submit_first_share(&mut downstream);
+
+        // The share is counted and hooks run, but no quote is attempted or failed
+        let stats = downstream.stats_registry.get_stats(1).unwrap();
+        assert_eq!(stats.shares_submitted.load(Ordering::Relaxed), 1);
+        assert!(stats.sum_difficulty_in_window() > 0.0);
+        assert_eq!(stats.quotes_created.load(Ordering::Relaxed), 0);
+        assert_eq!(stats.quotes_failed.load(Ordering::Relaxed), 0);
+        assert_eq!(hook_rx.recv().await, Some(channel_id));
+    }
+
+    /// Never finishes; reports through its sender once the pool gives up on it
+    struct HangingHook(tokio::sync::mpsc::UnboundedSender<()>);
+
+    /// Sends on drop, i.e. when the hook future holding it is abandoned
+    struct DropNotifier(tokio::sync::mpsc::UnboundedSender<()>);
+
+    impl Drop for DropNotifier {
+        fn drop(&mut self) {
+            let _ = self.0.send(());
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl share_hooks::ShareAcceptanceHook for HangingHook {
+        async fn on_share_accepted(
+            &self,
+            _event: share_hooks::ShareAcceptedEvent,
+        ) -> Result<(), share_hooks::HookError> {
+            let _abandoned = DropNotifier(self.0.clone());
+            std::future::pending().await
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_hanging_share_hook_times_out_without_holding_up_others() {
+        let timeout = std::time::Duration::from_millis(100);
+        let (hook_tx, mut hook_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (abandoned_tx, mut abandoned_rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut hooks = share_hooks::HookRegistry::new().with_timeout(timeout);
+        hooks.register(Arc::new(HangingHook(abandoned_tx)));
+        hooks.register(Arc::new(NotifyingHook(hook_tx)));
+        let mut downstream = no_mint_downstream(hooks);
+
+        let start = tokio::time::Instant::now();
+        let channel_id = submit_first_share(&mut downstream);
+
+        // The share is answered and the other hook runs without waiting on the hanging one
+        assert_eq!(hook_rx.recv().await, Some(channel_id));
+        assert!(start.elapsed() < timeout);
+
+        // The hanging hook is abandoned once the configured timeout expires
+        assert_eq!(abandoned_rx.recv().await, Some(()));
+        assert_eq!(start.elapsed(), timeout);
+    }
+
+    #[test]
+    fn test_coinbase_height_and_reward() {
+        use stratum_common::roles_logic_sv2::bitcoin::{
+            absolute::LockTime, consensus::serialize, transaction::Version, OutPoint, ScriptBuf,
+            Sequence, TxIn, Witness,
+        };
+
+        let coinbase = |script_sig: Vec<u8>| {
+            serialize(&Transaction {
+                version: Version::TWO,
+                lock_time: LockTime::ZERO,
+                input: vec![TxIn {
+                    previous_output: OutPoint::null(),
+                    script_sig: ScriptBuf::from_bytes(script_sig),
+                    sequence: Sequence::MAX,
+                    witness: Witness::new(),
+                }],
+                output: vec![
+                    TxOut {
+                        value: Amount::from_sat(312_000_000),
+                        script_pubkey: ScriptBuf::new(),
+                    },
+                    TxOut {
+                        value: Amount::from_sat(500_000),
+                        script_pubkey: ScriptBuf::new(),
+                    },
+                ],
+            })
+        };
+
+        // Height 840000 pushed as 3 little-endian bytes, followed by the pool tag
+        let mainnet = coinbase(vec![0x03, 0x40, 0xd1, 0x0c, 0x04, b'h', b'a', b's', b'h']);
+        assert_eq!(
+            coinbase_height_and_reward(&mainnet),
+            (Some(840_000), Some(312_500_000))
+        );
+        // Regtest heights up to 16 are encoded as OP_1..OP_16
+        let regtest = coinbase(vec![0x5a, 0x00]);
+        assert_eq!(
+            coinbase_height_and_reward(&regtest),
+            (Some(10), Some(312_500_000))
+        );
+        assert_eq!(coinbase_height_and_reward(&[0xff, 0x00]), (None, None));
+    }
 }
@@ -11,16 +11,19 @@
 
 use async_channel::{Receiver, Sender};
 use binary_sv2::from_bytes;
-use const_sv2::{MESSAGE_TYPE_MINT_QUOTE_ERROR, MESSAGE_TYPE_MINT_QUOTE_RESPONSE};
+use const_sv2::{
+    MESSAGE_TYPE_MINT_PONG, MESSAGE_TYPE_MINT_QUOTE_ERROR, MESSAGE_TYPE_MINT_QUOTE_RESPONSE,
+};
 use hex;
 use mint_pool_messaging::{
-    quote_request_frame_bytes, MintPoolMessageHub, MintQuoteError, MintQuoteResponse,
-    ParsedMintQuoteRequest, Role,
+    decode_heartbeat_nonce, ping_frame_bytes, quote_request_frame_bytes, MessagingError,
+    MintPoolMessageHub, MintQuoteError, MintQuoteResponse, ParsedMintQuoteRequest, Role,
 };
 use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tokio::{
     net::{TcpListener, TcpStream},
-    sync::{watch, RwLock},
+    sync::{broadcast, watch, RwLock},
+    time::{Instant, Interval, MissedTickBehavior},
 };
 use tracing::{debug, error, info, warn};
 
@@ -40,6 +43,9 @@ pub type MintMessage = AnyMessage<'static>;
 /// Frame type for mint/pool communication
 pub type MintFrame = StandardEitherFrame<MintMessage>;
 
+/// How long a connection past its lifetime keeps reading answers to requests already sent
+const LIFETIME_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Manages the connection to the mint service
 pub struct MintConnection {
     /// Remote address of the mint service
@@ -54,6 +60,12 @@ pub struct MintConnection {
     sender: Arc<RwLock<Option<Sender<MintFrame>>>>,
     /// Connection state
     is_connected: Arc<RwLock<bool>>,
+    /// Close the connection after this long without a frame from the mint
+    idle_timeout: Option<Duration>,
+    /// Ping the mint every half of this and drop the connection if a pong takes longer
+    heartbeat_timeout: Option<Duration>,
+    /// Recycle the connection once it has been open this long
+    max_lifetime: Option<Duration>,
 }
 
 impl MintConnection {
@@ -74,6 +86,9 @@ impl MintConnection {
             cert_validity_duration: Duration::from_secs(3600), // 1 hour default
             sender: Arc::new(RwLock::new(None)),
             is_connected: Arc::new(RwLock::new(false)),
+            idle_timeout: None,
+            heartbeat_timeout: None,
+            max_lifetime: None,
         }
     }
 
@@ -91,9 +106,34 @@ impl MintConnection {
             cert_validity_duration,
             sender: Arc::new(RwLock::new(None)),
             is_connected: Arc::new(RwLock::new(false)),
+            idle_timeout: None,
+            heartbeat_timeout: None,
+            max_lifetime: None,
         }
     }
 
+    /// Close connections that stay silent for longer than `idle_timeout`
+    pub fn with_idle_timeout(mut self, idle_timeout: Option<Duration>) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Ping the mint every `heartbeat_timeout / 2` and close the connection when a ping goes
+    /// unanswered for `heartbeat_timeout`
+    pub fn with_heartbeat(mut self, heartbeat_timeout: Option<Duration>) -> Self {
+        self.heartbeat_timeout = heartbeat_timeout;
+        self
+    }
+
+    /// Close connections once they have been open for `max_lifetime`, so the mint reconnects
+    /// with a fresh Noise session. Answers to requests already forwarded are still read for a
+    /// short grace period before the socket is dropped, and requests made meanwhile wait for
+    /// the next connection.
+    pub fn with_max_lifetime(mut self, max_lifetime: Option<Duration>) -> Self {
+        self.max_lifetime = max_lifetime;
+        self
+    }
+
     /// Get the sender for the encrypted connection (once established)
     pub fn get_sender(&self) -> Arc<RwLock<Option<Sender<MintFrame>>>> {
         self.sender.clone()
@@ -126,12 +166,19 @@ impl MintConnection {
         let listener = TcpListener::bind(self.address).await?;
         info!("📡 Listening for mint service on {}", self.address);
 
+        // One subscription outlives each connection, so quote requests made while a
+        // connection drains or the mint reconnects are forwarded by the next one
+        let mut quote_requests = Some(hub.subscribe_quote_requests().await?);
+
         loop {
             match listener.accept().await {
                 Ok((stream, peer_addr)) => {
                     info!("✓ Accepted connection from mint service: {}", peer_addr);
 
-                    match self.perform_handshake(stream, hub.clone(), peer_addr).await {
+                    match self
+                        .perform_handshake(stream, hub.clone(), peer_addr, &mut quote_requests)
+                        .await
+                    {
                         Ok(()) => {
                             info!(
                                 "Mint connection with {} closed; awaiting next attempt",
@@ -157,11 +204,15 @@ impl MintConnection {
     /// This establishes the Noise-encrypted channel and performs
     /// the SV2 SetupConnection/SetupConnectionSuccess exchange.
     /// Returns the sender for sending frames through the encrypted connection.
+    ///
+    /// Quote requests are forwarded from `quote_requests`, which is handed back once the
+    /// connection stops forwarding.
     async fn perform_handshake(
         &self,
         stream: TcpStream,
         hub: Arc<MintPoolMessageHub>,
         peer_addr: SocketAddr,
+        quote_requests: &mut Option<broadcast::Receiver<ParsedMintQuoteRequest>>,
     ) -> Result<(), String> {
         debug!("Starting Noise handshake as responder...");
 
@@ -236,27 +287,66 @@ impl MintConnection {
         *self.is_connected.write().await = true;
 
         let connection_id = format!("mint-{}", peer_addr);
-        hub.register_connection(connection_id.clone(), Role::Pool)
+        hub.register_connection(connection_id.clone(), Role::Mint)
             .await;
 
+        // A forwarder that panicked did not hand its subscription back
+        let requests = match quote_requests.take() {
+            Some(requests) => requests,
+            None => hub
+                .subscribe_quote_requests()
+                .await
+                .map_err(|e| format!("Unable to subscribe to hub quote requests: {e}"))?,
+        };
         let sender_arc = self.sender.clone();
-        let hub_for_requests = hub.clone();
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
-        let forward_connection_id = connection_id.clone();
-        let forward_handle = tokio::spawn(async move {
-            forward_hub_requests_to_mint(
-                hub_for_requests,
-                sender_arc,
-                forward_connection_id,
-                shutdown_rx,
-            )
-            .await;
+        let forward_handle = tokio::spawn(forward_hub_requests_to_mint(
+            requests,
+            sender_arc,
+            connection_id.clone(),
+            shutdown_rx,
+        ));
+
+        let heartbeat = self.heartbeat_timeout.map(|timeout| Heartbeat {
+            sender: sender.clone(),
+            timeout,
         });
-
-        let processing_result = process_mint_frames(receiver, hub.clone()).await;
+        let close_at = self.max_lifetime.map(|lifetime| Instant::now() + lifetime);
+        let processing_result = process_mint_frames(
+            receiver.clone(),
+            hub.clone(),
+            &connection_id,
+            self.idle_timeout,
+            heartbeat,
+            close_at,
+        )
+        .await;
 
         let _ = shutdown_tx.send(true);
-        let _ = forward_handle.await;
+        *quote_requests = forward_handle.await.ok();
+
+        // No new requests go out once the forwarder has stopped; read the answers still owed
+        let processing_result = match processing_result {
+            Ok(ConnectionEnd::LifetimeReached) => {
+                info!(
+                    "Mint connection {} reached its maximum lifetime; draining before recycling",
+                    connection_id
+                );
+                // The hub stops counting this connection as a live mint while it drains
+                hub.unregister_connection(&connection_id).await;
+                process_mint_frames(
+                    receiver,
+                    hub.clone(),
+                    &connection_id,
+                    None,
+                    None,
+                    Some(Instant::now() + LIFETIME_DRAIN_TIMEOUT),
+                )
+                .await
+                .map(|_| ())
+            }
+            other => other.map(|_| ()),
+        };
 
         hub.unregister_connection(&connection_id).await;
         {
@@ -273,52 +363,54 @@ impl MintConnection {
     }
 }
 
+/// Forward quote requests from `rx` to the mint until shutdown is signalled or the mint
+/// sender goes away, then hand `rx` back for the next connection.
 async fn forward_hub_requests_to_mint(
-    hub: Arc<MintPoolMessageHub>,
+    mut rx: broadcast::Receiver<ParsedMintQuoteRequest>,
     sender_arc: Arc<RwLock<Option<Sender<MintFrame>>>>,
     connection_id: String,
     mut shutdown_rx: watch::Receiver<bool>,
-) {
-    match hub.subscribe_quote_requests().await {
-        Ok(mut rx) => loop {
-            tokio::select! {
-                changed = shutdown_rx.changed() => {
-                    if changed.is_err() || *shutdown_rx.borrow() {
-                        debug!("Stopping quote forwarder for {} (shutdown signalled)", connection_id);
-                        break;
-                    }
+) -> broadcast::Receiver<ParsedMintQuoteRequest> {
+    loop {
+        tokio::select! {
+            changed = shutdown_rx.changed() => {
+                if changed.is_err() || *shutdown_rx.borrow() {
+                    debug!("Stopping quote forwarder for {} (shutdown signalled)", connection_id);
+                    break;
                 }
-                result = rx.recv() => {
-                    match result {
-                        Ok(parsed_request) => {
-                            if let Err(e) = send_quote_request_to_mint(&sender_arc, &parsed_request).await {
-                                error!(
-                                    "Failed to forward quote request via mint connection {}: {}",
-                                    connection_id, e
-                                );
-                                if e.contains("unavailable") {
-                                    break;
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            debug!(
-                                "Quote forwarder for {} exiting: broadcast receiver error: {}",
+            }
+            result = rx.recv() => {
+                match result {
+                    Ok(parsed_request) => {
+                        if let Err(e) = send_quote_request_to_mint(&sender_arc, &parsed_request).await {
+                            error!(
+                                "Failed to forward quote request via mint connection {}: {}",
                                 connection_id, e
                             );
-                            break;
+                            if e.contains("unavailable") {
+                                break;
+                            }
                         }
                     }
+                    // Requests queued while no connection was up can overflow the buffer
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "Quote forwarder for {} skipped {} requests that overflowed the buffer",
+                            connection_id, skipped
+                        );
+                    }
+                    Err(e) => {
+                        debug!(
+                            "Quote forwarder for {} exiting: broadcast receiver error: {}",
+                            connection_id, e
+                        );
+                        break;
+                    }
                 }
             }
-        },
-        Err(e) => {
-            error!(
-                "Unable to subscribe to hub quote requests for mint bridge {}: {}",
-                connection_id, e
-            );
         }
     }
+    rx
 }
 
 async fn send_quote_request_to_mint(
@@ -352,12 +444,93 @@ async fn send_quote_request_to_mint(
         .map_err(|e| format!("failed to send quote request: {}", e))
 }
 
+/// Pings sent to the mint while reading its frames
+struct Heartbeat {
+    sender: Sender<MintFrame>,
+    timeout: Duration,
+}
+
+/// Why [`process_mint_frames`] stopped reading without an error
+#[derive(Debug, PartialEq, Eq)]
+enum ConnectionEnd {
+    /// The mint closed the connection
+    Closed,
+    /// `close_at` passed while the connection was still open
+    LifetimeReached,
+}
+
+/// Read frames from the mint until the connection closes. With an `idle_timeout`, a mint that
+/// keeps the socket open but sends nothing for that long is treated as gone and an error is
+/// returned, so the connection is dropped and the listener waits for the mint to reconnect.
+/// With a `heartbeat` the same happens when no frame, pong or otherwise, arrives within the
+/// timeout of a ping.
+/// Reading also stops at `close_at`, which is not an error.
 async fn process_mint_frames(
     receiver: Receiver<MintFrame>,
     hub: Arc<MintPoolMessageHub>,
-) -> Result<(), String> {
+    connection_id: &str,
+    idle_timeout: Option<Duration>,
+    heartbeat: Option<Heartbeat>,
+    close_at: Option<Instant>,
+) -> Result<ConnectionEnd, String> {
     let rx = receiver;
-    while let Ok(frame) = rx.recv().await {
+    let mut last_frame = Instant::now();
+    let mut ping_ticker = heartbeat.as_ref().map(|heartbeat| {
+        let period = (heartbeat.timeout / 2).max(Duration::from_millis(1));
+        let mut ticker = tokio::time::interval_at(Instant::now() + period, period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker
+    });
+    let mut next_nonce: u64 = 0;
+    // Nonce and deadline of the ping still waiting for its pong
+    let mut pending_ping: Option<(u64, Instant)> = None;
+
+    loop {
+        let idle_deadline = idle_timeout.map(|idle| last_frame + idle);
+        let next = tokio::select! {
+            next = rx.recv() => next,
+            _ = sleep_until_deadline(close_at) => {
+                return Ok(ConnectionEnd::LifetimeReached);
+            }
+            _ = sleep_until_deadline(idle_deadline) => {
+                return Err(format!(
+                    "no frames from {} for {}ms; closing idle connection",
+                    connection_id,
+                    idle_timeout.unwrap_or_default().as_millis()
+                ));
+            }
+            _ = sleep_until_deadline(pending_ping.map(|(_, deadline)| deadline)) => {
+                return Err(format!(
+                    "no pong from {} within {}ms; closing connection",
+                    connection_id,
+                    heartbeat.as_ref().map(|h| h.timeout).unwrap_or_default().as_millis()
+                ));
+            }
+            _ = tick(&mut ping_ticker) => {
+                if let (Some(heartbeat), None) = (&heartbeat, pending_ping) {
+                    let frame_bytes = ping_frame_bytes(next_nonce)
+                        .map_err(|e| format!("failed to build ping frame: {e}"))?;
+                    let frame =
+                        MintFrame::Sv2(StandardSv2Frame::from_bytes_unchecked(frame_bytes.into()));
+                    heartbeat
+                        .sender
+                        .send(frame)
+                        .await
+                        .map_err(|e| format!("failed to send ping to {}: {}", connection_id, e))?;
+                    pending_ping = Some((next_nonce, Instant::now() + heartbeat.timeout));
+                    next_nonce = next_nonce.wrapping_add(1);
+                }
+                continue;
+            }
+        };
+        let Ok(frame) = next else {
+            break;
+        };
+        last_frame = Instant::now();
+        // Any frame shows the mint is alive, so a busy mint whose pong is queued behind
+        // other frames is not dropped
+        pending_ping = None;
+        hub.record_heartbeat(connection_id).await;
         match frame {
             MintFrame::Sv2(mut sv2_frame) => {
                 let header = sv2_frame
@@ -369,9 +542,15 @@ async fn process_mint_frames(
                 match msg_type {
                     MESSAGE_TYPE_MINT_QUOTE_RESPONSE => {
                         let response = decode_mint_quote_response(&mut payload)?;
-                        hub.send_quote_response(response)
-                            .await
-                            .map_err(|e| format!("failed to dispatch quote response: {:?}", e))?;
+                        match hub.send_quote_response(response).await {
+                            // Already logged by the hub; one bad response shouldn't drop the mint
+                            Ok(_)
+                            | Err(MessagingError::OrphanResponse(_))
+                            | Err(MessagingError::StaleResponse { .. }) => {}
+                            Err(e) => {
+                                return Err(format!("failed to dispatch quote response: {:?}", e))
+                            }
+                        }
                     }
                     MESSAGE_TYPE_MINT_QUOTE_ERROR => {
                         let error_msg = decode_mint_quote_error(&mut payload)?;
@@ -379,6 +558,11 @@ async fn process_mint_frames(
                             .await
                             .map_err(|e| format!("failed to dispatch quote error: {:?}", e))?;
                     }
+                    MESSAGE_TYPE_MINT_PONG => {
+                        let nonce = decode_heartbeat_nonce(&payload)
+                            .map_err(|e| format!("failed to decode pong: {e}"))?;
+                        debug!("Received pong with nonce {} from {}", nonce, connection_id);
+                    }
                     other => {
                         debug!("Ignoring mint frame with msg_type=0x{:02x}", other);
                     }
@@ -390,7 +574,25 @@ async fn process_mint_frames(
         }
     }
 
-    Ok(())
+    Ok(ConnectionEnd::Closed)
+}
+
+/// Sleep until `deadline`, or forever without one
+async fn sleep_until_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Wait for the next tick of `ticker`, or forever without one
+async fn tick(ticker: &mut Option<Interval>) {
+    match ticker {
+        Some(ticker) => {
+            ticker.tick().await;
+        }
+        None => std::future::pending().await,
+    }
 }
 
 fn decode_mint_quote_response(payload: &mut [u8]) -> Result<MintQuoteResponse<'static>, String> {
@@ -418,6 +620,196 @@ mod tests {
         assert_eq!(conn.address(), addr);
     }
 
+    #[tokio::test]
+    async fn test_idle_connection_is_closed() {
+        let hub = MintPoolMessageHub::new(mint_pool_messaging::MessagingConfig::default());
+        hub.register_connection("mint-test".to_string(), Role::Mint)
+            .await;
+        // Keep the sender alive so the channel stays open but silent
+        let (_sender, receiver) = async_channel::unbounded::<MintFrame>();
+
+        let result = process_mint_frames(
+            receiver,
+            hub.clone(),
+            "mint-test",
+            Some(Duration::from_millis(50)),
+            None,
+            None,
+        )
+        .await;
+
+        let err = result.unwrap_err();
+        assert!(err.contains("idle"));
+    }
+
+    #[tokio::test]
+    async fn test_closed_connection_without_idle_timeout() {
+        let hub = MintPoolMessageHub::new(mint_pool_messaging::MessagingConfig::default());
+        let (sender, receiver) = async_channel::unbounded::<MintFrame>();
+        drop(sender);
+
+        assert_eq!(
+            process_mint_frames(receiver, hub, "mint-test", None, None, None).await,
+            Ok(ConnectionEnd::Closed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connection_past_lifetime_is_recycled() {
+        let hub = MintPoolMessageHub::new(mint_pool_messaging::MessagingConfig::default());
+        let (sender, receiver) = async_channel::unbounded::<MintFrame>();
+        let close_at = Instant::now() + Duration::from_millis(50);
+
+        // A pong arriving before the deadline is still read
+        let pong = mint_pool_messaging::pong_frame_bytes(7).unwrap();
+        sender
+            .send(MintFrame::Sv2(StandardSv2Frame::from_bytes_unchecked(
+                pong.into(),
+            )))
+            .await
+            .unwrap();
+
+        let result =
+            process_mint_frames(receiver, hub, "mint-test", None, None, Some(close_at)).await;
+
+        assert_eq!(result, Ok(ConnectionEnd::LifetimeReached));
+        assert!(Instant::now() >= close_at);
+        assert!(sender.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_ping_closes_connection() {
+        let hub = MintPoolMessageHub::new(mint_pool_messaging::MessagingConfig::default());
+        let (_sender, receiver) = async_channel::unbounded::<MintFrame>();
+        let (ping_tx, ping_rx) = async_channel::unbounded::<MintFrame>();
+        let heartbeat = Heartbeat {
+            sender: ping_tx,
+            timeout: Duration::from_millis(40),
+        };
+
+        let result =
+            process_mint_frames(receiver, hub, "mint-test", None, Some(heartbeat), None).await;
+
+        assert!(result.unwrap_err().contains("pong"));
+        assert_eq!(ping_rx.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_answered_pings_keep_connection_open() {
+        let hub = MintPoolMessageHub::new(mint_pool_messaging::MessagingConfig::default());
+        let (sender, receiver) = async_channel::unbounded::<MintFrame>();
+        let (ping_tx, ping_rx) = async_channel::unbounded::<MintFrame>();
+        let heartbeat = Heartbeat {
+            sender: ping_tx,
+            timeout: Duration::from_millis(40),
+        };
+
+        // Answer a few pings like the mint does, then hang up
+        tokio::spawn(async move {
+            for _ in 0..5 {
+                let Ok(MintFrame::Sv2(mut ping)) = ping_rx.recv().await else {
+                    panic!("expected a ping frame");
+                };
+                let nonce = decode_heartbeat_nonce(ping.payload()).unwrap();
+                let pong = mint_pool_messaging::pong_frame_bytes(nonce).unwrap();
+                sender
+                    .send(MintFrame::Sv2(StandardSv2Frame::from_bytes_unchecked(
+                        pong.into(),
+                    )))
+                    .await
+                    .unwrap();
+            }
+        });
+
+        assert!(
+            process_mint_frames(receiver, hub, "mint-test", None, Some(heartbeat), None)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_other_frames_answer_pending_ping() {
+        let hub = MintPoolMessageHub::new(mint_pool_messaging::MessagingConfig::default());
+        let (sender, receiver) = async_channel::unbounded::<MintFrame>();
+        let (ping_tx, _ping_rx) = async_channel::unbounded::<MintFrame>();
+        let heartbeat = Heartbeat {
+            sender: ping_tx,
+            timeout: Duration::from_millis(40),
+        };
+
+        // A mint busy with other traffic never gets to the pong, then hangs up
+        tokio::spawn(async move {
+            for nonce in 0..20 {
+                let frame = mint_pool_messaging::ping_frame_bytes(nonce).unwrap();
+                sender
+                    .send(MintFrame::Sv2(StandardSv2Frame::from_bytes_unchecked(
+                        frame.into(),
+                    )))
+                    .await
+                    .unwrap();
+                tokio::time::sleep(Duration::from_millis(15)).await;
+            }
+        });
+
+        assert_eq!(
+            process_mint_frames(receiver, hub, "mint-test", None, Some(heartbeat), None).await,
+            Ok(ConnectionEnd::Closed)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_requests_during_reconnect_reach_next_connection() {
+        let hub = MintPoolMessageHub::new(mint_pool_messaging::MessagingConfig::default());
+        let requests = hub.subscribe_quote_requests().await.unwrap();
+
+        // The first connection stops forwarding and hands the subscription back
+        let (first_tx, _first_rx) = async_channel::unbounded::<MintFrame>();
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let forwarder = tokio::spawn(forward_hub_requests_to_mint(
+            requests,
+            Arc::new(RwLock::new(Some(first_tx))),
+            "mint-first".to_string(),
+            shutdown_rx,
+        ));
+        shutdown_tx.send(true).unwrap();
+        let requests = forwarder.await.unwrap();
+
+        // A quote requested before the mint reconnects
+        let mut encoded = [0u8; 34];
+        encoded[0] = 33;
+        encoded[1] = 0x02;
+        let locking_key = mint_pool_messaging::CompressedPubKey::from_bytes(&mut encoded[..])
+            .unwrap()
+            .into_static();
+        let parsed =
+            mint_pool_messaging::build_parsed_quote_request(16, &[0x5A; 32], locking_key).unwrap();
+        hub.send_quote_request(
+            parsed,
+            mint_pool_messaging::PendingQuoteContext {
+                channel_id: 1,
+                sequence_number: 1,
+                amount: 16,
+            },
+        )
+        .await
+        .unwrap();
+
+        let (second_tx, second_rx) = async_channel::unbounded::<MintFrame>();
+        let (_shutdown_tx, shutdown_rx) = watch::channel(false);
+        tokio::spawn(forward_hub_requests_to_mint(
+            requests,
+            Arc::new(RwLock::new(Some(second_tx))),
+            "mint-second".to_string(),
+            shutdown_rx,
+        ));
+
+        let forwarded = tokio::time::timeout(Duration::from_secs(5), second_rx.recv())
+            .await
+            .expect("queued request was not forwarded");
+        assert!(forwarded.is_ok());
+    }
+
     #[tokio::test]
     async fn test_mint_connection_not_connected_initially() {
         let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 34260);
@@ -5,10 +5,18 @@
 //! - TCP connection management
 //! - Quote request dispatching
 //! - Quote response handling
+//! - Routing mint quote errors back to the downstream that requested the quote
+//! - Counting quotes evicted by the hub's pending limit as failed for their downstream
 
+use mint_pool_messaging::{MintPoolMessageHub, MintQuoteErrorEvent, MintQuoteEvictedEvent};
+use pool_stats::{PoolStatsRegistry, StatsCallback};
+use quote_dispatcher::QuoteEventCallback;
 use std::{collections::HashMap, sync::Arc};
-use tokio::sync::RwLock;
-use tracing::debug;
+use tokio::{
+    sync::RwLock,
+    time::{sleep, Duration},
+};
+use tracing::{debug, error, warn};
 
 /// Context information for a mining channel
 #[derive(Debug, Clone)]
@@ -73,10 +81,171 @@ impl MintIntegrationManager {
         self.channel_contexts.read().await.get(&channel_id).cloned()
     }
 
+    /// Channel ids currently registered for a downstream
+    pub async fn channels_for_downstream(&self, downstream_id: u32) -> Vec<u32> {
+        self.channel_contexts
+            .read()
+            .await
+            .values()
+            .filter(|ctx| ctx.downstream_id == downstream_id)
+            .map(|ctx| ctx.channel_id)
+            .collect()
+    }
+
+    /// Contexts of all registered channels
+    pub async fn channel_contexts(&self) -> Vec<ChannelContext> {
+        self.channel_contexts
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Channel a paid quote should be delivered on.
+    ///
+    /// Channel ids are reused, e.g. after a pool restart, so a quote carrying its locking
+    /// key is only delivered to a channel holding that key: its own channel if that still
+    /// does, otherwise the newest channel opened with the key. `None` if no open channel
+    /// holds it.
+    pub async fn resolve_quote_channel(
+        &self,
+        channel_id: u32,
+        locking_key: Option<&[u8]>,
+    ) -> Option<u32> {
+        let Some(locking_key) = locking_key else {
+            return Some(channel_id);
+        };
+        let contexts = self.channel_contexts.read().await;
+        let holds_key =
+            |ctx: &ChannelContext| ctx.locking_key_bytes.as_deref() == Some(locking_key);
+        if contexts.get(&channel_id).is_some_and(holds_key) {
+            return Some(channel_id);
+        }
+        contexts
+            .values()
+            .filter(|ctx| holds_key(ctx))
+            .max_by_key(|ctx| ctx.created_at)
+            .map(|ctx| ctx.channel_id)
+    }
+
     /// Get mint address
     pub fn mint_address(&self) -> &str {
         &self.mint_address
     }
+
+    /// Follow the hub's quote errors, counting each as a failed quote for the downstream whose
+    /// channel requested it. Runs for the lifetime of the pool.
+    pub async fn handle_quote_errors(
+        self: Arc<Self>,
+        hub: Arc<MintPoolMessageHub>,
+        stats_registry: Arc<PoolStatsRegistry>,
+    ) {
+        loop {
+            match hub.subscribe_quote_errors().await {
+                Ok(mut rx) => {
+                    while let Ok(event) = rx.recv().await {
+                        self.record_quote_error(&event, &stats_registry).await;
+                    }
+                    warn!("Quote error subscription ended; attempting to resubscribe");
+                }
+                Err(e) => {
+                    error!("Failed to subscribe to hub quote errors: {}", e);
+                }
+            }
+
+            sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    /// Attribute one quote error to its downstream, returning the downstream id if the
+    /// originating channel is still registered
+    pub async fn record_quote_error(
+        &self,
+        event: &MintQuoteErrorEvent,
+        stats_registry: &PoolStatsRegistry,
+    ) -> Option<u32> {
+        let message =
+            std::str::from_utf8(event.error().error_message.inner_as_ref()).unwrap_or("invalid");
+        let Some(pending) = event.context() else {
+            warn!(
+                "Mint quote error for unknown share {}: {}",
+                event.share_hash, message
+            );
+            return None;
+        };
+        let Some(channel) = self.get_channel_context(pending.channel_id).await else {
+            warn!(
+                "Mint quote error for closed channel {} (share {}): {}",
+                pending.channel_id, event.share_hash, message
+            );
+            return None;
+        };
+
+        warn!(
+            "Mint rejected quote for channel {} (downstream {}, sequence {}): {}",
+            channel.channel_id, channel.downstream_id, pending.sequence_number, message
+        );
+        if let Some(stats) = stats_registry.get_stats(channel.downstream_id) {
+            stats.record_quote_failed();
+        }
+        Some(channel.downstream_id)
+    }
+
+    /// Follow the pending quotes the hub evicts under its `max_pending_quotes` limit,
+    /// reporting each as a failed quote to its downstream. Runs for the lifetime of the pool.
+    pub async fn handle_quote_evictions(
+        self: Arc<Self>,
+        hub: Arc<MintPoolMessageHub>,
+        stats_registry: Arc<PoolStatsRegistry>,
+    ) {
+        loop {
+            match hub.subscribe_quote_evictions().await {
+                Ok(mut rx) => {
+                    while let Ok(event) = rx.recv().await {
+                        self.record_quote_eviction(&event, &stats_registry).await;
+                    }
+                    warn!("Quote eviction subscription ended; attempting to resubscribe");
+                }
+                Err(e) => {
+                    error!("Failed to subscribe to hub quote evictions: {}", e);
+                }
+            }
+
+            sleep(Duration::from_secs(1)).await;
+        }
+    }
+
+    /// Report one evicted quote as failed to its downstream's stats, returning the downstream
+    /// id if the originating channel is still registered
+    pub async fn record_quote_eviction(
+        &self,
+        event: &MintQuoteEvictedEvent,
+        stats_registry: &PoolStatsRegistry,
+    ) -> Option<u32> {
+        self.record_quote_failure(
+            event.context.channel_id,
+            event.context.amount,
+            "evicted from pending quotes",
+            stats_registry,
+        )
+        .await
+    }
+
+    /// Report a quote that will never be paid out as failed to its downstream's stats,
+    /// returning the downstream id if `channel_id` is still registered
+    pub async fn record_quote_failure(
+        &self,
+        channel_id: u32,
+        amount: u64,
+        reason: &str,
+        stats_registry: &PoolStatsRegistry,
+    ) -> Option<u32> {
+        let channel = self.get_channel_context(channel_id).await?;
+        let stats = stats_registry.get_stats(channel.downstream_id)?;
+        StatsCallback::new(stats).on_quote_failed(channel.channel_id, amount, reason);
+        Some(channel.downstream_id)
+    }
 }
 
 #[cfg(test)]
@@ -104,4 +273,118 @@ mod tests {
         let context = manager.get_channel_context(1).await;
         assert!(context.is_none());
     }
+
+    #[tokio::test]
+    async fn test_channels_for_downstream() {
+        let manager = MintIntegrationManager::new("127.0.0.1:34260".to_string());
+
+        manager.register_channel(1, None, 100).await;
+        manager.register_channel(2, None, 100).await;
+        manager.register_channel(3, None, 200).await;
+
+        let mut channels = manager.channels_for_downstream(100).await;
+        channels.sort();
+        assert_eq!(channels, vec![1, 2]);
+        assert!(manager.channels_for_downstream(300).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_quote_channel_matches_locking_key() {
+        let manager = MintIntegrationManager::new("127.0.0.1:34260".to_string());
+        let (ours, theirs) = (vec![0x02; 33], vec![0x03; 33]);
+
+        // Channel 1 was reused by another miner; ours reconnected on channel 4
+        manager.register_channel(1, Some(theirs.clone()), 10).await;
+        manager.register_channel(4, Some(ours.clone()), 11).await;
+
+        assert_eq!(manager.resolve_quote_channel(1, Some(&ours)).await, Some(4));
+        assert_eq!(
+            manager.resolve_quote_channel(1, Some(&theirs)).await,
+            Some(1)
+        );
+        assert_eq!(manager.resolve_quote_channel(1, None).await, Some(1));
+
+        manager.unregister_channel(4).await;
+        assert_eq!(manager.resolve_quote_channel(1, Some(&ours)).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_quote_error_is_routed_to_requesting_downstream() {
+        use mint_pool_messaging::{
+            build_parsed_quote_request, CompressedPubKey, MessagingConfig, MintQuoteError,
+            PendingQuoteContext,
+        };
+        use std::sync::atomic::Ordering;
+        use stratum_common::roles_logic_sv2::codec_sv2::binary_sv2::Str0255;
+
+        let hub = MintPoolMessageHub::new(MessagingConfig::default());
+        let manager = MintIntegrationManager::new("127.0.0.1:34260".to_string());
+        let stats_registry = PoolStatsRegistry::new();
+        let stats = stats_registry.register_downstream(77);
+        manager.register_channel(5, Some(vec![0x02; 33]), 77).await;
+
+        let mut encoded = [0u8; 34];
+        encoded[0] = 33;
+        encoded[1] = 0x02;
+        let locking_key = CompressedPubKey::from_bytes(&mut encoded[..])
+            .unwrap()
+            .into_static();
+        let parsed = build_parsed_quote_request(16, &[0x5A; 32], locking_key).unwrap();
+        let context = PendingQuoteContext {
+            channel_id: 5,
+            sequence_number: 3,
+            amount: 16,
+        };
+        hub.send_quote_request(parsed.clone(), context)
+            .await
+            .unwrap();
+
+        let event = hub
+            .send_quote_error(MintQuoteError {
+                error_code: 1,
+                error_message: Str0255::try_from("below minimum".to_string()).unwrap(),
+                header_hash: parsed.share_hash.into_u256().unwrap(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            manager.record_quote_error(&event, &stats_registry).await,
+            Some(77)
+        );
+        assert_eq!(stats.quotes_failed.load(Ordering::Relaxed), 1);
+
+        // Once the channel is gone the error can no longer be attributed
+        manager.unregister_channel(5).await;
+        assert_eq!(
+            manager.record_quote_error(&event, &stats_registry).await,
+            None
+        );
+        assert_eq!(stats.quotes_failed.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_evicted_quote_is_reported_as_failed() {
+        use mint_pool_messaging::{PendingQuoteContext, ShareHash};
+        use std::sync::atomic::Ordering;
+
+        let manager = MintIntegrationManager::new("127.0.0.1:34260".to_string());
+        let stats_registry = PoolStatsRegistry::new();
+        let stats = stats_registry.register_downstream(12);
+        manager.register_channel(4, None, 12).await;
+
+        let event = MintQuoteEvictedEvent {
+            share_hash: ShareHash::from([0x44; 32]),
+            context: PendingQuoteContext {
+                channel_id: 4,
+                sequence_number: 1,
+                amount: 8,
+            },
+        };
+        assert_eq!(
+            manager.record_quote_eviction(&event, &stats_registry).await,
+            Some(12)
+        );
+        assert_eq!(stats.quotes_failed.load(Ordering::Relaxed), 1);
+    }
 }
@@ -0,0 +1,175 @@
+//! Denominations of the mint's active ehash keyset
+//!
+//! Quote amounts are rounded to sums of the keyset's denominations so the mint can issue
+//! them. The pool reads the active keyset from the mint's NUT-01 keys endpoint once at
+//! startup, retrying until the mint answers; until then the dispatcher assumes the HASH
+//! keyset's powers of two.
+
+use std::{collections::HashMap, sync::Arc};
+
+use quote_dispatcher::QuoteDispatcher;
+use reqwest::Url;
+use serde::Deserialize;
+use stratum_common::network_helpers_sv2::retry::{retry_with_backoff, RetryPolicy};
+use tokio::time::Duration;
+use tracing::{info, warn};
+
+/// Keys path relative to the mint HTTP endpoint
+pub const KEYS_PATH: &str = "v1/keys";
+/// Currency unit of the mint's ehash keyset
+const EHASH_UNIT: &str = "hash";
+/// Backoff while the mint is unreachable or has no ehash keyset yet
+const KEYS_RETRY: RetryPolicy = RetryPolicy {
+    max_attempts: None,
+    base_delay: Duration::from_secs(1),
+    max_delay: Duration::from_secs(60),
+    jitter: 0.2,
+};
+
+/// Body of the mint's keys response
+#[derive(Debug, Deserialize)]
+struct KeysResponse {
+    keysets: Vec<KeySet>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeySet {
+    unit: String,
+    /// Public keys by amount, with amounts as decimal strings
+    keys: HashMap<String, String>,
+}
+
+/// Amounts of the active ehash keysets in a keys response, smallest first
+fn ehash_denominations(response: &KeysResponse) -> Vec<u64> {
+    let mut amounts: Vec<u64> = response
+        .keysets
+        .iter()
+        .filter(|keyset| keyset.unit.eq_ignore_ascii_case(EHASH_UNIT))
+        .flat_map(|keyset| keyset.keys.keys())
+        .filter_map(|amount| amount.parse().ok())
+        .collect();
+    amounts.sort_unstable();
+    amounts.dedup();
+    amounts
+}
+
+/// Ask the mint at `base_url` for the denominations of its active ehash keyset
+pub async fn fetch_ehash_denominations(
+    client: &reqwest::Client,
+    base_url: &Url,
+) -> Result<Vec<u64>, String> {
+    // Without a trailing slash `join` would replace the last segment of a prefixed base
+    let mut base_url = base_url.clone();
+    if !base_url.path().ends_with('/') {
+        base_url.set_path(&format!("{}/", base_url.path()));
+    }
+    let endpoint = base_url.join(KEYS_PATH).map_err(|e| e.to_string())?;
+    let response = client
+        .get(endpoint)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?;
+    let keys = response
+        .json::<KeysResponse>()
+        .await
+        .map_err(|e| e.to_string())?;
+    let denominations = ehash_denominations(&keys);
+    if denominations.is_empty() {
+        return Err("mint has no active ehash keyset".to_string());
+    }
+    Ok(denominations)
+}
+
+/// Fetch the mint's ehash denominations in the background and round `dispatcher`'s quote
+/// amounts to them once they arrive
+pub fn spawn_denomination_sync(dispatcher: Arc<QuoteDispatcher>, mint_http_url: &str) {
+    let base_url = match Url::parse(mint_http_url) {
+        Ok(url) => url,
+        Err(e) => {
+            warn!(
+                "Invalid mint HTTP endpoint {}, quote amounts keep the default denominations: {}",
+                mint_http_url, e
+            );
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+        let fetched = retry_with_backoff(&KEYS_RETRY, || async {
+            fetch_ehash_denominations(&client, &base_url)
+                .await
+                .inspect_err(|e| warn!("Failed to fetch mint keyset denominations: {}", e))
+        })
+        .await;
+        if let Ok(denominations) = fetched {
+            info!(
+                "Rounding quote amounts to {} mint keyset denominations",
+                denominations.len()
+            );
+            dispatcher.set_denominations(denominations);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Mint stand-in serving `body` for the keys endpoint and 404 for anything else
+    async fn spawn_keys_mint(body: &'static str) -> Url {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let response = if request.starts_with("GET /mint/v1/keys ") {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                } else {
+                    "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                        .to_string()
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        Url::parse(&format!("http://{}/mint", addr)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fetch_reads_only_the_ehash_keyset() {
+        let base_url = spawn_keys_mint(
+            r#"{"keysets":[
+                {"id":"00aa","unit":"sat","keys":{"1":"02aa","3":"02bb"}},
+                {"id":"00bb","unit":"HASH","keys":{"64":"02cc","1":"02dd","1024":"02ee"}}
+            ]}"#,
+        )
+        .await;
+
+        let denominations = fetch_ehash_denominations(&reqwest::Client::new(), &base_url)
+            .await
+            .unwrap();
+
+        assert_eq!(denominations, vec![1, 64, 1024]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_fails_without_an_ehash_keyset() {
+        let base_url =
+            spawn_keys_mint(r#"{"keysets":[{"id":"00aa","unit":"sat","keys":{"1":"02aa"}}]}"#)
+                .await;
+
+        let result = fetch_ehash_denominations(&reqwest::Client::new(), &base_url).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -25,7 +25,6 @@ use super::{
 };
 use crate::config::PoolConfig;
 use async_channel::{Receiver, Sender};
-use config_helpers_sv2::CoinbaseRewardScript;
 use error_handling::handle_result;
 use hex;
 use key_utils::SignatureService;
@@ -35,6 +34,7 @@ use pool_stats;
 use quote_dispatcher::QuoteDispatcher;
 use secp256k1;
 use share_hooks;
+use stats::{ConnectionCounters, RejectReason};
 use std::{
     collections::HashMap,
     convert::TryInto,
@@ -50,7 +50,7 @@ use stratum_common::{
         channels_sv2::server::{
             extended::ExtendedChannel,
             group::GroupChannel,
-            jobs::{extended::ExtendedJob, job_store::DefaultJobStore, standard::StandardJob},
+            jobs::{extended::ExtendedJob, job_store::DefaultJobStore, standard::StandardJob, Job},
             standard::StandardChannel,
         },
         codec_sv2::{
@@ -87,12 +87,31 @@ pub mod mint_integration;
 // Module for mint service connection management (Noise handshake)
 pub mod mint_connection;
 
+// Module for reading the mint's keyset denominations
+pub mod mint_keys;
+
 // Module for periodic quote polling and notification delivery
 pub mod quote_poller;
+pub mod quote_store;
+
+// Module for persisting per-downstream lifetime totals across restarts
+pub mod lifetime_store;
 
 // Module for quote dispatch hook implementation
 pub mod quote_dispatch_hook;
 
+// Module for the bounded queue feeding accepted shares to quote dispatch
+pub mod quote_queue;
+
+// Module for optional per-share events sent to the stats service
+pub mod share_events;
+
+// Module for selecting and rotating the coinbase payout script
+pub mod coinbase_outputs;
+
+// Module for the opt-in diagnostic dump endpoint
+pub mod debug_dump;
+
 /// Represents a generic SV2 message with a static lifetime.
 pub type Message = AnyMessage<'static>;
 /// A standard SV2 frame containing a message.
@@ -125,6 +144,14 @@ pub struct Downstream {
     solution_sender: Sender<SubmitSolution<'static>>,
     // Quote dispatcher handle for routing accepted shares to the mint messaging hub
     quote_dispatcher: Option<Arc<QuoteDispatcher>>,
+    // Bounded queue shared with the pool; accepted shares are pushed here for dispatch
+    quote_queue: Option<quote_queue::QuoteQueue>,
+    // Per-share stats events, present only when `emit_share_events` is enabled
+    share_events: Option<share_events::ShareEventEmitter>,
+    // Hooks run for every accepted share, copied from the pool
+    share_hooks: share_hooks::HookRegistry,
+    // Hooks run for every found block, copied from the pool
+    block_hooks: Vec<Arc<dyn share_hooks::BlockFoundHook>>,
     // Reference to the mint integration manager for channel registration/tracking
     mint_manager: Arc<mint_integration::MintIntegrationManager>,
     // Registry for tracking downstream statistics (shares, quotes, ehash, last_share)
@@ -152,11 +179,15 @@ pub struct Downstream {
     shares_per_minute: f32,
     last_future_template: NewTemplate<'static>,
     last_new_prev_hash: SetNewPrevHashTdp<'static>,
-    coinbase_reward_script: CoinbaseRewardScript,
+    coinbase_outputs: Arc<coinbase_outputs::CoinbaseOutputProvider>,
     // string to be written into the coinbase scriptSig on non-JD jobs
     pool_tag_string: String,
     // Minimum share difficulty (leading zero bits) filter
     minimum_share_difficulty_bits: Option<u32>,
+    // Ehash minimum difficulty for extended channel quotes (dispatcher default if unset)
+    quote_min_difficulty_extended: Option<u32>,
+    // Superseded jobs that still accept shares (all jobs on the chain tip if unset)
+    stale_job_grace: Option<usize>,
     // Optional minimum downstream hashrate policy (in H/s) for channel creation
     min_downstream_hashrate: Option<f32>,
 }
@@ -187,9 +218,15 @@ pub struct Pool {
     solution_sender: Sender<SubmitSolution<'static>>,
     // Quote dispatcher handle for routing accepted shares to the mint messaging hub
     quote_dispatcher: Option<Arc<QuoteDispatcher>>,
+    // Bounded work queue drained by quote dispatch workers
+    quote_queue: Option<quote_queue::QuoteQueue>,
+    // Emitter for per-share stats events, if enabled
+    share_events: Option<share_events::ShareEventEmitter>,
     // Hooks that are called when shares are accepted
     // Non-fatal - errors in hooks don't fail share validation
-    pub share_hooks: Vec<Arc<dyn share_hooks::ShareAcceptanceHook>>,
+    pub share_hooks: share_hooks::HookRegistry,
+    // Hooks that are called when an accepted share solves a block
+    pub block_hooks: Vec<Arc<dyn share_hooks::BlockFoundHook>>,
     // Flag indicating whether at least one `NewTemplate` has been received and processed.
     // Might be used to ensure initial jobs are sent before accepting solutions??.
     new_template_processed: bool,
@@ -203,20 +240,30 @@ pub struct Pool {
     last_new_prev_hash: Option<SetNewPrevHashTdp<'static>>,
     // string to be written into the coinbase scriptSig on non-JD jobs
     pool_tag_string: String,
+    // Source of the coinbase payout script, rotated once per block
+    coinbase_outputs: Arc<coinbase_outputs::CoinbaseOutputProvider>,
     // Manager for mint service integration (channel tracking, quote dispatching)
     pub mint_manager: Arc<mint_integration::MintIntegrationManager>,
     pub mint_hub: Arc<MintPoolMessageHub>,
     // Connection to mint service for Noise-encrypted communication
     // Phase 2: Manages the TCP/Noise connection with the mint service
     pub mint_connection: Option<Arc<tokio::sync::Mutex<mint_connection::MintConnection>>>,
+    // Tracks quotes awaiting payment; set when a mint HTTP endpoint is configured
+    pub quote_poller: Option<Arc<quote_poller::QuotePoller>>,
     // Miner's compressed public key for quote attribution (33 bytes as Vec<u8>)
     locking_key_bytes: Option<Vec<u8>>,
     // Optional JD-Server address for stats reporting
     pub jd_server_address: Option<String>,
     // Registry for tracking downstream statistics (shares, quotes, ehash, last_share)
     pub stats_registry: Arc<pool_stats::PoolStatsRegistry>,
+    // Accepted/rejected downstream connections, by reject reason
+    pub connections: Arc<ConnectionCounters>,
     // Minimum share difficulty (leading zero bits) filter
     pub minimum_share_difficulty_bits: Option<u32>,
+    // Ehash minimum difficulty for extended channel quotes (dispatcher default if unset)
+    pub quote_min_difficulty_extended: Option<u32>,
+    // Superseded jobs that still accept shares (all jobs on the chain tip if unset)
+    pub stale_job_grace: Option<usize>,
     // Optional minimum downstream hashrate policy (in H/s) for channel creation
     pub min_downstream_hashrate: Option<f32>,
 }
@@ -238,7 +285,6 @@ impl Downstream {
         status_tx: status::Sender,
         address: SocketAddr,
         shares_per_minute: f32,
-        coinbase_reward_script: CoinbaseRewardScript,
         locking_key_bytes: Option<Vec<u8>>,
     ) -> PoolResult<Arc<Mutex<Self>>> {
         // Handle the SV2 SetupConnection message exchange.
@@ -285,9 +331,16 @@ impl Downstream {
         })?;
 
         let pool_tag = pool.safe_lock(|p| p.pool_tag_string.clone())?;
+        let coinbase_outputs = pool.safe_lock(|p| p.coinbase_outputs.clone())?;
         let mint_manager = pool.safe_lock(|p| p.mint_manager.clone())?;
+        let quote_queue = pool.safe_lock(|p| p.quote_queue.clone())?;
+        let share_events = pool.safe_lock(|p| p.share_events.clone())?;
+        let share_hooks = pool.safe_lock(|p| p.share_hooks.clone())?;
+        let block_hooks = pool.safe_lock(|p| p.block_hooks.clone())?;
         let stats_registry = pool.safe_lock(|p| p.stats_registry.clone())?;
         let minimum_share_difficulty_bits = pool.safe_lock(|p| p.minimum_share_difficulty_bits)?;
+        let quote_min_difficulty_extended = pool.safe_lock(|p| p.quote_min_difficulty_extended)?;
+        let stale_job_grace = pool.safe_lock(|p| p.stale_job_grace)?;
         let min_downstream_hashrate = pool.safe_lock(|p| p.min_downstream_hashrate)?;
 
         // Register downstream with stats and create per-downstream quote dispatcher with callback
@@ -321,6 +374,10 @@ impl Downstream {
             requires_custom_work,
             solution_sender,
             quote_dispatcher,
+            quote_queue,
+            share_events,
+            share_hooks,
+            block_hooks,
             mint_manager,
             stats_registry,
             channel_id_factory,
@@ -334,10 +391,12 @@ impl Downstream {
             shares_per_minute,
             last_future_template,
             last_new_prev_hash,
-            coinbase_reward_script,
+            coinbase_outputs,
             pool_tag_string: pool_tag,
             locking_key_bytes,
             minimum_share_difficulty_bits,
+            quote_min_difficulty_extended,
+            stale_job_grace,
             min_downstream_hashrate,
         }));
 
@@ -493,6 +552,14 @@ impl Downstream {
         sender.send(sv2_frame.into()).await?;
         Ok(())
     }
+
+    /// Job store for a new channel, honoring the configured stale job grace.
+    fn new_job_store<T: Job + Clone>(&self) -> DefaultJobStore<T> {
+        match self.stale_job_grace {
+            Some(grace) => DefaultJobStore::with_stale_job_grace(grace),
+            None => DefaultJobStore::new(),
+        }
+    }
 }
 
 // Verifies token for a custom job which is the signed tx_hash_list_hash by Job Declarator Server
@@ -528,6 +595,7 @@ impl Pool {
         shares_per_minute: f32,
     ) -> PoolResult<()> {
         let status_tx = self_.safe_lock(|s| s.status_tx.clone())?;
+        let connections = self_.safe_lock(|s| s.connections.clone())?;
         // Bind the TCP listener to the address specified in the config.
         let listener = TcpListener::bind(&config.listen_address()).await?;
         info!("Pool is running on: {}", config.listen_address());
@@ -556,17 +624,22 @@ impl Pool {
                                 match responder {
                                     Ok(resp) => {
                                         if let Ok((receiver, sender)) = Connection::new::<Message>(stream, HandshakeRole::Responder(resp)).await {
-                                            handle_result!(
-                                                status_tx,
-                                                Self::accept_incoming_connection_(
-                                                    self_.clone(),
-                                                    receiver,
-                                                    sender,
-                                                    address,
-                                                    shares_per_minute,
-                                                    config.coinbase_reward_script().clone()
-                                                ).await
-                                            );
+                                            let result = Self::accept_incoming_connection_(
+                                                self_.clone(),
+                                                receiver,
+                                                sender,
+                                                address,
+                                                shares_per_minute,
+                                            ).await;
+                                            if result.is_ok() {
+                                                connections.record_accepted();
+                                            } else {
+                                                connections.record_rejected(RejectReason::Setup);
+                                            }
+                                            handle_result!(status_tx, result);
+                                        } else {
+                                            let failures = connections.record_rejected(RejectReason::Handshake);
+                                            warn!("Noise handshake with {} failed ({} handshake failures so far)", address, failures);
                                         }
                                     }
                                     Err(_) => {
@@ -596,7 +669,6 @@ impl Pool {
         sender: Sender<EitherFrame>,
         address: SocketAddr,
         shares_per_minute: f32,
-        coinbase_reward_script: CoinbaseRewardScript,
     ) -> PoolResult<()> {
         let solution_sender = self_.safe_lock(|p| p.solution_sender.clone())?;
         let status_tx = self_.safe_lock(|s| s.status_tx.clone())?;
@@ -612,7 +684,6 @@ impl Pool {
             status_tx.listener_to_connection(),
             address,
             shares_per_minute,
-            coinbase_reward_script,
             locking_key_bytes,
         )
         .await?;
@@ -781,12 +852,20 @@ impl Pool {
         sender_message_received_signal: Sender<()>,
     ) -> PoolResult<()> {
         let status_tx = self_.safe_lock(|s| s.status_tx.clone())?;
+        let coinbase_outputs = self_.safe_lock(|s| s.coinbase_outputs.clone())?;
         while let Ok(new_template) = rx.recv().await {
             info!(
                 "New template received, creating a new mining job(s): {}",
                 new_template
             );
 
+            // A future template starts the next block, which is where the payout script rotates
+            let coinbase_script = if new_template.future_template {
+                coinbase_outputs.advance()
+            } else {
+                coinbase_outputs.current()
+            };
+
             let downstreams = self_
                 .safe_lock(|s| s.downstreams.clone())
                 .map_err(|e| PoolError::PoisonLock(e.to_string()));
@@ -804,7 +883,7 @@ impl Pool {
 
                     let pool_coinbase_output = TxOut {
                         value: Amount::from_sat(new_template.coinbase_tx_value_remaining),
-                        script_pubkey: d.coinbase_reward_script.script_pubkey(),
+                        script_pubkey: coinbase_script.clone(),
                     };
 
                     match new_template.future_template {
@@ -897,7 +976,7 @@ impl Pool {
 
                     let pool_coinbase_output = TxOut {
                         value: Amount::from_sat(new_template.coinbase_tx_value_remaining),
-                        script_pubkey: d.coinbase_reward_script.script_pubkey(),
+                        script_pubkey: coinbase_script.clone(),
                     };
 
                     let mut messages = Vec::new();
@@ -1084,6 +1163,7 @@ impl Pool {
         solution_sender: Sender<SubmitSolution<'static>>,
         quote_dispatcher: Option<Arc<QuoteDispatcher>>,
         mint_hub: Arc<MintPoolMessageHub>,
+        coinbase_outputs: Arc<coinbase_outputs::CoinbaseOutputProvider>,
         sender_message_received_signal: Sender<()>,
         status_tx: status::Sender,
         shares_per_minute: f32,
@@ -1127,12 +1207,9 @@ impl Pool {
 
         // --- Initialize Pool State ---
         // Initialize mint integration manager using shared configuration when available
-        let mint_listen_addr_str = config
-            .sv2_messaging()
-            .map(|cfg| cfg.mint_listen_address.clone())
-            .unwrap_or_else(|| "127.0.0.1:34260".to_string());
+        let mint_addr = config.mint_listen_socket_addr()?;
         let mint_manager = Arc::new(mint_integration::MintIntegrationManager::new(
-            mint_listen_addr_str.clone(),
+            config.mint_listen_address().to_string(),
         ));
 
         // Convert locking_pubkey from hex string to Vec<u8>
@@ -1171,15 +1248,48 @@ impl Pool {
             } else {
                 info!("✅ Loaded locking_pubkey successfully: 33 bytes (compressed pubkey)");
             }
-        } else {
+        } else if !config.no_mint() {
             warn!("⚠️  locking_pubkey is NOT set in config - quotes will NOT be attributed to miners!");
         }
 
+        // Quote workers are only needed when there is a dispatcher to feed
+        let quote_queue = quote_dispatcher.as_ref().map(|_| {
+            quote_queue::QuoteQueue::start(
+                config
+                    .quote_queue_capacity()
+                    .unwrap_or(quote_queue::QUOTE_QUEUE_CAPACITY),
+                config
+                    .quote_queue_workers()
+                    .unwrap_or(quote_queue::QUOTE_QUEUE_WORKERS),
+            )
+        });
+
+        let share_events = match (config.emit_share_events(), config.stats_server_address()) {
+            (true, Some(stats_addr)) => Some(share_events::ShareEventEmitter::start(
+                stats_addr.to_string(),
+                share_events::SHARE_EVENT_CAPACITY,
+            )),
+            (true, None) => {
+                warn!("emit_share_events is set but stats_server_address is not; ignoring");
+                None
+            }
+            (false, _) => None,
+        };
+
+        let share_hooks = share_hooks::HookRegistry::new().with_timeout(
+            config
+                .share_hook_timeout()
+                .unwrap_or(share_hooks::DEFAULT_HOOK_TIMEOUT),
+        );
+
         let pool = Arc::new(Mutex::new(Pool {
             downstreams: HashMap::with_hasher(BuildNoHashHasher::default()),
             solution_sender,
             quote_dispatcher,
-            share_hooks: Vec::new(),
+            quote_queue,
+            share_events,
+            share_hooks,
+            block_hooks: Vec::new(),
             new_template_processed: false,
             downstream_id_factory: IdFactory::new(),
             status_tx: status_tx.clone(),
@@ -1193,13 +1303,18 @@ impl Pool {
             last_future_template: None,
             last_new_prev_hash: None,
             pool_tag_string: config.pool_signature().clone(),
+            coinbase_outputs,
             mint_manager,
             mint_hub: mint_hub.clone(),
             mint_connection: None, // Phase 2: Will be established when mint service connects
+            quote_poller: None,
             locking_key_bytes,
             jd_server_address: config.jd_server_address().map(|s| s.to_string()),
             stats_registry: pool_stats::PoolStatsRegistry::new(),
+            connections: Arc::new(ConnectionCounters::new()),
             minimum_share_difficulty_bits: config.minimum_share_difficulty_bits(),
+            quote_min_difficulty_extended: config.quote_min_difficulty_extended(),
+            stale_job_grace: config.stale_job_grace(),
             min_downstream_hashrate: config.min_downstream_hashrate(),
         }));
 
@@ -1211,45 +1326,97 @@ impl Pool {
         // Phase 2: Spawn mint connection listener task
         // This listens for incoming connections from the mint service on port 34260
         // Pass the pool's configured authority keys so the responder can sign with the correct key
-        let authority_secret_key = config.authority_secret_key().clone();
-        let authority_public_key = config.authority_public_key().clone();
-        let cert_validity_sec = config.cert_validity_sec();
-
-        let mint_addr: std::net::SocketAddr = mint_listen_addr_str
-            .parse()
-            .expect("Failed to parse mint listen address");
-        let mint_connection_arc = Arc::new(tokio::sync::Mutex::new(
-            mint_connection::MintConnection::with_keys(
-                mint_addr,
-                authority_secret_key,
-                authority_public_key,
-                std::time::Duration::from_secs(cert_validity_sec),
-            ),
-        ));
+        // Without a mint there is nothing to listen for
+        if config.no_mint() {
+            info!("Skipping mint listener startup (no_mint is set)");
+        } else {
+            let authority_secret_key = config.authority_secret_key().clone();
+            let authority_public_key = config.authority_public_key().clone();
+            let cert_validity_sec = config.cert_validity_sec();
+
+            let mint_connection_arc = Arc::new(tokio::sync::Mutex::new(
+                mint_connection::MintConnection::with_keys(
+                    mint_addr,
+                    authority_secret_key,
+                    authority_public_key,
+                    std::time::Duration::from_secs(cert_validity_sec),
+                )
+                .with_idle_timeout(config.mint_idle_timeout())
+                .with_heartbeat(config.mint_heartbeat_timeout())
+                .with_max_lifetime(config.mint_max_connection_lifetime()),
+            ));
 
-        pool.safe_lock(|p| {
-            p.mint_connection = Some(mint_connection_arc.clone());
-        })?;
+            pool.safe_lock(|p| {
+                p.mint_connection = Some(mint_connection_arc.clone());
+            })?;
 
-        let hub_for_conn = mint_hub.clone();
-        task::spawn(async move {
-            let mut guard = mint_connection_arc.lock().await;
+            let hub_for_conn = mint_hub.clone();
+            task::spawn(async move {
+                let mut guard = mint_connection_arc.lock().await;
 
-            if let Err(e) = guard.establish_connection(hub_for_conn.clone()).await {
-                error!("Failed to establish mint connection: {}", e);
-                return;
-            }
+                if let Err(e) = guard.establish_connection(hub_for_conn.clone()).await {
+                    error!("Failed to establish mint connection: {}", e);
+                    return;
+                }
 
-            info!("✅ Mint connection established");
-        });
+                info!("✅ Mint connection established");
+            });
+
+            // Mint errors for a share are counted against the downstream that submitted it
+            let (error_manager, error_stats) =
+                pool.safe_lock(|p| (p.mint_manager.clone(), p.stats_registry.clone()))?;
+            task::spawn(
+                error_manager
+                    .clone()
+                    .handle_quote_errors(mint_hub.clone(), error_stats.clone()),
+            );
+            // So are quotes the hub evicts to stay under its pending limit
+            task::spawn(error_manager.handle_quote_evictions(mint_hub.clone(), error_stats));
+            // And quotes the mint never answers, reported through the same error channel
+            if let Some(max_age) = config.pending_quote_max_age() {
+                mint_hub.clone().spawn_pending_reaper(max_age);
+            }
+        }
 
         // Phase 3: Spawn quote poller task for periodic polling of mint's paid quotes
         // The quote poller will poll the mint HTTP API every 5 seconds for newly paid quotes
         // and send MintQuoteNotification extension messages to the respective translators
-        if let Some(http_url) = config.mint_http_url().map(|s| s.to_string()) {
-            let quote_poller = Arc::new(quote_poller::QuotePoller::new(Some(http_url.clone())));
+        if config.no_mint() {
+            info!("Skipping quote poller startup (no_mint is set)");
+        } else if let Some(http_url) = config.mint_http_url().map(|s| s.to_string()) {
+            let mut quote_poller = quote_poller::QuotePoller::new(Some(http_url.clone()));
+            if let Some(secs) = config.quote_poll_timeout_secs() {
+                quote_poller = quote_poller.with_request_timeout(Duration::from_secs(secs));
+            }
+            if let Some(limit) = config.quote_poll_concurrency() {
+                quote_poller = quote_poller.with_max_concurrent_polls(limit);
+            }
+            if let Some(path) = config.quote_status_path() {
+                quote_poller = quote_poller.with_quote_status_path(path);
+            }
+            if let Some(attempts) = config.quote_notify_attempts() {
+                quote_poller = quote_poller.with_max_notify_attempts(attempts);
+            }
+            if let Some(limit) = config.max_pending_quotes() {
+                quote_poller = quote_poller.with_max_pending_quotes(limit);
+            }
+            if let Some(path) = config.quote_store_path() {
+                let store = quote_store::QuoteStore::open(path).await.map_err(|e| {
+                    PoolError::Custom(format!("Failed to open quote store {}: {}", path, e))
+                })?;
+                quote_poller = quote_poller.with_store(store);
+                match quote_poller.restore_pending_quotes().await {
+                    Ok(0) => {}
+                    Ok(restored) => info!("Restored {} pending quotes from {}", restored, path),
+                    Err(e) => warn!("Failed to restore pending quotes from {}: {}", path, e),
+                }
+            }
+            let quote_poller = Arc::new(quote_poller);
             let poller_for_task = quote_poller.clone();
             let hub_for_poller = mint_hub.clone();
+            pool.safe_lock(|p| {
+                p.quote_poller = Some(quote_poller.clone());
+            })?;
             info!("Starting quote poller using endpoint {}", http_url);
             task::spawn(async move {
                 poller_for_task.start(cloned4, hub_for_poller).await;
@@ -1258,6 +1425,32 @@ impl Pool {
             info!("Skipping quote poller startup (no mint HTTP endpoint configured)");
         }
 
+        if let Some(path) = config.lifetime_stats_path() {
+            let store = lifetime_store::LifetimeStore::open(path).await.map_err(|e| {
+                PoolError::Custom(format!("Failed to open lifetime stats {}: {}", path, e))
+            })?;
+            let totals = store.load().await.map_err(|e| {
+                PoolError::Custom(format!("Failed to load lifetime stats {}: {}", path, e))
+            })?;
+            info!(
+                "Loaded lifetime totals for {} miners from {}",
+                totals.len(),
+                path
+            );
+            let registry = pool.safe_lock(|p| p.stats_registry.clone())?;
+            registry.restore_lifetime_totals(totals);
+            task::spawn(store.run_flush_loop(registry, lifetime_store::FLUSH_INTERVAL));
+        }
+
+        if let Some(address) = config.debug_dump_address().map(|s| s.to_string()) {
+            let pool_for_dump = pool.clone();
+            task::spawn(async move {
+                if let Err(e) = debug_dump::run_debug_server(address, pool_for_dump).await {
+                    error!("Debug dump server stopped: {}", e);
+                }
+            });
+        }
+
         // Extract stats configuration before config is moved
         let stats_addr_opt = config.stats_server_address().map(|s| s.to_string());
         let stats_poll_interval = config.snapshot_poll_interval_secs();
@@ -1326,36 +1519,28 @@ impl Pool {
 
         // Start snapshot-based stats polling loop to send stats to stats service
         if let Some(stats_addr) = stats_addr_opt {
-            use stats::{
-                stats_adapter::StatsSnapshotProvider,
-                stats_client::StatsClient,
-            };
+            use stats::stats_poller::run_snapshot_loop;
 
             info!("Starting stats polling loop, sending to {} every {} seconds",
                   stats_addr, stats_poll_interval);
 
+            // Read the hub's stats first, since they need an await, then take both
+            // snapshots under one lock and send without it
             let pool_clone = cloned3.clone();
-            let stats_addr_clone = stats_addr.clone();
-            task::spawn(async move {
-                let mut interval = tokio::time::interval(std::time::Duration::from_secs(stats_poll_interval));
-                let status_client = StatsClient::new(stats_addr);
-                let metrics_client = StatsClient::new(stats_addr_clone);
-
-                loop {
-                    interval.tick().await;
-
-                    // Get both snapshots while holding lock, then send without lock
-                    let (status, metrics) = match pool_clone.safe_lock(|p| {
-                        (p.get_snapshot(), p.get_metrics_snapshot())
-                    }) {
-                        Ok(pair) => pair,
-                        Err(_) => continue,
-                    };
-
-                    let _ = status_client.send_snapshot(status).await;
-                    let _ = metrics_client.send_snapshot(metrics).await;
-                }
-            });
+            let hub_for_stats = mint_hub.clone();
+            task::spawn(run_snapshot_loop(
+                stats_addr,
+                std::time::Duration::from_secs(stats_poll_interval),
+                move || {
+                    let pool = pool_clone.clone();
+                    let hub = hub_for_stats.clone();
+                    async move {
+                        let hub_stats = hub.get_stats().await;
+                        pool.safe_lock(|p| (p.get_snapshot(&hub_stats), p.get_metrics_snapshot()))
+                            .ok()
+                    }
+                },
+            ));
         }
 
         Ok(cloned3)
@@ -1374,6 +1559,12 @@ impl Pool {
         self.stats_registry.unregister_downstream(downstream_id);
         self.downstreams.remove(&downstream_id);
     }
+
+    /// Whether the template provider has sent a chain tip yet. The pool shuts down when the
+    /// template provider disconnects, so this doubles as its connection state.
+    pub fn has_chain_tip(&self) -> bool {
+        self.last_new_prev_hash.is_some()
+    }
 }
 
 async fn send_set_target_downstream(
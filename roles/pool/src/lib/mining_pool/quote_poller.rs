@@ -4,22 +4,93 @@
 //! then sends MintQuoteNotification extension messages to translators.
 //!
 //! Phase 3 Implementation:
-//! - Polls mint HTTP endpoint every 5s
+//! - Polls each pending quote every 5s, staggered across the interval by a
+//!   per-quote phase so the mint sees a steady trickle instead of bursts
 //! - Tracks pending quotes with timeouts
-//! - Sends MintQuoteNotification to downstream translators
+//! - Bounds each status request so one slow response can't hold up the other quotes
+//! - Retries transient mint failures (5xx, connection errors) within a poll, and drops a
+//!   quote only after repeated persistent failures (other 4xx, undecodable responses)
+//! - Polls the quotes due in a slot concurrently, with a cap on requests in flight
+//! - Builds status URLs from a configurable path template, for mints served under a
+//!   prefix or a different API version
+//! - Sends MintQuoteNotification to downstream translators, backing off when delivery
+//!   fails and abandoning the quote after a bounded number of attempts
 //! - Correlates quotes to channels for proper message routing
-
-use super::Downstream;
-use mint_pool_messaging::MintPoolMessageHub;
+//! - Optionally writes pending quotes through to a [`QuoteStore`] and reloads them on
+//!   startup, so quotes registered before a restart are still notified once paid
+//! - Delivers a paid quote only to a channel holding the quote's locking key, since channel
+//!   ids are reused after a restart. A quote with no such channel open is kept until one
+//!   opens or the quote expires
+//! - Optionally caps the number of pending quotes, evicting the oldest so a long mint
+//!   outage can't grow the map without bound. Evicted quotes count as failed in their
+//!   downstream's stats
+//! - Counts registered, paid, already issued, expired and evicted quotes and failed
+//!   notifications, reported in the pool's stats snapshots
+
+use super::{
+    mint_integration::MintIntegrationManager,
+    quote_store::{QuoteStore, StoredQuote},
+    Downstream, Pool,
+};
+use futures::stream::{self, StreamExt};
+use mint_pool_messaging::{InsertionOrderMap, MintPoolMessageHub};
 use reqwest::{self, StatusCode, Url};
-use std::{collections::HashMap, sync::Arc, time::Instant};
-use stratum_common::roles_logic_sv2::{
-    codec_sv2::binary_sv2::Str0255, handlers::mining::SendTo, mining_sv2::MintQuoteNotification,
-    parsers_sv2::Mining,
+use stats::quote_stats::{QuotePollerCounters, QuotePollerStats};
+use stats_sv2::types::unix_timestamp;
+use std::{
+    collections::hash_map::DefaultHasher,
+    future::Future,
+    hash::{Hash, Hasher},
+    sync::Arc,
+    time::Instant,
 };
-use tokio::time::{interval, sleep, Duration};
+use stratum_common::{
+    network_helpers_sv2::retry::{retry_with_backoff, RetryPolicy},
+    roles_logic_sv2::{
+        codec_sv2::binary_sv2::Str0255, handlers::mining::SendTo,
+        mining_sv2::MintQuoteNotification, parsers_sv2::Mining, utils::Mutex,
+    },
+};
+use tokio::time::{interval, sleep, timeout, Duration};
 use tracing::{debug, error, info, warn};
 
+/// How often each pending quote is polled
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Number of slots the poll interval is divided into for phase spreading
+const POLL_SLOTS: u32 = 20;
+/// How long a single quote status request may take before it is abandoned
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// How many quote status requests may be in flight at once
+pub const DEFAULT_MAX_CONCURRENT_POLLS: usize = 8;
+/// Quick retries within one poll for a status request that failed transiently
+const TRANSIENT_RETRY: RetryPolicy = RetryPolicy {
+    max_attempts: Some(3),
+    base_delay: Duration::from_millis(100),
+    max_delay: Duration::from_millis(500),
+    jitter: 0.5,
+};
+/// Consecutive polls failing persistently after which a quote is dropped
+const MAX_PERSISTENT_FAILURES: u32 = 3;
+/// Failed notification attempts after which a paid quote is abandoned
+pub const DEFAULT_MAX_NOTIFY_ATTEMPTS: u32 = 5;
+/// Backoff between notification attempts for a paid quote. Attempts are counted by the
+/// poller, so `max_attempts` is unused here.
+const NOTIFY_BACKOFF: RetryPolicy = RetryPolicy {
+    max_attempts: None,
+    base_delay: Duration::from_secs(5),
+    max_delay: Duration::from_secs(60),
+    jitter: 0.2,
+};
+/// Quote status path, relative to the mint HTTP endpoint; `{quote_id}` is substituted
+pub const DEFAULT_QUOTE_STATUS_PATH: &str = "v1/mint/quote/mining_share/{quote_id}";
+
+/// Assign a stable poll slot in `[0, POLL_SLOTS)` derived from the quote id
+fn poll_slot_for(quote_id: &str) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    quote_id.hash(&mut hasher);
+    (hasher.finish() % POLL_SLOTS as u64) as u32
+}
+
 /// Quote metadata for tracking pending quotes
 #[derive(Debug, Clone)]
 pub struct PendingQuote {
@@ -29,44 +100,260 @@ pub struct PendingQuote {
     pub created_at: Instant,
     /// Amount of the quote (in satoshis or HASH)
     pub amount: u64,
+    /// Compressed locking pubkey the quote pays out to, used to match it to a channel
+    pub locking_key: Option<Vec<u8>>,
+    /// Slot within the poll interval at which this quote is polled
+    pub poll_slot: u32,
+    /// Consecutive polls that failed persistently
+    pub persistent_failures: u32,
+    /// Failed attempts to notify the downstream after the quote was paid
+    pub notify_failures: u32,
+    /// Earliest time the quote is polled again after a failed notification
+    pub notify_after: Option<Instant>,
 }
 
 /// Quote poller that tracks pending quotes and polls for paid status
 pub struct QuotePoller {
     /// Pending quotes: quote_id → (channel_id, amount, timestamp)
-    pending_quotes: Arc<tokio::sync::RwLock<HashMap<String, PendingQuote>>>,
+    pending_quotes: Arc<tokio::sync::RwLock<InsertionOrderMap<String, PendingQuote>>>,
     /// Mint HTTP endpoint
     mint_http_endpoint: Option<String>,
     /// Quote timeout (5 minutes default)
     quote_timeout: Duration,
+    /// Per-request timeout for quote status polls
+    request_timeout: Duration,
+    /// Upper bound on quote status requests in flight at once
+    max_concurrent_polls: usize,
+    /// Quote status path template, relative to the mint HTTP endpoint
+    quote_status_path: String,
+    /// Write-through persistence for pending quotes, if configured
+    store: Option<QuoteStore>,
+    /// Failed notification attempts after which a paid quote is abandoned
+    max_notify_attempts: u32,
+    /// Most quotes kept pending before the oldest are evicted, if limited
+    max_pending_quotes: Option<usize>,
+    /// Quote outcome totals since startup
+    counters: QuotePollerCounters,
 }
 
 impl QuotePoller {
     /// Create a new quote poller
     pub fn new(mint_http_endpoint: Option<String>) -> Self {
         Self {
-            pending_quotes: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            pending_quotes: Arc::new(tokio::sync::RwLock::new(InsertionOrderMap::new())),
             mint_http_endpoint,
             quote_timeout: Duration::from_secs(300), // 5 minutes
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            max_concurrent_polls: DEFAULT_MAX_CONCURRENT_POLLS,
+            quote_status_path: DEFAULT_QUOTE_STATUS_PATH.to_string(),
+            store: None,
+            max_notify_attempts: DEFAULT_MAX_NOTIFY_ATTEMPTS,
+            max_pending_quotes: None,
+            counters: QuotePollerCounters::new(),
         }
     }
 
-    /// Register a new pending quote
-    pub async fn register_quote(&self, quote_id: String, channel_id: u32, amount: u64) {
+    /// Quote outcome totals since startup
+    pub fn metrics(&self) -> QuotePollerStats {
+        self.counters.snapshot()
+    }
+
+    /// Abandon a paid quote after `max_notify_attempts` failed notifications
+    pub fn with_max_notify_attempts(mut self, max_notify_attempts: u32) -> Self {
+        self.max_notify_attempts = max_notify_attempts.max(1);
+        self
+    }
+
+    /// Keep at most `max_pending_quotes` pending, evicting the oldest when a new quote would
+    /// exceed it
+    pub fn with_max_pending_quotes(mut self, max_pending_quotes: usize) -> Self {
+        self.max_pending_quotes = Some(max_pending_quotes.max(1));
+        self
+    }
+
+    /// Persist pending quotes in `store`; call [`Self::restore_pending_quotes`] to reload them
+    pub fn with_store(mut self, store: QuoteStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Load the quotes persisted before a restart, keeping their original age so they still
+    /// expire on time. Returns the number of quotes restored.
+    pub async fn restore_pending_quotes(&self) -> Result<usize, sqlx::Error> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+        let stored = store.load().await?;
+        let now = unix_timestamp();
+        let mut pending = self.pending_quotes.write().await;
+        for quote in &stored {
+            let age = Duration::from_secs(now.saturating_sub(quote.created_at));
+            pending.insert(
+                quote.quote_id.clone(),
+                PendingQuote {
+                    channel_id: quote.channel_id,
+                    created_at: Instant::now().checked_sub(age).unwrap_or_else(Instant::now),
+                    amount: quote.amount,
+                    locking_key: Some(quote.locking_key.clone()),
+                    poll_slot: poll_slot_for(&quote.quote_id),
+                    persistent_failures: 0,
+                    notify_failures: 0,
+                    notify_after: None,
+                },
+            );
+        }
+        Ok(stored.len())
+    }
+
+    /// Drop a quote from the store, if there is one. Failures are logged: the in-memory map
+    /// stays authoritative and a leftover row is at worst restored and expired later.
+    async fn forget_stored(&self, quote_id: &str) {
+        if let Some(store) = &self.store {
+            if let Err(e) = store.remove(quote_id).await {
+                warn!(
+                    "Failed to remove quote {} from the quote store: {}",
+                    quote_id, e
+                );
+            }
+        }
+    }
+
+    /// Abandon quote status requests that take longer than `request_timeout`
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
+    /// Allow up to `max_concurrent_polls` quote status requests in flight at once
+    pub fn with_max_concurrent_polls(mut self, max_concurrent_polls: usize) -> Self {
+        self.max_concurrent_polls = max_concurrent_polls.max(1);
+        self
+    }
+
+    /// Request quote statuses at `quote_status_path` under the mint endpoint instead of the
+    /// default; `{quote_id}` in the template is replaced with the quote id
+    pub fn with_quote_status_path(mut self, quote_status_path: impl Into<String>) -> Self {
+        self.quote_status_path = quote_status_path.into();
+        self
+    }
+
+    /// Status URL for `quote_id` under `base_url`
+    fn quote_status_url(
+        &self,
+        base_url: &Url,
+        quote_id: &str,
+    ) -> Result<Url, impl std::error::Error> {
+        // Without a trailing slash `join` would replace the last segment of a prefixed base
+        let mut base_url = base_url.clone();
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        base_url.join(&self.quote_status_path.replace("{quote_id}", quote_id))
+    }
+
+    /// HTTP client used for status polls, with the request timeout applied
+    fn http_client(&self) -> reqwest::Client {
+        reqwest::Client::builder()
+            .timeout(self.request_timeout)
+            .build()
+            .unwrap_or_else(|e| {
+                warn!(
+                    "Failed to build quote poller HTTP client, using defaults: {}",
+                    e
+                );
+                reqwest::Client::new()
+            })
+    }
+
+    /// Register a new pending quote, returning the quotes evicted to make room for it. Only
+    /// quotes with a locking key are persisted, as nothing else could match a restored quote
+    /// to its miner.
+    pub async fn register_quote(
+        &self,
+        quote_id: String,
+        channel_id: u32,
+        amount: u64,
+        locking_key: Option<Vec<u8>>,
+    ) -> Vec<(String, PendingQuote)> {
         let pending = PendingQuote {
             channel_id,
             created_at: Instant::now(),
             amount,
+            locking_key: locking_key.clone(),
+            poll_slot: poll_slot_for(&quote_id),
+            persistent_failures: 0,
+            notify_failures: 0,
+            notify_after: None,
         };
 
-        self.pending_quotes
-            .write()
-            .await
-            .insert(quote_id.clone(), pending);
+        let evicted = {
+            let mut pending_quotes = self.pending_quotes.write().await;
+            pending_quotes.insert(quote_id.clone(), pending);
+            self.evict_oldest(&mut pending_quotes)
+        };
+        self.counters.record_registered();
+        if !evicted.is_empty() {
+            warn!(
+                "Pending quote limit reached; evicted {} oldest quotes",
+                evicted.len()
+            );
+            self.counters.record_evicted(evicted.len() as u64);
+            for (evicted_id, _) in &evicted {
+                self.forget_stored(evicted_id).await;
+            }
+        }
+        if let (Some(store), Some(locking_key)) = (&self.store, locking_key) {
+            let stored = StoredQuote {
+                quote_id: quote_id.clone(),
+                channel_id,
+                amount,
+                locking_key,
+                created_at: unix_timestamp(),
+            };
+            if let Err(e) = store.save(&stored).await {
+                warn!("Failed to persist quote {}: {}", quote_id, e);
+            }
+        }
         debug!(
             "Registered pending quote: quote_id={}, channel_id={}, amount={}",
             quote_id, channel_id, amount
         );
+        evicted
+    }
+
+    /// Drop the oldest quotes until at most `max_pending_quotes` remain, returning them
+    fn evict_oldest(
+        &self,
+        pending: &mut InsertionOrderMap<String, PendingQuote>,
+    ) -> Vec<(String, PendingQuote)> {
+        let Some(max_pending) = self.max_pending_quotes else {
+            return Vec::new();
+        };
+        let mut evicted = Vec::new();
+        while pending.len() > max_pending {
+            let Some(oldest) = pending.pop_oldest() else {
+                break;
+            };
+            evicted.push(oldest);
+        }
+        evicted
+    }
+
+    /// Report evicted quotes as failed, since they will never be notified when paid
+    async fn report_evicted(
+        &self,
+        evicted: Vec<(String, PendingQuote)>,
+        notifier: &impl QuoteNotifier,
+    ) {
+        for (_, quote) in evicted {
+            notifier
+                .quote_failed(
+                    quote.channel_id,
+                    quote.amount,
+                    "evicted from the quote poller",
+                )
+                .await;
+        }
     }
 
     /// Get channel_id for a quote (for routing responses)
@@ -81,23 +368,76 @@ impl QuotePoller {
     /// Remove a quote (after processing)
     pub async fn remove_quote(&self, quote_id: &str) {
         self.pending_quotes.write().await.remove(quote_id);
+        self.forget_stored(quote_id).await;
         debug!("Removed quote from tracking: quote_id={}", quote_id);
     }
 
+    /// Count a persistently failed poll against a quote, dropping the quote after
+    /// `MAX_PERSISTENT_FAILURES` in a row. Returns `true` if the quote was dropped.
+    async fn record_persistent_failure(&self, quote_id: &str) -> bool {
+        {
+            let mut pending = self.pending_quotes.write().await;
+            let Some(quote) = pending.get_mut(quote_id) else {
+                return false;
+            };
+            quote.persistent_failures += 1;
+            if quote.persistent_failures < MAX_PERSISTENT_FAILURES {
+                return false;
+            }
+            pending.remove(quote_id);
+        }
+        self.forget_stored(quote_id).await;
+        true
+    }
+
+    /// Forget earlier persistent failures once the mint answers for a quote again
+    async fn clear_persistent_failures(&self, quote_id: &str) {
+        if let Some(quote) = self.pending_quotes.write().await.get_mut(quote_id) {
+            quote.persistent_failures = 0;
+        }
+    }
+
+    /// Count a failed notification for a paid quote. The quote is held back from polling
+    /// for a growing delay, or dropped after `max_notify_attempts` failures, in which case
+    /// `true` is returned.
+    async fn record_notify_failure(&self, quote_id: &str) -> bool {
+        {
+            let mut pending = self.pending_quotes.write().await;
+            let Some(quote) = pending.get_mut(quote_id) else {
+                return false;
+            };
+            quote.notify_failures += 1;
+            if quote.notify_failures < self.max_notify_attempts {
+                quote.notify_after =
+                    Some(Instant::now() + NOTIFY_BACKOFF.delay_for(quote.notify_failures));
+                return false;
+            }
+            pending.remove(quote_id);
+        }
+        self.forget_stored(quote_id).await;
+        true
+    }
+
     /// Clean up expired quotes
     pub async fn cleanup_expired_quotes(&self) {
         let now = Instant::now();
-        let mut pending = self.pending_quotes.write().await;
-
-        let expired: Vec<String> = pending
-            .iter()
-            .filter(|(_, q)| now.duration_since(q.created_at) > self.quote_timeout)
-            .map(|(id, _)| id.clone())
-            .collect();
+        let expired: Vec<String> = {
+            let mut pending = self.pending_quotes.write().await;
+            let expired: Vec<String> = pending
+                .iter()
+                .filter(|(_, q)| now.duration_since(q.created_at) > self.quote_timeout)
+                .map(|(id, _)| id.clone())
+                .collect();
+            for quote_id in &expired {
+                warn!("Quote expired (timeout after 5min): {}", quote_id);
+                pending.remove(quote_id);
+            }
+            expired
+        };
+        self.counters.record_expired(expired.len() as u64);
 
         for quote_id in expired {
-            warn!("Quote expired (timeout after 5min): {}", quote_id);
-            pending.remove(&quote_id);
+            self.forget_stored(&quote_id).await;
         }
     }
 
@@ -111,14 +451,55 @@ impl QuotePoller {
             .collect()
     }
 
+    /// Every pending quote with its metadata, e.g. for a diagnostic dump
+    pub async fn pending_quote_details(&self) -> Vec<(String, PendingQuote)> {
+        self.pending_quotes
+            .read()
+            .await
+            .iter()
+            .map(|(id, quote)| (id.clone(), quote.clone()))
+            .collect()
+    }
+
+    /// Pending quotes for one downstream, resolving each quote's channel through the mint
+    /// manager's channel contexts. Quotes whose channel has since closed are not included.
+    pub async fn pending_quotes_for_downstream(
+        &self,
+        downstream_id: u32,
+        mint_manager: &MintIntegrationManager,
+    ) -> Vec<(String, u32, u64)> {
+        let channels = mint_manager.channels_for_downstream(downstream_id).await;
+        self.pending_quotes
+            .read()
+            .await
+            .iter()
+            .filter(|(_, q)| channels.contains(&q.channel_id))
+            .map(|(id, q)| (id.clone(), q.channel_id, q.amount))
+            .collect()
+    }
+
+    /// Pending quotes whose poll slot falls at `elapsed` into the poll interval.
+    ///
+    /// Over one `POLL_INTERVAL` every pending quote is returned exactly once.
+    async fn quotes_due_at(&self, elapsed: Duration) -> Vec<(String, PendingQuote)> {
+        let slot_ms = (POLL_INTERVAL / POLL_SLOTS).as_millis();
+        let slot = ((elapsed.as_millis() / slot_ms) % POLL_SLOTS as u128) as u32;
+        let now = Instant::now();
+
+        self.pending_quotes
+            .read()
+            .await
+            .iter()
+            .filter(|(_, quote)| quote.poll_slot == slot)
+            .filter(|(_, quote)| quote.notify_after.is_none_or(|after| after <= now))
+            .map(|(id, quote)| (id.clone(), quote.clone()))
+            .collect()
+    }
+
     /// Start the polling loop
     ///
     /// Phase 3: Polls mint HTTP API and sends MintQuoteNotification extension messages
-    pub async fn start(
-        self: Arc<Self>,
-        pool: Arc<stratum_common::roles_logic_sv2::utils::Mutex<crate::mining_pool::Pool>>,
-        hub: Arc<MintPoolMessageHub>,
-    ) {
+    pub async fn start(self: Arc<Self>, pool: Arc<Mutex<Pool>>, hub: Arc<MintPoolMessageHub>) {
         let Some(mint_endpoint_base) = self.mint_http_endpoint.clone() else {
             info!("Quote poller disabled: no mint HTTP endpoint configured");
             return;
@@ -127,6 +508,18 @@ impl QuotePoller {
         info!("🚀 Quote poller started");
         info!("📍 Mint HTTP endpoint: {}", mint_endpoint_base);
         info!("⏱️  Polling interval: 5 seconds");
+        info!("⏱️  Request timeout: {:?}", self.request_timeout);
+        info!("⏱️  Max concurrent polls: {}", self.max_concurrent_polls);
+        info!(
+            "⏱️  Max notification attempts: {}",
+            self.max_notify_attempts
+        );
+        if !self.quote_status_path.contains("{quote_id}") {
+            warn!(
+                "Quote status path '{}' has no {{quote_id}} placeholder; every quote will poll the same URL",
+                self.quote_status_path
+            );
+        }
 
         let base_url = match Url::parse(&mint_endpoint_base) {
             Ok(url) => url,
@@ -139,143 +532,254 @@ impl QuotePoller {
             }
         };
 
-        let client = reqwest::Client::new();
-        let mut ticker = interval(Duration::from_secs(5));
-        let mut poll_count = 0;
+        let client = self.http_client();
+        let mut ticker = interval(POLL_INTERVAL / POLL_SLOTS);
+        let mut slot_count: u64 = 0;
 
         let response_listener = Arc::clone(&self);
+        let eviction_notifier = pool.clone();
         tokio::spawn(async move {
-            response_listener.listen_for_hub_responses(hub).await;
+            response_listener
+                .listen_for_hub_responses(hub, eviction_notifier)
+                .await;
         });
 
         loop {
             ticker.tick().await;
+            // Slot position is driven by tick count so missed ticks never skip a slot
+            let slot_offset =
+                (POLL_INTERVAL / POLL_SLOTS) * (slot_count % POLL_SLOTS as u64) as u32;
+            slot_count += 1;
 
-            // Clean up expired quotes every 10 polls
-            poll_count += 1;
-            if poll_count % 10 == 0 {
-                self.cleanup_expired_quotes().await;
-            }
+            if slot_count % POLL_SLOTS as u64 == 0 {
+                let poll_count = slot_count / POLL_SLOTS as u64;
+
+                // Clean up expired quotes every 10 polls
+                if poll_count % 10 == 0 {
+                    self.cleanup_expired_quotes().await;
+                }
 
-            // Log current pending quotes count
-            let pending_count = self.pending_quotes.read().await.len();
-            if pending_count > 0 {
-                debug!("Quote poller: {} pending quotes", pending_count);
+                // Log current pending quotes count
+                let pending_count = self.pending_quotes.read().await.len();
+                if pending_count > 0 {
+                    debug!("Quote poller: {} pending quotes", pending_count);
+                }
+
+                debug!("Quote poller tick #{}", poll_count);
             }
 
-            // Snapshot pending quotes to poll without holding the write lock
-            let pending_snapshot: Vec<(String, PendingQuote)> = self
-                .pending_quotes
-                .read()
-                .await
-                .iter()
-                .map(|(id, quote)| (id.clone(), quote.clone()))
-                .collect();
+            // Snapshot the quotes due in this slot without holding the lock
+            let due = self.quotes_due_at(slot_offset).await;
+            self.poll_due_quotes(&client, &base_url, &pool, due).await;
+        }
+    }
 
-            for (quote_id, quote_meta) in pending_snapshot {
-                let endpoint =
-                    match base_url.join(&format!("v1/mint/quote/mining_share/{}", quote_id)) {
-                        Ok(url) => url,
-                        Err(e) => {
-                            error!(
-                                "Failed to build mint quote status URL for {}: {}",
-                                quote_id, e
-                            );
-                            continue;
-                        }
-                    };
+    /// Poll each of `due`, keeping at most `max_concurrent_polls` requests in flight
+    async fn poll_due_quotes(
+        &self,
+        client: &reqwest::Client,
+        base_url: &Url,
+        notifier: &impl QuoteNotifier,
+        due: Vec<(String, PendingQuote)>,
+    ) {
+        poll_concurrently(
+            due,
+            self.max_concurrent_polls,
+            |quote_id, quote_meta| async move {
+                self.poll_quote(client, base_url, notifier, &quote_id, &quote_meta)
+                    .await;
+            },
+        )
+        .await;
+    }
 
-                match client.get(endpoint.clone()).send().await {
-                    Ok(response) => {
-                        let status = response.status();
+    /// Query the mint for a single quote's status and act on the result
+    async fn poll_quote(
+        &self,
+        client: &reqwest::Client,
+        base_url: &Url,
+        notifier: &impl QuoteNotifier,
+        quote_id: &str,
+        quote_meta: &PendingQuote,
+    ) {
+        let endpoint = match self.quote_status_url(base_url, quote_id) {
+            Ok(url) => url,
+            Err(e) => {
+                error!(
+                    "Failed to build mint quote status URL for {}: {}",
+                    quote_id, e
+                );
+                return;
+            }
+        };
 
-                        if status == StatusCode::NOT_FOUND {
-                            debug!(
-                                "Mint quote status endpoint returned 404 for {}; will retry",
-                                quote_id
-                            );
-                            continue;
-                        }
+        match self.fetch_quote_status_with_retry(client, &endpoint).await {
+            QuoteStatusFetch::Status(payload) => {
+                if quote_meta.persistent_failures > 0 {
+                    self.clear_persistent_failures(quote_id).await;
+                }
+                let state = payload.state.to_ascii_uppercase();
+                let fully_issued = match (payload.amount, payload.amount_issued) {
+                    (Some(expected), Some(issued)) => issued >= expected,
+                    _ => false,
+                };
+
+                debug!(
+                    "Mint quote {} status={}, issued={}, expected={:?}",
+                    quote_id,
+                    state,
+                    payload.amount_issued.unwrap_or_default(),
+                    payload.amount
+                );
 
-                        if !status.is_success() {
-                            error!(
-                                "Mint quote status for {} returned {} from {}",
-                                quote_id, status, endpoint
+                if state == "PAID" {
+                    let Some(channel_id) = notifier
+                        .resolve_channel(quote_meta.channel_id, quote_meta.locking_key.as_deref())
+                        .await
+                    else {
+                        debug!(
+                            "Paid quote {} has no open channel for its locking key; keeping it",
+                            quote_id
+                        );
+                        return;
+                    };
+                    match notifier
+                        .notify_paid(channel_id, quote_id, quote_meta.amount)
+                        .await
+                    {
+                        Ok(_) => {
+                            debug!(
+                                "✅ Sent MintQuoteNotification for quote {} to channel {}",
+                                quote_id, channel_id
                             );
-                            continue;
+                            self.counters.record_paid();
+                            self.remove_quote(quote_id).await;
                         }
-
-                        match response.json::<MintQuoteStatusResponse>().await {
-                            Ok(payload) => {
-                                let state = payload.state.to_ascii_uppercase();
-                                let fully_issued = match (payload.amount, payload.amount_issued) {
-                                    (Some(expected), Some(issued)) => issued >= expected,
-                                    _ => false,
-                                };
-
-                                debug!(
-                                    "Mint quote {} status={}, issued={}, expected={:?}",
-                                    quote_id,
-                                    state,
-                                    payload.amount_issued.unwrap_or_default(),
-                                    payload.amount
-                                );
-
-                                if state == "PAID" {
-                                    let channel_id = quote_meta.channel_id;
-                                    match self
-                                        .send_notification_to_translator(
-                                            pool.clone(),
-                                            channel_id,
-                                            &quote_id,
-                                            quote_meta.amount,
-                                        )
-                                        .await
-                                    {
-                                        Ok(_) => {
-                                            debug!(
-                                                "✅ Sent MintQuoteNotification for quote {} to channel {}",
-                                                quote_id, channel_id
-                                            );
-                                            self.remove_quote(&quote_id).await;
-                                        }
-                                        Err(e) => {
-                                            error!(
-                                                "Failed to send notification for quote {}: {}",
-                                                quote_id, e
-                                            );
-                                        }
-                                    }
-                                } else if state == "ISSUED" || fully_issued {
-                                    info!(
-                                        "Quote {} already issued according to mint; removing from tracking",
-                                        quote_id
-                                    );
-                                    self.remove_quote(&quote_id).await;
-                                }
-                            }
-                            Err(e) => {
+                        Err(e) => {
+                            error!("Failed to send notification for quote {}: {}", quote_id, e);
+                            self.counters.record_notification_failure();
+                            if self.record_notify_failure(quote_id).await {
                                 error!(
-                                    "Failed to decode mint quote status response for {}: {}",
-                                    quote_id, e
+                                    "Abandoning paid quote {} for channel {} after {} failed notifications",
+                                    quote_id, channel_id, self.max_notify_attempts
                                 );
                             }
                         }
                     }
-                    Err(e) => {
-                        error!(
-                            "Failed to poll mint status for {} at {}: {}",
-                            quote_id, endpoint, e
-                        );
-                    }
+                } else if state == "ISSUED" || fully_issued {
+                    info!(
+                        "Quote {} already issued according to mint; removing from tracking",
+                        quote_id
+                    );
+                    self.counters.record_issued_elsewhere();
+                    self.remove_quote(quote_id).await;
                 }
             }
+            QuoteStatusFetch::NotFound => {
+                debug!(
+                    "Mint quote status endpoint returned 404 for {}; will retry",
+                    quote_id
+                );
+            }
+            QuoteStatusFetch::HttpError(status) if status.is_server_error() => {
+                error!(
+                    "Mint quote status for {} returned {} from {}; will retry",
+                    quote_id, status, endpoint
+                );
+            }
+            QuoteStatusFetch::HttpError(status) => {
+                error!(
+                    "Mint quote status for {} returned {} from {}",
+                    quote_id, status, endpoint
+                );
+                self.handle_persistent_failure(quote_id).await;
+            }
+            QuoteStatusFetch::DecodeError(e) => {
+                error!(
+                    "Failed to decode mint quote status response for {}: {}",
+                    quote_id, e
+                );
+                self.handle_persistent_failure(quote_id).await;
+            }
+            QuoteStatusFetch::RequestError(e) => {
+                error!(
+                    "Failed to poll mint status for {} at {}: {}; will retry",
+                    quote_id, endpoint, e
+                );
+            }
+            QuoteStatusFetch::TimedOut => {
+                warn!(
+                    "Mint status poll for {} timed out after {:?}; will retry",
+                    quote_id, self.request_timeout
+                );
+            }
+        }
+    }
 
-            debug!("Quote poller tick #{}", poll_count);
+    /// Record a persistent failure for a quote, reporting when it is given up on
+    async fn handle_persistent_failure(&self, quote_id: &str) {
+        if self.record_persistent_failure(quote_id).await {
+            error!(
+                "Giving up on quote {} after {} consecutive failed polls",
+                quote_id, MAX_PERSISTENT_FAILURES
+            );
         }
     }
 
-    async fn listen_for_hub_responses(self: Arc<Self>, hub: Arc<MintPoolMessageHub>) {
+    /// Request a quote's status, retrying transient failures a few times before reporting them
+    async fn fetch_quote_status_with_retry(
+        &self,
+        client: &reqwest::Client,
+        endpoint: &Url,
+    ) -> QuoteStatusFetch {
+        retry_with_backoff(&TRANSIENT_RETRY, || async {
+            match self.fetch_quote_status(client, endpoint).await {
+                fetch if fetch.is_transient() => Err(fetch),
+                fetch => Ok(fetch),
+            }
+        })
+        .await
+        .unwrap_or_else(|fetch| fetch)
+    }
+
+    /// Request a quote's status from the mint, giving up after `request_timeout`
+    async fn fetch_quote_status(
+        &self,
+        client: &reqwest::Client,
+        endpoint: &Url,
+    ) -> QuoteStatusFetch {
+        let request = async {
+            let response = match client.get(endpoint.clone()).send().await {
+                Ok(response) => response,
+                Err(e) if e.is_timeout() => return QuoteStatusFetch::TimedOut,
+                Err(e) => return QuoteStatusFetch::RequestError(e),
+            };
+
+            let status = response.status();
+            if status == StatusCode::NOT_FOUND {
+                return QuoteStatusFetch::NotFound;
+            }
+            if !status.is_success() {
+                return QuoteStatusFetch::HttpError(status);
+            }
+
+            match response.json::<MintQuoteStatusResponse>().await {
+                Ok(payload) => QuoteStatusFetch::Status(payload),
+                Err(e) if e.is_timeout() => QuoteStatusFetch::TimedOut,
+                Err(e) => QuoteStatusFetch::DecodeError(e),
+            }
+        };
+
+        timeout(self.request_timeout, request)
+            .await
+            .unwrap_or(QuoteStatusFetch::TimedOut)
+    }
+
+    async fn listen_for_hub_responses(
+        self: Arc<Self>,
+        hub: Arc<MintPoolMessageHub>,
+        notifier: impl QuoteNotifier,
+    ) {
         loop {
             match hub.subscribe_quote_responses().await {
                 Ok(mut rx) => {
@@ -284,12 +788,15 @@ impl QuotePoller {
                             if let Ok(quote_id) =
                                 std::str::from_utf8(event.response().quote_id.inner_as_ref())
                             {
-                                self.register_quote(
-                                    quote_id.to_string(),
-                                    context.channel_id,
-                                    context.amount,
-                                )
-                                .await;
+                                let evicted = self
+                                    .register_quote(
+                                        quote_id.to_string(),
+                                        context.channel_id,
+                                        context.amount,
+                                        event.locking_key().map(<[u8]>::to_vec),
+                                    )
+                                    .await;
+                                self.report_evicted(evicted, &notifier).await;
                             } else {
                                 warn!(
                                     "Received non-UTF8 quote id from mint response; skipping registration"
@@ -315,11 +822,36 @@ impl QuotePoller {
             sleep(Duration::from_secs(1)).await;
         }
     }
+}
+
+/// Delivers the MintQuoteNotification for a paid quote to the channel that earned it
+#[async_trait::async_trait]
+pub trait QuoteNotifier: Send + Sync {
+    /// Channel to deliver a paid quote on, given the channel it was requested on and its
+    /// locking key, or `None` if no open channel belongs to the quote's miner
+    async fn resolve_channel(&self, channel_id: u32, _locking_key: Option<&[u8]>) -> Option<u32> {
+        Some(channel_id)
+    }
+
+    async fn notify_paid(&self, channel_id: u32, quote_id: &str, amount: u64)
+        -> Result<(), String>;
+
+    /// Record that a quote created on `channel_id` will never be notified
+    async fn quote_failed(&self, _channel_id: u32, _amount: u64, _reason: &str) {}
+}
+
+#[async_trait::async_trait]
+impl QuoteNotifier for Arc<Mutex<Pool>> {
+    async fn resolve_channel(&self, channel_id: u32, locking_key: Option<&[u8]>) -> Option<u32> {
+        let mint_manager = self.safe_lock(|p| p.mint_manager.clone()).ok()?;
+        mint_manager
+            .resolve_quote_channel(channel_id, locking_key)
+            .await
+    }
 
     /// Send MintQuoteNotification extension message to translator
-    async fn send_notification_to_translator(
+    async fn notify_paid(
         &self,
-        pool: Arc<stratum_common::roles_logic_sv2::utils::Mutex<crate::mining_pool::Pool>>,
         channel_id: u32,
         quote_id: &str,
         amount: u64,
@@ -335,7 +867,7 @@ impl QuotePoller {
         let mining_message = Mining::MintQuoteNotification(notification);
 
         // Resolve downstream id for this channel via mint manager context
-        let mint_manager = pool
+        let mint_manager = self
             .safe_lock(|p| p.mint_manager.clone())
             .map_err(|_| "Failed to lock pool for mint manager")?;
 
@@ -347,7 +879,7 @@ impl QuotePoller {
         let downstream_id = context.downstream_id;
 
         // Fetch downstream handle using connection id
-        let downstream = pool
+        let downstream = self
             .safe_lock(|p| p.downstreams.get(&downstream_id).cloned())
             .map_err(|_| "Failed to lock pool for downstream lookup")?
             .ok_or_else(|| {
@@ -362,6 +894,30 @@ impl QuotePoller {
             .await
             .map_err(|e| format!("Failed to send: {:?}", e))
     }
+
+    async fn quote_failed(&self, channel_id: u32, amount: u64, reason: &str) {
+        let Ok((mint_manager, stats_registry)) =
+            self.safe_lock(|p| (p.mint_manager.clone(), p.stats_registry.clone()))
+        else {
+            return;
+        };
+        mint_manager
+            .record_quote_failure(channel_id, amount, reason, &stats_registry)
+            .await;
+    }
+}
+
+/// Run `poll` for each due quote, keeping at most `limit` polls in flight
+async fn poll_concurrently<F, Fut>(due: Vec<(String, PendingQuote)>, limit: usize, poll: F)
+where
+    F: Fn(String, PendingQuote) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    stream::iter(due)
+        .map(|(quote_id, quote_meta)| poll(quote_id, quote_meta))
+        .buffer_unordered(limit.max(1))
+        .for_each(|()| async {})
+        .await;
 }
 
 /// Minimal representation of the mint quote status response
@@ -374,9 +930,33 @@ struct MintQuoteStatusResponse {
     state: String,
 }
 
+/// Outcome of a single quote status request
+#[derive(Debug)]
+enum QuoteStatusFetch {
+    Status(MintQuoteStatusResponse),
+    NotFound,
+    HttpError(StatusCode),
+    DecodeError(reqwest::Error),
+    RequestError(reqwest::Error),
+    TimedOut,
+}
+
+impl QuoteStatusFetch {
+    /// Failures worth retrying straight away. Timeouts are left to the next poll, since each
+    /// one already took `request_timeout`.
+    fn is_transient(&self) -> bool {
+        match self {
+            QuoteStatusFetch::HttpError(status) => status.is_server_error(),
+            QuoteStatusFetch::RequestError(_) => true,
+            _ => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
 
     // ============================================================================
     // Quote Registration and Basic Operations Tests
@@ -385,16 +965,47 @@ mod tests {
     #[tokio::test]
     async fn test_quote_registration() {
         let poller = QuotePoller::new(Some("http://localhost:34261".to_string()));
-        poller.register_quote("quote1".to_string(), 42, 1000).await;
+        poller
+            .register_quote("quote1".to_string(), 42, 1000, None)
+            .await;
 
         let channel_id = poller.get_quote_channel("quote1").await;
         assert_eq!(channel_id, Some(42));
     }
 
+    #[tokio::test]
+    async fn test_pending_quotes_for_downstream() {
+        let poller = QuotePoller::new(Some("http://localhost:34261".to_string()));
+        let mint_manager = MintIntegrationManager::new("127.0.0.1:34260".to_string());
+        mint_manager.register_channel(1, None, 10).await;
+        mint_manager.register_channel(2, None, 10).await;
+        mint_manager.register_channel(3, None, 20).await;
+
+        poller.register_quote("a".to_string(), 1, 100, None).await;
+        poller.register_quote("b".to_string(), 2, 200, None).await;
+        poller.register_quote("c".to_string(), 3, 300, None).await;
+
+        let mut pending = poller
+            .pending_quotes_for_downstream(10, &mint_manager)
+            .await;
+        pending.sort();
+        assert_eq!(
+            pending,
+            vec![("a".to_string(), 1, 100), ("b".to_string(), 2, 200)]
+        );
+
+        let other = poller
+            .pending_quotes_for_downstream(20, &mint_manager)
+            .await;
+        assert_eq!(other, vec![("c".to_string(), 3, 300)]);
+    }
+
     #[tokio::test]
     async fn test_quote_removal() {
         let poller = QuotePoller::new(Some("http://localhost:34261".to_string()));
-        poller.register_quote("quote1".to_string(), 42, 1000).await;
+        poller
+            .register_quote("quote1".to_string(), 42, 1000, None)
+            .await;
 
         poller.remove_quote("quote1").await;
 
@@ -406,9 +1017,15 @@ mod tests {
     async fn test_register_multiple_quotes() {
         let poller = QuotePoller::new(Some("http://localhost:34261".to_string()));
 
-        poller.register_quote("quote1".to_string(), 10, 1000).await;
-        poller.register_quote("quote2".to_string(), 20, 2000).await;
-        poller.register_quote("quote3".to_string(), 30, 3000).await;
+        poller
+            .register_quote("quote1".to_string(), 10, 1000, None)
+            .await;
+        poller
+            .register_quote("quote2".to_string(), 20, 2000, None)
+            .await;
+        poller
+            .register_quote("quote3".to_string(), 30, 3000, None)
+            .await;
 
         assert_eq!(poller.get_quote_channel("quote1").await, Some(10));
         assert_eq!(poller.get_quote_channel("quote2").await, Some(20));
@@ -422,11 +1039,15 @@ mod tests {
     async fn test_update_existing_quote() {
         let poller = QuotePoller::new(Some("http://localhost:34261".to_string()));
 
-        poller.register_quote("quote1".to_string(), 42, 1000).await;
+        poller
+            .register_quote("quote1".to_string(), 42, 1000, None)
+            .await;
         assert_eq!(poller.get_quote_channel("quote1").await, Some(42));
 
         // Re-register with different channel_id - should update
-        poller.register_quote("quote1".to_string(), 99, 5000).await;
+        poller
+            .register_quote("quote1".to_string(), 99, 5000, None)
+            .await;
         assert_eq!(poller.get_quote_channel("quote1").await, Some(99));
 
         let pending = poller.get_pending_quotes().await;
@@ -450,7 +1071,9 @@ mod tests {
         let poller = QuotePoller::new(Some("http://localhost:34261".to_string()));
 
         // Register a quote
-        poller.register_quote("quote1".to_string(), 42, 1000).await;
+        poller
+            .register_quote("quote1".to_string(), 42, 1000, None)
+            .await;
 
         // Verify it's there
         let pending = poller.get_pending_quotes().await;
@@ -476,8 +1099,12 @@ mod tests {
     async fn test_cleanup_ignores_recent_quotes() {
         let poller = QuotePoller::new(Some("http://localhost:34261".to_string()));
 
-        poller.register_quote("quote1".to_string(), 42, 1000).await;
-        poller.register_quote("quote2".to_string(), 43, 1000).await;
+        poller
+            .register_quote("quote1".to_string(), 42, 1000, None)
+            .await;
+        poller
+            .register_quote("quote2".to_string(), 43, 1000, None)
+            .await;
 
         // Cleanup should not remove recent quotes (created < 5 minutes ago)
         poller.cleanup_expired_quotes().await;
@@ -490,8 +1117,12 @@ mod tests {
     async fn test_cleanup_mixed_expired_and_recent() {
         let poller = QuotePoller::new(Some("http://localhost:34261".to_string()));
 
-        poller.register_quote("recent".to_string(), 42, 1000).await;
-        poller.register_quote("expired".to_string(), 43, 2000).await;
+        poller
+            .register_quote("recent".to_string(), 42, 1000, None)
+            .await;
+        poller
+            .register_quote("expired".to_string(), 43, 2000, None)
+            .await;
 
         // Make one quote appear old
         {
@@ -532,7 +1163,7 @@ mod tests {
         let amount = 50000;
 
         poller
-            .register_quote("test_quote".to_string(), channel_id, amount)
+            .register_quote("test_quote".to_string(), channel_id, amount, None)
             .await;
 
         let pending = poller.get_pending_quotes().await;
@@ -551,7 +1182,9 @@ mod tests {
 
         // Quote IDs should handle various characters
         let quote_id = "quote-123_abc.xyz";
-        poller.register_quote(quote_id.to_string(), 42, 1000).await;
+        poller
+            .register_quote(quote_id.to_string(), 42, 1000, None)
+            .await;
 
         assert_eq!(poller.get_quote_channel(quote_id).await, Some(42));
     }
@@ -571,7 +1204,7 @@ mod tests {
             let task = tokio::spawn(async move {
                 let quote_id = format!("quote_{}", i);
                 poller_clone
-                    .register_quote(quote_id, i as u32, i as u64 * 1000)
+                    .register_quote(quote_id, i as u32, i as u64 * 1000, None)
                     .await;
             });
             tasks.push(task);
@@ -593,7 +1226,7 @@ mod tests {
         for i in 0..5 {
             let quote_id = format!("quote_{}", i);
             poller
-                .register_quote(quote_id, i as u32, i as u64 * 1000)
+                .register_quote(quote_id, i as u32, i as u64 * 1000, None)
                 .await;
         }
 
@@ -625,7 +1258,7 @@ mod tests {
         for i in 0..20 {
             let quote_id = format!("quote_{}", i);
             poller
-                .register_quote(quote_id, i as u32, i as u64 * 1000)
+                .register_quote(quote_id, i as u32, i as u64 * 1000, None)
                 .await;
         }
 
@@ -664,9 +1297,9 @@ mod tests {
     async fn test_get_pending_quotes_snapshot() {
         let poller = QuotePoller::new(Some("http://localhost:34261".to_string()));
 
-        poller.register_quote("q1".to_string(), 1, 100).await;
-        poller.register_quote("q2".to_string(), 2, 200).await;
-        poller.register_quote("q3".to_string(), 3, 300).await;
+        poller.register_quote("q1".to_string(), 1, 100, None).await;
+        poller.register_quote("q2".to_string(), 2, 200, None).await;
+        poller.register_quote("q3".to_string(), 3, 300, None).await;
 
         let pending = poller.get_pending_quotes().await;
         assert_eq!(pending.len(), 3);
@@ -678,6 +1311,77 @@ mod tests {
         assert!(quote_ids.contains(&"q3".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_pending_quote_limit_evicts_oldest() {
+        let poller =
+            QuotePoller::new(Some("http://localhost:34261".to_string())).with_max_pending_quotes(2);
+
+        // Registered back to back, so creation times may tie; eviction follows registration
+        // order
+        let mut evicted = Vec::new();
+        for (i, quote_id) in ["q1", "q2", "q3", "q4"].into_iter().enumerate() {
+            evicted.extend(
+                poller
+                    .register_quote(quote_id.to_string(), i as u32, 100, None)
+                    .await,
+            );
+        }
+
+        let evicted_ids: Vec<&str> = evicted.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(evicted_ids, ["q1", "q2"]);
+
+        assert_eq!(poller.get_quote_channel("q1").await, None);
+        assert_eq!(poller.get_quote_channel("q2").await, None);
+        assert_eq!(poller.get_quote_channel("q3").await, Some(2));
+        assert_eq!(poller.get_quote_channel("q4").await, Some(3));
+        let metrics = poller.metrics();
+        assert_eq!(metrics.quotes_registered, 4);
+        assert_eq!(metrics.quotes_evicted, 2);
+    }
+
+    // ============================================================================
+    // Poll Scheduling Tests
+    // ============================================================================
+
+    #[test]
+    fn test_poll_slot_is_stable_and_in_range() {
+        for i in 0..100 {
+            let quote_id = format!("quote_{}", i);
+            let slot = poll_slot_for(&quote_id);
+            assert!(slot < POLL_SLOTS);
+            assert_eq!(slot, poll_slot_for(&quote_id));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_polls_spread_across_interval() {
+        let poller = QuotePoller::new(Some("http://localhost:34261".to_string()));
+        for i in 0..50 {
+            poller
+                .register_quote(format!("quote_{}", i), i as u32, 1000, None)
+                .await;
+        }
+
+        // Step a mock clock through one full interval, one slot at a time
+        let slot = POLL_INTERVAL / POLL_SLOTS;
+        let mut polls_per_quote: HashMap<String, u32> = HashMap::new();
+        let mut busy_slots = 0;
+        for step in 0..POLL_SLOTS {
+            let due = poller.quotes_due_at(slot * step).await;
+            if !due.is_empty() {
+                busy_slots += 1;
+            }
+            assert!(due.len() < 50, "all quotes fired in one slot");
+            for (quote_id, _) in due {
+                *polls_per_quote.entry(quote_id).or_default() += 1;
+            }
+        }
+
+        assert_eq!(polls_per_quote.len(), 50);
+        assert!(polls_per_quote.values().all(|&count| count == 1));
+        assert!(busy_slots > 1);
+    }
+
     // ============================================================================
     // Mint Quote Status Response Deserialization Tests
     // ============================================================================
@@ -741,7 +1445,9 @@ mod tests {
         let poller = QuotePoller::new(Some("http://localhost:34261".to_string()));
 
         // Step 1: Register quote (share received)
-        poller.register_quote("q1".to_string(), 42, 1000).await;
+        poller
+            .register_quote("q1".to_string(), 42, 1000, None)
+            .await;
         let pending = poller.get_pending_quotes().await;
         assert_eq!(pending.len(), 1);
 
@@ -763,7 +1469,7 @@ mod tests {
         for i in 0..50 {
             let quote_id = format!("quote_{:03}", i);
             poller
-                .register_quote(quote_id, (i % 10) as u32, (i as u64) * 1000)
+                .register_quote(quote_id, (i % 10) as u32, (i as u64) * 1000, None)
                 .await;
         }
 
@@ -779,4 +1485,531 @@ mod tests {
         let pending = poller.get_pending_quotes().await;
         assert_eq!(pending.len(), 25);
     }
+
+    /// Minimal mint stand-in: quotes whose id starts with "slow" never get a response, all
+    /// others are reported as UNPAID.
+    async fn spawn_mock_mint() -> Url {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    let n = stream.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    if request.contains("/mining_share/slow") {
+                        sleep(Duration::from_secs(60)).await;
+                        return;
+                    }
+                    let body = r#"{"state":"UNPAID"}"#;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        Url::parse(&format!("http://{}/", addr)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_poll_does_not_block_other_quotes() {
+        let base_url = spawn_mock_mint().await;
+        let poller = QuotePoller::new(Some(base_url.to_string()))
+            .with_request_timeout(Duration::from_millis(200));
+        let client = poller.http_client();
+        let endpoint = |quote_id: &str| {
+            base_url
+                .join(&format!("v1/mint/quote/mining_share/{}", quote_id))
+                .unwrap()
+        };
+
+        let started = Instant::now();
+        let slow = poller
+            .fetch_quote_status(&client, &endpoint("slow-quote"))
+            .await;
+        assert!(matches!(slow, QuoteStatusFetch::TimedOut));
+
+        let fast = poller
+            .fetch_quote_status(&client, &endpoint("fast-quote"))
+            .await;
+        match fast {
+            QuoteStatusFetch::Status(payload) => assert_eq!(payload.state, "UNPAID"),
+            other => panic!("expected a status for the fast quote, got {:?}", other),
+        }
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    /// Mock mint answering every status request with ISSUED after a short delay, recording the
+    /// highest number of requests it was serving at once
+    async fn spawn_counting_mint(peak: Arc<std::sync::atomic::AtomicUsize>) -> Url {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let (in_flight, peak) = (in_flight.clone(), peak.clone());
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    let _ = stream.read(&mut buf).await;
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    sleep(Duration::from_millis(100)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    let body = r#"{"state":"ISSUED"}"#;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        Url::parse(&format!("http://{}/", addr)).unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_due_quotes_are_polled_concurrently_up_to_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let peak = Arc::new(AtomicUsize::new(0));
+        let base_url = spawn_counting_mint(peak.clone()).await;
+        let poller = QuotePoller::new(Some(base_url.to_string())).with_max_concurrent_polls(3);
+        for i in 0..9 {
+            poller
+                .register_quote(format!("quote-{}", i), i, 100, None)
+                .await;
+        }
+
+        let due = poller.pending_quote_details().await;
+        let notifier = RecordingNotifier::default();
+        poller
+            .poll_due_quotes(&poller.http_client(), &base_url, &notifier, due)
+            .await;
+
+        // Every quote was polled and handled
+        assert!(poller.get_pending_quotes().await.is_empty());
+        assert_eq!(poller.metrics().quotes_issued_elsewhere, 9);
+        // Requests overlapped, but never beyond the limit
+        let peak = peak.load(Ordering::SeqCst);
+        assert!(peak > 1 && peak <= 3, "peak concurrency was {}", peak);
+    }
+
+    #[test]
+    fn test_quote_status_url_uses_configured_path() {
+        let default = QuotePoller::new(None);
+        let base_url = Url::parse("http://mint.local:3338").unwrap();
+        assert_eq!(
+            default.quote_status_url(&base_url, "abc").unwrap().as_str(),
+            "http://mint.local:3338/v1/mint/quote/mining_share/abc"
+        );
+
+        // A mint behind a reverse-proxy prefix with a newer API version
+        let custom =
+            QuotePoller::new(None).with_quote_status_path("v2/quote/mining_share/{quote_id}");
+        let prefixed = Url::parse("https://example.com/ehash-mint").unwrap();
+        assert_eq!(
+            custom.quote_status_url(&prefixed, "abc").unwrap().as_str(),
+            "https://example.com/ehash-mint/v2/quote/mining_share/abc"
+        );
+        let prefixed = Url::parse("https://example.com/ehash-mint/").unwrap();
+        assert_eq!(
+            custom.quote_status_url(&prefixed, "abc").unwrap().as_str(),
+            "https://example.com/ehash-mint/v2/quote/mining_share/abc"
+        );
+    }
+
+    /// Mock mint replying with each `(status, body)` in turn, repeating the last one; returns
+    /// the base URL and a count of requests served
+    async fn spawn_scripted_mint(
+        replies: Vec<(u16, &'static str)>,
+    ) -> (Url, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let served = Arc::new(AtomicUsize::new(0));
+        let counter = served.clone();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf).await;
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                let (status, body) = replies[n.min(replies.len() - 1)];
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (Url::parse(&format!("http://{}/", addr)).unwrap(), served)
+    }
+
+    #[tokio::test]
+    async fn test_server_error_is_retried_until_quote_is_paid() {
+        let (base_url, served) = spawn_scripted_mint(vec![
+            (503, r#"{"detail":"unavailable"}"#),
+            (200, r#"{"state":"PAID","amount":64}"#),
+        ])
+        .await;
+        let poller = QuotePoller::new(Some(base_url.to_string()));
+        poller
+            .register_quote("flaky".to_string(), 1, 64, None)
+            .await;
+
+        let endpoint = poller.quote_status_url(&base_url, "flaky").unwrap();
+        let fetch = poller
+            .fetch_quote_status_with_retry(&poller.http_client(), &endpoint)
+            .await;
+
+        match fetch {
+            QuoteStatusFetch::Status(payload) => assert_eq!(payload.state, "PAID"),
+            other => panic!("expected a PAID status, got {:?}", other),
+        }
+        assert_eq!(served.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert_eq!(poller.get_quote_channel("flaky").await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let (base_url, served) = spawn_scripted_mint(vec![(400, "{}")]).await;
+        let poller = QuotePoller::new(Some(base_url.to_string()));
+
+        let endpoint = poller.quote_status_url(&base_url, "bad").unwrap();
+        let fetch = poller
+            .fetch_quote_status_with_retry(&poller.http_client(), &endpoint)
+            .await;
+
+        assert!(matches!(
+            fetch,
+            QuoteStatusFetch::HttpError(StatusCode::BAD_REQUEST)
+        ));
+        assert_eq!(served.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_quote_dropped_after_repeated_persistent_failures() {
+        let poller = QuotePoller::new(None);
+        poller
+            .register_quote("broken".to_string(), 1, 64, None)
+            .await;
+
+        assert!(!poller.record_persistent_failure("broken").await);
+        assert!(!poller.record_persistent_failure("broken").await);
+        // A successful answer in between starts the count again
+        poller.clear_persistent_failures("broken").await;
+        for _ in 1..MAX_PERSISTENT_FAILURES {
+            assert!(!poller.record_persistent_failure("broken").await);
+        }
+        assert!(poller.get_quote_channel("broken").await.is_some());
+
+        assert!(poller.record_persistent_failure("broken").await);
+        assert!(poller.get_quote_channel("broken").await.is_none());
+    }
+
+    /// Records every notification instead of sending it to a downstream. With `channels`
+    /// set, quotes are matched to a channel the way the pool does.
+    #[derive(Default)]
+    struct RecordingNotifier {
+        sent: tokio::sync::Mutex<Vec<(u32, String, u64)>>,
+        failed: tokio::sync::Mutex<Vec<(u32, u64)>>,
+        channels: Option<MintIntegrationManager>,
+    }
+
+    #[async_trait::async_trait]
+    impl QuoteNotifier for RecordingNotifier {
+        async fn resolve_channel(
+            &self,
+            channel_id: u32,
+            locking_key: Option<&[u8]>,
+        ) -> Option<u32> {
+            match &self.channels {
+                Some(channels) => {
+                    channels
+                        .resolve_quote_channel(channel_id, locking_key)
+                        .await
+                }
+                None => Some(channel_id),
+            }
+        }
+
+        async fn notify_paid(
+            &self,
+            channel_id: u32,
+            quote_id: &str,
+            amount: u64,
+        ) -> Result<(), String> {
+            self.sent
+                .lock()
+                .await
+                .push((channel_id, quote_id.to_string(), amount));
+            Ok(())
+        }
+
+        async fn quote_failed(&self, channel_id: u32, amount: u64, _reason: &str) {
+            self.failed.lock().await.push((channel_id, amount));
+        }
+    }
+
+    /// Fails every notification, as if the quote's downstream had disconnected
+    #[derive(Default)]
+    struct UnreachableNotifier {
+        attempts: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait::async_trait]
+    impl QuoteNotifier for UnreachableNotifier {
+        async fn notify_paid(&self, channel_id: u32, _: &str, _: u64) -> Result<(), String> {
+            self.attempts
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Err(format!("No mint context for channel {}", channel_id))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evicted_quotes_are_reported_as_failed() {
+        let poller =
+            QuotePoller::new(Some("http://localhost:34261".to_string())).with_max_pending_quotes(1);
+        let notifier = RecordingNotifier::default();
+
+        let evicted = poller.register_quote("q1".to_string(), 7, 100, None).await;
+        poller.report_evicted(evicted, &notifier).await;
+        assert!(notifier.failed.lock().await.is_empty());
+
+        let evicted = poller.register_quote("q2".to_string(), 8, 200, None).await;
+        poller.report_evicted(evicted, &notifier).await;
+        assert_eq!(*notifier.failed.lock().await, vec![(7, 100)]);
+        assert!(notifier.sent.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_notification_to_missing_downstream_is_abandoned() {
+        let (base_url, _) = spawn_scripted_mint(vec![(200, r#"{"state":"PAID"}"#)]).await;
+        let poller = QuotePoller::new(Some(base_url.to_string())).with_max_notify_attempts(3);
+        poller
+            .register_quote("orphan".to_string(), 9, 64, None)
+            .await;
+        let notifier = UnreachableNotifier::default();
+        let client = poller.http_client();
+
+        for attempt in 1..=3 {
+            let Some((quote_id, quote_meta)) = poller.pending_quote_details().await.pop() else {
+                panic!("quote abandoned before attempt {}", attempt);
+            };
+            poller
+                .poll_quote(&client, &base_url, &notifier, &quote_id, &quote_meta)
+                .await;
+
+            if attempt < 3 {
+                // Held back from polling until the backoff has passed
+                let (_, quote) = poller.pending_quote_details().await.pop().unwrap();
+                assert_eq!(quote.notify_failures, attempt);
+                assert!(quote.notify_after.unwrap() > Instant::now());
+                let slot_offset = (POLL_INTERVAL / POLL_SLOTS) * quote.poll_slot;
+                assert!(poller.quotes_due_at(slot_offset).await.is_empty());
+            }
+        }
+
+        assert_eq!(
+            notifier.attempts.load(std::sync::atomic::Ordering::SeqCst),
+            3
+        );
+        assert!(poller.get_pending_quotes().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_metrics_follow_quote_lifecycle() {
+        let (base_url, _) = spawn_scripted_mint(vec![
+            (200, r#"{"state":"PAID"}"#),
+            (200, r#"{"state":"ISSUED"}"#),
+            (200, r#"{"state":"PAID"}"#),
+        ])
+        .await;
+        let poller = QuotePoller::new(Some(base_url.to_string()));
+        for (quote_id, channel_id) in [("paid", 1), ("issued", 2), ("orphan", 3), ("stale", 4)] {
+            poller
+                .register_quote(quote_id.to_string(), channel_id, 64, None)
+                .await;
+        }
+        let client = poller.http_client();
+        let details: HashMap<_, _> = poller.pending_quote_details().await.into_iter().collect();
+
+        // Replies are served in order: PAID, ISSUED, then PAID again
+        poller
+            .poll_quote(
+                &client,
+                &base_url,
+                &RecordingNotifier::default(),
+                "paid",
+                &details["paid"],
+            )
+            .await;
+        poller
+            .poll_quote(
+                &client,
+                &base_url,
+                &RecordingNotifier::default(),
+                "issued",
+                &details["issued"],
+            )
+            .await;
+        poller
+            .poll_quote(
+                &client,
+                &base_url,
+                &UnreachableNotifier::default(),
+                "orphan",
+                &details["orphan"],
+            )
+            .await;
+
+        if let Some(quote) = poller.pending_quotes.write().await.get_mut("stale") {
+            quote.created_at = Instant::now() - Duration::from_secs(301);
+        }
+        poller.cleanup_expired_quotes().await;
+
+        assert_eq!(
+            poller.metrics(),
+            QuotePollerStats {
+                quotes_registered: 4,
+                quotes_paid: 1,
+                quotes_issued_elsewhere: 1,
+                quotes_expired: 1,
+                notification_failures: 1,
+                quotes_evicted: 0,
+            }
+        );
+        // Only the quote whose notification failed is still waiting
+        assert_eq!(poller.get_pending_quotes().await.len(), 1);
+        assert_eq!(poller.get_quote_channel("orphan").await, Some(3));
+    }
+
+    fn temp_store_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "hashpool-{}-{}-{}.sqlite",
+            name,
+            std::process::id(),
+            unix_timestamp()
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_restored_quote_is_notified_when_paid() {
+        let db_path = temp_store_path("quote-store");
+        let (base_url, _) = spawn_scripted_mint(vec![(200, r#"{"state":"PAID"}"#)]).await;
+        let locking_key = vec![0x02; 33];
+
+        // Registered before the restart
+        let before = QuotePoller::new(Some(base_url.to_string()))
+            .with_store(QuoteStore::open(&db_path).await.unwrap());
+        before
+            .register_quote("survivor".to_string(), 7, 64, Some(locking_key.clone()))
+            .await;
+        drop(before);
+
+        let after = QuotePoller::new(Some(base_url.to_string()))
+            .with_store(QuoteStore::open(&db_path).await.unwrap());
+        assert_eq!(after.restore_pending_quotes().await.unwrap(), 1);
+        let (quote_id, quote_meta) = after.pending_quote_details().await.remove(0);
+        assert_eq!(quote_id, "survivor");
+        assert_eq!(quote_meta.locking_key, Some(locking_key.clone()));
+
+        // The miner reconnected and holds its old channel id again
+        let channels = MintIntegrationManager::new("127.0.0.1:34260".to_string());
+        channels.register_channel(7, Some(locking_key), 1).await;
+        let notifier = RecordingNotifier {
+            channels: Some(channels),
+            ..Default::default()
+        };
+        after
+            .poll_quote(
+                &after.http_client(),
+                &base_url,
+                &notifier,
+                &quote_id,
+                &quote_meta,
+            )
+            .await;
+
+        assert_eq!(
+            *notifier.sent.lock().await,
+            vec![(7, "survivor".to_string(), 64)]
+        );
+        // Once notified the quote is gone from memory and from disk
+        assert!(after.get_pending_quotes().await.is_empty());
+        assert_eq!(after.restore_pending_quotes().await.unwrap(), 0);
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+
+    #[tokio::test]
+    async fn test_restored_quote_is_not_delivered_to_reused_channel() {
+        let db_path = temp_store_path("quote-store-reused");
+        let (base_url, _) = spawn_scripted_mint(vec![
+            (200, r#"{"state":"PAID"}"#),
+            (200, r#"{"state":"PAID"}"#),
+        ])
+        .await;
+        let (ours, theirs) = (vec![0x02; 33], vec![0x03; 33]);
+
+        let before = QuotePoller::new(Some(base_url.to_string()))
+            .with_store(QuoteStore::open(&db_path).await.unwrap());
+        before
+            .register_quote("survivor".to_string(), 1, 64, Some(ours.clone()))
+            .await;
+        drop(before);
+
+        let after = QuotePoller::new(Some(base_url.to_string()))
+            .with_store(QuoteStore::open(&db_path).await.unwrap());
+        after.restore_pending_quotes().await.unwrap();
+        let (quote_id, quote_meta) = after.pending_quote_details().await.remove(0);
+
+        // Channel ids restart, so another miner now holds channel 1
+        let channels = MintIntegrationManager::new("127.0.0.1:34260".to_string());
+        channels.register_channel(1, Some(theirs), 1).await;
+        let notifier = RecordingNotifier {
+            channels: Some(channels),
+            ..Default::default()
+        };
+        let client = after.http_client();
+        after
+            .poll_quote(&client, &base_url, &notifier, &quote_id, &quote_meta)
+            .await;
+
+        // Parked rather than delivered or counted as a failed notification
+        assert!(notifier.sent.lock().await.is_empty());
+        assert_eq!(after.get_quote_channel("survivor").await, Some(1));
+        assert_eq!(after.metrics().notification_failures, 0);
+
+        // Delivered once the quote's own miner is back, on its new channel
+        notifier
+            .channels
+            .as_ref()
+            .unwrap()
+            .register_channel(2, Some(ours), 2)
+            .await;
+        after
+            .poll_quote(&client, &base_url, &notifier, &quote_id, &quote_meta)
+            .await;
+        assert_eq!(
+            *notifier.sent.lock().await,
+            vec![(2, "survivor".to_string(), 64)]
+        );
+        assert!(after.get_pending_quotes().await.is_empty());
+
+        let _ = std::fs::remove_file(&db_path);
+    }
 }
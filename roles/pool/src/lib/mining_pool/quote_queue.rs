@@ -0,0 +1,271 @@
+//! Bounded work queue between share acceptance and quote dispatch
+//!
+//! Accepted shares are pushed onto a fixed-capacity mpsc queue that a small
+//! pool of worker tasks drains. Each worker waits until the hub has taken its
+//! quote before picking up the next one, so a slow mint side fills the queue.
+//! When the queue is full the share's quote is shed and counted as failed
+//! instead of spawning yet another task, which keeps memory bounded when the
+//! mint side falls behind.
+//!
+//! Capacity and worker count default to [`QUOTE_QUEUE_CAPACITY`] and
+//! [`QUOTE_QUEUE_WORKERS`] and can be set with `quote_queue_capacity` and
+//! `quote_queue_workers` in the pool config.
+
+use std::sync::Arc;
+
+use ehash::QuoteDispatchError;
+use pool_stats::DownstreamStats;
+use quote_dispatcher::QuoteDispatcher;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{debug, warn};
+
+use super::{message_handler::dispatch_quote, mint_integration::MintIntegrationManager};
+
+/// Default maximum number of quotes waiting for a worker
+pub const QUOTE_QUEUE_CAPACITY: usize = 1024;
+/// Default number of worker tasks draining the queue
+pub const QUOTE_QUEUE_WORKERS: usize = 4;
+
+/// A single accepted share waiting for its quote to be dispatched
+pub struct QuoteJob {
+    pub dispatcher: Arc<QuoteDispatcher>,
+    pub mint_manager: Arc<MintIntegrationManager>,
+    pub stats: Option<Arc<DownstreamStats>>,
+    pub channel_id: u32,
+    pub sequence_number: u32,
+    pub header_hash: [u8; 32],
+    pub locking_key_hint: Option<Vec<u8>>,
+    /// Ehash minimum difficulty override, the dispatcher's default if unset
+    pub minimum_difficulty: Option<u32>,
+}
+
+impl QuoteJob {
+    fn record_failure(&self) {
+        if let Some(stats) = &self.stats {
+            stats.record_quote_failed();
+        }
+    }
+}
+
+/// Producer handle for the quote work queue
+#[derive(Clone)]
+pub struct QuoteQueue {
+    sender: mpsc::Sender<QuoteJob>,
+}
+
+impl QuoteQueue {
+    /// Create a queue with the given capacity (at least one), returning the receiving end
+    /// for workers
+    pub fn new(capacity: usize) -> (Self, mpsc::Receiver<QuoteJob>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        (Self { sender }, receiver)
+    }
+
+    /// Create a queue and spawn `workers` tasks to drain it
+    pub fn start(capacity: usize, workers: usize) -> Self {
+        let (queue, receiver) = Self::new(capacity);
+        Self::spawn_workers(receiver, workers);
+        queue
+    }
+
+    /// Spawn worker tasks that share a single receiver
+    pub fn spawn_workers(receiver: mpsc::Receiver<QuoteJob>, workers: usize) {
+        let receiver = Arc::new(Mutex::new(receiver));
+        for worker_id in 0..workers.max(1) {
+            let receiver = receiver.clone();
+            tokio::spawn(async move {
+                loop {
+                    let job = { receiver.lock().await.recv().await };
+                    let Some(job) = job else {
+                        debug!("Quote worker {} exiting: queue closed", worker_id);
+                        break;
+                    };
+                    run_job(job).await;
+                }
+            });
+        }
+    }
+
+    /// Enqueue a job without waiting. Returns `false` if the job was shed.
+    pub fn enqueue(&self, job: QuoteJob) -> bool {
+        match self.sender.try_send(job) {
+            Ok(()) => true,
+            Err(mpsc::error::TrySendError::Full(job)) => {
+                warn!(
+                    "Quote queue full; shedding quote for channel {} (seq {})",
+                    job.channel_id, job.sequence_number
+                );
+                job.record_failure();
+                false
+            }
+            Err(mpsc::error::TrySendError::Closed(job)) => {
+                warn!(
+                    "Quote queue closed; dropping quote for channel {} (seq {})",
+                    job.channel_id, job.sequence_number
+                );
+                job.record_failure();
+                false
+            }
+        }
+    }
+}
+
+async fn run_job(job: QuoteJob) {
+    let result = dispatch_quote(
+        job.dispatcher.clone(),
+        job.mint_manager.clone(),
+        job.channel_id,
+        job.sequence_number,
+        job.header_hash,
+        job.locking_key_hint.clone(),
+        job.minimum_difficulty,
+    )
+    .await;
+
+    match result {
+        Ok(()) => {
+            debug!(
+                "Successfully queued mint quote via dispatcher: channel={}, seq={}",
+                job.channel_id, job.sequence_number
+            );
+        }
+        Err(e) => {
+            warn!(
+                "Quote dispatch error for channel {} (seq {}): {}",
+                job.channel_id, job.sequence_number, e
+            );
+            // The dispatcher reports its own failures through its stats callback
+            if !matches!(e, QuoteDispatchError::QuoteDispatchFailed(_)) {
+                job.record_failure();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mint_pool_messaging::{
+        MessagingConfig, MessagingResult, MintPoolMessageHub, ParsedMintQuoteRequest,
+        PendingQuoteContext, ShareHash,
+    };
+    use quote_dispatcher::QuoteSink;
+    use std::{
+        sync::{atomic::Ordering, Mutex as StdMutex},
+        time::Duration,
+    };
+
+    fn job(stats: &Arc<DownstreamStats>, sequence_number: u32) -> QuoteJob {
+        let hub = MintPoolMessageHub::new(MessagingConfig::default());
+        QuoteJob {
+            dispatcher: Arc::new(QuoteDispatcher::new(hub, None, 32)),
+            mint_manager: Arc::new(MintIntegrationManager::new("127.0.0.1:34260".to_string())),
+            stats: Some(stats.clone()),
+            channel_id: 1,
+            sequence_number,
+            header_hash: [0u8; 32],
+            locking_key_hint: None,
+            minimum_difficulty: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queue_sheds_when_full() {
+        let stats = Arc::new(DownstreamStats::new());
+        // No workers are spawned, so nothing drains the queue
+        let (queue, _receiver) = QuoteQueue::new(4);
+
+        let accepted = (0..10).filter(|seq| queue.enqueue(job(&stats, *seq))).count();
+
+        assert_eq!(accepted, 4);
+        assert_eq!(stats.quotes_failed.load(Ordering::Relaxed), 6);
+    }
+
+    /// Never takes a quote, as if the mint connection had stalled
+    struct StalledHub;
+
+    #[async_trait::async_trait]
+    impl QuoteSink for StalledHub {
+        async fn send_quote_request(
+            &self,
+            _request: ParsedMintQuoteRequest,
+            _context: PendingQuoteContext,
+        ) -> MessagingResult<()> {
+            std::future::pending().await
+        }
+
+        async fn abandon_quote_request(&self, _share_hash: &ShareHash) {}
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stalled_hub_fills_queue() {
+        let stats = Arc::new(DownstreamStats::new());
+        let dispatcher = Arc::new(QuoteDispatcher::new(Arc::new(StalledHub), None, 32));
+        let stalled_job = |sequence_number| QuoteJob {
+            dispatcher: dispatcher.clone(),
+            locking_key_hint: Some([&[0x02][..], &[0u8; 32]].concat()),
+            ..job(&stats, sequence_number)
+        };
+        let queue = QuoteQueue::start(2, 1);
+
+        // The only worker picks up the first quote and waits on the hub with it
+        assert!(queue.enqueue(stalled_job(0)));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        assert!(queue.enqueue(stalled_job(1)));
+        assert!(queue.enqueue(stalled_job(2)));
+        assert!(!queue.enqueue(stalled_job(3)));
+        assert_eq!(stats.quotes_failed.load(Ordering::Relaxed), 1);
+    }
+
+    /// Takes every quote, recording its amount
+    #[derive(Default)]
+    struct RecordingHub(StdMutex<Vec<u64>>);
+
+    #[async_trait::async_trait]
+    impl QuoteSink for RecordingHub {
+        async fn send_quote_request(
+            &self,
+            _request: ParsedMintQuoteRequest,
+            context: PendingQuoteContext,
+        ) -> MessagingResult<()> {
+            self.0.lock().unwrap().push(context.amount);
+            Ok(())
+        }
+
+        async fn abandon_quote_request(&self, _share_hash: &ShareHash) {}
+    }
+
+    #[tokio::test]
+    async fn test_job_minimum_difficulty_overrides_dispatcher_default() {
+        let stats = Arc::new(DownstreamStats::new());
+        let hub = Arc::new(RecordingHub::default());
+        let dispatcher = Arc::new(QuoteDispatcher::new(hub.clone(), None, 32));
+        // 39 leading zero bits
+        let mut header_hash = [0u8; 32];
+        header_hash[4] = 0x01;
+        let quote_job = |sequence_number, minimum_difficulty| QuoteJob {
+            dispatcher: dispatcher.clone(),
+            header_hash,
+            locking_key_hint: Some([&[0x02][..], &[0u8; 32]].concat()),
+            minimum_difficulty,
+            ..job(&stats, sequence_number)
+        };
+
+        run_job(quote_job(0, None)).await;
+        run_job(quote_job(1, Some(36))).await;
+
+        assert_eq!(*hub.0.lock().unwrap(), vec![1 << 7, 1 << 3]);
+        assert_eq!(stats.quotes_failed.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_queue_sheds_when_closed() {
+        let stats = Arc::new(DownstreamStats::new());
+        let (queue, receiver) = QuoteQueue::new(4);
+        drop(receiver);
+
+        assert!(!queue.enqueue(job(&stats, 0)));
+        assert_eq!(stats.quotes_failed.load(Ordering::Relaxed), 1);
+    }
+}
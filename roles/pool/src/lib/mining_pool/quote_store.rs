@@ -0,0 +1,117 @@
+//! SQLite persistence for the quote poller's pending quotes
+//!
+//! The quote poller keeps its quote → channel mapping in memory, so a restart used to
+//! forget every quote that was not paid yet and its MintQuoteNotification was never sent.
+//! With `quote_store_path` configured the poller writes each pending quote through to this
+//! store and reloads the table on startup.
+//!
+//! Each quote is stored with the locking pubkey it pays out to. Channel ids restart after a
+//! pool restart, so restored quotes are matched to a channel by that key, not by the stored
+//! channel id.
+//!
+//! Only the poller's quotes are stored. Quote requests still waiting in the message hub
+//! for a mint response are short-lived and are not persisted; a restart drops them as
+//! before.
+
+use std::{path::Path, str::FromStr};
+
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Pool, Row, Sqlite,
+};
+
+/// A pending quote as stored on disk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredQuote {
+    pub quote_id: String,
+    pub channel_id: u32,
+    pub amount: u64,
+    /// Compressed locking pubkey the quote pays out to
+    pub locking_key: Vec<u8>,
+    /// Unix timestamp at which the quote was registered
+    pub created_at: u64,
+}
+
+/// Pending quotes table in a SQLite database
+pub struct QuoteStore {
+    pool: Pool<Sqlite>,
+}
+
+impl QuoteStore {
+    /// Open the database at `path`, creating it and its parent directories if needed
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, sqlx::Error> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let options = SqliteConnectOptions::from_str(&format!("sqlite://{}", path.display()))?
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS pending_quotes (
+                quote_id TEXT PRIMARY KEY,
+                channel_id INTEGER NOT NULL,
+                amount INTEGER NOT NULL,
+                locking_key BLOB NOT NULL,
+                created_at INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(Self { pool })
+    }
+
+    /// Store a quote, replacing any earlier entry with the same id
+    pub async fn save(&self, quote: &StoredQuote) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT OR REPLACE INTO pending_quotes (quote_id, channel_id, amount, locking_key, created_at) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&quote.quote_id)
+        .bind(quote.channel_id as i64)
+        .bind(quote.amount as i64)
+        .bind(&quote.locking_key)
+        .bind(quote.created_at as i64)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn remove(&self, quote_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM pending_quotes WHERE quote_id = ?")
+            .bind(quote_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Every stored quote, oldest first
+    pub async fn load(&self) -> Result<Vec<StoredQuote>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT quote_id, channel_id, amount, locking_key, created_at FROM pending_quotes \
+             ORDER BY created_at",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(StoredQuote {
+                    quote_id: row.try_get("quote_id")?,
+                    channel_id: row.try_get::<i64, _>("channel_id")? as u32,
+                    amount: row.try_get::<i64, _>("amount")? as u64,
+                    locking_key: row.try_get("locking_key")?,
+                    created_at: row.try_get::<i64, _>("created_at")? as u64,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,85 @@
+//! Per-share events for the stats service
+//!
+//! Snapshots only carry windowed aggregates. When `emit_share_events` is enabled the
+//! pool additionally sends a `ShareSubmitted` message for every accepted share. Events
+//! go through a bounded channel to a single forwarding task so share validation never
+//! waits on the stats connection; if the channel is full the event is dropped.
+
+use stats::{stats_adapter::ShareSubmitted, stats_client::StatsClient};
+use stats_sv2::types::unix_timestamp;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+/// Maximum number of share events waiting to be forwarded
+pub const SHARE_EVENT_CAPACITY: usize = 4096;
+
+/// Producer handle for per-share stats events
+#[derive(Clone)]
+pub struct ShareEventEmitter {
+    sender: mpsc::Sender<ShareSubmitted>,
+}
+
+impl ShareEventEmitter {
+    /// Create an emitter with the given capacity, returning the receiving end
+    pub fn new(capacity: usize) -> (Self, mpsc::Receiver<ShareSubmitted>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        (Self { sender }, receiver)
+    }
+
+    /// Create an emitter and spawn the task forwarding its events to `stats_addr`
+    pub fn start(stats_addr: String, capacity: usize) -> Self {
+        let (emitter, mut receiver) = Self::new(capacity);
+        tokio::spawn(async move {
+            let client = StatsClient::<ShareSubmitted>::new(stats_addr);
+            while let Some(event) = receiver.recv().await {
+                if let Err(e) = client.send_snapshot(event).await {
+                    warn!("Failed to send share event: {}", e);
+                }
+            }
+        });
+        emitter
+    }
+
+    /// Queue a `ShareSubmitted` event. Returns `false` if the event was dropped.
+    pub fn emit(&self, downstream_id: u32, channel_id: u32, difficulty: f64) -> bool {
+        let event = ShareSubmitted {
+            downstream_id,
+            channel_id,
+            difficulty,
+            timestamp: unix_timestamp(),
+        };
+        match self.sender.try_send(event) {
+            Ok(()) => true,
+            Err(e) => {
+                debug!("Dropping share event for downstream {}: {}", downstream_id, e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_emit_produces_one_share_submitted() {
+        let (emitter, mut receiver) = ShareEventEmitter::new(8);
+
+        assert!(emitter.emit(3, 7, 1024.0));
+
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(event.downstream_id, 3);
+        assert_eq!(event.channel_id, 7);
+        assert_eq!(event.difficulty, 1024.0);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_emit_drops_when_full() {
+        let (emitter, _receiver) = ShareEventEmitter::new(1);
+
+        assert!(emitter.emit(1, 1, 1.0));
+        assert!(!emitter.emit(1, 1, 1.0));
+    }
+}
@@ -14,17 +14,21 @@ pub mod status;
 pub mod template_receiver;
 use async_channel::{bounded, unbounded};
 use config::PoolConfig;
+use ehash::AmountPolicy;
 use error::PoolError;
-use mining_pool::Pool;
+use mining_pool::{coinbase_outputs::CoinbaseOutputProvider, Pool};
 use mint_pool_messaging::{MessagingConfig, MintPoolMessageHub};
-use quote_dispatcher::QuoteDispatcher;
-use std::sync::{Arc, Mutex};
+use quote_dispatcher::{QuoteDispatcher, DEFAULT_BATCH_FLUSH_INTERVAL};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use stratum_common::roles_logic_sv2::bitcoin::{
     absolute::LockTime,
     blockdata::witness::Witness,
     script::ScriptBuf,
     transaction::{OutPoint, Transaction, Version},
-    Amount, Sequence, TxIn, TxOut,
+    Sequence, TxIn,
 };
 use template_receiver::TemplateRx;
 use tokio::select;
@@ -37,6 +41,7 @@ use tracing::{error, info, warn};
 pub struct PoolSv2 {
     config: PoolConfig,
     status_tx: Arc<Mutex<Option<async_channel::Sender<status::Status>>>>,
+    status_loop: Arc<Mutex<Option<tokio::task::JoinHandle<Result<(), PoolError>>>>>,
 }
 
 impl PoolSv2 {
@@ -45,6 +50,7 @@ impl PoolSv2 {
         PoolSv2 {
             config,
             status_tx: Arc::new(Mutex::new(None)),
+            status_loop: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -89,33 +95,66 @@ impl PoolSv2 {
                 mpsc_buffer_size: cfg.mpsc_buffer_size,
                 max_retries: cfg.max_retries,
                 timeout_ms: cfg.timeout_ms,
+                dedup_window_ms: cfg
+                    .dedup_window_ms
+                    .unwrap_or_else(|| MessagingConfig::default_dedup_window_ms(cfg.timeout_ms)),
+                reject_orphan_responses: cfg.reject_orphan_responses,
+                max_response_age_ms: cfg.max_response_age_ms,
+                max_pending_quotes: cfg.max_pending_quotes,
+                ..MessagingConfig::default()
             })
             .unwrap_or_default();
         let mint_hub = MintPoolMessageHub::new(messaging_config);
         let minimum_difficulty = config.minimum_difficulty().unwrap_or(32);
-        let quote_dispatcher = if sv2_messaging_cfg
+        let quote_dispatcher = if config.no_mint() {
+            info!("no_mint is set: shares are accepted without ehash quotes");
+            None
+        } else if sv2_messaging_cfg
             .as_ref()
             .map(|cfg| cfg.enabled)
             .unwrap_or(true)
         {
-            Some(Arc::new(QuoteDispatcher::new(
+            let mut amount_policy = AmountPolicy::new();
+            if let Some(minimum) = config.quote_min_amount() {
+                amount_policy = amount_policy
+                    .with_minimum(minimum, config.quote_below_min().unwrap_or_default());
+            }
+            let mut dispatcher = QuoteDispatcher::new(
                 mint_hub.clone(),
                 sv2_messaging_cfg.clone(),
                 minimum_difficulty,
-            )))
+            )
+            .with_amount_policy(amount_policy);
+            if let Some(max_batch) = config.quote_batch_size() {
+                let flush_interval = config
+                    .quote_batch_flush_ms()
+                    .map_or(DEFAULT_BATCH_FLUSH_INTERVAL, Duration::from_millis);
+                info!(
+                    "Batching mint quotes: up to {} per batch, flushed every {:?}",
+                    max_batch, flush_interval
+                );
+                dispatcher = dispatcher.with_batching(max_batch, flush_interval);
+            }
+            Some(Arc::new(dispatcher))
         } else {
             None
         };
+        if let (Some(dispatcher), Some(mint_http_url)) =
+            (quote_dispatcher.as_ref(), config.mint_http_url())
+        {
+            mining_pool::mint_keys::spawn_denomination_sync(dispatcher.clone(), mint_http_url);
+        }
+
+        let coinbase_outputs = Arc::new(CoinbaseOutputProvider::from_config(&config)?);
 
         // Prepare coinbase output information required by TemplateRx.
         // We use an empty output here only for calculation of the size and sigops of the coinbase
-        // output. We still don't know the template revenue.
-        let empty_coinbase_output = TxOut {
-            value: Amount::from_sat(0),
-            script_pubkey: config.coinbase_reward_script().script_pubkey(),
-        };
+        // output. We still don't know the template revenue. With rotation enabled, the longest
+        // script we may pay to is used so every rotated output fits.
+        let empty_coinbase_output = coinbase_outputs.sizing_output()?;
         let coinbase_output_len = empty_coinbase_output.size() as u32;
         let tp_authority_public_key = config.tp_authority_public_key().cloned();
+        let max_reconnect_attempts = config.max_reconnect_attempts().unwrap_or(0);
 
         // create a dummy coinbase transaction with the empty output
         // this is used to calculate the sigops of the coinbase output
@@ -135,20 +174,30 @@ impl PoolSv2 {
 
         // --- Spawn Template Receiver Task ---
         let tp_address = config.tp_address().clone();
-        let cloned_status_tx = status_tx.clone();
+        let upstream_status_tx = status::Sender::Upstream(status_tx.clone());
         tokio::spawn(async move {
-            let _ = TemplateRx::connect(
-                tp_address.parse().unwrap(),
+            if let Err(e) = TemplateRx::connect(
+                tp_address,
                 s_new_t,
                 s_prev_hash,
                 r_solution,
                 r_message_recv_signal,
-                status::Sender::Upstream(cloned_status_tx),
+                upstream_status_tx.clone(),
                 coinbase_output_len,
                 coinbase_output_sigops,
                 tp_authority_public_key,
+                max_reconnect_attempts,
             )
-            .await;
+            .await
+            {
+                // Only reachable once the connection attempts are bounded and used up, or the
+                // handshake fails; either way the pool cannot get templates
+                let _ = upstream_status_tx
+                    .send(status::Status {
+                        state: status::State::TemplateProviderShutdown(e),
+                    })
+                    .await;
+            }
         });
 
         // NOTE: Quote dispatcher task is now spawned in Pool::start() and integrated
@@ -162,6 +211,7 @@ impl PoolSv2 {
             s_solution,
             quote_dispatcher.clone(),
             mint_hub.clone(),
+            coinbase_outputs,
             s_message_recv_signal,
             status::Sender::DownstreamListener(status_tx),
             config.shares_per_minute(),
@@ -173,7 +223,7 @@ impl PoolSv2 {
         // Start the error handling loop
         // See `./status.rs` and `utils/error_handling` for information on how this operates
         // --- Spawn Status Monitoring and Shutdown Handling Loop ---
-        tokio::spawn(async move {
+        let status_loop = tokio::spawn(async move {
             loop {
                 let task_status = select! {
                     task_status = status_rx.recv() => task_status,
@@ -205,12 +255,12 @@ impl PoolSv2 {
                             err
                         );
                         let _ = send_stop_signal.send(());
-                        break;
+                        return Err(err);
                     }
                     status::State::TemplateProviderShutdown(err) => {
                         error!("SHUTDOWN from Upstream: {}\nTry to reconnecting or connecting to a new upstream", err);
                         let _ = send_stop_signal.send(());
-                        break;
+                        return Err(err);
                     }
                     status::State::Healthy(msg) => {
                         info!("HEALTHY message: {}", msg);
@@ -227,10 +277,33 @@ impl PoolSv2 {
                     }
                 }
             }
+            Ok(())
         });
+        if let Ok(mut slot) = self.status_loop.lock() {
+            *slot = Some(status_loop);
+        }
         Ok(())
     }
 
+    /// Waits for a started pool to stop.
+    ///
+    /// Returns the error that shut the pool down, such as
+    /// [`PoolError::ReconnectAttemptsExhausted`], or `Ok` after a requested shutdown or an
+    /// interrupt. Returns immediately if the pool was never started or is already awaited.
+    pub async fn wait(&self) -> Result<(), PoolError> {
+        let status_loop = self
+            .status_loop
+            .lock()
+            .ok()
+            .and_then(|mut slot| slot.take());
+        match status_loop {
+            Some(handle) => handle
+                .await
+                .map_err(|e| PoolError::ComponentShutdown(e.to_string()))?,
+            None => Ok(()),
+        }
+    }
+
     /// Initiates a graceful shutdown of the running pool instance.
     ///
     /// It attempts to acquire the lock on the `status_tx` mutex. If successful
@@ -1,13 +1,18 @@
 //! Stats integration for Pool
 //!
-//! Implements `StatsSnapshotProvider` trait for Pool to send snapshot updates
-//! to the stats service for web dashboard consumption.
+//! Builds the Pool's snapshot updates for the stats service's web dashboard.
+//!
+//! Unlike the translator, the Pool does not implement `StatsSnapshotProvider`: its status
+//! needs the mint hub's stats, which are read asynchronously before the Pool lock is taken
+//! and passed in.
 
 use super::mining_pool::Pool;
-use stats::stats_adapter::{
-    PoolStatus, ProxyConnection, ServiceConnection, ServiceType, StatsSnapshotProvider,
+use mint_pool_messaging::MessageHubStats;
+use stats::{
+    health::HealthInputs,
+    stats_adapter::{PoolStatus, ProxyConnection, ServiceConnection, ServiceType},
 };
-use stats_sv2::types::{DownstreamSnapshot, ServiceSnapshot, ServiceType as MetricsServiceType, unix_timestamp};
+use stats_sv2::types::{DownstreamSnapshot, ServiceSnapshot, ServiceType as MetricsServiceType, unix_timestamp, UNKNOWN_USER_AGENT};
 use std::time::SystemTime;
 
 // Unix timestamp helper (kept for potential future use)
@@ -18,10 +23,9 @@ fn _unix_timestamp_helper() -> u64 {
         .as_secs()
 }
 
-impl StatsSnapshotProvider for Pool {
-    type Snapshot = PoolStatus;
-
-    fn get_snapshot(&self) -> PoolStatus {
+impl Pool {
+    /// Operational status shown on the dashboard, given the mint hub's current stats.
+    pub fn get_snapshot(&self, hub_stats: &MessageHubStats) -> PoolStatus {
         // Get service connections (pool, mint, jd-server if connected)
         let mut services = Vec::new();
 
@@ -61,8 +65,7 @@ impl StatsSnapshotProvider for Pool {
                 (d.address.to_string(), d.requires_custom_work)
             }) {
                 // Lookup stats from registry
-                let (shares, quotes, ehash, last_share) =
-                    stats_snapshot.get(id).copied().unwrap_or((0, 0, 0, None));
+                let stats = stats_snapshot.get(id).copied().unwrap_or_default();
 
                 // Track both Translators and JDCs
                 // JDC: requires_custom_work = true (Job Declaration Client)
@@ -71,25 +74,43 @@ impl StatsSnapshotProvider for Pool {
                     id: *id,
                     address,
                     channels: Vec::new(), // Would need to track channel mapping
-                    shares_submitted: shares,
-                    quotes_created: quotes,
-                    ehash_mined: ehash,
-                    last_share_at: last_share,
+                    shares_submitted: stats.shares_submitted,
+                    quotes_created: stats.quotes_created,
+                    quotes_failed: stats.quotes_failed,
+                    ehash_mined: stats.ehash_mined,
+                    last_share_at: stats.last_share_at,
                     work_selection: requires_custom_work, // JDC has work_selection = true
+                    quote_success_ratio: stats.quote_success_ratio,
+                    difficulty_histogram: stats.difficulty_histogram.to_vec(),
+                    reject_ratio: stats.reject_ratio,
+                    shares_per_minute: stats.shares_per_minute,
                 });
             }
         }
 
+        let health = HealthInputs {
+            upstream_connected: self.has_chain_tip(),
+            mint_available: self
+                .mint_connection
+                .as_ref()
+                .map(|_| hub_stats.mint_available),
+            pending_quotes: hub_stats.pending_quotes,
+        }
+        .status();
+
         PoolStatus {
             services,
             downstream_proxies,
             listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: self.stats_registry.shares_since_last_block(),
+            last_block_found_at: self.stats_registry.last_block_found_at(),
+            connections: self.connections.snapshot(),
+            health,
+            quote_poller: self.quote_poller.as_ref().map(|poller| poller.metrics()),
             timestamp: unix_timestamp(),
         }
     }
-}
 
-impl Pool {
     /// Get a ServiceSnapshot for time-series metrics collection.
     pub fn get_metrics_snapshot(&self) -> ServiceSnapshot {
         let mut downstreams = Vec::new();
@@ -103,6 +124,7 @@ impl Pool {
                         downstream_id: *id,
                         name: format!("translator_{}", id),
                         address,
+                        user_agent: UNKNOWN_USER_AGENT.to_string(),
                         shares_lifetime: stats.shares_submitted.load(std::sync::atomic::Ordering::Relaxed),
                         shares_in_window: stats.shares_in_window(),
                         sum_difficulty_in_window: stats.sum_difficulty_in_window(),
@@ -164,6 +164,12 @@ pub async fn handle_error(sender: &Sender, e: PoolError) -> error_handling::Erro
         PoolError::ComponentShutdown(_) => {
             send_status(sender, e, error_handling::ErrorBranch::Break).await
         }
+        PoolError::InvalidAddress { .. } | PoolError::InvalidCoinbaseConfig { .. } => {
+            send_status(sender, e, error_handling::ErrorBranch::Break).await
+        }
+        PoolError::ReconnectAttemptsExhausted { .. } => {
+            send_status(sender, e, error_handling::ErrorBranch::Break).await
+        }
         PoolError::Sv2ProtocolError(_) => {
             send_status(sender, e, error_handling::ErrorBranch::Break).await
         }
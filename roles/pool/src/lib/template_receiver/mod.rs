@@ -17,7 +17,11 @@ use error_handling::handle_result;
 use key_utils::Secp256k1PublicKey;
 use std::{convert::TryInto, net::SocketAddr, sync::Arc};
 use stratum_common::{
-    network_helpers_sv2::noise_connection::Connection,
+    network_helpers_sv2::{
+        noise_connection::Connection,
+        resolve::resolve_host_port,
+        retry::{retry_with_backoff, RetryPolicy},
+    },
     roles_logic_sv2::{
         self, codec_sv2,
         codec_sv2::{HandshakeRole, Initiator},
@@ -36,6 +40,50 @@ mod message_handler;
 mod setup_connection;
 use setup_connection::SetupConnectionHandler;
 
+/// Reconnect to the template provider indefinitely, backing off from 1s up to 30s
+const TP_CONNECT_RETRY: RetryPolicy = RetryPolicy {
+    max_attempts: None,
+    base_delay: std::time::Duration::from_secs(1),
+    max_delay: std::time::Duration::from_secs(30),
+    jitter: 0.2,
+};
+
+/// [`TP_CONNECT_RETRY`] limited to `max_reconnect_attempts` attempts, or unlimited for `0`
+fn tp_connect_retry(max_reconnect_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: (max_reconnect_attempts > 0).then_some(max_reconnect_attempts),
+        ..TP_CONNECT_RETRY
+    }
+}
+
+/// Opens a TCP connection to `address`, retrying as `policy` allows.
+///
+/// The address is re-resolved on every attempt so DNS changes are picked up. Once the policy's
+/// attempts are used up this fails with [`PoolError::ReconnectAttemptsExhausted`].
+async fn connect_with_retry(
+    address: &str,
+    policy: &RetryPolicy,
+) -> PoolResult<(TcpStream, SocketAddr)> {
+    retry_with_backoff(policy, || async {
+        let resolved = resolve_host_port(address).await.map_err(|err| {
+            warn!("Failed to resolve {}: {}. Retrying...", address, err);
+            PoolError::Io(err)
+        })?;
+        match TcpStream::connect(resolved).await {
+            Ok(stream) => Ok((stream, resolved)),
+            Err(err) => {
+                warn!("Failed to connect to {}: {}. Retrying...", resolved, err);
+                Err(PoolError::Io(err))
+            }
+        }
+    })
+    .await
+    .map_err(|err| PoolError::ReconnectAttemptsExhausted {
+        attempts: policy.max_attempts.unwrap_or_default(),
+        last_error: err.to_string(),
+    })
+}
+
 /// Manages communication with the template provider and relays relevant messages downstream.
 ///
 /// This struct maintains connection channels to the Template Provider and handles:
@@ -68,7 +116,7 @@ impl TemplateRx {
     /// from downstream (`on_new_solution`).
     #[allow(clippy::too_many_arguments)]
     pub async fn connect(
-        address: SocketAddr,
+        address: String,
         templ_sender: Sender<NewTemplate<'static>>,
         prev_h_sender: Sender<SetNewPrevHash<'static>>,
         solution_receiver: Receiver<SubmitSolution<'static>>,
@@ -77,17 +125,11 @@ impl TemplateRx {
         coinbase_out_len: u32,
         coinbase_out_sigops: u16,
         expected_tp_authority_public_key: Option<Secp256k1PublicKey>,
+        max_reconnect_attempts: u32,
     ) -> PoolResult<()> {
         // Attempt to establish a TCP connection to the template provider, retrying on failure.
-        let stream = loop {
-            match TcpStream::connect(address).await {
-                Ok(stream) => break stream,
-                Err(err) => {
-                    warn!("Failed to connect to {}: {}. Retrying...", address, err);
-                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-                }
-            }
-        };
+        let (stream, address) =
+            connect_with_retry(&address, &tp_connect_retry(max_reconnect_attempts)).await?;
         info!("Connected to template distribution server at {}", address);
 
         // Initialize the Noise protocol initiator for secure communication.
@@ -237,3 +279,29 @@ impl TemplateRx {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_connect_gives_up_after_max_reconnect_attempts() {
+        // Nothing listens on a port freed right after binding it
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let policy = RetryPolicy {
+            base_delay: std::time::Duration::from_millis(1),
+            max_delay: std::time::Duration::from_millis(1),
+            ..tp_connect_retry(3)
+        };
+        let err = connect_with_retry(&address, &policy).await.unwrap_err();
+        assert!(matches!(
+            err,
+            PoolError::ReconnectAttemptsExhausted { attempts: 3, .. }
+        ));
+
+        assert_eq!(tp_connect_retry(0).max_attempts, None);
+    }
+}
@@ -11,14 +11,20 @@ use tracing::{error, info};
 
 mod args;
 use args::process_cli_args;
-use config_helpers_sv2::logging::init_logging;
+use config_helpers_sv2::logging::init_logging_with_filter;
+use shared_config::RoleConfig;
 
 /// Initializes logging, parses arguments, loads configuration, and starts the Pool runtime.
 #[tokio::main]
 async fn main() {
     let config = process_cli_args();
-    init_logging(config.log_dir());
-    let _ = PoolSv2::new(config).start().await;
+    init_logging_with_filter(config.log_dir(), config.log_filter());
+    config.log_effective_config();
+    let pool = PoolSv2::new(config);
+    if let Err(e) = pool.start().await {
+        error!("Pool(bin): Failed to start: {}", e);
+        std::process::exit(1);
+    }
     select! {
         interrupt_signal = tokio::signal::ctrl_c() => {
             match interrupt_signal {
@@ -32,5 +38,12 @@ async fn main() {
                 },
             }
         }
+        // Exit non-zero on a fatal error so a supervisor can restart the pool
+        result = pool.wait() => {
+            if let Err(err) = result {
+                error!("Pool(bin): Stopped after fatal error: {}", err);
+                std::process::exit(1);
+            }
+        }
     };
 }
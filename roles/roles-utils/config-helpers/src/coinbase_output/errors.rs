@@ -25,6 +25,10 @@ pub enum Error {
     UnknownOutputScriptType,
     /// Error from the `miniscript` crate.
     Miniscript(miniscript::Error),
+    /// A descriptor used for address rotation has no `*` wildcard to derive from
+    DescriptorNotRanged,
+    /// Deriving a script from a ranged descriptor failed
+    Derivation(String),
 }
 
 impl fmt::Display for Error {
@@ -42,6 +46,8 @@ impl fmt::Display for Error {
             UnknownOutputScriptType => write!(f, "Unknown script type in config"),
             InvalidOutputScript => write!(f, "Invalid output_script_value for your script type. It must be a valid public key/script"),
             Miniscript(ref e) => write!(f, "Miniscript: {e}"),
+            DescriptorNotRanged => write!(f, "Descriptor has no wildcard (*) to derive coinbase outputs from"),
+            Derivation(ref e) => write!(f, "Deriving coinbase output: {e}"),
         }
     }
 }
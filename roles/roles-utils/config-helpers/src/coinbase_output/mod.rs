@@ -3,7 +3,7 @@ mod serde_types;
 
 use miniscript::{
     bitcoin::{address::NetworkUnchecked, hex::FromHex as _, Address, Network, ScriptBuf},
-    DefiniteDescriptorKey, Descriptor,
+    DefiniteDescriptorKey, Descriptor, DescriptorPublicKey,
 };
 
 pub use errors::Error;
@@ -112,6 +112,38 @@ impl CoinbaseRewardScript {
     }
 }
 
+/// Ranged output descriptor (one containing a `*` wildcard).
+///
+/// Used to derive a distinct coinbase output for each index, e.g. to rotate the pool's
+/// payout address from an xpub.
+#[derive(Debug, Clone)]
+pub struct CoinbaseRewardDescriptor {
+    descriptor: Descriptor<DescriptorPublicKey>,
+}
+
+impl CoinbaseRewardDescriptor {
+    /// Creates a new [`CoinbaseRewardDescriptor`] from a ranged descriptor string.
+    pub fn from_descriptor(s: &str) -> Result<Self, Error> {
+        let descriptor = s.parse::<Descriptor<DescriptorPublicKey>>()?;
+        if !descriptor.has_wildcard() {
+            return Err(Error::DescriptorNotRanged);
+        }
+        // Hardened wildcards can't be derived from public keys; fail here rather than
+        // on the first template.
+        let this = Self { descriptor };
+        this.script_pubkey_at(0)?;
+        Ok(this)
+    }
+
+    /// The `scriptPubKey` derived at `index`.
+    pub fn script_pubkey_at(&self, index: u32) -> Result<ScriptBuf, Error> {
+        self.descriptor
+            .at_derivation_index(index)
+            .map(|desc| desc.script_pubkey())
+            .map_err(|e| Error::Derivation(e.to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -374,4 +406,21 @@ mod tests {
             "Miniscript: unexpected «Public keys must be 64/66/130 characters in size»",
         );
     }
+
+    #[test]
+    fn ranged_descriptor() {
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+
+        let ranged =
+            CoinbaseRewardDescriptor::from_descriptor(&format!("wpkh({xpub}/0/*)")).unwrap();
+        assert_ne!(
+            ranged.script_pubkey_at(0).unwrap(),
+            ranged.script_pubkey_at(1).unwrap()
+        );
+
+        assert!(matches!(
+            CoinbaseRewardDescriptor::from_descriptor(&format!("wpkh({xpub}/0/0)")),
+            Err(Error::DescriptorNotRanged)
+        ));
+    }
 }
@@ -1,5 +1,7 @@
 mod coinbase_output;
-pub use coinbase_output::{CoinbaseRewardScript, Error as CoinbaseOutputError};
+pub use coinbase_output::{
+    CoinbaseRewardDescriptor, CoinbaseRewardScript, Error as CoinbaseOutputError,
+};
 
 pub mod logging;
 
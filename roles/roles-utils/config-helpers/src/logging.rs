@@ -7,9 +7,13 @@ use tracing_subscriber::{fmt, prelude::*, EnvFilter, Registry};
 /// If `log_file` is Some, logs will be written to both stdout and the file.
 /// If `log_level` is not provided or is invalid, it defaults to "info".
 pub fn init_logging(log_file: Option<&Path>) {
-    let rust_log = std::env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string());
-    let log_level_filter = LevelFilter::from_str(&rust_log).unwrap_or(LevelFilter::INFO);
-    let env_filter = EnvFilter::new(log_level_filter.to_string());
+    init_logging_with_filter(log_file, None);
+}
+
+/// Like [`init_logging`], but seeds the filter from a config-provided directive string
+/// (e.g. `"info,roles_logic_sv2=warn"`) when `RUST_LOG` is not set. `RUST_LOG` always wins.
+pub fn init_logging_with_filter(log_file: Option<&Path>, log_filter: Option<&str>) {
+    let env_filter = resolve_env_filter(std::env::var("RUST_LOG").ok(), log_filter);
 
     let subscriber: Box<dyn tracing::Subscriber + Send + Sync> = match log_file {
         Some(path) => {
@@ -39,3 +43,63 @@ pub fn init_logging(log_file: Option<&Path>) {
 
     tracing::subscriber::set_global_default(subscriber).expect("Failed to set global subscriber");
 }
+
+/// Pick the filter from `RUST_LOG` if present, else from the config directive, else "info".
+fn resolve_env_filter(rust_log: Option<String>, log_filter: Option<&str>) -> EnvFilter {
+    if let Some(rust_log) = rust_log {
+        let log_level_filter = LevelFilter::from_str(&rust_log).unwrap_or(LevelFilter::INFO);
+        return EnvFilter::new(log_level_filter.to_string());
+    }
+    match log_filter.map(EnvFilter::try_new) {
+        Some(Ok(filter)) => filter,
+        Some(Err(e)) => {
+            // The subscriber isn't installed yet, so this can't go through tracing
+            eprintln!("Invalid log_filter in config ({e}); defaulting to info");
+            EnvFilter::new(LevelFilter::INFO.to_string())
+        }
+        None => EnvFilter::new(LevelFilter::INFO.to_string()),
+    }
+}
+
+/// Placeholder logged in place of secret configuration values.
+pub const REDACTED: &str = "<redacted>";
+
+/// Log a service's resolved configuration as a single `info` event.
+///
+/// `entries` are `(key, value)` pairs in the order they should appear. Callers are responsible
+/// for replacing secrets with [`REDACTED`] before passing them in.
+pub fn log_effective_config(service: &str, entries: &[(&'static str, String)]) {
+    let config = entries
+        .iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    tracing::info!(service, %config, "Effective configuration");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_filter_used_without_rust_log() {
+        let filter = resolve_env_filter(None, Some("warn,pool_sv2=debug"));
+        assert!(filter.to_string().contains("pool_sv2=debug"));
+        assert_eq!(filter.max_level_hint(), Some(LevelFilter::DEBUG));
+    }
+
+    #[test]
+    fn rust_log_overrides_config_filter() {
+        let filter = resolve_env_filter(Some("error".to_string()), Some("debug"));
+        assert_eq!(filter.to_string(), "error");
+    }
+
+    #[test]
+    fn defaults_to_info() {
+        assert_eq!(resolve_env_filter(None, None).to_string(), "info");
+        assert_eq!(
+            resolve_env_filter(None, Some("not a [valid filter")).to_string(),
+            "info"
+        );
+    }
+}
@@ -1,5 +1,97 @@
-use config::{Config, ConfigError, File, FileFormat};
-use serde::Deserialize;
+use std::{fmt::Display, path::Path};
+
+use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+use config::{Config, ConfigError, Environment, File, FileFormat};
+use config_helpers_sv2::logging::log_effective_config;
+use serde::{de::DeserializeOwned, Deserialize};
+
+/// Prefix of environment variables that override values from the config file, unless the
+/// config sets its own [`RoleConfig::ENV_PREFIX`].
+///
+/// Nested keys are separated by `__`, so `HASHPOOL_POOL__PORT=34260` overrides `port` in the
+/// `[pool]` table. Environment values always win over the file.
+pub const ENV_PREFIX: &str = "HASHPOOL";
+
+/// Loading, validation and logging shared by every role's configuration file
+pub trait RoleConfig: DeserializeOwned {
+    /// Service name the effective configuration is logged under
+    const SERVICE: &'static str;
+
+    /// Prefix of the environment variables overriding this config. Services with a config
+    /// file of their own use their own prefix, so an override meant for one role's file is
+    /// never applied to another's.
+    const ENV_PREFIX: &'static str = ENV_PREFIX;
+
+    /// Loads and validates the config at `path`.
+    ///
+    /// The format follows the file extension: `.json` files are read as JSON and anything else
+    /// as TOML. Environment variables are applied on top as described on [`ENV_PREFIX`], with
+    /// [`Self::ENV_PREFIX`] as their prefix.
+    fn from_path(path: &str) -> Result<Self, ConfigError> {
+        Self::from_paths(&[path])
+    }
+
+    /// Like [`Self::from_path`], but a missing file leaves every setting at its default, for
+    /// services that run without a config file. Environment overrides still apply.
+    fn from_optional_path(path: &str) -> Result<Self, ConfigError> {
+        if Path::new(path).is_file() {
+            Self::from_path(path)
+        } else {
+            Self::from_paths(&[])
+        }
+    }
+
+    /// Loads a base config followed by overlays, each later file overriding keys set by the
+    /// earlier ones, then deserializes and validates the merged result once. Every file must
+    /// exist.
+    fn from_paths(paths: &[&str]) -> Result<Self, ConfigError> {
+        let config: Self = load(paths, Self::ENV_PREFIX)?.try_deserialize()?;
+        config.validate().map_err(ConfigError::Message)?;
+        Ok(config)
+    }
+
+    /// Rejects settings that deserialize but cannot work, naming the offending field
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Resolved configuration as `(key, value)` pairs, with secrets redacted
+    fn effective_config(&self) -> Vec<(&'static str, String)>;
+
+    /// Log the resolved configuration as a single structured event.
+    fn log_effective_config(&self) {
+        log_effective_config(Self::SERVICE, &self.effective_config());
+    }
+}
+
+fn file_format(path: &str) -> FileFormat {
+    match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("json") => FileFormat::Json,
+        _ => FileFormat::Toml,
+    }
+}
+
+/// Layers the files in `paths` in order, with environment overrides under `env_prefix`
+/// applied on top
+fn load(paths: &[&str], env_prefix: &str) -> Result<Config, ConfigError> {
+    let mut builder = Config::builder();
+    for path in paths {
+        if !Path::new(path).is_file() {
+            return Err(ConfigError::Message(format!(
+                "config file {path} not found"
+            )));
+        }
+        builder = builder.add_source(File::new(path, file_format(path)));
+    }
+    builder
+        .add_source(
+            Environment::with_prefix(env_prefix)
+                .prefix_separator("_")
+                .separator("__")
+                .try_parsing(true),
+        )
+        .build()
+}
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct MintConfig {
@@ -24,19 +116,45 @@ pub struct WalletConfig {
     pub db_path: String,
     pub locking_pubkey: Option<String>,
     pub locking_privkey: Option<String>,
+    /// Keys decoded by [`WalletConfig::initialize`]
+    #[serde(skip)]
+    parsed_locking_pubkey: Option<PublicKey>,
+    #[serde(skip)]
+    parsed_locking_privkey: Option<SecretKey>,
 }
 
 impl WalletConfig {
-    /// Initialize and validate the wallet config, deriving pubkey from privkey if needed
+    /// Build a wallet config by hand; call [`WalletConfig::initialize`] before reading the
+    /// decoded keys
+    pub fn new(
+        mnemonic: String,
+        db_path: String,
+        locking_pubkey: Option<String>,
+        locking_privkey: Option<String>,
+    ) -> Self {
+        Self {
+            mnemonic,
+            db_path,
+            locking_pubkey,
+            locking_privkey,
+            parsed_locking_pubkey: None,
+            parsed_locking_privkey: None,
+        }
+    }
+
+    /// Initialize and validate the wallet config, deriving pubkey from privkey if needed.
+    ///
+    /// The decoded key is kept, so calling this again is a no-op.
     pub fn initialize(&mut self) -> Result<(), String> {
-        match (&self.locking_pubkey, &self.locking_privkey) {
+        if self.parsed_locking_pubkey.is_some() {
+            return Ok(());
+        }
+        let public_key = match (&self.locking_pubkey, &self.locking_privkey) {
             (None, None) => {
-                Err("Either locking_pubkey or locking_privkey must be provided".to_string())
+                return Err("Either locking_pubkey or locking_privkey must be provided".to_string())
             }
             (pubkey_opt, Some(privkey)) => {
                 // Derive pubkey from privkey
-                use bitcoin::secp256k1::{Secp256k1, SecretKey};
-
                 let privkey_bytes =
                     hex::decode(privkey).map_err(|_| "Invalid private key hex format")?;
 
@@ -44,7 +162,7 @@ impl WalletConfig {
                     return Err("Private key must be 32 bytes".to_string());
                 }
 
-                let secp = Secp256k1::new();
+                let secp = Secp256k1::signing_only();
                 let secret_key =
                     SecretKey::from_slice(&privkey_bytes).map_err(|_| "Invalid private key")?;
                 let public_key = secret_key.public_key(&secp);
@@ -59,28 +177,59 @@ impl WalletConfig {
                     // Only privkey provided - set the derived pubkey
                     self.locking_pubkey = Some(derived_pubkey);
                 }
-                Ok(())
+                self.parsed_locking_privkey = Some(secret_key);
+                public_key
             }
-            (Some(pubkey), None) => {
-                // Only pubkey provided - validate it
-                use bitcoin::secp256k1::{PublicKey, Secp256k1};
-
-                let pubkey_bytes =
-                    hex::decode(pubkey).map_err(|_| "Invalid public key hex format")?;
-
-                let _secp = Secp256k1::new();
-                PublicKey::from_slice(&pubkey_bytes).map_err(|_| "Invalid public key format")?;
+            // Only pubkey provided - validate it
+            (Some(pubkey), None) => parse_pubkey(pubkey)?,
+        };
+        self.parsed_locking_pubkey = Some(public_key);
+        Ok(())
+    }
 
-                Ok(())
-            }
+    /// The validated locking pubkey, decoded from hex only if [`WalletConfig::initialize`]
+    /// has not already done so
+    pub fn locking_pubkey_parsed(&self) -> Result<PublicKey, String> {
+        match (&self.parsed_locking_pubkey, &self.locking_pubkey) {
+            (Some(public_key), _) => Ok(*public_key),
+            (None, Some(pubkey)) => parse_pubkey(pubkey),
+            (None, None) => Err("locking_pubkey is not set; call initialize first".to_string()),
         }
     }
+
+    /// The locking private key decoded by [`WalletConfig::initialize`], if one was configured
+    pub fn locking_privkey_parsed(&self) -> Option<SecretKey> {
+        self.parsed_locking_privkey
+    }
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<PublicKey, String> {
+    let pubkey_bytes = hex::decode(pubkey).map_err(|_| "Invalid public key hex format")?;
+    PublicKey::from_slice(&pubkey_bytes).map_err(|_| "Invalid public key format".to_string())
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ValidationConfig {
     #[serde(default)]
     pub minimum_share_difficulty_bits: Option<u32>,
+    /// How many superseded jobs still accept shares on the current chain tip
+    #[serde(default)]
+    pub stale_job_grace: Option<usize>,
+}
+
+impl ValidationConfig {
+    /// Reject settings that cannot describe a usable share target
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(bits) = self.minimum_share_difficulty_bits {
+            if !(1..=255).contains(&bits) {
+                return Err(format!(
+                    "validation.minimum_share_difficulty_bits must be between 1 and 255, got {}",
+                    bits
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -106,12 +255,41 @@ pub struct MinerGlobalConfig {
     pub faucet: Option<FaucetConfig>,
 }
 
-impl MinerGlobalConfig {
-    pub fn from_path(path: &str) -> Result<Self, ConfigError> {
-        Config::builder()
-            .add_source(File::new(path, FileFormat::Toml))
-            .build()?
-            .try_deserialize()
+fn opt<T: Display>(value: Option<T>) -> String {
+    value.map_or_else(|| "none".to_string(), |v| v.to_string())
+}
+
+impl RoleConfig for MinerGlobalConfig {
+    const SERVICE: &'static str = "miner-global";
+
+    fn validate(&self) -> Result<(), String> {
+        self.validation
+            .as_ref()
+            .map_or(Ok(()), ValidationConfig::validate)
+    }
+
+    fn effective_config(&self) -> Vec<(&'static str, String)> {
+        let validation = self.validation.as_ref();
+        let faucet = self.faucet.as_ref();
+        vec![
+            ("mint.url", self.mint.url.clone()),
+            ("pool.port", self.pool.port.to_string()),
+            ("proxy.port", self.proxy.port.to_string()),
+            (
+                "validation.minimum_share_difficulty_bits",
+                opt(validation.and_then(|v| v.minimum_share_difficulty_bits)),
+            ),
+            (
+                "validation.stale_job_grace",
+                opt(validation.and_then(|v| v.stale_job_grace)),
+            ),
+            (
+                "ehash.minimum_difficulty",
+                opt(self.ehash.as_ref().map(|e| e.minimum_difficulty)),
+            ),
+            ("faucet.enabled", opt(faucet.map(|f| f.enabled))),
+            ("faucet.port", opt(faucet.map(|f| f.port))),
+        ]
     }
 }
 
@@ -124,6 +302,30 @@ pub struct Sv2MessagingConfig {
     pub max_retries: u32,
     pub timeout_ms: u64,
     pub pool_authority_public_key: Option<String>,
+    /// How long a share hash blocks a repeat quote request, in milliseconds.
+    /// Defaults to a multiple of `timeout_ms` when unset.
+    #[serde(default)]
+    pub dedup_window_ms: Option<u64>,
+    /// Drop mint quote responses that match no pending quote instead of routing them
+    #[serde(default)]
+    pub reject_orphan_responses: bool,
+    /// Drop mint quote responses that arrive more than this long after their quote request,
+    /// in milliseconds, instead of routing them to a possibly stale channel. Disabled when
+    /// unset.
+    #[serde(default)]
+    pub max_response_age_ms: Option<u64>,
+    /// Close the mint connection after this long without any frame from the mint, in
+    /// milliseconds. The mint reconnects on its own. Disabled when unset.
+    #[serde(default)]
+    pub idle_timeout_ms: Option<u64>,
+    /// Most quotes kept waiting on the mint, both for a quote response and for payment. Past
+    /// it the oldest are evicted and counted as failed. Unbounded when unset.
+    #[serde(default)]
+    pub max_pending_quotes: Option<usize>,
+    /// Recycle the mint connection once it has been open this long, in milliseconds, so the
+    /// mint reconnects with a fresh Noise session. Unlimited when unset.
+    #[serde(default)]
+    pub max_connection_lifetime_ms: Option<u64>,
 }
 
 impl Default for Sv2MessagingConfig {
@@ -136,6 +338,12 @@ impl Default for Sv2MessagingConfig {
             max_retries: 3,
             timeout_ms: 5000,
             pool_authority_public_key: None,
+            dedup_window_ms: None,
+            reject_orphan_responses: false,
+            max_response_age_ms: None,
+            idle_timeout_ms: None,
+            max_pending_quotes: None,
+            max_connection_lifetime_ms: None,
         }
     }
 }
@@ -150,11 +358,229 @@ pub struct PoolGlobalConfig {
     pub ehash: Option<EhashConfig>,
 }
 
-impl PoolGlobalConfig {
-    pub fn from_path(path: &str) -> Result<Self, ConfigError> {
-        Config::builder()
-            .add_source(File::new(path, FileFormat::Toml))
-            .build()?
-            .try_deserialize()
+impl RoleConfig for PoolGlobalConfig {
+    const SERVICE: &'static str = "pool-global";
+
+    fn validate(&self) -> Result<(), String> {
+        self.validation
+            .as_ref()
+            .map_or(Ok(()), ValidationConfig::validate)
+    }
+
+    fn effective_config(&self) -> Vec<(&'static str, String)> {
+        let validation = self.validation.as_ref();
+        let messaging = self.sv2_messaging.as_ref();
+        vec![
+            ("mint.url", self.mint.url.clone()),
+            ("pool.port", self.pool.port.to_string()),
+            (
+                "pool.min_downstream_hashrate",
+                opt(self.pool.min_downstream_hashrate),
+            ),
+            ("proxy.port", self.proxy.port.to_string()),
+            ("sv2_messaging.enabled", opt(messaging.map(|m| m.enabled))),
+            (
+                "sv2_messaging.mint_listen_address",
+                opt(messaging.map(|m| &m.mint_listen_address)),
+            ),
+            (
+                "validation.minimum_share_difficulty_bits",
+                opt(validation.and_then(|v| v.minimum_share_difficulty_bits)),
+            ),
+            (
+                "validation.stale_job_grace",
+                opt(validation.and_then(|v| v.stale_job_grace)),
+            ),
+            (
+                "ehash.minimum_difficulty",
+                opt(self.ehash.as_ref().map(|e| e.minimum_difficulty)),
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{path::PathBuf, sync::Mutex};
+
+    /// Held while loading configs, since one test sets a `HASHPOOL_` variable the others
+    /// would pick up
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    const GLOBAL_TOML: &str = r#"
+        [mint]
+        url = "http://127.0.0.1:3338"
+
+        [pool]
+        port = 34254
+
+        [proxy]
+        port = 34255
+    "#;
+
+    /// Writes `contents` to a file in the temp dir unique to this test process
+    fn write_temp(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("hashpool-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn value(entries: &[(&'static str, String)], key: &str) -> String {
+        entries
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.clone())
+            .unwrap_or_else(|| panic!("missing {key}"))
+    }
+
+    #[test]
+    fn test_wallet_initialize_caches_derived_pubkey() {
+        let mut wallet = WalletConfig::new(
+            "test mnemonic".to_string(),
+            "/tmp/test_wallet.db".to_string(),
+            None,
+            Some("01".repeat(32)),
+        );
+        assert!(wallet.locking_pubkey_parsed().is_err());
+        assert!(wallet.locking_privkey_parsed().is_none());
+
+        wallet.initialize().unwrap();
+        let key = wallet.locking_pubkey_parsed().unwrap();
+        let secret = wallet.locking_privkey_parsed().unwrap();
+        assert_eq!(secret.secret_bytes(), [1u8; 32]);
+        assert_eq!(
+            wallet.locking_pubkey,
+            Some(hex::encode(key.serialize())),
+            "derived pubkey is stored as hex too"
+        );
+
+        // A second call keeps the cached key rather than re-deriving it
+        wallet.locking_privkey = Some("not hex".to_string());
+        assert!(wallet.initialize().is_ok());
+        assert_eq!(wallet.locking_pubkey_parsed().unwrap(), key);
+        assert_eq!(wallet.locking_privkey_parsed(), Some(secret));
+
+        let from_hex = WalletConfig::new(
+            wallet.mnemonic.clone(),
+            wallet.db_path.clone(),
+            wallet.locking_pubkey.clone(),
+            None,
+        );
+        assert_eq!(from_hex.locking_pubkey_parsed().unwrap(), key);
+        assert!(from_hex.locking_privkey_parsed().is_none());
+    }
+
+    #[test]
+    fn test_env_overrides_file_values() {
+        let _env = ENV_LOCK.lock().unwrap();
+        let path = write_temp("env.toml", GLOBAL_TOML);
+
+        std::env::set_var("HASHPOOL_POOL__PORT", "34260");
+        let config = PoolGlobalConfig::from_path(path.to_str().unwrap());
+        std::env::remove_var("HASHPOOL_POOL__PORT");
+        std::fs::remove_file(&path).unwrap();
+
+        let config = config.unwrap();
+        assert_eq!(config.pool.port, 34260);
+        assert_eq!(config.proxy.port, 34255);
+        assert_eq!(config.mint.url, "http://127.0.0.1:3338");
+    }
+
+    #[test]
+    fn test_global_configs_load_validate_and_echo() {
+        let toml = format!("{GLOBAL_TOML}\n[validation]\nminimum_share_difficulty_bits = 32\n");
+        let _env = ENV_LOCK.lock().unwrap();
+        let path = write_temp("global.toml", &toml);
+        let path_str = path.to_str().unwrap();
+        let pool = PoolGlobalConfig::from_path(path_str);
+        let miner = MinerGlobalConfig::from_path(path_str);
+        std::fs::remove_file(&path).unwrap();
+
+        let pool = pool.unwrap();
+        assert!(pool.validate().is_ok());
+        let entries = pool.effective_config();
+        assert_eq!(value(&entries, "pool.port"), "34254");
+        assert_eq!(
+            value(&entries, "validation.minimum_share_difficulty_bits"),
+            "32"
+        );
+
+        let miner = miner.unwrap();
+        assert!(miner.validate().is_ok());
+        let entries = miner.effective_config();
+        assert_eq!(value(&entries, "proxy.port"), "34255");
+        assert_eq!(value(&entries, "faucet.enabled"), "none");
+    }
+
+    #[test]
+    fn test_global_configs_reject_invalid_values() {
+        let toml = format!("{GLOBAL_TOML}\n[validation]\nminimum_share_difficulty_bits = 256\n");
+        let _env = ENV_LOCK.lock().unwrap();
+        let path = write_temp("invalid.toml", &toml);
+        let path_str = path.to_str().unwrap();
+        let pool = PoolGlobalConfig::from_path(path_str);
+        let miner = MinerGlobalConfig::from_path(path_str);
+        std::fs::remove_file(&path).unwrap();
+
+        for err in [pool.unwrap_err(), miner.unwrap_err()] {
+            assert!(err.to_string().contains("minimum_share_difficulty_bits"));
+        }
+    }
+
+    #[test]
+    fn test_later_files_override_earlier_ones() {
+        let base = format!(
+            "{GLOBAL_TOML}
+            [sv2_messaging]
+            enabled = true
+            mint_listen_address = \"127.0.0.1:34260\"
+            broadcast_buffer_size = 1000
+            mpsc_buffer_size = 100
+            max_retries = 3
+            timeout_ms = 5000
+            "
+        );
+        let overlay = r#"
+            [pool]
+            port = 44254
+
+            [sv2_messaging]
+            enabled = false
+        "#;
+        let _env = ENV_LOCK.lock().unwrap();
+        let base_path = write_temp("base.toml", &base);
+        let overlay_path = write_temp("overlay.toml", overlay);
+        let missing_path = std::env::temp_dir().join("hashpool-missing-overlay.toml");
+        let base_str = base_path.to_str().unwrap();
+        let merged = PoolGlobalConfig::from_paths(&[base_str, overlay_path.to_str().unwrap()]);
+        let missing = PoolGlobalConfig::from_paths(&[base_str, missing_path.to_str().unwrap()]);
+        std::fs::remove_file(&base_path).unwrap();
+        std::fs::remove_file(&overlay_path).unwrap();
+
+        let merged = merged.unwrap();
+        assert_eq!(merged.pool.port, 44254);
+        assert_eq!(merged.proxy.port, 34255);
+        let messaging = merged.sv2_messaging.unwrap();
+        assert!(!messaging.enabled);
+        assert_eq!(messaging.mint_listen_address, "127.0.0.1:34260");
+
+        let err = missing.unwrap_err().to_string();
+        assert!(err.contains("hashpool-missing-overlay.toml"), "{err}");
+    }
+
+    #[test]
+    fn test_json_config_detected_by_extension() {
+        let json = r#"{
+            "mint": { "url": "http://127.0.0.1:3338" },
+            "pool": { "port": 4000 },
+            "proxy": { "port": 4001 }
+        }"#;
+        let _env = ENV_LOCK.lock().unwrap();
+        let path = write_temp("global.json", json);
+        let config = MinerGlobalConfig::from_path(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(config.unwrap().pool.port, 4000);
     }
 }
@@ -0,0 +1,169 @@
+//! A map that remembers insertion order, so the oldest entries can be evicted without
+//! scanning every entry
+
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// Extra order entries tolerated before removed keys are purged from the order queue
+const COMPACT_SLACK: usize = 64;
+
+/// A `HashMap` that also keeps its keys in insertion order. Removing a key leaves its
+/// place in the order queue behind; such leftovers are skipped when popping and purged
+/// once they outnumber the live entries, so every operation stays amortised O(1).
+#[derive(Debug, Clone)]
+pub struct InsertionOrderMap<K, V> {
+    entries: HashMap<K, (u64, V)>,
+    // (insertion sequence, key), oldest first; stale when the key was since removed or
+    // reinserted under a later sequence
+    order: VecDeque<(u64, K)>,
+    next_seq: u64,
+}
+
+impl<K, V> Default for InsertionOrderMap<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            next_seq: 0,
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V> InsertionOrderMap<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Insert `value` as the newest entry, returning the value it replaced. A reinserted
+    /// key moves to the back of the order.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.order.push_back((seq, key.clone()));
+        let replaced = self.entries.insert(key, (seq, value)).map(|(_, v)| v);
+        self.compact();
+        replaced
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.entries.get(key).map(|(_, v)| v)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.entries.get_mut(key).map(|(_, v)| v)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let removed = self.entries.remove(key).map(|(_, v)| v);
+        self.compact();
+        removed
+    }
+
+    /// The oldest entry, without removing it
+    pub fn peek_oldest(&mut self) -> Option<(&K, &V)> {
+        while let Some((seq, key)) = self.order.front() {
+            if self.entries.get(key).is_some_and(|(live, _)| live == seq) {
+                break;
+            }
+            self.order.pop_front();
+        }
+        let (_, key) = self.order.front()?;
+        self.entries.get_key_value(key).map(|(k, (_, v))| (k, v))
+    }
+
+    /// Remove and return the oldest entry
+    pub fn pop_oldest(&mut self) -> Option<(K, V)> {
+        while let Some((seq, key)) = self.order.pop_front() {
+            if self.entries.get(&key).is_some_and(|(live, _)| *live == seq) {
+                return self.entries.remove(&key).map(|(_, v)| (key, v));
+            }
+        }
+        None
+    }
+
+    /// Iterate over the entries in arbitrary order
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(k, (_, v))| (k, v))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.values().map(|(_, v)| v)
+    }
+
+    fn compact(&mut self) {
+        if self.order.len() <= self.entries.len() * 2 + COMPACT_SLACK {
+            return;
+        }
+        let entries = &self.entries;
+        self.order
+            .retain(|(seq, key)| entries.get(key).is_some_and(|(live, _)| live == seq));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pops_in_insertion_order() {
+        let mut map = InsertionOrderMap::new();
+        for i in 0..5u32 {
+            map.insert(i, i * 10);
+        }
+        assert_eq!(map.pop_oldest(), Some((0, 0)));
+        assert_eq!(map.pop_oldest(), Some((1, 10)));
+        assert_eq!(map.len(), 3);
+    }
+
+    #[test]
+    fn removed_and_reinserted_keys_keep_order_consistent() {
+        let mut map = InsertionOrderMap::new();
+        map.insert("a", 1);
+        map.insert("b", 2);
+        map.insert("c", 3);
+        assert_eq!(map.remove("a"), Some(1));
+        // Reinserting makes "b" the newest entry
+        assert_eq!(map.insert("b", 20), Some(2));
+
+        assert_eq!(map.peek_oldest(), Some((&"c", &3)));
+        assert_eq!(map.pop_oldest(), Some(("c", 3)));
+        assert_eq!(map.pop_oldest(), Some(("b", 20)));
+        assert_eq!(map.pop_oldest(), None);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn order_queue_stays_bounded_under_churn() {
+        let mut map = InsertionOrderMap::new();
+        for i in 0..10_000u32 {
+            map.insert(i, ());
+            map.remove(&i);
+        }
+        map.insert(u32::MAX, ());
+        assert!(map.order.len() <= map.len() * 2 + COMPACT_SLACK + 1);
+        assert_eq!(map.pop_oldest(), Some((u32::MAX, ())));
+    }
+}
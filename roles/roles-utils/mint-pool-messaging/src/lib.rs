@@ -24,17 +24,22 @@ pub enum Role {
 }
 
 mod channel_manager;
+mod insertion_order;
 mod message_codec;
 mod message_hub;
 mod sv2_frames;
 
 pub use channel_manager::{ChannelError, ChannelManager};
+pub use insertion_order::InsertionOrderMap;
 pub use message_codec::{MessageCodec, MessageType, MintQuoteMessage};
 pub use message_hub::{
-    MessageHubStats, MintPoolMessageHub, MintQuoteResponseEvent, PendingQuoteContext,
+    MessageHubDiagnostics, MessageHubStats, MintPoolMessageHub, MintQuoteErrorEvent,
+    MintQuoteEvictedEvent, MintQuoteResponseEvent, PendingQuoteContext, PendingQuoteSummary,
+    PENDING_QUOTE_EXPIRED_ERROR_CODE,
 };
 pub use sv2_frames::{
-    quote_error_frame_bytes, quote_request_frame_bytes, quote_response_frame_bytes,
+    decode_heartbeat_nonce, ping_frame_bytes, pong_frame_bytes, quote_error_frame_bytes,
+    quote_request_frame_bytes, quote_response_frame_bytes,
 };
 
 /// Configuration for the messaging system
@@ -48,15 +53,41 @@ pub struct MessagingConfig {
     pub max_retries: u32,
     /// Timeout for message operations in milliseconds
     pub timeout_ms: u64,
+    /// How long a mint connection stays healthy without traffic, in milliseconds
+    pub heartbeat_timeout_ms: u64,
+    /// How long a pending or completed share hash blocks a repeat quote request, in milliseconds
+    pub dedup_window_ms: u64,
+    /// Drop quote responses with no pending quote rather than broadcasting them without context
+    pub reject_orphan_responses: bool,
+    /// Drop quote responses whose pending quote is older than this, in milliseconds, as its
+    /// channel may be gone by then. Disabled when `None`.
+    pub max_response_age_ms: Option<u64>,
+    /// Most quotes kept waiting for a mint response; past it the oldest are evicted and
+    /// reported as failed. Unbounded when `None`.
+    pub max_pending_quotes: Option<usize>,
+}
+
+impl MessagingConfig {
+    /// Dedup window used when a pool doesn't configure one. Twice the message timeout covers a
+    /// request that timed out plus the miner's follow-up resubmission.
+    pub fn default_dedup_window_ms(timeout_ms: u64) -> u64 {
+        timeout_ms.saturating_mul(2)
+    }
 }
 
 impl Default for MessagingConfig {
     fn default() -> Self {
+        let timeout_ms = 5000;
         Self {
             broadcast_buffer_size: 1000,
             mpsc_buffer_size: 100,
             max_retries: 3,
-            timeout_ms: 5000,
+            timeout_ms,
+            heartbeat_timeout_ms: 60_000,
+            dedup_window_ms: Self::default_dedup_window_ms(timeout_ms),
+            reject_orphan_responses: false,
+            max_response_age_ms: None,
+            max_pending_quotes: None,
         }
     }
 }
@@ -76,6 +107,12 @@ pub enum MessagingError {
     InvalidMessageType(u8),
     #[error("Connection error: {0}")]
     Connection(String),
+    #[error("Duplicate quote request for share hash {0}")]
+    DuplicateQuote(ShareHash),
+    #[error("Dropped quote response with no pending quote for share hash {0}")]
+    OrphanResponse(ShareHash),
+    #[error("Dropped quote response for share hash {share_hash} after {age_ms}ms")]
+    StaleResponse { share_hash: ShareHash, age_ms: u64 },
 }
 
 /// Result type for messaging operations
@@ -25,6 +25,16 @@ impl MessageCodec {
     pub fn get_error_type() -> MessageType {
         MessageType::error()
     }
+
+    /// Get the message type for a heartbeat ping
+    pub fn get_ping_type() -> MessageType {
+        MessageType::Ping
+    }
+
+    /// Get the message type for a heartbeat pong
+    pub fn get_pong_type() -> MessageType {
+        MessageType::Pong
+    }
 }
 
 #[cfg(test)]
@@ -46,4 +56,25 @@ mod tests {
         assert_ne!(response, error);
         assert_ne!(request, error);
     }
+
+    #[test]
+    fn test_ping_pong_round_trip() {
+        let ping = crate::ping_frame_bytes(0xdead_beef_0042).unwrap();
+        assert_eq!(
+            MessageType::from_u8(ping[2]).unwrap(),
+            MessageCodec::get_ping_type()
+        );
+
+        // The mint echoes the ping's nonce back in its pong
+        let nonce = crate::decode_heartbeat_nonce(&ping[6..]).unwrap();
+        assert_eq!(nonce, 0xdead_beef_0042);
+        let pong = crate::pong_frame_bytes(nonce).unwrap();
+        assert_eq!(
+            MessageType::from_u8(pong[2]).unwrap(),
+            MessageCodec::get_pong_type()
+        );
+        assert_eq!(crate::decode_heartbeat_nonce(&pong[6..]).unwrap(), nonce);
+
+        assert!(crate::decode_heartbeat_nonce(&pong[6..10]).is_err());
+    }
 }
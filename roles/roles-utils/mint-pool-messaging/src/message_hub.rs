@@ -1,10 +1,17 @@
 use super::*;
-use std::{collections::HashMap, time::Instant};
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
 use tokio::{
     sync::broadcast,
-    time::{timeout, Duration},
+    task::JoinHandle,
+    time::{timeout, Duration, Instant, MissedTickBehavior},
 };
 
+/// Error code of the quote error broadcast for a pending quote the mint never answered
+pub const PENDING_QUOTE_EXPIRED_ERROR_CODE: u32 = 0x10;
+
 /// Central hub for mint-pool communication using MPSC broadcast streams
 pub struct MintPoolMessageHub {
     config: MessagingConfig,
@@ -20,20 +27,30 @@ pub struct MintPoolMessageHub {
     quote_response_rx: RwLock<Option<broadcast::Receiver<MintQuoteResponseEvent>>>,
 
     // Error channels
-    quote_error_tx: broadcast::Sender<MintQuoteError<'static>>,
+    quote_error_tx: broadcast::Sender<MintQuoteErrorEvent>,
     #[allow(dead_code)]
-    quote_error_rx: RwLock<Option<broadcast::Receiver<MintQuoteError<'static>>>>,
+    quote_error_rx: RwLock<Option<broadcast::Receiver<MintQuoteErrorEvent>>>,
+
+    // Pending quotes dropped to stay under `max_pending_quotes`
+    quote_evicted_tx: broadcast::Sender<MintQuoteEvictedEvent>,
 
     // Active connections tracking
     connections: RwLock<HashMap<String, ConnectionInfo>>,
-    pending_quotes: RwLock<HashMap<ShareHash, PendingQuote>>,
+    // Reference point for `ConnectionInfo::last_seen_ms`
+    epoch: Instant,
+    pending_quotes: RwLock<InsertionOrderMap<ShareHash, PendingQuote>>,
+    // Last time each share hash was requested or answered, for dedup. Refreshing a hash
+    // reinserts it, so the entries are ordered by time and expire from the front.
+    recent_share_hashes: RwLock<InsertionOrderMap<ShareHash, Instant>>,
 }
 
 #[derive(Debug, Clone)]
 struct ConnectionInfo {
     role: Role,
     #[allow(dead_code)]
-    connected_at: std::time::Instant,
+    connected_at: Instant,
+    // Milliseconds since the hub's epoch, updated under the read lock on every frame
+    last_seen_ms: Arc<AtomicU64>,
 }
 
 #[derive(Debug, Clone)]
@@ -55,6 +72,8 @@ pub struct MintQuoteResponseEvent {
     pub response: MintQuoteResponse<'static>,
     pub share_hash: ShareHash,
     pub context: Option<PendingQuoteContext>,
+    /// Compressed locking pubkey the quote was requested for, when the request was pending
+    pub locking_key: Option<Vec<u8>>,
 }
 
 impl MintQuoteResponseEvent {
@@ -68,6 +87,7 @@ impl MintQuoteResponseEvent {
             response,
             share_hash,
             context,
+            locking_key: None,
         })
     }
 
@@ -80,6 +100,7 @@ impl MintQuoteResponseEvent {
             response,
             share_hash,
             context,
+            locking_key: None,
         }
     }
 
@@ -90,6 +111,36 @@ impl MintQuoteResponseEvent {
     pub fn context(&self) -> Option<&PendingQuoteContext> {
         self.context.as_ref()
     }
+
+    pub fn locking_key(&self) -> Option<&[u8]> {
+        self.locking_key.as_deref()
+    }
+}
+
+/// A quote error from the mint, correlated with the pending request it answers
+#[derive(Debug, Clone)]
+pub struct MintQuoteErrorEvent {
+    pub error: MintQuoteError<'static>,
+    pub share_hash: ShareHash,
+    pub context: Option<PendingQuoteContext>,
+}
+
+impl MintQuoteErrorEvent {
+    pub fn error(&self) -> &MintQuoteError<'static> {
+        &self.error
+    }
+
+    pub fn context(&self) -> Option<&PendingQuoteContext> {
+        self.context.as_ref()
+    }
+}
+
+/// A pending quote evicted to keep the pending map under `max_pending_quotes`. Its share
+/// will not be credited; a late mint response for it arrives as an orphan.
+#[derive(Debug, Clone)]
+pub struct MintQuoteEvictedEvent {
+    pub share_hash: ShareHash,
+    pub context: PendingQuoteContext,
 }
 
 impl MintPoolMessageHub {
@@ -99,6 +150,7 @@ impl MintPoolMessageHub {
         let (quote_response_tx, quote_response_rx) =
             broadcast::channel(config.broadcast_buffer_size);
         let (quote_error_tx, quote_error_rx) = broadcast::channel(config.broadcast_buffer_size);
+        let (quote_evicted_tx, _) = broadcast::channel(config.broadcast_buffer_size);
 
         Arc::new(Self {
             config,
@@ -108,8 +160,11 @@ impl MintPoolMessageHub {
             quote_response_rx: RwLock::new(Some(quote_response_rx)),
             quote_error_tx,
             quote_error_rx: RwLock::new(Some(quote_error_rx)),
+            quote_evicted_tx,
             connections: RwLock::new(HashMap::new()),
-            pending_quotes: RwLock::new(HashMap::new()),
+            epoch: Instant::now(),
+            pending_quotes: RwLock::new(InsertionOrderMap::new()),
+            recent_share_hashes: RwLock::new(InsertionOrderMap::new()),
         })
     }
 
@@ -120,7 +175,8 @@ impl MintPoolMessageHub {
             connection_id.clone(),
             ConnectionInfo {
                 role: role.clone(),
-                connected_at: std::time::Instant::now(),
+                connected_at: Instant::now(),
+                last_seen_ms: Arc::new(AtomicU64::new(self.elapsed_ms())),
             },
         );
 
@@ -139,6 +195,37 @@ impl MintPoolMessageHub {
         }
     }
 
+    /// Mark a connection as alive; call whenever traffic is received from the peer.
+    ///
+    /// Only takes the connections read lock, so calling it on every frame does not contend
+    /// with registration or stats.
+    pub async fn record_heartbeat(&self, connection_id: &str) {
+        let connections = self.connections.read().await;
+        if let Some(info) = connections.get(connection_id) {
+            info.last_seen_ms
+                .store(self.elapsed_ms(), Ordering::Relaxed);
+        }
+    }
+
+    /// Whether at least one mint connection has been heard from within the heartbeat timeout
+    pub async fn mint_available(&self) -> bool {
+        let connections = self.connections.read().await;
+        self.has_live_mint(&connections)
+    }
+
+    fn has_live_mint(&self, connections: &HashMap<String, ConnectionInfo>) -> bool {
+        let now_ms = self.elapsed_ms();
+        connections.values().any(|c| {
+            c.role == Role::Mint
+                && now_ms.saturating_sub(c.last_seen_ms.load(Ordering::Relaxed))
+                    <= self.config.heartbeat_timeout_ms
+        })
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.epoch.elapsed().as_millis() as u64
+    }
+
     /// Track a pending quote request so responses can be correlated back to the originating share.
     /// Send a mint quote request (from pool to mint)
     pub async fn send_quote_request(
@@ -152,6 +239,22 @@ impl MintPoolMessageHub {
         );
 
         {
+            let dedup_window = Duration::from_millis(self.config.dedup_window_ms);
+            let mut recent = self.recent_share_hashes.write().await;
+            let now = Instant::now();
+            while recent
+                .peek_oldest()
+                .is_some_and(|(_, seen_at)| now.duration_since(*seen_at) >= dedup_window)
+            {
+                recent.pop_oldest();
+            }
+            if recent.get(&request.share_hash).is_some() {
+                return Err(MessagingError::DuplicateQuote(request.share_hash));
+            }
+            recent.insert(request.share_hash, now);
+        }
+
+        let evicted = {
             let mut guard = self.pending_quotes.write().await;
             guard.insert(
                 request.share_hash,
@@ -161,15 +264,57 @@ impl MintPoolMessageHub {
                     context,
                 },
             );
+            self.evict_oldest_pending(&mut guard)
+        };
+        for event in evicted {
+            warn!(
+                "Evicted pending quote for share hash {} on channel {}: pending quote limit reached",
+                event.share_hash, event.context.channel_id
+            );
+            // Nobody listening just means nobody counts the failure
+            let _ = self.quote_evicted_tx.send(event);
         }
 
-        self.quote_request_tx
-            .send(request)
-            .map_err(|_| MessagingError::ChannelClosed("quote_request".to_string()))?;
+        let share_hash = request.share_hash;
+        if self.quote_request_tx.send(request).is_err() {
+            // Nothing was sent, so let a retry of the same share through the dedup window
+            self.abandon_quote_request(&share_hash).await;
+            return Err(MessagingError::ChannelClosed("quote_request".to_string()));
+        }
 
         Ok(())
     }
 
+    /// Forget a quote request whose send did not complete, e.g. because the caller gave up
+    /// on it after a timeout. The request is only broadcast as the last step of
+    /// [`Self::send_quote_request`], so an unfinished send never reached the mint; dropping
+    /// its dedup and pending entries lets a retry of the same share through.
+    pub async fn abandon_quote_request(&self, share_hash: &ShareHash) {
+        self.recent_share_hashes.write().await.remove(share_hash);
+        self.pending_quotes.write().await.remove(share_hash);
+    }
+
+    /// Drop the oldest pending quotes until at most `max_pending_quotes` remain
+    fn evict_oldest_pending(
+        &self,
+        pending: &mut InsertionOrderMap<ShareHash, PendingQuote>,
+    ) -> Vec<MintQuoteEvictedEvent> {
+        let Some(max_pending) = self.config.max_pending_quotes else {
+            return Vec::new();
+        };
+        let mut evicted = Vec::new();
+        while pending.len() > max_pending {
+            let Some((share_hash, quote)) = pending.pop_oldest() else {
+                break;
+            };
+            evicted.push(MintQuoteEvictedEvent {
+                share_hash,
+                context: quote.context,
+            });
+        }
+        evicted
+    }
+
     /// Send a mint quote response (from mint to pool) and return the dispatched event
     pub async fn send_quote_response(
         &self,
@@ -178,12 +323,36 @@ impl MintPoolMessageHub {
         let share_hash = ShareHash::from_u256(&response.header_hash)
             .map_err(|e| MessagingError::Decoding(format!("invalid share hash: {e}")))?;
 
-        let context = {
-            let mut guard = self.pending_quotes.write().await;
-            guard.remove(&share_hash).map(|pending| pending.context)
-        };
+        let pending = self.pending_quotes.write().await.remove(&share_hash);
+
+        // Restart the dedup window so a late resubmission of an answered share is still blocked
+        {
+            let mut recent = self.recent_share_hashes.write().await;
+            if recent.get(&share_hash).is_some() {
+                recent.insert(share_hash, Instant::now());
+            }
+        }
+
+        if let (Some(pending), Some(max_age_ms)) = (&pending, self.config.max_response_age_ms) {
+            let age_ms = pending.created_at.elapsed().as_millis() as u64;
+            if age_ms > max_age_ms {
+                warn!(
+                    "Dropping mint quote response for share hash {} on channel {}: pending for {}ms, limit is {}ms",
+                    share_hash, pending.context.channel_id, age_ms, max_age_ms
+                );
+                return Err(MessagingError::StaleResponse { share_hash, age_ms });
+            }
+        }
 
+        let locking_key = pending
+            .as_ref()
+            .map(|pending| pending.parsed.request.locking_key.inner_as_ref().to_vec());
+        let context = pending.map(|pending| pending.context);
         if context.is_none() {
+            if self.config.reject_orphan_responses {
+                warn!("Dropping mint quote response for unknown share hash {}", share_hash);
+                return Err(MessagingError::OrphanResponse(share_hash));
+            }
             warn!(
                 "Received mint quote response with no pending context for share hash {}",
                 share_hash
@@ -193,6 +362,7 @@ impl MintPoolMessageHub {
         let event = MintQuoteResponseEvent {
             share_hash,
             context,
+            locking_key,
             response,
         };
 
@@ -209,19 +379,113 @@ impl MintPoolMessageHub {
         Ok(event)
     }
 
-    /// Send a mint quote error (from mint to pool)
-    pub async fn send_quote_error(&self, error: MintQuoteError<'static>) -> MessagingResult<()> {
+    /// Send a mint quote error (from mint to pool) and return the dispatched event. The failed
+    /// request is no longer pending; its context is attached so the error can reach its channel.
+    pub async fn send_quote_error(
+        &self,
+        error: MintQuoteError<'static>,
+    ) -> MessagingResult<MintQuoteErrorEvent> {
+        let share_hash = ShareHash::from_u256(&error.header_hash)
+            .map_err(|e| MessagingError::Decoding(format!("invalid share hash: {e}")))?;
+
+        let context = {
+            let mut guard = self.pending_quotes.write().await;
+            guard.remove(&share_hash).map(|pending| pending.context)
+        };
+
         debug!(
-            "Sending mint quote error: code={}, message={}",
+            "Sending mint quote error: code={}, message={}, share_hash={}",
             error.error_code,
-            std::str::from_utf8(error.error_message.inner_as_ref()).unwrap_or("invalid")
+            std::str::from_utf8(error.error_message.inner_as_ref()).unwrap_or("invalid"),
+            share_hash
         );
+        if context.is_none() {
+            warn!(
+                "Received mint quote error with no pending context for share hash {}",
+                share_hash
+            );
+        }
+
+        let event = MintQuoteErrorEvent {
+            error,
+            share_hash,
+            context,
+        };
 
         self.quote_error_tx
-            .send(error)
+            .send(event.clone())
             .map_err(|_| MessagingError::ChannelClosed("quote_error".to_string()))?;
 
-        Ok(())
+        Ok(event)
+    }
+
+    /// Spawn a task that checks every `max_age / 2` for pending quotes older than `max_age`,
+    /// removing each and broadcasting a quote error for it so its channel learns the share
+    /// won't be credited. The task stops once the hub is dropped.
+    pub fn spawn_pending_reaper(self: Arc<Self>, max_age: Duration) -> JoinHandle<()> {
+        let hub = Arc::downgrade(&self);
+        drop(self);
+        let period = (max_age / 2).max(Duration::from_millis(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let Some(hub) = hub.upgrade() else {
+                    break;
+                };
+                let reaped = hub.reap_stale_pending(max_age).await;
+                if reaped > 0 {
+                    warn!(
+                        "Reaped {} pending quotes with no mint response after {}ms",
+                        reaped,
+                        max_age.as_millis()
+                    );
+                }
+            }
+        })
+    }
+
+    /// Remove the pending quotes older than `max_age`, broadcasting a
+    /// [`PENDING_QUOTE_EXPIRED_ERROR_CODE`] quote error for each. Returns how many were removed.
+    pub async fn reap_stale_pending(&self, max_age: Duration) -> usize {
+        let stale: Vec<(ShareHash, PendingQuoteContext)> = {
+            let mut pending = self.pending_quotes.write().await;
+            let now = Instant::now();
+            let stale_hashes: Vec<ShareHash> = pending
+                .iter()
+                .filter(|(_, quote)| now.duration_since(quote.created_at) > max_age)
+                .map(|(share_hash, _)| *share_hash)
+                .collect();
+            stale_hashes
+                .into_iter()
+                .filter_map(|share_hash| {
+                    pending
+                        .remove(&share_hash)
+                        .map(|quote| (share_hash, quote.context))
+                })
+                .collect()
+        };
+
+        for (share_hash, context) in &stale {
+            let error = match expired_quote_error(*share_hash, max_age) {
+                Ok(error) => error,
+                Err(e) => {
+                    warn!(
+                        "Failed to build quote error for reaped share hash {}: {}",
+                        share_hash, e
+                    );
+                    continue;
+                }
+            };
+            // Nobody listening just means nobody is told
+            let _ = self.quote_error_tx.send(MintQuoteErrorEvent {
+                error,
+                share_hash: *share_hash,
+                context: Some(context.clone()),
+            });
+        }
+        stale.len()
     }
 
     /// Subscribe to quote requests (for mint)
@@ -241,10 +505,17 @@ impl MintPoolMessageHub {
     /// Subscribe to quote errors (for pool)
     pub async fn subscribe_quote_errors(
         &self,
-    ) -> MessagingResult<broadcast::Receiver<MintQuoteError<'static>>> {
+    ) -> MessagingResult<broadcast::Receiver<MintQuoteErrorEvent>> {
         Ok(self.quote_error_tx.subscribe())
     }
 
+    /// Subscribe to pending quotes evicted by the `max_pending_quotes` limit (for pool)
+    pub async fn subscribe_quote_evictions(
+        &self,
+    ) -> MessagingResult<broadcast::Receiver<MintQuoteEvictedEvent>> {
+        Ok(self.quote_evicted_tx.subscribe())
+    }
+
     /// Receive a quote request with timeout (for mint)
     pub async fn receive_quote_request(&self) -> MessagingResult<ParsedMintQuoteRequest> {
         let mut rx = self.subscribe_quote_requests().await?;
@@ -267,8 +538,40 @@ impl MintPoolMessageHub {
 
     /// Get statistics about the message hub
     pub async fn get_stats(&self) -> MessageHubStats {
+        let connections = self.connections.read().await;
+        let pending = self.pending_quotes.read().await;
+        self.stats_from(&connections, &pending)
+    }
+
+    /// Hub statistics together with every pending quote, read under the same locks so the
+    /// two agree. Only read locks are taken and nothing is awaited while they are held.
+    pub async fn get_diagnostics(&self) -> MessageHubDiagnostics {
         let connections = self.connections.read().await;
         let pending = self.pending_quotes.read().await;
+        let now = Instant::now();
+        let mut pending_quotes: Vec<PendingQuoteSummary> = pending
+            .iter()
+            .map(|(share_hash, quote)| PendingQuoteSummary {
+                share_hash: *share_hash,
+                channel_id: quote.context.channel_id,
+                sequence_number: quote.context.sequence_number,
+                amount: quote.context.amount,
+                age_ms: now.duration_since(quote.created_at).as_millis() as u64,
+            })
+            .collect();
+        pending_quotes.sort_by(|a, b| b.age_ms.cmp(&a.age_ms));
+
+        MessageHubDiagnostics {
+            stats: self.stats_from(&connections, &pending),
+            pending_quotes,
+        }
+    }
+
+    fn stats_from(
+        &self,
+        connections: &HashMap<String, ConnectionInfo>,
+        pending: &InsertionOrderMap<ShareHash, PendingQuote>,
+    ) -> MessageHubStats {
         let now = Instant::now();
         let oldest_pending_ms = pending
             .values()
@@ -285,6 +588,7 @@ impl MintPoolMessageHub {
                 .values()
                 .filter(|c| c.role == Role::Mint)
                 .count(),
+            mint_available: self.has_live_mint(connections),
             quote_request_subscribers: self.quote_request_tx.receiver_count(),
             quote_response_subscribers: self.quote_response_tx.receiver_count(),
             quote_error_subscribers: self.quote_error_tx.receiver_count(),
@@ -303,12 +607,32 @@ impl MintPoolMessageHub {
     }
 }
 
+/// The quote error reported for a pending quote reaped after `max_age` without a response
+fn expired_quote_error(
+    share_hash: ShareHash,
+    max_age: Duration,
+) -> MessagingResult<MintQuoteError<'static>> {
+    let message = format!("no response from mint within {}ms", max_age.as_millis());
+    let error_message = binary_sv2::Str0255::try_from(message)
+        .map_err(|e| MessagingError::Encoding(format!("invalid error message: {e:?}")))?
+        .into_static();
+    let header_hash = share_hash
+        .into_u256()
+        .map_err(|e| MessagingError::Encoding(format!("invalid share hash: {e}")))?;
+    Ok(MintQuoteError {
+        error_code: PENDING_QUOTE_EXPIRED_ERROR_CODE,
+        error_message,
+        header_hash,
+    })
+}
+
 /// Statistics about the message hub
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MessageHubStats {
     pub total_connections: usize,
     pub pool_connections: usize,
     pub mint_connections: usize,
+    pub mint_available: bool,
     pub quote_request_subscribers: usize,
     pub quote_response_subscribers: usize,
     pub quote_error_subscribers: usize,
@@ -316,6 +640,24 @@ pub struct MessageHubStats {
     pub oldest_pending_ms: Option<u64>,
 }
 
+/// A quote request still waiting for the mint's response
+#[derive(Debug, Clone)]
+pub struct PendingQuoteSummary {
+    pub share_hash: ShareHash,
+    pub channel_id: u32,
+    pub sequence_number: u32,
+    pub amount: u64,
+    pub age_ms: u64,
+}
+
+/// Consistent view of the hub for diagnostics, see [`MintPoolMessageHub::get_diagnostics`]
+#[derive(Debug, Clone)]
+pub struct MessageHubDiagnostics {
+    pub stats: MessageHubStats,
+    /// Oldest first
+    pub pending_quotes: Vec<PendingQuoteSummary>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -372,6 +714,10 @@ mod tests {
 
         let received_event = resp_rx.recv().await.unwrap();
         assert_eq!(received_event.share_hash, parsed.share_hash);
+        assert_eq!(
+            received_event.locking_key(),
+            Some(locking_key().inner_as_ref())
+        );
         assert!(received_event.context.is_some());
         let received_context = received_event.context.unwrap();
         assert_eq!(received_context.channel_id, context.channel_id);
@@ -442,6 +788,46 @@ mod tests {
         assert_eq!(hub.get_stats().await.total_connections, 0);
     }
 
+    #[tokio::test]
+    async fn test_mint_available_follows_mint_connection() {
+        let hub = MintPoolMessageHub::new(MessagingConfig::default());
+        assert!(!hub.mint_available().await);
+
+        hub.register_connection("pool-1".to_string(), Role::Pool)
+            .await;
+        assert!(!hub.mint_available().await);
+
+        hub.register_connection("mint-1".to_string(), Role::Mint)
+            .await;
+        assert!(hub.mint_available().await);
+        assert!(hub.get_stats().await.mint_available);
+
+        hub.unregister_connection("mint-1").await;
+        assert!(!hub.mint_available().await);
+        assert!(!hub.get_stats().await.mint_available);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_mint_unavailable_after_heartbeat_timeout() {
+        let config = MessagingConfig {
+            heartbeat_timeout_ms: 20,
+            ..MessagingConfig::default()
+        };
+        let hub = MintPoolMessageHub::new(config);
+
+        hub.register_connection("mint-1".to_string(), Role::Mint)
+            .await;
+        tokio::time::advance(Duration::from_millis(20)).await;
+        assert!(hub.mint_available().await);
+        tokio::time::advance(Duration::from_millis(1)).await;
+        assert!(!hub.mint_available().await);
+
+        hub.record_heartbeat("mint-1").await;
+        assert!(hub.mint_available().await);
+        tokio::time::advance(Duration::from_millis(21)).await;
+        assert!(!hub.get_stats().await.mint_available);
+    }
+
     // ============================================================================
     // Quote Request Subscription Tests
     // ============================================================================
@@ -533,12 +919,42 @@ mod tests {
         let error = MintQuoteError {
             error_code: 0x01,
             error_message: Str0255::try_from("test error".to_string()).unwrap(),
+            header_hash: [0x01u8; 32].into(),
         };
 
         hub.send_quote_error(error.clone()).await.unwrap();
 
         let received = rx.recv().await.unwrap();
-        assert_eq!(received.error_code, 0x01);
+        assert_eq!(received.error().error_code, 0x01);
+        assert!(received.context().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_quote_error_resolves_pending_context() {
+        let hub = MintPoolMessageHub::new(MessagingConfig::default());
+        let mut rx = hub.subscribe_quote_errors().await.unwrap();
+
+        let parsed = crate::build_parsed_quote_request(8, &[0x21; 32], locking_key()).unwrap();
+        let context = PendingQuoteContext {
+            channel_id: 42,
+            sequence_number: 9,
+            amount: 8,
+        };
+        hub.send_quote_request(parsed.clone(), context).await.unwrap();
+
+        let error = MintQuoteError {
+            error_code: 0x01,
+            error_message: Str0255::try_from("below minimum".to_string()).unwrap(),
+            header_hash: parsed.share_hash.into_u256().unwrap(),
+        };
+        hub.send_quote_error(error).await.unwrap();
+
+        let received = rx.recv().await.unwrap();
+        assert_eq!(received.share_hash, parsed.share_hash);
+        let context = received.context().expect("context for pending share");
+        assert_eq!(context.channel_id, 42);
+        assert_eq!(context.sequence_number, 9);
+        assert!(hub.pending_quote(parsed.share_hash).await.is_none());
     }
 
     // ============================================================================
@@ -566,6 +982,101 @@ mod tests {
         assert_eq!(stats.pending_quotes, 3);
     }
 
+    #[tokio::test]
+    async fn test_pending_quotes_evict_oldest_past_limit() {
+        let config = MessagingConfig {
+            max_pending_quotes: Some(2),
+            ..MessagingConfig::default()
+        };
+        let hub = MintPoolMessageHub::new(config);
+        let mut evictions = hub.subscribe_quote_evictions().await.unwrap();
+
+        for i in 1..=4u8 {
+            let parsed =
+                crate::build_parsed_quote_request(i as u64, &[i; 32], locking_key()).unwrap();
+            let context = PendingQuoteContext {
+                channel_id: i as u32,
+                sequence_number: i as u32,
+                amount: i as u64,
+            };
+            // Sent back to back, so creation times may tie; eviction follows insertion order
+            hub.send_quote_request(parsed, context).await.unwrap();
+        }
+
+        assert_eq!(hub.get_stats().await.pending_quotes, 2);
+        for i in 1..=2u8 {
+            let event = evictions.try_recv().unwrap();
+            assert_eq!(event.share_hash, ShareHash::from([i; 32]));
+            assert_eq!(event.context.channel_id, i as u32);
+            assert_eq!(event.context.amount, i as u64);
+            assert!(hub.pending_quote(event.share_hash).await.is_none());
+        }
+        assert!(evictions.try_recv().is_err());
+        assert!(hub
+            .pending_quote(ShareHash::from([4u8; 32]))
+            .await
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_reaper_fails_stale_pending_quotes() {
+        let hub = MintPoolMessageHub::new(MessagingConfig::default());
+        let mut errors = hub.subscribe_quote_errors().await.unwrap();
+
+        let parsed = crate::build_parsed_quote_request(21, &[0x5E; 32], locking_key()).unwrap();
+        let context = PendingQuoteContext {
+            channel_id: 6,
+            sequence_number: 2,
+            amount: 21,
+        };
+        hub.send_quote_request(parsed.clone(), context)
+            .await
+            .unwrap();
+
+        let reaper = hub.clone().spawn_pending_reaper(Duration::from_millis(20));
+        let event = timeout(Duration::from_secs(1), errors.recv())
+            .await
+            .expect("reaper should report the stale quote")
+            .unwrap();
+        reaper.abort();
+
+        assert_eq!(event.share_hash, parsed.share_hash);
+        assert_eq!(event.error().error_code, PENDING_QUOTE_EXPIRED_ERROR_CODE);
+        assert_eq!(event.context().map(|ctx| ctx.channel_id), Some(6));
+        assert!(hub.pending_quote(parsed.share_hash).await.is_none());
+        assert_eq!(hub.get_stats().await.pending_quotes, 0);
+    }
+
+    #[tokio::test]
+    async fn test_diagnostics_list_pending_quotes() {
+        let hub = MintPoolMessageHub::new(MessagingConfig::default());
+
+        for i in 1..=2u8 {
+            let parsed =
+                crate::build_parsed_quote_request(i as u64, &[i; 32], locking_key()).unwrap();
+            let context = PendingQuoteContext {
+                channel_id: 10 + i as u32,
+                sequence_number: i as u32,
+                amount: i as u64,
+            };
+            hub.send_quote_request(parsed, context).await.unwrap();
+        }
+
+        let diagnostics = hub.get_diagnostics().await;
+        assert_eq!(diagnostics.stats.pending_quotes, 2);
+        let mut channels: Vec<u32> = diagnostics
+            .pending_quotes
+            .iter()
+            .map(|q| q.channel_id)
+            .collect();
+        channels.sort();
+        assert_eq!(channels, vec![11, 12]);
+        assert!(diagnostics
+            .pending_quotes
+            .iter()
+            .any(|q| q.share_hash == ShareHash::from([1u8; 32])));
+    }
+
     #[tokio::test]
     async fn test_pending_quote_removed_on_response() {
         let hub = MintPoolMessageHub::new(MessagingConfig::default());
@@ -590,6 +1101,69 @@ mod tests {
         assert_eq!(hub.get_stats().await.pending_quotes, 0);
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_duplicate_quote_blocked_within_dedup_window() {
+        let config = MessagingConfig {
+            dedup_window_ms: 30,
+            ..MessagingConfig::default()
+        };
+        let hub = MintPoolMessageHub::new(config);
+
+        let hash = [0x5Au8; 32];
+        let parsed = crate::build_parsed_quote_request(400, &hash, locking_key()).unwrap();
+        let context = PendingQuoteContext {
+            channel_id: 4,
+            sequence_number: 4,
+            amount: 400,
+        };
+
+        hub.send_quote_request(parsed.clone(), context.clone())
+            .await
+            .unwrap();
+        let repeat = hub
+            .send_quote_request(parsed.clone(), context.clone())
+            .await;
+        assert!(matches!(repeat, Err(MessagingError::DuplicateQuote(h)) if h == parsed.share_hash));
+
+        tokio::time::advance(Duration::from_millis(29)).await;
+        let repeat = hub
+            .send_quote_request(parsed.clone(), context.clone())
+            .await;
+        assert!(matches!(repeat, Err(MessagingError::DuplicateQuote(_))));
+
+        // An expired hash is dropped from the front when the next request arrives
+        tokio::time::advance(Duration::from_millis(1)).await;
+        let other = crate::build_parsed_quote_request(400, &[0x5Bu8; 32], locking_key()).unwrap();
+        hub.send_quote_request(other, context.clone())
+            .await
+            .unwrap();
+        assert_eq!(hub.recent_share_hashes.read().await.len(), 1);
+        assert!(hub.send_quote_request(parsed, context).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_quote_can_be_retried() {
+        let hub = MintPoolMessageHub::new(MessagingConfig::default());
+
+        let hash = [0x5Bu8; 32];
+        let parsed = crate::build_parsed_quote_request(500, &hash, locking_key()).unwrap();
+        let context = PendingQuoteContext {
+            channel_id: 5,
+            sequence_number: 5,
+            amount: 500,
+        };
+
+        hub.send_quote_request(parsed.clone(), context.clone())
+            .await
+            .unwrap();
+        hub.abandon_quote_request(&parsed.share_hash).await;
+        assert_eq!(hub.get_stats().await.pending_quotes, 0);
+
+        // The retry is not mistaken for a duplicate of the abandoned send
+        assert!(hub.send_quote_request(parsed, context).await.is_ok());
+        assert_eq!(hub.get_stats().await.pending_quotes, 1);
+    }
+
     #[tokio::test]
     async fn test_response_without_pending_context() {
         let hub = MintPoolMessageHub::new(MessagingConfig::default());
@@ -606,6 +1180,73 @@ mod tests {
         // We don't use _event, so prefixed with underscore
     }
 
+    #[tokio::test]
+    async fn test_orphan_response_dropped_in_strict_mode() {
+        let config = MessagingConfig {
+            reject_orphan_responses: true,
+            ..MessagingConfig::default()
+        };
+        let hub = MintPoolMessageHub::new(config);
+        let mut rx = hub.subscribe_quote_responses().await.unwrap();
+
+        let response = MintQuoteResponse {
+            quote_id: Str0255::try_from("ORPHAN".to_string()).unwrap(),
+            header_hash: [0x99u8; 32].into(),
+        };
+
+        let result = hub.send_quote_response(response).await;
+        assert!(matches!(result, Err(MessagingError::OrphanResponse(_))));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_orphan_response_broadcast_in_lenient_mode() {
+        let hub = MintPoolMessageHub::new(MessagingConfig::default());
+        let mut rx = hub.subscribe_quote_responses().await.unwrap();
+
+        let response = MintQuoteResponse {
+            quote_id: Str0255::try_from("ORPHAN".to_string()).unwrap(),
+            header_hash: [0x99u8; 32].into(),
+        };
+
+        hub.send_quote_response(response).await.unwrap();
+        let received = rx.try_recv().unwrap();
+        assert!(received.context.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stale_response_dropped_under_max_age() {
+        let config = MessagingConfig {
+            max_response_age_ms: Some(20),
+            ..MessagingConfig::default()
+        };
+        let hub = MintPoolMessageHub::new(config);
+        let mut rx = hub.subscribe_quote_responses().await.unwrap();
+
+        let parsed = crate::build_parsed_quote_request(100, &[0x5Au8; 32], locking_key()).unwrap();
+        let context = PendingQuoteContext {
+            channel_id: 4,
+            sequence_number: 1,
+            amount: 100,
+        };
+        hub.send_quote_request(parsed.clone(), context).await.unwrap();
+        tokio::time::advance(Duration::from_millis(21)).await;
+
+        let response = MintQuoteResponse {
+            quote_id: Str0255::try_from("LATE".to_string()).unwrap(),
+            header_hash: parsed.share_hash.into_u256().unwrap(),
+        };
+        let result = hub.send_quote_response(response).await;
+
+        assert!(matches!(
+            result,
+            Err(MessagingError::StaleResponse { share_hash, age_ms })
+                if share_hash == parsed.share_hash && age_ms == 21
+        ));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(hub.get_stats().await.pending_quotes, 0);
+    }
+
     // ============================================================================
     // Message Hub Statistics Tests
     // ============================================================================
@@ -618,6 +1259,7 @@ mod tests {
         assert_eq!(stats.total_connections, 0);
         assert_eq!(stats.pool_connections, 0);
         assert_eq!(stats.mint_connections, 0);
+        assert!(!stats.mint_available);
         assert_eq!(stats.pending_quotes, 0);
         assert_eq!(stats.oldest_pending_ms, None);
     }
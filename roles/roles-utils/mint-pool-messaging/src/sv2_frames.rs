@@ -6,8 +6,9 @@
 
 use binary_sv2::to_bytes;
 use const_sv2::{
-    CHANNEL_BIT_MINT_QUOTE_ERROR, CHANNEL_BIT_MINT_QUOTE_REQUEST, CHANNEL_BIT_MINT_QUOTE_RESPONSE,
-    MESSAGE_TYPE_MINT_QUOTE_ERROR, MESSAGE_TYPE_MINT_QUOTE_REQUEST,
+    CHANNEL_BIT_MINT_PING, CHANNEL_BIT_MINT_PONG, CHANNEL_BIT_MINT_QUOTE_ERROR,
+    CHANNEL_BIT_MINT_QUOTE_REQUEST, CHANNEL_BIT_MINT_QUOTE_RESPONSE, MESSAGE_TYPE_MINT_PING,
+    MESSAGE_TYPE_MINT_PONG, MESSAGE_TYPE_MINT_QUOTE_ERROR, MESSAGE_TYPE_MINT_QUOTE_REQUEST,
     MESSAGE_TYPE_MINT_QUOTE_RESPONSE, SV2_MINT_QUOTE_PROTOCOL_DISCRIMINANT,
 };
 use mint_quote_sv2::{MintQuoteError, MintQuoteRequest, MintQuoteResponse};
@@ -51,6 +52,35 @@ pub fn quote_error_frame_bytes(error: &MintQuoteError<'static>) -> MessagingResu
     )
 }
 
+/// Build a noise-ready SV2 heartbeat ping carrying `nonce`.
+pub fn ping_frame_bytes(nonce: u64) -> MessagingResult<Vec<u8>> {
+    build_frame_bytes(
+        nonce.to_le_bytes().to_vec(),
+        MESSAGE_TYPE_MINT_PING,
+        CHANNEL_BIT_MINT_PING,
+    )
+}
+
+/// Build a noise-ready SV2 heartbeat pong echoing the `nonce` of the ping it answers.
+pub fn pong_frame_bytes(nonce: u64) -> MessagingResult<Vec<u8>> {
+    build_frame_bytes(
+        nonce.to_le_bytes().to_vec(),
+        MESSAGE_TYPE_MINT_PONG,
+        CHANNEL_BIT_MINT_PONG,
+    )
+}
+
+/// Read the nonce from the payload of a ping or pong frame.
+pub fn decode_heartbeat_nonce(payload: &[u8]) -> MessagingResult<u64> {
+    let bytes: [u8; 8] = payload.try_into().map_err(|_| {
+        MessagingError::Encoding(format!(
+            "heartbeat payload must be 8 bytes, got {}",
+            payload.len()
+        ))
+    })?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
 fn build_frame_bytes(
     payload: Vec<u8>,
     message_type: u8,
@@ -89,6 +119,7 @@ mod tests {
         let error = MintQuoteError {
             error_code: 0x01,
             error_message: Str0255::try_from("Invalid request".to_string()).unwrap(),
+            header_hash: [0x51u8; 32].into(),
         };
 
         let frame = quote_error_frame_bytes(&error).unwrap();
@@ -106,6 +137,7 @@ mod tests {
             let error = MintQuoteError {
                 error_code,
                 error_message: Str0255::try_from("error".to_string()).unwrap(),
+                header_hash: [0x52u8; 32].into(),
             };
 
             let frame = quote_error_frame_bytes(&error).unwrap();
@@ -148,6 +180,7 @@ mod tests {
         let error = MintQuoteError {
             error_code: 0x05,
             error_message: Str0255::try_from("test".to_string()).unwrap(),
+            header_hash: [0x53u8; 32].into(),
         };
 
         let frame = quote_error_frame_bytes(&error).unwrap();
@@ -161,6 +194,7 @@ mod tests {
         let error = MintQuoteError {
             error_code: 0x20,
             error_message: Str0255::try_from("message".to_string()).unwrap(),
+            header_hash: [0x54u8; 32].into(),
         };
 
         let frame = quote_error_frame_bytes(&error).unwrap();
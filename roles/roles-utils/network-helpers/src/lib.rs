@@ -1,6 +1,8 @@
 pub mod noise_connection;
 pub mod noise_stream;
 pub mod plain_connection;
+pub mod resolve;
+pub mod retry;
 #[cfg(feature = "sv1")]
 pub mod sv1_connection;
 
@@ -0,0 +1,147 @@
+//! Resolution of configured upstream addresses.
+//!
+//! Roles keep upstream addresses as configured (an IP literal or a hostname) and resolve them
+//! on every connection attempt, so a hostname whose records change (e.g. during failover) is
+//! picked up on reconnect.
+
+use std::{
+    io::{Error, ErrorKind},
+    net::{IpAddr, SocketAddr},
+};
+
+/// Resolves `host` and `port` to a socket address, using the first address returned by the
+/// resolver.
+///
+/// IP literals are returned without a lookup. Anything else must be a syntactically valid
+/// hostname before it is handed to the system resolver.
+pub async fn resolve_address(host: &str, port: u16) -> Result<SocketAddr, Error> {
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, port));
+    }
+    if !is_valid_hostname(host) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("`{host}` is neither an IP address nor a hostname"),
+        ));
+    }
+    tokio::net::lookup_host((host, port))
+        .await?
+        .next()
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::NotFound,
+                format!("{host}:{port} has no addresses"),
+            )
+        })
+}
+
+/// Resolves a `host:port` string with [`resolve_address`]. IPv6 literals are written in
+/// brackets, as in `[::1]:8442`.
+pub async fn resolve_host_port(address: &str) -> Result<SocketAddr, Error> {
+    let (host, port) = split_host_port(address)?;
+    resolve_address(host, port).await
+}
+
+/// Splits a `host:port` string into its host, without IPv6 brackets, and port.
+pub fn split_host_port(address: &str) -> Result<(&str, u16), Error> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("`{address}` is not a host:port address"),
+        )
+    };
+    let (host, port) = address.rsplit_once(':').ok_or_else(invalid)?;
+    let host = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.strip_suffix(']').ok_or_else(invalid)?,
+        None if host.contains(':') => return Err(invalid()),
+        None => host,
+    };
+    let port = port.parse().map_err(|_| invalid())?;
+    Ok((host, port))
+}
+
+/// Whether `host` is a DNS name: dot-separated labels of 1 to 63 ASCII letters, digits,
+/// hyphens and underscores (as in container service names), not starting or ending with a
+/// hyphen, at most 253 characters in total.
+fn is_valid_hostname(host: &str) -> bool {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    !host.is_empty()
+        && host.len() <= 253
+        && host.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_ip_literals() {
+        assert_eq!(
+            resolve_address("127.0.0.1", 8442).await.unwrap(),
+            "127.0.0.1:8442".parse().unwrap()
+        );
+        assert_eq!(
+            resolve_address("::1", 8442).await.unwrap(),
+            "[::1]:8442".parse().unwrap()
+        );
+        assert_eq!(
+            resolve_host_port("[::1]:8442").await.unwrap(),
+            "[::1]:8442".parse().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_rejects_malformed_hosts_without_lookup() {
+        for host in [
+            "",
+            "not a host",
+            "-pool.example",
+            "pool..example",
+            "pool/1.example",
+        ] {
+            let err = resolve_address(host, 8442).await.unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidInput, "{host:?}");
+        }
+    }
+
+    #[test]
+    fn test_split_host_port() {
+        assert_eq!(
+            split_host_port("pool.example.com:34254").unwrap(),
+            ("pool.example.com", 34254)
+        );
+        assert_eq!(
+            split_host_port("10.0.0.1:8442").unwrap(),
+            ("10.0.0.1", 8442)
+        );
+        assert_eq!(split_host_port("[::1]:8442").unwrap(), ("::1", 8442));
+
+        for address in [
+            "missing-port",
+            "host:",
+            "host:port",
+            "host:70000",
+            "::1:8442",
+            "[::1:8442",
+        ] {
+            assert!(split_host_port(address).is_err(), "{address}");
+        }
+    }
+
+    #[test]
+    fn test_valid_hostnames() {
+        assert!(is_valid_hostname("localhost"));
+        assert!(is_valid_hostname("pool-1.example.com."));
+        assert!(is_valid_hostname("hashpool_pool_1"));
+        assert!(!is_valid_hostname(&"a".repeat(64)));
+        assert!(!is_valid_hostname("pool-.example"));
+    }
+}
@@ -0,0 +1,146 @@
+//! Retrying async operations with exponential backoff and jitter.
+//!
+//! [`retry_with_backoff`] runs an operation until it succeeds or the policy's attempts are used
+//! up. The delay before retry `n` is `base_delay * 2^(n-1)`, capped at `max_delay`, with up to
+//! `jitter` of it randomly shaved off so that many clients failing together do not retry in
+//! lockstep.
+
+use std::{
+    collections::hash_map::RandomState,
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+use tracing::debug;
+
+/// How often and how patiently [`retry_with_backoff`] retries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts including the first one; retries forever when `None`
+    pub max_attempts: Option<u32>,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Upper bound on any single delay
+    pub max_delay: Duration,
+    /// Fraction of each delay, from 0 to 1, that may be randomly removed
+    pub jitter: f64,
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry`, counting the first retry as 1.
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(31);
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        backoff.mul_f64(1.0 - jitter * random_unit())
+    }
+}
+
+// Uniform value in [0, 1). Each `RandomState` is freshly keyed, which is random enough to spread
+// retries without pulling in an RNG.
+fn random_unit() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Runs `op` until it returns `Ok`, sleeping between attempts as `policy` dictates.
+///
+/// Returns the error of the last attempt once `policy.max_attempts` attempts have failed.
+pub async fn retry_with_backoff<T, E, F, Fut>(policy: &RetryPolicy, mut op: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                if policy.max_attempts.is_some_and(|max| attempt >= max) {
+                    return Err(e);
+                }
+                let delay = policy.delay_for(attempt);
+                debug!("Attempt {} failed, retrying in {:?}", attempt, delay);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy(max_attempts: Option<u32>) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(4),
+            jitter: 0.5,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_succeeds_after_retries() {
+        let calls = AtomicU32::new(0);
+        let result: Result<u32, &str> = retry_with_backoff(&policy(Some(5)), || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err("not yet"),
+                n => Ok(n),
+            }
+        })
+        .await;
+        assert_eq!(result, Ok(2));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_returns_last_error_when_exhausted() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), u32> = retry_with_backoff(&policy(Some(3)), || async {
+            Err(calls.fetch_add(1, Ordering::SeqCst))
+        })
+        .await;
+        assert_eq!(result, Err(2));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_delays_grow_within_bounds() {
+        let policy = RetryPolicy {
+            max_attempts: None,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            jitter: 0.25,
+        };
+        for _ in 0..100 {
+            for (retry, full) in [
+                (1, 100),
+                (2, 200),
+                (3, 400),
+                (4, 800),
+                (5, 1000),
+                (40, 1000),
+            ] {
+                let delay = policy.delay_for(retry);
+                let full = Duration::from_millis(full);
+                assert!(delay <= full, "retry {retry}: {delay:?} > {full:?}");
+                assert!(
+                    delay >= full.mul_f64(0.75),
+                    "retry {retry}: {delay:?} too short"
+                );
+            }
+        }
+
+        let no_jitter = RetryPolicy {
+            jitter: 0.0,
+            ..policy
+        };
+        assert_eq!(no_jitter.delay_for(3), Duration::from_millis(400));
+    }
+}
@@ -5,50 +5,115 @@
 //! core pool code.
 
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc,
     },
-    time::{SystemTime, UNIX_EPOCH},
 };
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use quote_dispatcher::QuoteEventCallback;
+use stats::clock::{system_clock, Clock};
 use stats_sv2::WindowedMetricsCollector;
 
-/// Get current Unix timestamp in seconds.
-fn unix_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
-        .as_secs()
+/// Width of the hashrate window kept by each downstream's metrics collector.
+const METRICS_WINDOW_SECS: u64 = 60;
+
+/// Number of log2-scale buckets in the share difficulty histogram.
+pub const DIFFICULTY_BUCKETS: usize = 32;
+
+/// Default number of share timestamps kept for [`DownstreamStats::shares_per_minute`].
+pub const DEFAULT_RECENT_SHARES_CAPACITY: usize = 64;
+
+/// Most identities whose lifetime totals are kept; beyond that the smallest are dropped.
+pub const MAX_LIFETIME_ENTRIES: usize = 10_000;
+
+/// Histogram bucket for a share difficulty: bucket `i` counts difficulties in
+/// `[2^i, 2^(i+1))`, with everything below 2 in bucket 0 and everything from
+/// `2^(DIFFICULTY_BUCKETS - 1)` up in the last bucket.
+pub fn difficulty_bucket(difficulty: f64) -> usize {
+    if difficulty.is_nan() || difficulty < 2.0 {
+        return 0;
+    }
+    (difficulty.log2().floor() as usize).min(DIFFICULTY_BUCKETS - 1)
+}
+
+/// Adds `value` to the `f64` stored as bits in `cell`. Zero bits are `0.0`, so a cell starts
+/// and resets like the integer counters.
+///
+/// The sum saturates at `f64::MAX` instead of overflowing to infinity, and NaN is ignored,
+/// so an absurd difficulty from a misbehaving miner cannot poison the lifetime sums.
+fn add_f64(cell: &AtomicU64, value: f64) {
+    if value.is_nan() {
+        return;
+    }
+    let _ = cell.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+        Some((f64::from_bits(bits) + value).min(f64::MAX).to_bits())
+    });
 }
 
 /// Per-downstream stats tracked externally from SRI code.
 pub struct DownstreamStats {
     pub shares_submitted: AtomicU64,
     pub quotes_created: AtomicU64,
+    pub quotes_failed: AtomicU64,
     pub ehash_mined: AtomicU64,
+    pub blocks_found: AtomicU64,
     pub last_share_at: AtomicU64,
+    // Lifetime counts of submitted share difficulties, see `difficulty_bucket`
+    pub difficulty_histogram: [AtomicU64; DIFFICULTY_BUCKETS],
+    // Lifetime difficulty of all submitted shares and of the rejected ones, as f64 bits
+    pub submitted_sum_difficulty: AtomicU64,
+    pub rejected_sum_difficulty: AtomicU64,
     // Shared windowed metrics collector for accurate time-series hashrate
     pub metrics_collector: RwLock<WindowedMetricsCollector>,
+    // Timestamps of the most recent shares, oldest first, capped at `recent_shares_capacity`
+    recent_shares: Mutex<VecDeque<u64>>,
+    recent_shares_capacity: usize,
+    clock: Clock,
 }
 
 impl DownstreamStats {
     pub fn new() -> Self {
+        Self::with_recent_shares_capacity(DEFAULT_RECENT_SHARES_CAPACITY)
+    }
+
+    /// Like [`Self::new`], keeping `capacity` share timestamps for
+    /// [`Self::shares_per_minute`] (at least one).
+    pub fn with_recent_shares_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
         Self {
             shares_submitted: AtomicU64::new(0),
             quotes_created: AtomicU64::new(0),
+            quotes_failed: AtomicU64::new(0),
             ehash_mined: AtomicU64::new(0),
+            blocks_found: AtomicU64::new(0),
             last_share_at: AtomicU64::new(0),
-            metrics_collector: RwLock::new(WindowedMetricsCollector::new(60)), // 60-second (1-minute) window
+            difficulty_histogram: std::array::from_fn(|_| AtomicU64::new(0)),
+            submitted_sum_difficulty: AtomicU64::new(0),
+            rejected_sum_difficulty: AtomicU64::new(0),
+            metrics_collector: RwLock::new(WindowedMetricsCollector::new(METRICS_WINDOW_SECS)),
+            recent_shares: Mutex::new(VecDeque::with_capacity(capacity)),
+            recent_shares_capacity: capacity,
+            clock: system_clock(),
         }
     }
 
+    /// Timestamp shares, blocks and the hashrate window with `clock` instead of the system
+    /// time.
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.metrics_collector = RwLock::new(WindowedMetricsCollector::with_clock(
+            METRICS_WINDOW_SECS,
+            clock.clone(),
+        ));
+        self.clock = clock;
+        self
+    }
+
     /// Track a standard share (no quote).
     pub fn record_share(&self) {
-        let now = unix_timestamp();
+        let now = (self.clock)();
         self.shares_submitted.fetch_add(1, Ordering::Relaxed);
         self.last_share_at.store(now, Ordering::Relaxed);
     }
@@ -56,15 +121,100 @@ impl DownstreamStats {
     /// Record a share with its difficulty for time-series metrics.
     /// Uses the shared WindowedMetricsCollector.
     pub fn record_share_with_difficulty(&self, difficulty: f64) {
-        let now = unix_timestamp();
+        let now = (self.clock)();
         self.shares_submitted.fetch_add(1, Ordering::Relaxed);
         self.last_share_at.store(now, Ordering::Relaxed);
+        self.difficulty_histogram[difficulty_bucket(difficulty)].fetch_add(1, Ordering::Relaxed);
+        add_f64(&self.submitted_sum_difficulty, difficulty);
+
+        self.push_recent_share(now);
 
         // Record with shared metrics collector (handles windowing)
         let mut collector = self.metrics_collector.write();
         collector.record_share(difficulty);
     }
 
+    fn push_recent_share(&self, timestamp: u64) {
+        let mut recent = self.recent_shares.lock();
+        if recent.len() == self.recent_shares_capacity {
+            recent.pop_front();
+        }
+        recent.push_back(timestamp);
+    }
+
+    /// Shares recorded with [`Self::record_share_with_difficulty`] in the last 60 seconds.
+    ///
+    /// Only the most recent shares are remembered (see
+    /// [`Self::with_recent_shares_capacity`]), so the rate saturates at that capacity.
+    pub fn shares_per_minute(&self) -> f64 {
+        self.shares_per_minute_at((self.clock)())
+    }
+
+    fn shares_per_minute_at(&self, now: u64) -> f64 {
+        let recent = self.recent_shares.lock();
+        recent
+            .iter()
+            .rev()
+            .take_while(|&&timestamp| now.saturating_sub(timestamp) < 60)
+            .count() as f64
+    }
+
+    /// Count a rejected share's difficulty. The share must also have gone through
+    /// [`Self::record_share_with_difficulty`], which counts every submission.
+    pub fn record_rejected_share_with_difficulty(&self, difficulty: f64) {
+        add_f64(&self.rejected_sum_difficulty, difficulty);
+    }
+
+    /// Lifetime difficulty of rejected shares.
+    pub fn get_rejected_sum_difficulty(&self) -> f64 {
+        f64::from_bits(self.rejected_sum_difficulty.load(Ordering::Relaxed))
+    }
+
+    /// Share of submitted difficulty that was rejected, i.e. rejected / (accepted + rejected),
+    /// or 0 before any share.
+    pub fn reject_ratio(&self) -> f64 {
+        let submitted = f64::from_bits(self.submitted_sum_difficulty.load(Ordering::Relaxed));
+        if submitted <= 0.0 {
+            return 0.0;
+        }
+        (self.get_rejected_sum_difficulty() / submitted).min(1.0)
+    }
+
+    /// Track a block found by one of this downstream's shares.
+    pub fn record_block_found(&self) {
+        self.blocks_found.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counters that feed [`LifetimeTotals`], as recorded since registration or the last reset.
+    pub fn live_totals(&self) -> LifetimeTotals {
+        LifetimeTotals {
+            shares: self.shares_submitted.load(Ordering::Relaxed),
+            ehash: self.ehash_mined.load(Ordering::Relaxed),
+            blocks: self.blocks_found.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Track a share whose quote could not be dispatched to the mint.
+    pub fn record_quote_failed(&self) {
+        self.quotes_failed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Fraction of quote attempts that succeeded, or `None` before any attempt.
+    pub fn quote_success_ratio(&self) -> Option<f64> {
+        let created = self.quotes_created.load(Ordering::Relaxed);
+        let failed = self.quotes_failed.load(Ordering::Relaxed);
+        let attempts = created.saturating_add(failed);
+        if attempts == 0 {
+            return None;
+        }
+        Some(created as f64 / attempts as f64)
+    }
+
+    /// Share counts per difficulty bucket, indexed as in [`difficulty_bucket`].
+    pub fn difficulty_histogram(&self) -> [u64; DIFFICULTY_BUCKETS] {
+        std::array::from_fn(|i| self.difficulty_histogram[i].load(Ordering::Relaxed))
+    }
+
     /// Get the sum of difficulties in the current window (60 seconds).
     pub fn sum_difficulty_in_window(&self) -> f64 {
         let collector = self.metrics_collector.read();
@@ -82,6 +232,68 @@ impl DownstreamStats {
         let collector = self.metrics_collector.read();
         collector.window_seconds()
     }
+
+    /// Zero every counter, including the difficulty histogram, and forget the recent share
+    /// timestamps so the snapshot rate starts over too. The hashrate window is left alone
+    /// since it only ever covers the last minute.
+    ///
+    /// Each counter is cleared with its own `Relaxed` store, matching how they are
+    /// updated. A share recorded concurrently is either cleared with the rest or counted
+    /// in full after the reset, but the counters are not reset as one atomic unit: such a
+    /// share may survive in `shares_submitted` while its histogram entry is cleared.
+    pub fn reset(&self) {
+        for counter in [
+            &self.shares_submitted,
+            &self.quotes_created,
+            &self.quotes_failed,
+            &self.ehash_mined,
+            &self.blocks_found,
+            &self.last_share_at,
+            &self.submitted_sum_difficulty,
+            &self.rejected_sum_difficulty,
+        ]
+        .into_iter()
+        .chain(&self.difficulty_histogram)
+        {
+            counter.store(0, Ordering::Relaxed);
+        }
+        self.recent_shares.lock().clear();
+    }
+}
+
+/// Point-in-time copy of a downstream's counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DownstreamStatsSnapshot {
+    pub shares_submitted: u64,
+    pub quotes_created: u64,
+    pub quotes_failed: u64,
+    pub ehash_mined: u64,
+    pub blocks_found: u64,
+    pub last_share_at: Option<u64>,
+    pub quote_success_ratio: Option<f64>,
+    pub difficulty_histogram: [u64; DIFFICULTY_BUCKETS],
+    pub reject_ratio: f64,
+    /// Shares submitted in the last minute, see [`DownstreamStats::shares_per_minute`]
+    pub shares_per_minute: f64,
+}
+
+/// Cumulative totals for one miner identity across resets, reconnects and, when persisted,
+/// pool restarts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LifetimeTotals {
+    pub shares: u64,
+    pub ehash: u64,
+    pub blocks: u64,
+}
+
+impl LifetimeTotals {
+    fn saturating_add(self, other: Self) -> Self {
+        Self {
+            shares: self.shares.saturating_add(other.shares),
+            ehash: self.ehash.saturating_add(other.ehash),
+            blocks: self.blocks.saturating_add(other.blocks),
+        }
+    }
 }
 
 impl Default for DownstreamStats {
@@ -93,44 +305,193 @@ impl Default for DownstreamStats {
 /// Global stats registry for all downstreams.
 pub struct PoolStatsRegistry {
     stats: RwLock<HashMap<u32, Arc<DownstreamStats>>>,
+    // Accepted shares across all downstreams since the last block (or since startup)
+    shares_since_last_block: AtomicU64,
+    // Unix timestamp of the last block found, 0 if none since startup
+    last_block_found_at: AtomicU64,
+    // User identity each downstream's lifetime totals are counted under. Downstream ids
+    // restart at 1 with the pool and change on every reconnect, so they can't be the key.
+    identities: RwLock<HashMap<u32, String>>,
+    // Totals no longer held by live counters, by identity: restored at startup, or folded
+    // in when a downstream is reset or unregistered
+    lifetime_base: Mutex<HashMap<String, LifetimeTotals>>,
+    max_lifetime_entries: usize,
+    // Handed to every registered downstream
+    clock: Clock,
 }
 
 impl PoolStatsRegistry {
     pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Registry whose downstreams and block timestamps use `clock` instead of the system time.
+    pub fn with_clock(clock: Clock) -> Arc<Self> {
         Arc::new(Self {
-            stats: RwLock::new(HashMap::new()),
+            clock,
+            ..Self::default()
         })
     }
 
     pub fn register_downstream(&self, downstream_id: u32) -> Arc<DownstreamStats> {
-        let stats = Arc::new(DownstreamStats::new());
+        let stats = Arc::new(DownstreamStats::new().with_clock(self.clock.clone()));
         self.stats.write().insert(downstream_id, stats.clone());
         stats
     }
 
     pub fn unregister_downstream(&self, downstream_id: u32) {
-        self.stats.write().remove(&downstream_id);
+        if let Some(stats) = self.stats.write().remove(&downstream_id) {
+            self.fold_into_lifetime(downstream_id, &stats);
+        }
+        self.identities.write().remove(&downstream_id);
+    }
+
+    /// Count a downstream's lifetime totals under `user_identity`, typically that of its
+    /// first channel. Later calls for the same downstream are ignored.
+    pub fn set_identity(&self, downstream_id: u32, user_identity: &str) {
+        self.identities
+            .write()
+            .entry(downstream_id)
+            .or_insert_with(|| user_identity.to_string());
     }
 
     pub fn get_stats(&self, downstream_id: u32) -> Option<Arc<DownstreamStats>> {
         self.stats.read().get(&downstream_id).cloned()
     }
 
-    pub fn snapshot(&self) -> HashMap<u32, (u64, u64, u64, Option<u64>)> {
+    /// Zero a downstream's counters, e.g. at the start of a payout round, keeping it
+    /// registered. Returns `false` if the downstream is unknown. See
+    /// [`DownstreamStats::reset`] for how this interacts with concurrent recording.
+    pub fn reset_downstream(&self, downstream_id: u32) -> bool {
+        match self.get_stats(downstream_id) {
+            Some(stats) => {
+                self.fold_into_lifetime(downstream_id, &stats);
+                stats.reset();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Zero the counters of every registered downstream. The round counters
+    /// (`shares_since_last_block`, `last_block_found_at`) follow blocks and are kept.
+    pub fn reset_all(&self) {
+        for (id, stats) in self.stats.read().iter() {
+            self.fold_into_lifetime(*id, stats);
+            stats.reset();
+        }
+    }
+
+    /// Seed the lifetime totals, e.g. with what was persisted before a restart. Replaces any
+    /// earlier seed; live counters keep adding on top. Only the largest
+    /// [`MAX_LIFETIME_ENTRIES`] are kept.
+    pub fn restore_lifetime_totals(&self, totals: HashMap<String, LifetimeTotals>) {
+        let mut totals: Vec<_> = totals.into_iter().collect();
+        if totals.len() > self.max_lifetime_entries {
+            totals.sort_by(|(a_id, a), (b_id, b)| b.shares.cmp(&a.shares).then(a_id.cmp(b_id)));
+            totals.truncate(self.max_lifetime_entries);
+        }
+        *self.lifetime_base.lock() = totals.into_iter().collect();
+    }
+
+    /// Lifetime totals of every identity seen, merging the restored totals with the live
+    /// counters. Downstreams that never opened a channel have no identity and are left out.
+    pub fn lifetime_totals(&self) -> HashMap<String, LifetimeTotals> {
+        let mut totals = self.lifetime_base.lock().clone();
+        let identities = self.identities.read();
+        for (id, stats) in self.stats.read().iter() {
+            if let Some(identity) = identities.get(id) {
+                let entry = totals.entry(identity.clone()).or_default();
+                *entry = entry.saturating_add(stats.live_totals());
+            }
+        }
+        totals
+    }
+
+    /// The `limit` identities with the most lifetime shares, most first.
+    pub fn top_lifetime(&self, limit: usize) -> Vec<(String, LifetimeTotals)> {
+        let mut totals: Vec<_> = self.lifetime_totals().into_iter().collect();
+        totals.sort_by(|(a_id, a), (b_id, b)| b.shares.cmp(&a.shares).then(a_id.cmp(b_id)));
+        totals.truncate(limit);
+        totals
+    }
+
+    fn fold_into_lifetime(&self, downstream_id: u32, stats: &DownstreamStats) {
+        let Some(identity) = self.identities.read().get(&downstream_id).cloned() else {
+            return;
+        };
+        let mut base = self.lifetime_base.lock();
+        let entry = base.entry(identity.clone()).or_default();
+        *entry = entry.saturating_add(stats.live_totals());
+        if base.len() > self.max_lifetime_entries {
+            Self::evict_smallest(&mut base, &identity);
+        }
+    }
+
+    /// Drop the entry with the fewest shares, other than `keep`. Only runs when a reset or
+    /// disconnect adds an identity past the limit, so the linear scan stays off the share
+    /// path.
+    fn evict_smallest(base: &mut HashMap<String, LifetimeTotals>, keep: &str) {
+        let smallest = base
+            .iter()
+            .filter(|(identity, _)| identity.as_str() != keep)
+            .min_by(|(a_id, a), (b_id, b)| a.shares.cmp(&b.shares).then(b_id.cmp(a_id)))
+            .map(|(identity, _)| identity.clone());
+        if let Some(identity) = smallest {
+            base.remove(&identity);
+        }
+    }
+
+    /// Count an accepted share towards the current round.
+    pub fn record_accepted_share(&self) {
+        self.shares_since_last_block.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Start a new round after a block was found.
+    pub fn record_block_found(&self) {
+        self.shares_since_last_block.store(0, Ordering::Relaxed);
+        self.last_block_found_at
+            .store((self.clock)(), Ordering::Relaxed);
+    }
+
+    /// Accepted shares since the last block. Before the first block this counts
+    /// from pool startup; check [`Self::last_block_found_at`] to tell the two apart.
+    pub fn shares_since_last_block(&self) -> u64 {
+        self.shares_since_last_block.load(Ordering::Relaxed)
+    }
+
+    /// When the last block was found, or `None` if none since startup.
+    pub fn last_block_found_at(&self) -> Option<u64> {
+        match self.last_block_found_at.load(Ordering::Relaxed) {
+            0 => None,
+            ts => Some(ts),
+        }
+    }
+
+    pub fn snapshot(&self) -> HashMap<u32, DownstreamStatsSnapshot> {
         self.stats
             .read()
             .iter()
             .map(|(id, stats)| {
-                let shares = stats.shares_submitted.load(Ordering::Relaxed);
-                let quotes = stats.quotes_created.load(Ordering::Relaxed);
-                let ehash = stats.ehash_mined.load(Ordering::Relaxed);
                 let last_share = stats.last_share_at.load(Ordering::Relaxed);
                 let last_share_opt = if last_share > 0 {
                     Some(last_share)
                 } else {
                     None
                 };
-                (*id, (shares, quotes, ehash, last_share_opt))
+                let snapshot = DownstreamStatsSnapshot {
+                    shares_submitted: stats.shares_submitted.load(Ordering::Relaxed),
+                    quotes_created: stats.quotes_created.load(Ordering::Relaxed),
+                    quotes_failed: stats.quotes_failed.load(Ordering::Relaxed),
+                    ehash_mined: stats.ehash_mined.load(Ordering::Relaxed),
+                    blocks_found: stats.blocks_found.load(Ordering::Relaxed),
+                    last_share_at: last_share_opt,
+                    quote_success_ratio: stats.quote_success_ratio(),
+                    difficulty_histogram: stats.difficulty_histogram(),
+                    reject_ratio: stats.reject_ratio(),
+                    shares_per_minute: stats.shares_per_minute(),
+                };
+                (*id, snapshot)
             })
             .collect()
     }
@@ -140,11 +501,20 @@ impl Default for PoolStatsRegistry {
     fn default() -> Self {
         Self {
             stats: RwLock::new(HashMap::new()),
+            shares_since_last_block: AtomicU64::new(0),
+            last_block_found_at: AtomicU64::new(0),
+            identities: RwLock::new(HashMap::new()),
+            lifetime_base: Mutex::new(HashMap::new()),
+            max_lifetime_entries: MAX_LIFETIME_ENTRIES,
+            clock: system_clock(),
         }
     }
 }
 
-/// Callback that updates stats when quotes are created.
+/// Callback that updates stats as quotes reach the mint hub or fail to.
+///
+/// Only the quote and ehash counters are touched: the share behind a quote was already
+/// counted by [`DownstreamStats::record_share_with_difficulty`] when it was accepted.
 pub struct StatsCallback {
     stats: Arc<DownstreamStats>,
 }
@@ -157,10 +527,351 @@ impl StatsCallback {
 
 impl QuoteEventCallback for StatsCallback {
     fn on_quote_created(&self, _channel_id: u32, amount: u64) {
-        let now = unix_timestamp();
-        self.stats.shares_submitted.fetch_add(1, Ordering::Relaxed);
         self.stats.quotes_created.fetch_add(1, Ordering::Relaxed);
         self.stats.ehash_mined.fetch_add(amount, Ordering::Relaxed);
-        self.stats.last_share_at.store(now, Ordering::Relaxed);
+    }
+
+    fn on_quote_failed(&self, _channel_id: u32, _amount: u64, _reason: &str) {
+        self.stats.record_quote_failed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_success_ratio_without_attempts() {
+        let stats = DownstreamStats::new();
+        assert_eq!(stats.quote_success_ratio(), None);
+    }
+
+    #[test]
+    fn test_quote_success_ratio_mixed_counts() {
+        let stats = DownstreamStats::new();
+        stats.quotes_created.store(3, Ordering::Relaxed);
+        stats.quotes_failed.store(1, Ordering::Relaxed);
+
+        assert_eq!(stats.quote_success_ratio(), Some(0.75));
+    }
+
+    #[test]
+    fn test_snapshot_includes_quote_success_ratio() {
+        let registry = PoolStatsRegistry::new();
+        let stats = registry.register_downstream(7);
+        stats.quotes_created.store(1, Ordering::Relaxed);
+        stats.record_quote_failed();
+
+        let snapshot = registry.snapshot();
+        let entry = snapshot.get(&7).unwrap();
+        assert_eq!(entry.quotes_failed, 1);
+        assert_eq!(entry.quote_success_ratio, Some(0.5));
+    }
+
+    #[test]
+    fn test_difficulty_histogram_buckets() {
+        let stats = DownstreamStats::new();
+        for difficulty in [0.5, 1.0, 3.0, 8.0, 12.0, 15.9, 1024.0, 1e12] {
+            stats.record_share_with_difficulty(difficulty);
+        }
+
+        let histogram = stats.difficulty_histogram();
+        assert_eq!(histogram[0], 2);
+        assert_eq!(histogram[1], 1);
+        assert_eq!(histogram[3], 3);
+        assert_eq!(histogram[10], 1);
+        assert_eq!(histogram[DIFFICULTY_BUCKETS - 1], 1);
+        assert_eq!(histogram.iter().sum::<u64>(), 8);
+    }
+
+    #[test]
+    fn test_shares_since_last_block_resets_on_block_found() {
+        let registry = PoolStatsRegistry::new();
+        assert_eq!(registry.shares_since_last_block(), 0);
+        assert_eq!(registry.last_block_found_at(), None);
+
+        for _ in 0..3 {
+            registry.record_accepted_share();
+        }
+        assert_eq!(registry.shares_since_last_block(), 3);
+
+        registry.record_block_found();
+        assert_eq!(registry.shares_since_last_block(), 0);
+        assert!(registry.last_block_found_at().is_some());
+
+        registry.record_accepted_share();
+        assert_eq!(registry.shares_since_last_block(), 1);
+    }
+
+    #[test]
+    fn test_reset_downstream_keeps_registration() {
+        let registry = PoolStatsRegistry::new();
+        let stats = registry.register_downstream(1);
+        registry.register_downstream(2).record_share();
+        stats.record_share_with_difficulty(16.0);
+        stats.record_quote_failed();
+
+        assert!(registry.reset_downstream(1));
+        assert!(!registry.reset_downstream(99));
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot[&1], DownstreamStatsSnapshot::default());
+        assert_eq!(snapshot[&2].shares_submitted, 1);
+
+        // Recording continues on the same handle
+        stats.record_share();
+        assert_eq!(registry.snapshot()[&1].shares_submitted, 1);
+
+        registry.reset_all();
+        assert!(registry
+            .snapshot()
+            .values()
+            .all(|s| *s == DownstreamStatsSnapshot::default()));
+    }
+
+    #[test]
+    fn test_reset_while_recording_concurrently() {
+        let registry = PoolStatsRegistry::new();
+        let stats = registry.register_downstream(1);
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                let stats = stats.clone();
+                scope.spawn(move || {
+                    for _ in 0..1_000 {
+                        stats.record_share_with_difficulty(1024.0);
+                    }
+                });
+            }
+            for _ in 0..100 {
+                assert!(registry.reset_downstream(1));
+                let shares = registry.snapshot()[&1].shares_submitted;
+                assert!(shares <= 4_000);
+            }
+        });
+
+        // Nothing recorded after the last reset is lost
+        registry.reset_all();
+        for _ in 0..5 {
+            stats.record_share_with_difficulty(1024.0);
+        }
+        let snapshot = registry.snapshot()[&1];
+        assert_eq!(snapshot.shares_submitted, 5);
+        assert_eq!(snapshot.difficulty_histogram[10], 5);
+    }
+
+    #[test]
+    fn test_lifetime_totals_survive_reset_and_disconnect() {
+        let registry = PoolStatsRegistry::new();
+        registry.restore_lifetime_totals(HashMap::from([(
+            "alice".to_string(),
+            LifetimeTotals {
+                shares: 10,
+                ehash: 100,
+                blocks: 1,
+            },
+        )]));
+
+        let stats = registry.register_downstream(1);
+        registry.set_identity(1, "alice");
+        stats.record_share();
+        StatsCallback::new(stats.clone()).on_quote_created(0, 8);
+        stats.record_block_found();
+        registry.register_downstream(2).record_share();
+        registry.set_identity(2, "bob");
+
+        let expected = LifetimeTotals {
+            shares: 11,
+            ehash: 108,
+            blocks: 2,
+        };
+        assert_eq!(registry.lifetime_totals()["alice"], expected);
+
+        // A payout round reset and a disconnect only clear the live counters
+        registry.reset_downstream(1);
+        assert_eq!(registry.snapshot()[&1].shares_submitted, 0);
+        assert_eq!(registry.lifetime_totals()["alice"], expected);
+        registry.unregister_downstream(1);
+        assert_eq!(registry.lifetime_totals()["alice"], expected);
+
+        let top = registry.top_lifetime(1);
+        assert_eq!(top, vec![("alice".to_string(), expected)]);
+        assert_eq!(registry.top_lifetime(5).len(), 2);
+    }
+
+    #[test]
+    fn test_lifetime_totals_follow_identity_across_reconnects() {
+        let registry = PoolStatsRegistry::new();
+
+        // The first connection's channel names the identity; later channels don't change it
+        registry.register_downstream(1).record_share();
+        registry.set_identity(1, "alice");
+        registry.set_identity(1, "alice.rig2");
+        registry.unregister_downstream(1);
+
+        // Downstream ids restart after a pool restart, so id 1 now belongs to someone else
+        registry.register_downstream(1).record_share();
+        registry.set_identity(1, "bob");
+        let stats = registry.register_downstream(7);
+        registry.set_identity(7, "alice");
+        stats.record_share();
+
+        let totals = registry.lifetime_totals();
+        assert_eq!(totals["alice"].shares, 2);
+        assert_eq!(totals["bob"].shares, 1);
+        assert!(!totals.contains_key("alice.rig2"));
+    }
+
+    #[test]
+    fn test_lifetime_totals_are_bounded() {
+        let registry = PoolStatsRegistry {
+            max_lifetime_entries: 2,
+            ..Default::default()
+        };
+        let totals = [("small", 1), ("medium", 5), ("large", 9)].map(|(identity, shares)| {
+            let totals = LifetimeTotals {
+                shares,
+                ..Default::default()
+            };
+            (identity.to_string(), totals)
+        });
+        registry.restore_lifetime_totals(HashMap::from(totals));
+        let mut kept: Vec<_> = registry.lifetime_totals().into_keys().collect();
+        kept.sort();
+        assert_eq!(kept, vec!["large", "medium"]);
+
+        // A newly seen identity is kept in place of the smallest other entry
+        registry.register_downstream(1).record_share();
+        registry.set_identity(1, "newcomer");
+        registry.unregister_downstream(1);
+        let mut kept: Vec<_> = registry.lifetime_totals().into_keys().collect();
+        kept.sort();
+        assert_eq!(kept, vec!["large", "newcomer"]);
+    }
+
+    #[test]
+    fn test_share_with_quote_is_counted_once() {
+        let registry = PoolStatsRegistry::new();
+        let stats = registry.register_downstream(1);
+
+        // The accept path records the share, then the dispatcher reports its quote
+        stats.record_share_with_difficulty(64.0);
+        StatsCallback::new(stats.clone()).on_quote_created(0, 64);
+
+        let snapshot = registry.snapshot()[&1];
+        assert_eq!(snapshot.shares_submitted, 1);
+        assert_eq!(snapshot.quotes_created, 1);
+        assert_eq!(snapshot.ehash_mined, 64);
+        registry.set_identity(1, "alice");
+        assert_eq!(registry.lifetime_totals()["alice"].shares, 1);
+    }
+
+    #[test]
+    fn test_shares_per_minute_counts_last_minute_only() {
+        let stats = DownstreamStats::with_recent_shares_capacity(4);
+        assert_eq!(stats.shares_per_minute(), 0.0);
+
+        for timestamp in [1_000, 1_030, 1_050] {
+            stats.push_recent_share(timestamp);
+        }
+        assert_eq!(stats.shares_per_minute_at(1_059), 3.0);
+        assert_eq!(stats.shares_per_minute_at(1_060), 2.0);
+        assert_eq!(stats.shares_per_minute_at(1_200), 0.0);
+
+        // The ring keeps the newest shares, so a burst saturates at its capacity
+        for _ in 0..10 {
+            stats.push_recent_share(1_100);
+        }
+        assert_eq!(stats.shares_per_minute_at(1_100), 4.0);
+
+        stats.record_share_with_difficulty(1.0);
+        assert_eq!(stats.shares_per_minute(), 1.0);
+    }
+
+    #[test]
+    fn test_snapshot_reports_shares_per_minute() {
+        let registry = PoolStatsRegistry::new();
+        let stats = registry.register_downstream(1);
+        stats.record_share_with_difficulty(100.0);
+        stats.record_share_with_difficulty(50.0);
+
+        assert_eq!(registry.snapshot()[&1].shares_per_minute, 2.0);
+
+        // A reset starts the rate over along with the counters
+        assert!(registry.reset_downstream(1));
+        assert_eq!(registry.snapshot()[&1].shares_per_minute, 0.0);
+    }
+
+    #[test]
+    fn test_timestamps_follow_pinned_clock() {
+        let now = Arc::new(AtomicU64::new(5_000));
+        let clock_now = now.clone();
+        let registry =
+            PoolStatsRegistry::with_clock(Arc::new(move || clock_now.load(Ordering::SeqCst)));
+        let stats = registry.register_downstream(1);
+
+        stats.record_share_with_difficulty(100.0);
+        now.store(5_030, Ordering::SeqCst);
+        stats.record_share_with_difficulty(50.0);
+        registry.record_block_found();
+
+        assert_eq!(registry.snapshot()[&1].last_share_at, Some(5_030));
+        assert_eq!(registry.snapshot()[&1].shares_per_minute, 2.0);
+        assert_eq!(registry.last_block_found_at(), Some(5_030));
+        assert_eq!(stats.shares_per_minute(), 2.0);
+        assert_eq!(stats.sum_difficulty_in_window(), 150.0);
+
+        // A minute after the first share only the second is left in either window
+        now.store(5_060, Ordering::SeqCst);
+        assert_eq!(stats.shares_per_minute(), 1.0);
+        assert_eq!(stats.sum_difficulty_in_window(), 50.0);
+
+        now.store(5_090, Ordering::SeqCst);
+        assert_eq!(stats.shares_per_minute(), 0.0);
+        assert_eq!(stats.shares_in_window(), 0);
+    }
+
+    #[test]
+    fn test_difficulty_sums_saturate_instead_of_overflowing() {
+        let stats = DownstreamStats::new();
+        let submitted = || f64::from_bits(stats.submitted_sum_difficulty.load(Ordering::Relaxed));
+
+        let mut previous = 0.0;
+        for difficulty in [5e9, f64::MAX, f64::MAX, 5e9, f64::NAN, f64::INFINITY] {
+            stats.record_share_with_difficulty(difficulty);
+            stats.record_rejected_share_with_difficulty(difficulty);
+            let sum = submitted();
+            assert!(sum.is_finite(), "sum overflowed after {difficulty}");
+            assert!(sum >= previous, "sum went backwards after {difficulty}");
+            previous = sum;
+        }
+        assert_eq!(submitted(), f64::MAX);
+        assert_eq!(stats.rejected_sum_difficulty(), f64::MAX);
+        assert_eq!(stats.reject_ratio(), 1.0);
+    }
+
+    #[test]
+    fn test_reject_ratio_weights_by_difficulty() {
+        let registry = PoolStatsRegistry::new();
+        let stats = registry.register_downstream(1);
+        assert_eq!(stats.reject_ratio(), 0.0);
+
+        // Three accepted shares at 100 and one rejected at 100
+        for _ in 0..4 {
+            stats.record_share_with_difficulty(100.0);
+        }
+        stats.record_rejected_share_with_difficulty(100.0);
+        assert_eq!(stats.get_rejected_sum_difficulty(), 100.0);
+        assert_eq!(stats.reject_ratio(), 0.25);
+
+        // A rejected high-difficulty share weighs more than a low one
+        stats.record_share_with_difficulty(400.0);
+        stats.record_rejected_share_with_difficulty(400.0);
+        assert_eq!(stats.reject_ratio(), 0.625);
+        assert_eq!(registry.snapshot()[&1].reject_ratio, 0.625);
+
+        stats.reset();
+        assert_eq!(stats.get_rejected_sum_difficulty(), 0.0);
+        assert_eq!(stats.reject_ratio(), 0.0);
     }
 }
@@ -3,17 +3,41 @@
 //! This crate handles all mint quote logic, keeping it separate from the core
 //! pool message handling to minimize changes to upstream SRI code.
 
-use std::sync::Arc;
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, PoisonError, RwLock,
+    },
+    time::Duration,
+};
 use thiserror::Error;
+use tokio::sync::mpsc;
 
+use async_trait::async_trait;
 use bitcoin_hashes::{sha256::Hash as Sha256Hash, Hash};
 use mint_quote_sv2::CompressedPubKey;
-use ehash::calculate_ehash_amount;
-use mint_pool_messaging::{build_parsed_quote_request, MintPoolMessageHub, PendingQuoteContext};
+use ehash::{calculate_ehash_amount, AmountPolicy};
+use mint_pool_messaging::{
+    build_parsed_quote_request, MessagingError, MessagingResult, MintPoolMessageHub,
+    ParsedMintQuoteRequest, PendingQuoteContext, ShareHash,
+};
 use shared_config::Sv2MessagingConfig;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use tracing::error as log_error;
 
+/// Retries of a failed quote send when no messaging config is given
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Delay before the first retry of a failed quote send; doubled for each further retry
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+
+/// Full batches the batching queue holds before further quotes are refused
+const BATCH_QUEUE_BATCHES: usize = 16;
+
+/// Flush interval for partial batches suggested to callers of
+/// [`QuoteDispatcher::with_batching`]
+pub const DEFAULT_BATCH_FLUSH_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Error type for quote dispatcher operations
 #[derive(Debug, Error)]
 pub enum DispatchError {
@@ -29,8 +53,57 @@ pub enum DispatchError {
 ///
 /// Implementations can track stats or perform other actions when quotes are created.
 pub trait QuoteEventCallback: Send + Sync {
-    /// Called when a quote is successfully created.
+    /// Called once the hub has accepted the quote request for a share.
     fn on_quote_created(&self, channel_id: u32, amount: u64);
+
+    /// Called when a quote for an accepted share will not reach the mint: the header hash was
+    /// invalid, the request could not be built, or every send to the hub failed. `amount` is
+    /// 0 when the failure happened before it was computed.
+    fn on_quote_failed(&self, _channel_id: u32, _amount: u64, _reason: &str) {}
+}
+
+/// Destination for quote requests, implemented by [`MintPoolMessageHub`].
+#[async_trait]
+pub trait QuoteSink: Send + Sync {
+    /// Queue `request` for the mint, remembering `context` for the response.
+    async fn send_quote_request(
+        &self,
+        request: ParsedMintQuoteRequest,
+        context: PendingQuoteContext,
+    ) -> MessagingResult<()>;
+
+    /// Forget a request whose send timed out, so its retry is not rejected as a duplicate.
+    async fn abandon_quote_request(&self, share_hash: &ShareHash);
+}
+
+#[async_trait]
+impl QuoteSink for MintPoolMessageHub {
+    async fn send_quote_request(
+        &self,
+        request: ParsedMintQuoteRequest,
+        context: PendingQuoteContext,
+    ) -> MessagingResult<()> {
+        MintPoolMessageHub::send_quote_request(self, request, context).await
+    }
+
+    async fn abandon_quote_request(&self, share_hash: &ShareHash) {
+        MintPoolMessageHub::abandon_quote_request(self, share_hash).await
+    }
+}
+
+/// A built quote request waiting in the batching queue.
+struct QueuedQuote {
+    request: ParsedMintQuoteRequest,
+    context: PendingQuoteContext,
+    callback: Option<Arc<dyn QuoteEventCallback>>,
+}
+
+/// How a quote request reaches the hub once it has been built.
+#[derive(Clone)]
+struct Delivery {
+    hub: Arc<dyn QuoteSink>,
+    max_retries: u32,
+    attempt_timeout: Option<Duration>,
 }
 
 /// Dispatcher for submitting mint quotes.
@@ -39,16 +112,19 @@ pub trait QuoteEventCallback: Send + Sync {
 /// to the mint service, keeping this functionality isolated from pool logic.
 #[derive(Clone)]
 pub struct QuoteDispatcher {
-    hub: Arc<MintPoolMessageHub>,
+    hub: Arc<dyn QuoteSink>,
     sv2_config: Option<Sv2MessagingConfig>,
     minimum_difficulty: u32,
+    amount_policy: Arc<RwLock<AmountPolicy>>,
     callback: Option<Arc<dyn QuoteEventCallback>>,
+    batch_tx: Option<mpsc::Sender<QueuedQuote>>,
+    batches_flushed: Arc<AtomicU64>,
 }
 
 impl QuoteDispatcher {
     /// Create a new quote dispatcher.
     pub fn new(
-        hub: Arc<MintPoolMessageHub>,
+        hub: Arc<dyn QuoteSink>,
         sv2_config: Option<Sv2MessagingConfig>,
         minimum_difficulty: u32,
     ) -> Self {
@@ -56,16 +132,67 @@ impl QuoteDispatcher {
             hub,
             sv2_config,
             minimum_difficulty,
+            amount_policy: Arc::new(RwLock::new(AmountPolicy::default())),
             callback: None,
+            batch_tx: None,
+            batches_flushed: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Adjust computed amounts with `amount_policy` before quoting them.
+    ///
+    /// By default amounts are only rounded to the HASH keyset's denominations, which every
+    /// computed amount already is, and no minimum applies.
+    pub fn with_amount_policy(mut self, amount_policy: AmountPolicy) -> Self {
+        self.amount_policy = Arc::new(RwLock::new(amount_policy));
+        self
+    }
+
+    /// Round amounts to sums of the mint's active keyset `denominations` from now on,
+    /// keeping the configured minimum. Applies to every clone of this dispatcher.
+    pub fn set_denominations(&self, denominations: impl IntoIterator<Item = u64>) {
+        let mut policy = self
+            .amount_policy
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        *policy = policy.clone().with_denominations(denominations);
+    }
+
     /// Set the callback for quote events.
     pub fn with_callback(mut self, callback: Arc<dyn QuoteEventCallback>) -> Self {
         self.callback = Some(callback);
         self
     }
 
+    /// Send quotes in batches from a single background task instead of spawning a task per
+    /// share. A batch is flushed once it holds `max_batch` quotes or `flush_interval` has
+    /// passed with quotes waiting.
+    ///
+    /// At most 16 batches' worth of quotes wait for the task: beyond that
+    /// [`Self::submit_quote`] reports the quote as failed and [`Self::send_quote`] waits for
+    /// room.
+    ///
+    /// Must be called from within a Tokio runtime. The background task stops once every clone
+    /// of the dispatcher has been dropped, after flushing what is still queued.
+    pub fn with_batching(mut self, max_batch: usize, flush_interval: Duration) -> Self {
+        let max_batch = max_batch.max(1);
+        let (tx, rx) = mpsc::channel(max_batch.saturating_mul(BATCH_QUEUE_BATCHES));
+        tokio::spawn(run_batches(
+            rx,
+            self.delivery(),
+            max_batch,
+            flush_interval,
+            self.batches_flushed.clone(),
+        ));
+        self.batch_tx = Some(tx);
+        self
+    }
+
+    /// Number of batches handed to the hub since batching was enabled.
+    pub fn batches_flushed(&self) -> u64 {
+        self.batches_flushed.load(Ordering::Relaxed)
+    }
+
     /// Submit a quote for a share.
     ///
     /// This is the main entry point called by the pool when a share is accepted.
@@ -83,14 +210,133 @@ impl QuoteDispatcher {
         channel_id: u32,
         sequence_number: u32,
     ) -> Result<(), DispatchError> {
-        let hash = Sha256Hash::from_slice(header_hash)
-            .map_err(|e| DispatchError::InvalidHeaderHash(format!("Invalid header hash: {e}")))?;
+        self.submit_quote_with_difficulty(
+            header_hash,
+            locking_pubkey,
+            channel_id,
+            sequence_number,
+            self.minimum_difficulty,
+        )
+    }
 
-        let amount = calculate_ehash_amount(hash.to_byte_array(), self.minimum_difficulty);
+    /// Like [`Self::submit_quote`], computing the amount against `minimum_difficulty`
+    /// (in leading zero bits) instead of the dispatcher's default, e.g. for a channel
+    /// configured with its own floor.
+    pub fn submit_quote_with_difficulty(
+        &self,
+        header_hash: &[u8],
+        locking_pubkey: CompressedPubKey<'static>,
+        channel_id: u32,
+        sequence_number: u32,
+        minimum_difficulty: u32,
+    ) -> Result<(), DispatchError> {
+        let Some(queued) = self.prepare(
+            header_hash,
+            locking_pubkey,
+            channel_id,
+            sequence_number,
+            minimum_difficulty,
+        )?
+        else {
+            return Ok(());
+        };
 
-        // Notify callback if set
-        if let Some(ref callback) = self.callback {
-            callback.on_quote_created(channel_id, amount);
+        if let Some(ref batch_tx) = self.batch_tx {
+            return self.try_enqueue_batched(batch_tx, queued);
+        }
+
+        // Spawn async task to dispatch via hub
+        let delivery = self.delivery();
+        tokio::spawn(async move { delivery.deliver(queued).await });
+
+        Ok(())
+    }
+
+    /// Like [`Self::submit_quote`], but returns only once the hub has taken the request or
+    /// it was given up on, so a caller with a bounded queue of shares feels a slow hub as
+    /// backpressure instead of piling up send tasks. A lost request is reported to the
+    /// callback and returned as [`DispatchError::FailedToDispatch`]. With batching enabled
+    /// this returns once the quote is queued for its batch.
+    pub async fn send_quote(
+        &self,
+        header_hash: &[u8],
+        locking_pubkey: CompressedPubKey<'static>,
+        channel_id: u32,
+        sequence_number: u32,
+    ) -> Result<(), DispatchError> {
+        self.send_quote_with_difficulty(
+            header_hash,
+            locking_pubkey,
+            channel_id,
+            sequence_number,
+            self.minimum_difficulty,
+        )
+        .await
+    }
+
+    /// [`Self::send_quote`] with the amount computed against `minimum_difficulty`, see
+    /// [`Self::submit_quote_with_difficulty`].
+    pub async fn send_quote_with_difficulty(
+        &self,
+        header_hash: &[u8],
+        locking_pubkey: CompressedPubKey<'static>,
+        channel_id: u32,
+        sequence_number: u32,
+        minimum_difficulty: u32,
+    ) -> Result<(), DispatchError> {
+        let Some(queued) = self.prepare(
+            header_hash,
+            locking_pubkey,
+            channel_id,
+            sequence_number,
+            minimum_difficulty,
+        )?
+        else {
+            return Ok(());
+        };
+
+        match self.batch_tx {
+            Some(ref batch_tx) => self.enqueue_batched(batch_tx, queued).await,
+            None => self.delivery().deliver(queued).await,
+        }
+    }
+
+    /// Computes the amount for a share and builds its quote request, or `None` if no quote
+    /// should be sent for it.
+    fn prepare(
+        &self,
+        header_hash: &[u8],
+        locking_pubkey: CompressedPubKey<'static>,
+        channel_id: u32,
+        sequence_number: u32,
+        minimum_difficulty: u32,
+    ) -> Result<Option<QueuedQuote>, DispatchError> {
+        let hash = Sha256Hash::from_slice(header_hash).map_err(|e| {
+            self.report_failure(
+                channel_id,
+                0,
+                DispatchError::InvalidHeaderHash(format!("Invalid header hash: {e}")),
+            )
+        })?;
+
+        let raw_amount = calculate_ehash_amount(hash.to_byte_array(), minimum_difficulty);
+        let adjusted = self
+            .amount_policy
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .apply(raw_amount);
+        let Some(amount) = adjusted else {
+            debug!(
+                "Ehash amount {} for channel {} is below the quote minimum; skipping quote",
+                raw_amount, channel_id
+            );
+            return Ok(None);
+        };
+        if amount != raw_amount {
+            debug!(
+                "Adjusted ehash amount for channel {} from {} to {}",
+                channel_id, raw_amount, amount
+            );
         }
 
         // Check if messaging is enabled
@@ -100,12 +346,18 @@ impl QuoteDispatcher {
                 "SV2 messaging disabled; skipping mint quote dispatch for channel {}",
                 channel_id
             );
-            return Ok(());
+            return Ok(None);
         }
 
         // Build the parsed quote request
-        let parsed = build_parsed_quote_request(amount, header_hash, locking_pubkey)
-            .map_err(|e| DispatchError::FailedToBuildQuote(format!("Failed to build quote: {e}")))?;
+        let parsed =
+            build_parsed_quote_request(amount, header_hash, locking_pubkey).map_err(|e| {
+                self.report_failure(
+                    channel_id,
+                    amount,
+                    DispatchError::FailedToBuildQuote(format!("Failed to build quote: {e}")),
+                )
+            })?;
 
         let context = PendingQuoteContext {
             channel_id,
@@ -113,21 +365,598 @@ impl QuoteDispatcher {
             amount,
         };
 
-        let share_hash_hex = hex::encode(parsed.share_hash.as_bytes());
-        let hub = self.hub.clone();
+        Ok(Some(QueuedQuote {
+            request: parsed,
+            context,
+            callback: self.callback.clone(),
+        }))
+    }
 
-        // Spawn async task to dispatch via hub
-        tokio::spawn(async move {
-            if let Err(e) = hub.send_quote_request(parsed, context).await {
-                log_error!("Failed to dispatch mint quote request via hub: {}", e);
-            } else {
+    /// Queues a quote for its batch, failing it if the queue is full.
+    fn try_enqueue_batched(
+        &self,
+        batch_tx: &mpsc::Sender<QueuedQuote>,
+        queued: QueuedQuote,
+    ) -> Result<(), DispatchError> {
+        let (channel_id, amount) = (queued.context.channel_id, queued.context.amount);
+        batch_tx.try_send(queued).map_err(|e| {
+            let reason = match e {
+                mpsc::error::TrySendError::Full(_) => "quote batching queue is full",
+                mpsc::error::TrySendError::Closed(_) => "quote batching task has stopped",
+            };
+            self.report_failure(
+                channel_id,
+                amount,
+                DispatchError::FailedToDispatch(reason.to_string()),
+            )
+        })
+    }
+
+    /// Queues a quote for its batch, waiting for room if the queue is full.
+    async fn enqueue_batched(
+        &self,
+        batch_tx: &mpsc::Sender<QueuedQuote>,
+        queued: QueuedQuote,
+    ) -> Result<(), DispatchError> {
+        let (channel_id, amount) = (queued.context.channel_id, queued.context.amount);
+        batch_tx.send(queued).await.map_err(|_| {
+            self.report_failure(
+                channel_id,
+                amount,
+                DispatchError::FailedToDispatch("quote batching task has stopped".to_string()),
+            )
+        })
+    }
+
+    fn delivery(&self) -> Delivery {
+        Delivery {
+            hub: self.hub.clone(),
+            max_retries: self
+                .sv2_config
+                .as_ref()
+                .map_or(DEFAULT_MAX_RETRIES, |cfg| cfg.max_retries),
+            attempt_timeout: self
+                .sv2_config
+                .as_ref()
+                .map(|cfg| Duration::from_millis(cfg.timeout_ms)),
+        }
+    }
+
+    /// Tells the callback, if any, that the quote for `channel_id` failed with `error`.
+    fn report_failure(&self, channel_id: u32, amount: u64, error: DispatchError) -> DispatchError {
+        if let Some(ref callback) = self.callback {
+            callback.on_quote_failed(channel_id, amount, &error.to_string());
+        }
+        error
+    }
+}
+
+impl Delivery {
+    /// Sends one quote to the hub, reporting it to its callback if it is lost.
+    async fn deliver(&self, queued: QueuedQuote) -> Result<(), DispatchError> {
+        let QueuedQuote {
+            request,
+            context,
+            callback,
+        } = queued;
+        let share_hash_hex = hex::encode(request.share_hash.as_bytes());
+        let (channel_id, amount) = (context.channel_id, context.amount);
+
+        match send_with_retry(
+            self.hub.as_ref(),
+            request,
+            context,
+            self.max_retries,
+            self.attempt_timeout,
+        )
+        .await
+        {
+            Ok(()) => {
                 debug!(
                     "Queued mint quote request via hub: share_hash={}",
                     share_hash_hex
                 );
+                if let Some(callback) = callback {
+                    callback.on_quote_created(channel_id, amount);
+                }
+                Ok(())
+            }
+            // The share is already quoted, so nothing was lost
+            Err(e @ MessagingError::DuplicateQuote(_)) => {
+                warn!("Skipping mint quote request: {}", e);
+                Ok(())
+            }
+            Err(e) => {
+                log_error!(
+                    "Failed to dispatch mint quote request via hub after {} retries: {}",
+                    self.max_retries,
+                    e
+                );
+                if let Some(callback) = callback {
+                    callback.on_quote_failed(channel_id, amount, &e.to_string());
+                }
+                Err(DispatchError::FailedToDispatch(e.to_string()))
+            }
+        }
+    }
+
+    /// Sends a batch in order; a quote that needs retries holds back the rest of its batch.
+    async fn deliver_batch(&self, batch: Vec<QueuedQuote>) {
+        for queued in batch {
+            // Losses are reported to each quote's callback
+            let _ = self.deliver(queued).await;
+        }
+    }
+}
+
+/// Drains `rx` into batches of up to `max_batch` quotes, sending full batches immediately
+/// and partial ones every `flush_interval`. Batches are sent one at a time; quotes arriving
+/// meanwhile wait in the bounded queue.
+async fn run_batches(
+    mut rx: mpsc::Receiver<QueuedQuote>,
+    delivery: Delivery,
+    max_batch: usize,
+    flush_interval: Duration,
+    batches_flushed: Arc<AtomicU64>,
+) {
+    let flush = |batch: &mut Vec<QueuedQuote>| {
+        let batch = std::mem::replace(batch, Vec::with_capacity(max_batch));
+        batches_flushed.fetch_add(1, Ordering::Relaxed);
+        debug!("Flushing batch of {} mint quote requests", batch.len());
+        delivery.deliver_batch(batch)
+    };
+
+    let mut batch = Vec::with_capacity(max_batch);
+    // The first flush is due one interval in, not immediately
+    let mut ticker =
+        tokio::time::interval_at(tokio::time::Instant::now() + flush_interval, flush_interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            queued = rx.recv() => match queued {
+                Some(queued) => {
+                    batch.push(queued);
+                    if batch.len() >= max_batch {
+                        flush(&mut batch).await;
+                    }
+                }
+                None => {
+                    if !batch.is_empty() {
+                        flush(&mut batch).await;
+                    }
+                    return;
+                }
+            },
+            _ = ticker.tick() => {
+                if !batch.is_empty() {
+                    flush(&mut batch).await;
+                }
+            }
+        }
+    }
+}
+
+/// Sends a quote request, retrying up to `max_retries` times with exponential backoff from
+/// [`RETRY_BASE_DELAY`]. Each attempt is abandoned after `attempt_timeout`, if set, and the
+/// hub told to forget it. Duplicate quotes are never retried; a duplicate reported for a
+/// retry means an earlier attempt of ours still holds the share, so the earlier error is
+/// returned instead.
+async fn send_with_retry(
+    hub: &dyn QuoteSink,
+    request: ParsedMintQuoteRequest,
+    context: PendingQuoteContext,
+    max_retries: u32,
+    attempt_timeout: Option<Duration>,
+) -> MessagingResult<()> {
+    let mut retry = 0;
+    let mut last_error = None;
+    loop {
+        let attempt = hub.send_quote_request(request.clone(), context.clone());
+        let result = match attempt_timeout {
+            Some(limit) => match tokio::time::timeout(limit, attempt).await {
+                Ok(result) => result,
+                Err(_) => {
+                    hub.abandon_quote_request(&request.share_hash).await;
+                    Err(MessagingError::Timeout)
+                }
+            },
+            None => attempt.await,
+        };
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e @ MessagingError::DuplicateQuote(_)) => return Err(last_error.unwrap_or(e)),
+            Err(e) if retry >= max_retries => return Err(e),
+            Err(e) => {
+                retry += 1;
+                let delay = RETRY_BASE_DELAY.saturating_mul(1 << (retry - 1).min(16));
+                debug!(
+                    "Mint quote send failed ({}), retry {}/{} in {:?}",
+                    e, retry, max_retries, delay
+                );
+                last_error = Some(e);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    };
+
+    /// Fails the first `failures` sends, then accepts everything
+    struct FlakyHub {
+        failures: u32,
+        attempts: AtomicU32,
+        sent: Mutex<Vec<PendingQuoteContext>>,
+    }
+
+    #[async_trait]
+    impl QuoteSink for FlakyHub {
+        async fn send_quote_request(
+            &self,
+            _request: ParsedMintQuoteRequest,
+            context: PendingQuoteContext,
+        ) -> MessagingResult<()> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(MessagingError::ChannelClosed("quote_request".to_string()));
+            }
+            self.sent.lock().unwrap().push(context);
+            Ok(())
+        }
+
+        async fn abandon_quote_request(&self, _share_hash: &ShareHash) {}
+    }
+
+    /// Claims each share for good like the hub's dedup window, and stalls on the first send
+    struct TimeoutOnceHub {
+        forgets_abandoned: bool,
+        attempts: AtomicU32,
+        claimed: Mutex<Vec<[u8; 32]>>,
+    }
+
+    #[async_trait]
+    impl QuoteSink for TimeoutOnceHub {
+        async fn send_quote_request(
+            &self,
+            request: ParsedMintQuoteRequest,
+            _context: PendingQuoteContext,
+        ) -> MessagingResult<()> {
+            let first = self.attempts.fetch_add(1, Ordering::SeqCst) == 0;
+            {
+                let mut claimed = self.claimed.lock().unwrap();
+                if claimed.contains(request.share_hash.as_bytes()) {
+                    return Err(MessagingError::DuplicateQuote(request.share_hash));
+                }
+                claimed.push(*request.share_hash.as_bytes());
+            }
+            if first {
+                std::future::pending::<()>().await;
             }
+            Ok(())
+        }
+
+        async fn abandon_quote_request(&self, share_hash: &ShareHash) {
+            if self.forgets_abandoned {
+                self.claimed
+                    .lock()
+                    .unwrap()
+                    .retain(|claimed| claimed != share_hash.as_bytes());
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct FailureCounter(AtomicU32);
+
+    impl QuoteEventCallback for FailureCounter {
+        fn on_quote_created(&self, _channel_id: u32, _amount: u64) {}
+
+        fn on_quote_failed(&self, _channel_id: u32, _amount: u64, _reason: &str) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn locking_key() -> CompressedPubKey<'static> {
+        let mut encoded = [0u8; 34];
+        encoded[0] = 33;
+        encoded[1] = 0x02;
+        CompressedPubKey::from_bytes(&mut encoded[..])
+            .expect("valid compressed key")
+            .into_static()
+    }
+
+    async fn dispatch(failures: u32) -> (Arc<FlakyHub>, Arc<FailureCounter>) {
+        let hub = Arc::new(FlakyHub {
+            failures,
+            attempts: AtomicU32::new(0),
+            sent: Mutex::new(Vec::new()),
         });
+        let failed = Arc::new(FailureCounter::default());
+        let dispatcher = QuoteDispatcher::new(hub.clone(), None, 32).with_callback(failed.clone());
 
-        Ok(())
+        dispatcher
+            .submit_quote(&[0u8; 32], locking_key(), 7, 1)
+            .unwrap();
+        // Covers the 50ms + 100ms + 200ms of backoff; callers run with the clock paused, so
+        // this only lets the spawned send finish
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        (hub, failed)
+    }
+
+    #[test]
+    fn test_submit_quote_reports_invalid_header_hash() {
+        let hub = Arc::new(FlakyHub {
+            failures: 0,
+            attempts: AtomicU32::new(0),
+            sent: Mutex::new(Vec::new()),
+        });
+        let failed = Arc::new(FailureCounter::default());
+        let dispatcher = QuoteDispatcher::new(hub.clone(), None, 32).with_callback(failed.clone());
+
+        let result = dispatcher.submit_quote(&[0u8; 5], locking_key(), 7, 1);
+
+        assert!(matches!(result, Err(DispatchError::InvalidHeaderHash(_))));
+        assert_eq!(failed.0.load(Ordering::SeqCst), 1);
+        assert_eq!(hub.attempts.load(Ordering::SeqCst), 0);
+    }
+
+    #[derive(Default)]
+    struct CreatedAmounts(Mutex<Vec<u64>>);
+
+    impl QuoteEventCallback for CreatedAmounts {
+        fn on_quote_created(&self, _channel_id: u32, amount: u64) {
+            self.0.lock().unwrap().push(amount);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_minimum_difficulty_override_changes_amount() {
+        let hub = Arc::new(FlakyHub {
+            failures: 0,
+            attempts: AtomicU32::new(0),
+            sent: Mutex::new(Vec::new()),
+        });
+        let created = Arc::new(CreatedAmounts::default());
+        let dispatcher = QuoteDispatcher::new(hub, None, 32).with_callback(created.clone());
+
+        // 39 leading zero bits
+        let mut header_hash = [0u8; 32];
+        header_hash[4] = 0x01;
+        dispatcher
+            .send_quote(&header_hash, locking_key(), 7, 1)
+            .await
+            .unwrap();
+        dispatcher
+            .send_quote_with_difficulty(&header_hash, locking_key(), 7, 2, 36)
+            .await
+            .unwrap();
+        dispatcher
+            .send_quote_with_difficulty(&header_hash, locking_key(), 7, 3, 32)
+            .await
+            .unwrap();
+
+        assert_eq!(*created.0.lock().unwrap(), vec![1 << 7, 1 << 3, 1 << 7]);
+    }
+
+    #[tokio::test]
+    async fn test_set_denominations_rounds_later_quotes() {
+        let hub = Arc::new(FlakyHub {
+            failures: 0,
+            attempts: AtomicU32::new(0),
+            sent: Mutex::new(Vec::new()),
+        });
+        let created = Arc::new(CreatedAmounts::default());
+        let dispatcher = QuoteDispatcher::new(hub, None, 32).with_callback(created.clone());
+        let shared = dispatcher.clone();
+
+        // 39 leading zero bits, worth 2^7 at the default minimum difficulty
+        let mut header_hash = [0u8; 32];
+        header_hash[4] = 0x01;
+        dispatcher
+            .send_quote(&header_hash, locking_key(), 7, 1)
+            .await
+            .unwrap();
+        shared.set_denominations([100, 20, 5]);
+        dispatcher
+            .send_quote(&header_hash, locking_key(), 7, 2)
+            .await
+            .unwrap();
+
+        assert_eq!(*created.0.lock().unwrap(), vec![128, 125]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_quote_created_only_once_hub_accepts() {
+        let hub = Arc::new(FlakyHub {
+            failures: u32::MAX,
+            attempts: AtomicU32::new(0),
+            sent: Mutex::new(Vec::new()),
+        });
+        let created = Arc::new(CreatedAmounts::default());
+        let dispatcher = QuoteDispatcher::new(hub, None, 32).with_callback(created.clone());
+
+        let result = dispatcher.send_quote(&[0u8; 32], locking_key(), 7, 1).await;
+        assert!(result.is_err());
+        assert!(created.0.lock().unwrap().is_empty());
+
+        // Nothing reaches the hub with messaging disabled, so nothing is created either
+        let disabled = Sv2MessagingConfig {
+            enabled: false,
+            ..Default::default()
+        };
+        let hub = Arc::new(FlakyHub {
+            failures: 0,
+            attempts: AtomicU32::new(0),
+            sent: Mutex::new(Vec::new()),
+        });
+        let dispatcher =
+            QuoteDispatcher::new(hub, Some(disabled), 32).with_callback(created.clone());
+        dispatcher
+            .send_quote(&[0u8; 32], locking_key(), 7, 2)
+            .await
+            .unwrap();
+        assert!(created.0.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_submit_quote_retries_until_hub_accepts() {
+        let (hub, failed) = dispatch(2).await;
+
+        assert_eq!(hub.attempts.load(Ordering::SeqCst), 3);
+        let sent = hub.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].channel_id, 7);
+        assert_eq!(failed.0.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_send_quote_returns_after_delivery() {
+        let hub = Arc::new(FlakyHub {
+            failures: 2,
+            attempts: AtomicU32::new(0),
+            sent: Mutex::new(Vec::new()),
+        });
+        let dispatcher = QuoteDispatcher::new(hub.clone(), None, 32);
+
+        dispatcher
+            .send_quote(&[0u8; 32], locking_key(), 7, 1)
+            .await
+            .unwrap();
+
+        // The retries already happened before the call returned
+        assert_eq!(hub.attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(hub.sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_send_quote_returns_loss_after_max_retries() {
+        let hub = Arc::new(FlakyHub {
+            failures: u32::MAX,
+            attempts: AtomicU32::new(0),
+            sent: Mutex::new(Vec::new()),
+        });
+        let failed = Arc::new(FailureCounter::default());
+        let dispatcher = QuoteDispatcher::new(hub.clone(), None, 32).with_callback(failed.clone());
+
+        let result = dispatcher.send_quote(&[0u8; 32], locking_key(), 7, 1).await;
+
+        assert!(matches!(result, Err(DispatchError::FailedToDispatch(_))));
+        assert_eq!(hub.attempts.load(Ordering::SeqCst), DEFAULT_MAX_RETRIES + 1);
+        assert_eq!(failed.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batching_flushes_full_and_partial_batches() {
+        let hub = Arc::new(FlakyHub {
+            failures: 0,
+            attempts: AtomicU32::new(0),
+            sent: Mutex::new(Vec::new()),
+        });
+        let dispatcher = QuoteDispatcher::new(hub.clone(), None, 32)
+            .with_batching(4, Duration::from_millis(100));
+
+        for sequence_number in 0..10 {
+            dispatcher
+                .submit_quote(&[0u8; 32], locking_key(), 7, sequence_number)
+                .unwrap();
+        }
+        // Two full batches go out right away, the last two quotes at the first tick
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(dispatcher.batches_flushed(), 2);
+        assert_eq!(hub.sent.lock().unwrap().len(), 8);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(dispatcher.batches_flushed(), 3);
+        let sent = hub.sent.lock().unwrap();
+        let sequence_numbers: Vec<u32> = sent.iter().map(|c| c.sequence_number).collect();
+        assert_eq!(sequence_numbers, (0..10).collect::<Vec<_>>());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batching_queue_is_bounded() {
+        let hub = Arc::new(FlakyHub {
+            failures: 0,
+            attempts: AtomicU32::new(0),
+            sent: Mutex::new(Vec::new()),
+        });
+        let failed = Arc::new(FailureCounter::default());
+        let dispatcher = QuoteDispatcher::new(hub.clone(), None, 32)
+            .with_callback(failed.clone())
+            .with_batching(2, Duration::from_millis(100));
+
+        // The batching task has not run yet, so nothing leaves the queue
+        let capacity = (2 * BATCH_QUEUE_BATCHES) as u32;
+        for sequence_number in 0..capacity {
+            dispatcher
+                .submit_quote(&[0u8; 32], locking_key(), 7, sequence_number)
+                .unwrap();
+        }
+        let result = dispatcher.submit_quote(&[0u8; 32], locking_key(), 7, capacity);
+        assert!(matches!(result, Err(DispatchError::FailedToDispatch(_))));
+        assert_eq!(failed.0.load(Ordering::SeqCst), 1);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(hub.sent.lock().unwrap().len(), capacity as usize);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_submit_quote_reports_loss_after_max_retries() {
+        let (hub, failed) = dispatch(u32::MAX).await;
+
+        assert_eq!(hub.attempts.load(Ordering::SeqCst), DEFAULT_MAX_RETRIES + 1);
+        assert!(hub.sent.lock().unwrap().is_empty());
+        assert_eq!(failed.0.load(Ordering::SeqCst), 1);
+    }
+
+    fn timeout_config() -> Sv2MessagingConfig {
+        Sv2MessagingConfig {
+            enabled: true,
+            max_retries: 3,
+            timeout_ms: 100,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_timed_out_send_is_abandoned_before_retry() {
+        let hub = Arc::new(TimeoutOnceHub {
+            forgets_abandoned: true,
+            attempts: AtomicU32::new(0),
+            claimed: Mutex::new(Vec::new()),
+        });
+        let created = Arc::new(CreatedAmounts::default());
+        let dispatcher = QuoteDispatcher::new(hub.clone(), Some(timeout_config()), 32)
+            .with_callback(created.clone());
+
+        dispatcher
+            .send_quote(&[0u8; 32], locking_key(), 7, 1)
+            .await
+            .unwrap();
+
+        assert_eq!(hub.attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(created.0.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_duplicate_of_own_timed_out_send_is_a_loss() {
+        let hub = Arc::new(TimeoutOnceHub {
+            forgets_abandoned: false,
+            attempts: AtomicU32::new(0),
+            claimed: Mutex::new(Vec::new()),
+        });
+        let failed = Arc::new(FailureCounter::default());
+        let dispatcher = QuoteDispatcher::new(hub.clone(), Some(timeout_config()), 32)
+            .with_callback(failed.clone());
+
+        let result = dispatcher.send_quote(&[0u8; 32], locking_key(), 7, 1).await;
+
+        assert!(matches!(result, Err(DispatchError::FailedToDispatch(_))));
+        assert_eq!(hub.attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(failed.0.load(Ordering::SeqCst), 1);
     }
 }
@@ -12,8 +12,23 @@
 //! - Multiple independent handlers for the same event
 //! - Easy addition of new functionality (new hooks) without modifying core pool logic
 //! - Non-fatal hook failures (hooks can't break share validation)
+//!
+//! Hooks should be run through [`dispatch_share_accepted`] or a [`HookRegistry`], which
+//! isolate each hook in its own task so that one returning an error or panicking is logged
+//! without affecting the others or the caller.
+//!
+//! Found blocks are reported separately to [`BlockFoundHook`]s through
+//! [`dispatch_block_found`], so share hooks that do not care about blocks need not check
+//! [`ShareAcceptedEvent::is_block`].
 
+use std::{
+    collections::HashMap, future::Future, panic::AssertUnwindSafe, sync::Arc, time::Duration,
+};
+
+use futures::{future::join_all, FutureExt};
+use stats_sv2::{BlockFound, ShareSubmitted};
 use thiserror::Error;
+use tracing::{error, warn};
 
 /// Error types returned by share acceptance hooks
 #[derive(Error, Debug, Clone)]
@@ -53,11 +68,45 @@ pub struct ShareAcceptedEvent {
     /// Nonce submitted with the share
     pub nonce: u32,
 
+    /// Difficulty of the channel target the share was validated against
+    pub difficulty: f64,
+
+    /// Channel target the share was validated against (32 bytes, little endian)
+    pub target: [u8; 32],
+
     /// Timestamp when the share was accepted
     pub timestamp: u64,
 
     /// Whether this share also qualifies as a block
     pub is_block: bool,
+
+    /// Derived data attached by [`ShareAcceptanceHook::enrich`] before the event is dispatched
+    pub extra: HashMap<String, String>,
+}
+
+impl ShareAcceptedEvent {
+    /// The stats protocol `BlockFound` message for this share, if it solved a block
+    pub fn to_block_found(&self) -> Option<BlockFound> {
+        self.is_block.then_some(BlockFound {
+            downstream_id: self.downstream_id,
+            channel_id: self.channel_id,
+            timestamp: self.timestamp,
+        })
+    }
+}
+
+/// Builds the stats protocol message for an accepted share, so hooks that report to the
+/// stats service need not assemble it by hand.
+impl From<&ShareAcceptedEvent> for ShareSubmitted<'static> {
+    fn from(event: &ShareAcceptedEvent) -> Self {
+        ShareSubmitted {
+            downstream_id: event.downstream_id,
+            channel_id: event.channel_id,
+            difficulty_millis: (event.difficulty * 1000.0) as u64,
+            target: event.target.into(),
+            timestamp: event.timestamp,
+        }
+    }
 }
 
 /// Trait for handling share acceptance events
@@ -80,6 +129,241 @@ pub trait ShareAcceptanceHook: Send + Sync {
     /// * `Ok(())` - Hook completed successfully
     /// * `Err(HookError)` - Hook encountered an error (non-fatal)
     async fn on_share_accepted(&self, event: ShareAcceptedEvent) -> Result<(), HookError>;
+
+    /// Called before any hook's [`on_share_accepted`](Self::on_share_accepted) to attach
+    /// derived data to the event, usually in [`ShareAcceptedEvent::extra`]
+    ///
+    /// Enrichers run one at a time in registration order, so each sees what earlier ones
+    /// wrote. A failing enricher is logged and none of its changes are kept; the share is
+    /// still dispatched to every hook.
+    async fn enrich(&self, _event: &mut ShareAcceptedEvent) -> Result<(), HookError> {
+        Ok(())
+    }
+}
+
+/// Event triggered when an accepted share also solves a block
+#[derive(Debug, Clone)]
+pub struct BlockFoundEvent {
+    /// Header hash of the found block
+    pub block_hash: Vec<u8>,
+
+    /// Height of the block, if it could be read from the coinbase
+    pub height: Option<u64>,
+
+    /// The channel ID where the block solution was submitted
+    pub channel_id: u32,
+
+    /// The downstream/miner connection ID
+    pub downstream_id: u32,
+
+    /// Total coinbase output value in satoshis, if the coinbase could be decoded
+    pub reward_sats: Option<u64>,
+
+    /// Timestamp when the block was found
+    pub timestamp: u64,
+}
+
+/// Trait for handling found blocks
+///
+/// Called once per found block, after the share's [`ShareAcceptanceHook`]s have been
+/// started. As with share hooks, errors are logged and never affect the block submission.
+#[async_trait::async_trait]
+pub trait BlockFoundHook: Send + Sync {
+    /// Called when an accepted share solves a block
+    async fn on_block_found(&self, event: BlockFoundEvent) -> Result<(), HookError>;
+}
+
+/// Runs every hook for `event`, one after another, each in its own task, once all hooks
+/// have had the chance to [enrich](ShareAcceptanceHook::enrich) it.
+///
+/// A hook that returns an error or panics is logged and skipped; the remaining hooks still
+/// run. Returns the number of hooks that failed.
+pub async fn dispatch_share_accepted(
+    hooks: &[Arc<dyn ShareAcceptanceHook>],
+    mut event: ShareAcceptedEvent,
+) -> usize {
+    enrich_event(hooks, &mut event, None).await;
+    let mut failed = 0;
+    for (index, hook) in hooks.iter().enumerate() {
+        if run_hook(index, hook.clone(), event.clone(), None)
+            .await
+            .is_err()
+        {
+            failed += 1;
+        }
+    }
+    failed
+}
+
+/// Runs every block hook for `event`, one after another, each in its own task.
+///
+/// Failures and panics are logged as for [`dispatch_share_accepted`]. Returns the number of
+/// hooks that failed.
+pub async fn dispatch_block_found(
+    hooks: &[Arc<dyn BlockFoundHook>],
+    event: BlockFoundEvent,
+) -> usize {
+    let mut failed = 0;
+    for (index, hook) in hooks.iter().enumerate() {
+        let channel_id = event.channel_id;
+        let event = event.clone();
+        let hook = hook.clone();
+        let call = async move { hook.on_block_found(event).await };
+        let result = run_isolated(call, None, |outcome| {
+            format!(
+                "Block hook {} {} for channel {}",
+                index, outcome, channel_id
+            )
+        })
+        .await;
+        if result.is_err() {
+            failed += 1;
+        }
+    }
+    failed
+}
+
+/// Runs one share hook in its own task, logging a failure or panic. A panic is reported as
+/// [`HookError::ExecutionFailed`], and a hook still running after `timeout` as
+/// [`HookError::Timeout`].
+async fn run_hook(
+    index: usize,
+    hook: Arc<dyn ShareAcceptanceHook>,
+    event: ShareAcceptedEvent,
+    timeout: Option<Duration>,
+) -> Result<(), HookError> {
+    let (channel_id, sequence_number) = (event.channel_id, event.sequence_number);
+    let call = async move { hook.on_share_accepted(event).await };
+    run_isolated(call, timeout, |outcome| {
+        format!(
+            "Share hook {} {} for channel {} (seq {})",
+            index, outcome, channel_id, sequence_number
+        )
+    })
+    .await
+}
+
+/// Lets each hook enrich `event` in turn. Each enricher works on a copy that replaces
+/// `event` only if it succeeds; one that fails, panics or outlives `timeout` is logged and
+/// leaves `event` as it was.
+async fn enrich_event(
+    hooks: &[Arc<dyn ShareAcceptanceHook>],
+    event: &mut ShareAcceptedEvent,
+    timeout: Option<Duration>,
+) {
+    for (index, hook) in hooks.iter().enumerate() {
+        let mut enriched = event.clone();
+        // `enrich` borrows the event, so it can't move to its own task like the other calls
+        let call = AssertUnwindSafe(hook.enrich(&mut enriched)).catch_unwind();
+        let result = match timeout {
+            Some(limit) => tokio::time::timeout(limit, call)
+                .await
+                .unwrap_or(Ok(Err(HookError::Timeout))),
+            None => call.await,
+        };
+        let error = match result {
+            Ok(Ok(())) => {
+                *event = enriched;
+                continue;
+            }
+            Ok(Err(e)) => e.to_string(),
+            Err(_) => "hook panicked".to_string(),
+        };
+        warn!(
+            "Share hook {} failed to enrich share for channel {} (seq {}): {}",
+            index, event.channel_id, event.sequence_number, error
+        );
+    }
+}
+
+/// Drives a hook call in its own task. `describe` turns "failed" or "panicked" into the
+/// log line's subject; it is only called when the hook does not succeed.
+async fn run_isolated<F>(
+    call: F,
+    timeout: Option<Duration>,
+    describe: impl FnOnce(&str) -> String,
+) -> Result<(), HookError>
+where
+    F: Future<Output = Result<(), HookError>> + Send + 'static,
+{
+    let task = async move {
+        match timeout {
+            Some(limit) => tokio::time::timeout(limit, call)
+                .await
+                .unwrap_or(Err(HookError::Timeout)),
+            None => call.await,
+        }
+    };
+    // A panic unwinds only the spawned task and surfaces as a `JoinError`
+    match tokio::spawn(task).await {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => {
+            warn!("{}: {}", describe("failed"), e);
+            Err(e)
+        }
+        Err(e) => {
+            error!("{}: {}", describe("panicked"), e);
+            Err(HookError::ExecutionFailed(format!("hook panicked: {}", e)))
+        }
+    }
+}
+
+/// How long [`HookRegistry::dispatch`] waits for a hook by default
+pub const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Hooks registered to run on every accepted share
+#[derive(Clone)]
+pub struct HookRegistry {
+    hooks: Vec<Arc<dyn ShareAcceptanceHook>>,
+    timeout: Duration,
+}
+
+impl Default for HookRegistry {
+    fn default() -> Self {
+        Self {
+            hooks: Vec::new(),
+            timeout: DEFAULT_HOOK_TIMEOUT,
+        }
+    }
+}
+
+impl HookRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Give up on a hook that has not finished after `timeout`
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn register(&mut self, hook: Arc<dyn ShareAcceptanceHook>) {
+        self.hooks.push(hook);
+    }
+
+    pub fn len(&self) -> usize {
+        self.hooks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Runs every registered hook for `event` concurrently, each in its own task.
+    ///
+    /// The hooks first [enrich](ShareAcceptanceHook::enrich) the event one after another.
+    /// Failures, panics and timeouts are logged and do not stop the other hooks. Returns each
+    /// hook's `on_share_accepted` outcome in registration order.
+    pub async fn dispatch(&self, mut event: ShareAcceptedEvent) -> Vec<Result<(), HookError>> {
+        enrich_event(&self.hooks, &mut event, Some(self.timeout)).await;
+        join_all(
+            self.hooks.iter().enumerate().map(|(index, hook)| {
+                run_hook(index, hook.clone(), event.clone(), Some(self.timeout))
+            }),
+        )
+        .await
+    }
 }
 
 #[cfg(test)]
@@ -131,8 +415,11 @@ mod tests {
             downstream_id: 2,
             prev_hash: vec![0; 32],
             nonce: 12345,
+            difficulty: 1.0,
+            target: [0xff; 32],
             timestamp: 1000,
             is_block: false,
+            extra: HashMap::new(),
         };
 
         assert_eq!(event.sequence_number, 42);
@@ -151,14 +438,55 @@ mod tests {
             downstream_id: 10,
             prev_hash: vec![1; 32],
             nonce: 999,
+            difficulty: 1.0,
+            target: [0xff; 32],
             timestamp: 2000,
             is_block: true,
+            extra: HashMap::new(),
         };
 
         assert!(event.is_block);
         assert_eq!(event.sequence_number, 1);
     }
 
+    #[test]
+    fn test_share_accepted_event_converts_to_stats_messages() {
+        let mut event = ShareAcceptedEvent {
+            sequence_number: 7,
+            channel_id: 5,
+            downstream_id: 10,
+            prev_hash: vec![1; 32],
+            nonce: 999,
+            difficulty: 2.5,
+            target: [0x0f; 32],
+            timestamp: 2000,
+            is_block: false,
+            extra: HashMap::new(),
+        };
+
+        assert_eq!(
+            ShareSubmitted::from(&event),
+            ShareSubmitted {
+                downstream_id: 10,
+                channel_id: 5,
+                difficulty_millis: 2500,
+                target: [0x0f; 32].into(),
+                timestamp: 2000,
+            }
+        );
+        assert_eq!(event.to_block_found(), None);
+
+        event.is_block = true;
+        assert_eq!(
+            event.to_block_found(),
+            Some(BlockFound {
+                downstream_id: 10,
+                channel_id: 5,
+                timestamp: 2000,
+            })
+        );
+    }
+
     #[test]
     fn test_share_accepted_event_clone() {
         let event1 = ShareAcceptedEvent {
@@ -167,8 +495,11 @@ mod tests {
             downstream_id: 10,
             prev_hash: vec![2; 32],
             nonce: 5000,
+            difficulty: 1.0,
+            target: [0xff; 32],
             timestamp: 3000,
             is_block: false,
+            extra: HashMap::new(),
         };
 
         let event2 = event1.clone();
@@ -204,8 +535,11 @@ mod tests {
             downstream_id: 1,
             prev_hash: vec![0; 32],
             nonce: 100,
+            difficulty: 1.0,
+            target: [0xff; 32],
             timestamp: 1000,
             is_block: false,
+            extra: HashMap::new(),
         };
 
         hook.on_share_accepted(event).await.unwrap();
@@ -226,8 +560,11 @@ mod tests {
                 downstream_id: 1,
                 prev_hash: vec![0; 32],
                 nonce: 100 + i,
+                difficulty: 1.0,
+                target: [0xff; 32],
                 timestamp: 1000 + i as u64,
                 is_block: false,
+                extra: HashMap::new(),
             };
 
             hook.on_share_accepted(event).await.unwrap();
@@ -256,8 +593,11 @@ mod tests {
             downstream_id: 1,
             prev_hash: vec![0; 32],
             nonce: 100,
+            difficulty: 1.0,
+            target: [0xff; 32],
             timestamp: 1000,
             is_block: false,
+            extra: HashMap::new(),
         };
 
         let result = hook.on_share_accepted(event).await;
@@ -290,8 +630,11 @@ mod tests {
             downstream_id: 1,
             prev_hash: vec![0; 32],
             nonce: 100,
+            difficulty: 1.0,
+            target: [0xff; 32],
             timestamp: 1000,
             is_block: false,
+            extra: HashMap::new(),
         };
 
         // Both hooks should be callable independently
@@ -302,6 +645,335 @@ mod tests {
         assert_eq!(count2.load(Ordering::SeqCst), 1);
     }
 
+    struct PanickingHook;
+
+    #[async_trait::async_trait]
+    impl ShareAcceptanceHook for PanickingHook {
+        async fn on_share_accepted(&self, _event: ShareAcceptedEvent) -> Result<(), HookError> {
+            panic!("intentional panic");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_survives_panicking_hook() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let hooks: Vec<Arc<dyn ShareAcceptanceHook>> = vec![
+            Arc::new(PanickingHook),
+            Arc::new(FailingHook),
+            Arc::new(CountingHook {
+                call_count: count.clone(),
+            }),
+        ];
+
+        let event = ShareAcceptedEvent {
+            sequence_number: 1,
+            channel_id: 1,
+            downstream_id: 1,
+            prev_hash: vec![0; 32],
+            nonce: 100,
+            difficulty: 1.0,
+            target: [0xff; 32],
+            timestamp: 1000,
+            is_block: false,
+            extra: HashMap::new(),
+        };
+
+        assert_eq!(dispatch_share_accepted(&hooks, event.clone()).await, 2);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // The dispatcher stays usable after a panic
+        assert_eq!(dispatch_share_accepted(&hooks, event).await, 2);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    /// Waits until `peers` hooks are running at once, so it only completes if they overlap
+    struct RendezvousHook {
+        barrier: Arc<tokio::sync::Barrier>,
+    }
+
+    #[async_trait::async_trait]
+    impl ShareAcceptanceHook for RendezvousHook {
+        async fn on_share_accepted(&self, _event: ShareAcceptedEvent) -> Result<(), HookError> {
+            self.barrier.wait().await;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_dispatches_concurrently_and_reports_each_outcome() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+        let mut registry = HookRegistry::new();
+        registry.register(Arc::new(RendezvousHook {
+            barrier: barrier.clone(),
+        }));
+        registry.register(Arc::new(FailingHook));
+        registry.register(Arc::new(PanickingHook));
+        registry.register(Arc::new(RendezvousHook { barrier }));
+        registry.register(Arc::new(CountingHook {
+            call_count: count.clone(),
+        }));
+        assert_eq!(registry.len(), 5);
+
+        let event = ShareAcceptedEvent {
+            sequence_number: 1,
+            channel_id: 1,
+            downstream_id: 1,
+            prev_hash: vec![0; 32],
+            nonce: 100,
+            difficulty: 1.0,
+            target: [0xff; 32],
+            timestamp: 1000,
+            is_block: false,
+            extra: HashMap::new(),
+        };
+
+        // Run one after another, the first rendezvous hook would wait forever
+        let outcomes =
+            tokio::time::timeout(std::time::Duration::from_secs(5), registry.dispatch(event))
+                .await
+                .expect("hooks did not run concurrently");
+
+        assert_eq!(outcomes.len(), 5);
+        assert!(outcomes[0].is_ok());
+        assert!(matches!(
+            &outcomes[1],
+            Err(HookError::ExecutionFailed(msg)) if msg == "intentional failure"
+        ));
+        assert!(matches!(&outcomes[2], Err(HookError::ExecutionFailed(_))));
+        assert!(outcomes[3].is_ok());
+        assert!(outcomes[4].is_ok());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    struct SlowHook {
+        delay: std::time::Duration,
+    }
+
+    #[async_trait::async_trait]
+    impl ShareAcceptanceHook for SlowHook {
+        async fn on_share_accepted(&self, _event: ShareAcceptedEvent) -> Result<(), HookError> {
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_times_out_slow_hook() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let mut registry = HookRegistry::new().with_timeout(std::time::Duration::from_millis(50));
+        registry.register(Arc::new(SlowHook {
+            delay: std::time::Duration::from_secs(10),
+        }));
+        registry.register(Arc::new(CountingHook {
+            call_count: count.clone(),
+        }));
+
+        let event = ShareAcceptedEvent {
+            sequence_number: 1,
+            channel_id: 1,
+            downstream_id: 1,
+            prev_hash: vec![0; 32],
+            nonce: 100,
+            difficulty: 1.0,
+            target: [0xff; 32],
+            timestamp: 1000,
+            is_block: false,
+            extra: HashMap::new(),
+        };
+        let outcomes = registry.dispatch(event).await;
+
+        assert!(matches!(outcomes[0], Err(HookError::Timeout)));
+        assert!(outcomes[1].is_ok());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    /// Writes `key` into the event, then fails if `fail` is set
+    struct EnrichingHook {
+        key: &'static str,
+        fail: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl ShareAcceptanceHook for EnrichingHook {
+        async fn on_share_accepted(&self, _event: ShareAcceptedEvent) -> Result<(), HookError> {
+            Ok(())
+        }
+
+        async fn enrich(&self, event: &mut ShareAcceptedEvent) -> Result<(), HookError> {
+            event
+                .extra
+                .insert(self.key.to_string(), event.nonce.to_string());
+            if self.fail {
+                // Changes outside `extra` must be dropped along with the rest
+                event.nonce = 0;
+                return Err(HookError::Custom("enrichment rejected".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    struct RecordingHook {
+        seen: Arc<std::sync::Mutex<Option<ShareAcceptedEvent>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ShareAcceptanceHook for RecordingHook {
+        async fn on_share_accepted(&self, event: ShareAcceptedEvent) -> Result<(), HookError> {
+            *self.seen.lock().unwrap() = Some(event);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_registry_enriches_before_dispatch_and_drops_failed_enrichment() {
+        let seen = Arc::new(std::sync::Mutex::new(None));
+        let mut registry = HookRegistry::new();
+        registry.register(Arc::new(EnrichingHook {
+            key: "ehash_amount",
+            fail: false,
+        }));
+        registry.register(Arc::new(EnrichingHook {
+            key: "rejected",
+            fail: true,
+        }));
+        registry.register(Arc::new(RecordingHook { seen: seen.clone() }));
+
+        let event = ShareAcceptedEvent {
+            sequence_number: 1,
+            channel_id: 1,
+            downstream_id: 1,
+            prev_hash: vec![0; 32],
+            nonce: 100,
+            difficulty: 1.0,
+            target: [0xff; 32],
+            timestamp: 1000,
+            is_block: false,
+            extra: HashMap::new(),
+        };
+        let outcomes = registry.dispatch(event).await;
+
+        assert!(outcomes.iter().all(|outcome| outcome.is_ok()));
+        let seen = seen.lock().unwrap().take().unwrap();
+        assert_eq!(
+            seen.extra.get("ehash_amount").map(String::as_str),
+            Some("100")
+        );
+        assert!(!seen.extra.contains_key("rejected"));
+        assert_eq!(seen.nonce, 100);
+    }
+
+    // ====== Block Found Hook Tests ======
+
+    fn block_event() -> BlockFoundEvent {
+        BlockFoundEvent {
+            block_hash: vec![7; 32],
+            height: Some(840_000),
+            channel_id: 3,
+            downstream_id: 4,
+            reward_sats: Some(312_500_000),
+            timestamp: 5000,
+        }
+    }
+
+    #[test]
+    fn test_block_found_event_creation() {
+        let event = block_event();
+
+        assert_eq!(event.block_hash.len(), 32);
+        assert_eq!(event.height, Some(840_000));
+        assert_eq!(event.channel_id, 3);
+        assert_eq!(event.downstream_id, 4);
+        assert_eq!(event.reward_sats, Some(312_500_000));
+    }
+
+    #[test]
+    fn test_block_found_event_clone() {
+        let event1 = BlockFoundEvent {
+            height: None,
+            reward_sats: None,
+            ..block_event()
+        };
+
+        let event2 = event1.clone();
+        assert_eq!(event1.block_hash, event2.block_hash);
+        assert_eq!(event2.height, None);
+        assert_eq!(event2.reward_sats, None);
+    }
+
+    struct CountingBlockHook {
+        call_count: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl BlockFoundHook for CountingBlockHook {
+        async fn on_block_found(&self, _event: BlockFoundEvent) -> Result<(), HookError> {
+            self.call_count.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    struct FailingBlockHook;
+
+    #[async_trait::async_trait]
+    impl BlockFoundHook for FailingBlockHook {
+        async fn on_block_found(&self, _event: BlockFoundEvent) -> Result<(), HookError> {
+            Err(HookError::ExecutionFailed(
+                "intentional failure".to_string(),
+            ))
+        }
+    }
+
+    struct PanickingBlockHook;
+
+    #[async_trait::async_trait]
+    impl BlockFoundHook for PanickingBlockHook {
+        async fn on_block_found(&self, _event: BlockFoundEvent) -> Result<(), HookError> {
+            panic!("intentional panic");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_counting_block_hook_single_call() {
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let hook = CountingBlockHook {
+            call_count: call_count.clone(),
+        };
+
+        hook.on_block_found(block_event()).await.unwrap();
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_failing_block_hook_returns_error() {
+        let result = FailingBlockHook.on_block_found(block_event()).await;
+
+        if let Err(HookError::ExecutionFailed(msg)) = result {
+            assert_eq!(msg, "intentional failure");
+        } else {
+            panic!("Expected ExecutionFailed variant");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_block_dispatch_survives_panicking_hook() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let hooks: Vec<Arc<dyn BlockFoundHook>> = vec![
+            Arc::new(PanickingBlockHook),
+            Arc::new(FailingBlockHook),
+            Arc::new(CountingBlockHook {
+                call_count: count.clone(),
+            }),
+        ];
+
+        assert_eq!(dispatch_block_found(&hooks, block_event()).await, 2);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // The dispatcher stays usable after a panic
+        assert_eq!(dispatch_block_found(&hooks, block_event()).await, 2);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
     // ====== Event Serialization-like Tests ======
 
     #[test]
@@ -312,8 +984,11 @@ mod tests {
             downstream_id: 2,
             prev_hash: vec![0; 32],
             nonce: 12345,
+            difficulty: 1.0,
+            target: [0xff; 32],
             timestamp: 1000,
             is_block: false,
+            extra: HashMap::new(),
         };
 
         let debug_str = format!("{:?}", event);
@@ -333,7 +1008,10 @@ mod tests {
                 downstream_id: 1,
                 prev_hash: vec![0; 32],
                 nonce: 1,
+                difficulty: 1.0,
+                target: [0xff; 32],
                 is_block: false,
+                extra: HashMap::new(),
             },
             ShareAcceptedEvent {
                 timestamp: u64::MAX,
@@ -342,7 +1020,10 @@ mod tests {
                 downstream_id: 1,
                 prev_hash: vec![0; 32],
                 nonce: 2,
+                difficulty: 1.0,
+                target: [0xff; 32],
                 is_block: false,
+                extra: HashMap::new(),
             },
             ShareAcceptedEvent {
                 timestamp: 1_000_000_000,
@@ -351,7 +1032,10 @@ mod tests {
                 downstream_id: 1,
                 prev_hash: vec![0; 32],
                 nonce: 3,
+                difficulty: 1.0,
+                target: [0xff; 32],
                 is_block: false,
+                extra: HashMap::new(),
             },
         ];
 
@@ -5,14 +5,16 @@
 
 pub mod bucketing;
 pub mod metrics;
+pub mod sample_cache;
 pub mod storage;
 pub mod types;
 pub mod windowing;
 
 pub use bucketing::calculate_bucket_size;
 pub use metrics::derive_hashrate;
-pub use storage::StatsStorage;
-pub use types::{DownstreamSnapshot, ServiceSnapshot, ServiceType};
+pub use sample_cache::WarmCacheConfig;
+pub use storage::{RollupConfig, StatsStorage};
+pub use types::{DownstreamSnapshot, ServiceSnapshot, ServiceType, UNKNOWN_USER_AGENT};
 pub use windowing::{WindowedMetricsCollector, unix_timestamp};
 
 #[cfg(test)]
@@ -0,0 +1,212 @@
+//! In-memory cache of recent hashrate samples.
+//!
+//! After a restart the first dashboard queries scan SQLite. With a warm cache enabled,
+//! `SqliteStorage` preloads the most recent window of samples at startup and keeps appending
+//! new ones, so queries whose range lies entirely inside the cached span are answered from
+//! memory using the same bucketing rules as the SQL queries.
+//!
+//! The cache never holds more than `max_samples` samples. When it is full the oldest samples
+//! are dropped and the covered span shrinks accordingly; older ranges go to SQLite.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::storage::BucketRow;
+use crate::types::DownstreamSnapshot;
+
+/// Opt-in settings for preloading recent samples into memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarmCacheConfig {
+    /// How far back, in seconds, samples are preloaded at startup
+    pub window_secs: u64,
+    /// Maximum number of samples held in memory
+    pub max_samples: usize,
+}
+
+/// The fields of a stored sample that hashrate queries read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CachedSample {
+    pub timestamp: u64,
+    pub downstream_id: u32,
+    pub sum_difficulty: f64,
+    pub window_seconds: u64,
+}
+
+impl From<&DownstreamSnapshot> for CachedSample {
+    fn from(downstream: &DownstreamSnapshot) -> Self {
+        Self {
+            timestamp: downstream.timestamp,
+            downstream_id: downstream.downstream_id,
+            sum_difficulty: downstream.sum_difficulty_in_window,
+            window_seconds: downstream.window_seconds,
+        }
+    }
+}
+
+pub(crate) struct SampleCache {
+    max_samples: usize,
+    // Every stored sample at or after this timestamp is in the cache
+    covers_from: u64,
+    // Ordered by timestamp
+    samples: VecDeque<CachedSample>,
+}
+
+impl SampleCache {
+    /// Build a cache holding `samples` (ordered by timestamp) and covering everything from
+    /// `covers_from` on.
+    pub fn new(max_samples: usize, covers_from: u64, samples: Vec<CachedSample>) -> Self {
+        let mut cache = Self {
+            max_samples,
+            covers_from,
+            samples: samples.into(),
+        };
+        cache.evict();
+        cache
+    }
+
+    /// Whether a query starting at `from_timestamp` can be answered from memory.
+    pub fn covers(&self, from_timestamp: u64) -> bool {
+        from_timestamp >= self.covers_from
+    }
+
+    pub fn insert(&mut self, sample: CachedSample) {
+        if sample.timestamp < self.covers_from {
+            return;
+        }
+        let index = self
+            .samples
+            .partition_point(|s| s.timestamp <= sample.timestamp);
+        self.samples.insert(index, sample);
+        self.evict();
+    }
+
+    /// Drop samples before `timestamp`, e.g. once they have been rolled up in the database.
+    pub fn discard_before(&mut self, timestamp: u64) {
+        let keep_from = self.samples.partition_point(|s| s.timestamp < timestamp);
+        self.samples.drain(..keep_from);
+        self.covers_from = self.covers_from.max(timestamp);
+    }
+
+    fn evict(&mut self) {
+        while self.samples.len() > self.max_samples {
+            if let Some(oldest) = self.samples.pop_front() {
+                self.covers_from = self.covers_from.max(oldest.timestamp + 1);
+            }
+        }
+    }
+
+    fn in_range(&self, from: u64, to: u64) -> impl Iterator<Item = &CachedSample> {
+        let start = self.samples.partition_point(|s| s.timestamp < from);
+        self.samples
+            .range(start..)
+            .take_while(move |s| s.timestamp <= to)
+    }
+
+    /// Buckets for one downstream, mirroring `SqliteStorage::query_hashrate`: the samples of
+    /// each bucket are averaged over their measurement window.
+    pub fn downstream_buckets(
+        &self,
+        downstream_id: u32,
+        from: u64,
+        to: u64,
+        bucket_seconds: u64,
+    ) -> Vec<BucketRow> {
+        let mut buckets: BTreeMap<u64, BucketRow> = BTreeMap::new();
+        for sample in self
+            .in_range(from, to)
+            .filter(|s| s.downstream_id == downstream_id)
+        {
+            let bucket_timestamp = (sample.timestamp / bucket_seconds) * bucket_seconds;
+            let row = buckets.entry(bucket_timestamp).or_insert(BucketRow {
+                bucket_timestamp,
+                total_difficulty: 0.0,
+                sample_count: 0,
+                window_seconds: 0,
+            });
+            row.total_difficulty += sample.sum_difficulty;
+            row.sample_count += 1;
+            row.window_seconds = row.window_seconds.max(sample.window_seconds);
+        }
+        buckets.into_values().collect()
+    }
+
+    /// Buckets across all downstreams, mirroring `SqliteStorage::query_aggregate_hashrate`:
+    /// each downstream's samples are averaged per bucket and the averages summed.
+    pub fn aggregate_buckets(&self, from: u64, to: u64, bucket_seconds: u64) -> Vec<BucketRow> {
+        // (sum of difficulties, sample count, widest window) per bucket and downstream
+        let mut per_downstream: BTreeMap<(u64, u32), (f64, u64, u64)> = BTreeMap::new();
+        for sample in self.in_range(from, to) {
+            let bucket = (sample.timestamp / bucket_seconds) * bucket_seconds;
+            let entry = per_downstream
+                .entry((bucket, sample.downstream_id))
+                .or_insert((0.0, 0, 0));
+            entry.0 += sample.sum_difficulty;
+            entry.1 += 1;
+            entry.2 = entry.2.max(sample.window_seconds);
+        }
+
+        let mut buckets: BTreeMap<u64, BucketRow> = BTreeMap::new();
+        for ((bucket_timestamp, _), (sum, count, window_seconds)) in per_downstream {
+            let row = buckets.entry(bucket_timestamp).or_insert(BucketRow {
+                bucket_timestamp,
+                total_difficulty: 0.0,
+                sample_count: 1,
+                window_seconds: 0,
+            });
+            row.total_difficulty += sum / count as f64;
+            row.window_seconds = row.window_seconds.max(window_seconds);
+        }
+        buckets.into_values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: u64, downstream_id: u32, sum_difficulty: f64) -> CachedSample {
+        CachedSample {
+            timestamp,
+            downstream_id,
+            sum_difficulty,
+            window_seconds: 60,
+        }
+    }
+
+    #[test]
+    fn test_eviction_narrows_covered_range() {
+        let mut cache = SampleCache::new(2, 100, vec![sample(100, 1, 1.0), sample(110, 1, 1.0)]);
+        assert!(cache.covers(100));
+
+        cache.insert(sample(120, 1, 1.0));
+        assert!(!cache.covers(100));
+        assert!(cache.covers(101));
+
+        // Samples before the covered range are left to the database
+        cache.insert(sample(90, 1, 1.0));
+        assert_eq!(cache.samples.len(), 2);
+    }
+
+    #[test]
+    fn test_downstream_buckets_average_samples() {
+        let cache = SampleCache::new(
+            10,
+            0,
+            vec![
+                sample(6000, 1, 10.0),
+                sample(6030, 2, 99.0),
+                sample(6050, 1, 20.0),
+                sample(6070, 1, 30.0),
+            ],
+        );
+
+        let buckets = cache.downstream_buckets(1, 6000, 6100, 60);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].bucket_timestamp, 6000);
+        assert_eq!(buckets[0].total_difficulty, 30.0);
+        assert_eq!(buckets[0].sample_count, 2);
+        assert_eq!(buckets[0].window_seconds, 60);
+        assert_eq!(buckets[1].bucket_timestamp, 6060);
+        assert_eq!(buckets[1].total_difficulty, 30.0);
+        assert_eq!(buckets[1].sample_count, 1);
+    }
+}
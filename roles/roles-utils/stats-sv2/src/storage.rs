@@ -1,11 +1,15 @@
 //! SQLite storage backend for time-series metrics.
 
 use crate::bucketing::calculate_bucket_size;
+use crate::sample_cache::{CachedSample, SampleCache, WarmCacheConfig};
 use crate::types::{DownstreamSnapshot, HashratePoint};
+use crate::windowing::unix_timestamp;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::{Pool, Sqlite, Row};
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{PoisonError, RwLock};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -44,9 +48,33 @@ pub trait StatsStorage: Send + Sync {
     ) -> Result<Vec<HashratePoint>>;
 }
 
+/// Thresholds for rolling old samples up into coarser buckets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RollupConfig {
+    /// Samples older than this many seconds are rolled up and deleted
+    pub rollup_after_secs: u64,
+    /// Width of a rollup bucket in seconds
+    pub bucket_secs: u64,
+    /// Rollup buckets older than this many seconds are deleted (kept forever if unset)
+    pub retention_secs: Option<u64>,
+}
+
+/// One bucket of a hashrate query before it is converted to a [`HashratePoint`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BucketRow {
+    pub bucket_timestamp: u64,
+    pub total_difficulty: f64,
+    pub sample_count: u64,
+    pub window_seconds: u64,
+}
+
 /// SQLite-backed storage implementation.
 pub struct SqliteStorage {
     pool: Pool<Sqlite>,
+    // Recent samples, present only when enabled with `with_warm_cache`
+    cache: Option<RwLock<SampleCache>>,
+    // Hashrate queries that had to go to SQLite
+    db_queries: AtomicU64,
 }
 
 impl SqliteStorage {
@@ -69,12 +97,93 @@ impl SqliteStorage {
             .connect_with(connection_options)
             .await?;
 
-        let storage = Self { pool };
+        let storage = Self {
+            pool,
+            cache: None,
+            db_queries: AtomicU64::new(0),
+        };
         storage.init_schema().await?;
 
         Ok(storage)
     }
 
+    /// Preload the last `window_secs` of samples (at most `max_samples`) into memory and
+    /// serve hashrate queries over that span without touching the database.
+    pub async fn with_warm_cache(self, config: WarmCacheConfig) -> Result<Self> {
+        let covers_from = unix_timestamp().saturating_sub(config.window_secs);
+        self.preload_cache(covers_from, config.max_samples).await
+    }
+
+    async fn preload_cache(mut self, covers_from: u64, max_samples: usize) -> Result<Self> {
+        // Rolled-up history only exists in SQLite, so the cache can't cover it
+        let rollup_end: Option<i64> =
+            sqlx::query_scalar("SELECT MAX(timestamp + bucket_seconds) FROM hashrate_rollup")
+                .fetch_one(&self.pool)
+                .await?;
+        let covers_from = covers_from.max(rollup_end.unwrap_or(0) as u64);
+
+        let rows = sqlx::query(
+            r#"
+            SELECT timestamp, downstream_id, sum_difficulty, window_seconds
+            FROM hashrate_samples
+            WHERE timestamp >= ?
+            ORDER BY timestamp DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(covers_from as i64)
+        .bind(max_samples as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut samples: Vec<CachedSample> = rows
+            .iter()
+            .map(|row| CachedSample {
+                timestamp: row.get::<i64, _>("timestamp") as u64,
+                downstream_id: row.get::<i64, _>("downstream_id") as u32,
+                sum_difficulty: row.get::<f64, _>("sum_difficulty"),
+                window_seconds: row.get::<i64, _>("window_seconds") as u64,
+            })
+            .collect();
+        samples.reverse();
+
+        // A full load may have cut off part of the oldest timestamp
+        let covers_from = match samples.first() {
+            Some(oldest) if samples.len() == max_samples => oldest.timestamp + 1,
+            _ => covers_from,
+        };
+
+        tracing::info!(
+            "Preloaded {} hashrate samples from {} into the query cache",
+            samples.len(),
+            covers_from
+        );
+        let cache = SampleCache::new(max_samples, covers_from, samples);
+        self.cache = Some(RwLock::new(cache));
+        Ok(self)
+    }
+
+    /// Number of hashrate queries answered by SQLite rather than the warm cache.
+    pub fn db_query_count(&self) -> u64 {
+        self.db_queries.load(Ordering::Relaxed)
+    }
+
+    fn query_cache(
+        &self,
+        from_timestamp: u64,
+        query: impl FnOnce(&SampleCache) -> Vec<BucketRow>,
+    ) -> Option<Vec<HashratePoint>> {
+        let cache = self
+            .cache
+            .as_ref()?
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        if !cache.covers(from_timestamp) {
+            return None;
+        }
+        Some(Self::buckets_to_hashrate_points(&query(&cache)))
+    }
+
     /// Initialize the database schema.
     async fn init_schema(&self) -> Result<()> {
         // Create downstreams table
@@ -129,9 +238,116 @@ impl SqliteStorage {
         .execute(&self.pool)
         .await?;
 
+        // Rolled-up history: each row folds `sample_count` samples of one downstream in the
+        // bucket starting at `timestamp`
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS hashrate_rollup (
+                timestamp INTEGER NOT NULL,
+                downstream_id INTEGER NOT NULL,
+                bucket_seconds INTEGER NOT NULL,
+                sum_difficulty REAL NOT NULL,
+                sample_count INTEGER NOT NULL,
+                window_seconds INTEGER NOT NULL,
+
+                PRIMARY KEY (timestamp, downstream_id)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
         Ok(())
     }
 
+    /// Roll samples older than `config.rollup_after_secs` up into `hashrate_rollup`, delete
+    /// them, and apply the rollup retention. Returns the number of samples rolled up.
+    pub async fn rollup(&self, config: &RollupConfig) -> Result<u64> {
+        let now = unix_timestamp();
+        self.rollup_before(
+            now.saturating_sub(config.rollup_after_secs),
+            config.bucket_secs,
+            config
+                .retention_secs
+                .map(|retention| now.saturating_sub(retention)),
+        )
+        .await
+    }
+
+    async fn rollup_before(
+        &self,
+        cutoff: u64,
+        bucket_secs: u64,
+        retention_cutoff: Option<u64>,
+    ) -> Result<u64> {
+        if bucket_secs == 0 {
+            return Err(StorageError::InvalidArgument(
+                "rollup bucket must be at least one second".to_string(),
+            ));
+        }
+        // Only whole buckets are rolled up, so a bucket is never split across the tables
+        let cutoff = (cutoff / bucket_secs) * bucket_secs;
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO hashrate_rollup
+            (timestamp, downstream_id, bucket_seconds, sum_difficulty, sample_count, window_seconds)
+            SELECT
+                (timestamp / ?) * ? AS bucket_timestamp,
+                downstream_id,
+                ?,
+                SUM(CAST(sum_difficulty AS REAL)),
+                COUNT(*),
+                MAX(window_seconds)
+            FROM hashrate_samples
+            WHERE timestamp < ?
+            GROUP BY bucket_timestamp, downstream_id
+            ON CONFLICT(timestamp, downstream_id) DO UPDATE SET
+                sum_difficulty = sum_difficulty + excluded.sum_difficulty,
+                sample_count = sample_count + excluded.sample_count,
+                window_seconds = MAX(window_seconds, excluded.window_seconds)
+            "#,
+        )
+        .bind(bucket_secs as i64)
+        .bind(bucket_secs as i64)
+        .bind(bucket_secs as i64)
+        .bind(cutoff as i64)
+        .execute(&mut *tx)
+        .await?;
+
+        let rolled_up = sqlx::query("DELETE FROM hashrate_samples WHERE timestamp < ?")
+            .bind(cutoff as i64)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        if let Some(retention_cutoff) = retention_cutoff {
+            sqlx::query("DELETE FROM hashrate_rollup WHERE timestamp + bucket_seconds <= ?")
+                .bind(retention_cutoff as i64)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        if let Some(cache) = &self.cache {
+            cache
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .discard_before(cutoff);
+        }
+
+        if rolled_up > 0 {
+            tracing::info!(
+                "Rolled up {} hashrate samples older than {} into {}s buckets",
+                rolled_up,
+                cutoff,
+                bucket_secs
+            );
+        }
+        Ok(rolled_up)
+    }
+
     /// Store or update a downstream's metadata.
     async fn upsert_downstream(&self, downstream: &DownstreamSnapshot) -> Result<()> {
         sqlx::query(
@@ -154,43 +370,57 @@ impl SqliteStorage {
 
     /// Convert raw query rows to HashratePoint with bucketed aggregation.
     fn aggregate_rows_to_hashrate_points(rows: Vec<sqlx::sqlite::SqliteRow>) -> Vec<HashratePoint> {
-        rows.iter()
+        let buckets: Vec<BucketRow> = rows
+            .iter()
+            .map(|row| BucketRow {
+                bucket_timestamp: row.get::<i64, _>("bucket_timestamp") as u64,
+                total_difficulty: row.get::<f64, _>("total_difficulty"),
+                sample_count: row.get::<i64, _>("sample_count") as u64,
+                window_seconds: row.get::<i64, _>("window_seconds") as u64,
+            })
+            .collect();
+        Self::buckets_to_hashrate_points(&buckets)
+    }
+
+    fn buckets_to_hashrate_points(buckets: &[BucketRow]) -> Vec<HashratePoint> {
+        buckets
+            .iter()
             .enumerate()
-            .map(|(idx, row)| {
-                let bucket_timestamp = row.get::<i64, _>("bucket_timestamp") as u64;
-                let total_difficulty = row.get::<f64, _>("total_difficulty");
-                let sample_count = row.get::<i64, _>("sample_count") as u64;
-                let bucket_duration_seconds = row.get::<i64, _>("bucket_duration_seconds") as u64;
-                let window_seconds = row.get::<i64, _>("window_seconds") as u64;
+            .map(|(idx, bucket)| {
+                let BucketRow {
+                    bucket_timestamp,
+                    total_difficulty,
+                    sample_count,
+                    window_seconds,
+                } = *bucket;
+
+                // A bucket with no work (every sample idle) is 0 H/s. Returning early keeps
+                // the duration fallback below from applying to it.
+                if sample_count == 0 || total_difficulty <= 0.0 || !total_difficulty.is_finite() {
+                    return HashratePoint {
+                        timestamp: bucket_timestamp,
+                        hashrate_hs: 0.0,
+                    };
+                }
 
                 // Calculate average hashrate across samples
                 // Average the difficulty first, then derive hashrate
                 let avg_difficulty = total_difficulty / sample_count as f64;
 
-                // Use the actual bucket duration (time span from first to last sample in bucket).
-                // If bucket_duration is 0 (single sample), fall back to the sample's window_seconds.
-                // This preserves the measurement window for single-sample buckets while using
-                // actual duration for multi-sample aggregations.
-                let effective_duration = if bucket_duration_seconds > 0 {
-                    bucket_duration_seconds
-                } else {
-                    window_seconds
-                };
-
-                let hashrate = crate::metrics::derive_hashrate(avg_difficulty, effective_duration);
+                // Each sample's difficulty was measured over its own window, so the average
+                // is divided by that window rather than by the span between samples.
+                let hashrate = crate::metrics::derive_hashrate(avg_difficulty, window_seconds);
 
                 // Log first few and last few buckets for debugging
-                if idx < 3 || idx >= rows.len().saturating_sub(3) {
+                if idx < 3 || idx >= buckets.len().saturating_sub(3) {
                     tracing::debug!(
-                        "Bucket[{}] timestamp={}, total_diff={:.2}, samples={}, bucket_duration={}, window_secs={}, avg_diff={:.2}, effective_duration={}, hashrate={:.2}H/s",
+                        "Bucket[{}] timestamp={}, total_diff={:.2}, samples={}, window_secs={}, avg_diff={:.2}, hashrate={:.2}H/s",
                         idx,
                         bucket_timestamp,
                         total_difficulty,
                         sample_count,
-                        bucket_duration_seconds,
                         window_seconds,
                         avg_difficulty,
-                        effective_duration,
                         hashrate
                     );
                 }
@@ -236,6 +466,13 @@ impl StatsStorage for SqliteStorage {
         .execute(&self.pool)
         .await?;
 
+        if let Some(cache) = &self.cache {
+            cache
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(CachedSample::from(downstream));
+        }
+
         Ok(())
     }
 
@@ -248,43 +485,36 @@ impl StatsStorage for SqliteStorage {
         // Calculate adaptive bucket size to maintain ~60 data points per graph
         let bucket_seconds = calculate_bucket_size(from_timestamp, to_timestamp, 60);
 
+        if let Some(points) = self.query_cache(from_timestamp, |cache| {
+            cache.downstream_buckets(downstream_id, from_timestamp, to_timestamp, bucket_seconds)
+        }) {
+            return Ok(points);
+        }
+        self.db_queries.fetch_add(1, Ordering::Relaxed);
+
         // Aggregate data into calculated buckets to smooth the graph
-        // Key fix: Use the LATEST snapshot per bucket (MAX timestamp) instead of summing
-        // This prevents overcounting since each snapshot's sum_difficulty is already
-        // aggregated over a 60-second measurement window.
+        // Each snapshot's sum_difficulty already covers a 60-second measurement window, so
+        // samples in a bucket are averaged rather than summed to avoid overcounting. Idle
+        // samples (sum_difficulty = 0) count towards the average, so one idle sample between
+        // active ones lowers the bucket proportionally instead of zeroing it.
+        // Rolled-up rows carry their sample count, so they weigh in as the samples they replaced.
         let rows = sqlx::query(
             r#"
-            WITH bucketed AS (
-                SELECT
-                    timestamp,
-                    downstream_id,
-                    sum_difficulty,
-                    window_seconds,
-                    (timestamp / ?) * ? AS bucket_timestamp
+            WITH samples AS (
+                SELECT timestamp, downstream_id, sum_difficulty, 1 AS sample_count, window_seconds
                 FROM hashrate_samples
-                WHERE downstream_id = ? AND timestamp >= ? AND timestamp <= ?
-            ), ranked AS (
-                SELECT
-                    bucket_timestamp,
-                    timestamp,
-                    sum_difficulty,
-                    window_seconds,
-                    ROW_NUMBER() OVER (
-                        PARTITION BY bucket_timestamp
-                        ORDER BY timestamp DESC
-                    ) AS rn,
-                    MAX(timestamp) OVER (PARTITION BY bucket_timestamp) -
-                        MIN(timestamp) OVER (PARTITION BY bucket_timestamp) AS bucket_duration_seconds
-                FROM bucketed
+                UNION ALL
+                SELECT timestamp, downstream_id, sum_difficulty, sample_count, window_seconds
+                FROM hashrate_rollup
             )
             SELECT
-                bucket_timestamp,
-                CAST(sum_difficulty AS REAL) AS total_difficulty,
-                1 AS sample_count,
-                bucket_duration_seconds,
-                window_seconds
-            FROM ranked
-            WHERE rn = 1
+                (timestamp / ?) * ? AS bucket_timestamp,
+                SUM(CAST(sum_difficulty AS REAL)) AS total_difficulty,
+                SUM(sample_count) AS sample_count,
+                MAX(window_seconds) AS window_seconds
+            FROM samples
+            WHERE downstream_id = ? AND timestamp >= ? AND timestamp <= ?
+            GROUP BY bucket_timestamp
             ORDER BY bucket_timestamp ASC
             "#,
         )
@@ -315,41 +545,41 @@ impl StatsStorage for SqliteStorage {
             to_timestamp.saturating_sub(from_timestamp)
         );
 
+        if let Some(points) = self.query_cache(from_timestamp, |cache| {
+            cache.aggregate_buckets(from_timestamp, to_timestamp, bucket_seconds)
+        }) {
+            return Ok(points);
+        }
+        self.db_queries.fetch_add(1, Ordering::Relaxed);
+
         // Aggregate data into calculated buckets to smooth the graph
-        // Key fix: Use the LATEST snapshot per bucket (MAX timestamp) instead of summing
-        // This prevents overcounting since each snapshot's sum_difficulty is already
-        // aggregated over a 60-second measurement window.
+        // Each downstream's samples are averaged within the bucket (idle samples count as 0,
+        // as in query_hashrate), then the per-downstream averages are summed.
         let rows = sqlx::query(
             r#"
-            WITH bucketed AS (
-                SELECT
-                    timestamp,
-                    downstream_id,
-                    sum_difficulty,
-                    window_seconds,
-                    (timestamp / ?) * ? AS bucket_timestamp
+            WITH samples AS (
+                SELECT timestamp, downstream_id, sum_difficulty, 1 AS sample_count, window_seconds
                 FROM hashrate_samples
-                WHERE timestamp >= ? AND timestamp <= ?
-            ), ranked AS (
+                UNION ALL
+                SELECT timestamp, downstream_id, sum_difficulty, sample_count, window_seconds
+                FROM hashrate_rollup
+            ),
+            per_downstream AS (
                 SELECT
-                    bucket_timestamp,
+                    (timestamp / ?) * ? AS bucket_timestamp,
                     downstream_id,
-                    sum_difficulty,
-                    window_seconds,
-                    ROW_NUMBER() OVER (
-                        PARTITION BY downstream_id, bucket_timestamp
-                        ORDER BY timestamp DESC
-                    ) AS rn
-                FROM bucketed
+                    SUM(CAST(sum_difficulty AS REAL)) / SUM(sample_count) AS avg_difficulty,
+                    MAX(window_seconds) AS window_seconds
+                FROM samples
+                WHERE timestamp >= ? AND timestamp <= ?
+                GROUP BY bucket_timestamp, downstream_id
             )
             SELECT
                 bucket_timestamp,
-                SUM(CAST(sum_difficulty AS REAL)) AS total_difficulty,
+                SUM(avg_difficulty) AS total_difficulty,
                 1 AS sample_count,
-                0 AS bucket_duration_seconds,
                 MAX(window_seconds) AS window_seconds
-            FROM ranked
-            WHERE rn = 1
+            FROM per_downstream
             GROUP BY bucket_timestamp
             ORDER BY bucket_timestamp ASC
             "#,
@@ -382,6 +612,7 @@ impl StatsStorage for SqliteStorage {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::metrics::derive_hashrate;
     use tempfile::TempDir;
 
     #[tokio::test]
@@ -415,6 +646,7 @@ mod tests {
             downstream_id: 1,
             name: "test_miner".to_string(),
             address: "192.168.1.1:4444".to_string(),
+            user_agent: "unknown".to_string(),
             shares_lifetime: 100,
             shares_in_window: 10,
             sum_difficulty_in_window: 100.0,
@@ -446,6 +678,7 @@ mod tests {
                 downstream_id: 1,
                 name: "miner_1".to_string(),
                 address: "192.168.1.1:4444".to_string(),
+                user_agent: "unknown".to_string(),
                 shares_lifetime: (i + 1) * 10,
                 shares_in_window: 10,
                 sum_difficulty_in_window: 1000.0,
@@ -460,10 +693,9 @@ mod tests {
         // All 6 samples fall into the same bucket
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].timestamp, 6000);
-        // Average of 6 samples of 1000 difficulty each = 1000
-        // Bucket duration: MAX(timestamp) - MIN(timestamp) = 6050 - 6000 = 50 seconds
-        // (1000 * 2^32) / 50 seconds = 85,899,345,920 H/s
-        assert_eq!(results[0].hashrate_hs, 85_899_345_920.0);
+        // Average of 6 samples of 1000 difficulty each = 1000 over each 10-second window
+        // (1000 * 2^32) / 10 seconds = 429,496,729,600 H/s
+        assert_eq!(results[0].hashrate_hs, 429_496_729_600.0);
     }
 
     #[tokio::test]
@@ -480,6 +712,7 @@ mod tests {
             downstream_id: 1,
             name: "miner_1".to_string(),
             address: "192.168.1.1:4444".to_string(),
+            user_agent: "unknown".to_string(),
             shares_lifetime: 100,
             shares_in_window: 10,
             sum_difficulty_in_window: 1000.0,
@@ -491,6 +724,7 @@ mod tests {
             downstream_id: 2,
             name: "miner_2".to_string(),
             address: "192.168.1.2:4444".to_string(),
+            user_agent: "unknown".to_string(),
             shares_lifetime: 50,
             shares_in_window: 5,
             sum_difficulty_in_window: 500.0,
@@ -528,6 +762,7 @@ mod tests {
             downstream_id: 1,
             name: "miner_1".to_string(),
             address: "192.168.1.1:4444".to_string(),
+            user_agent: "unknown".to_string(),
             shares_lifetime: 100,
             shares_in_window: 10,
             sum_difficulty_in_window: 1000.0,
@@ -539,6 +774,7 @@ mod tests {
             downstream_id: 2,
             name: "miner_2".to_string(),
             address: "192.168.1.2:4444".to_string(),
+            user_agent: "unknown".to_string(),
             shares_lifetime: 50,
             shares_in_window: 5,
             sum_difficulty_in_window: 1000.0,
@@ -589,6 +825,7 @@ mod tests {
                 downstream_id: 1,
                 name: "miner_1".to_string(),
                 address: "192.168.1.1:4444".to_string(),
+                user_agent: "unknown".to_string(),
                 shares_lifetime: 100,
                 shares_in_window: 10,
                 sum_difficulty_in_window: 100.0,
@@ -606,4 +843,218 @@ mod tests {
         assert_eq!(results[0].timestamp, 6000); // First bucket (samples at 6000, 6010)
         assert_eq!(results[1].timestamp, 6120); // Second bucket (samples at 6120, 6130)
     }
+
+    #[tokio::test]
+    async fn test_idle_windows_contribute_zero() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let storage = SqliteStorage::new(&db_path).await.unwrap();
+
+        // Query range 6000..6300 uses 60-second buckets:
+        // 6000 bucket: active, idle, active
+        // 6060 bucket: idle only
+        // 6120 bucket: active only
+        let samples = [
+            (6000, 1000.0),
+            (6020, 0.0),
+            (6040, 1000.0),
+            (6060, 0.0),
+            (6120, 1000.0),
+        ];
+        for (timestamp, sum_difficulty) in samples {
+            let downstream = DownstreamSnapshot {
+                downstream_id: 1,
+                name: "miner_1".to_string(),
+                address: "192.168.1.1:4444".to_string(),
+                user_agent: "unknown".to_string(),
+                shares_lifetime: 100,
+                shares_in_window: if sum_difficulty > 0.0 { 10 } else { 0 },
+                sum_difficulty_in_window: sum_difficulty,
+                window_seconds: 10,
+                timestamp,
+            };
+            storage.store_downstream(&downstream).await.unwrap();
+        }
+
+        let results = storage.query_hashrate(1, 6000, 6300).await.unwrap();
+        assert_eq!(results.len(), 3);
+
+        // The idle sample counts as 0 in the average: (1000 + 0 + 1000) / 3 over the
+        // 10-second sample window
+        let expected = derive_hashrate(2000.0 / 3.0, 10);
+        assert!((results[0].hashrate_hs - expected).abs() < 1e-3);
+
+        // A bucket holding only an idle sample reports 0 H/s
+        assert_eq!(results[1].timestamp, 6060);
+        assert_eq!(results[1].hashrate_hs, 0.0);
+
+        // (1000 * 2^32) / 10 seconds
+        assert_eq!(results[2].hashrate_hs, 429_496_729_600.0);
+
+        let aggregate = storage.query_aggregate_hashrate(6000, 6300).await.unwrap();
+        assert_eq!(aggregate.len(), 3);
+        assert_eq!(aggregate[1].hashrate_hs, 0.0);
+        assert!(aggregate[0].hashrate_hs > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_single_downstream_matches_aggregate() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let storage = SqliteStorage::new(&db_path).await.unwrap();
+
+        // Several samples per 60-second bucket, as the pool's snapshot interval produces
+        let samples = [1000.0, 2000.0, 0.0, 1500.0, 500.0, 3000.0];
+        for (i, sum_difficulty) in samples.into_iter().enumerate() {
+            let downstream = DownstreamSnapshot {
+                downstream_id: 1,
+                name: "miner_1".to_string(),
+                address: "192.168.1.1:4444".to_string(),
+                user_agent: "unknown".to_string(),
+                shares_lifetime: 100,
+                shares_in_window: 10,
+                sum_difficulty_in_window: sum_difficulty,
+                window_seconds: 60,
+                timestamp: 6000 + i as u64 * 20,
+            };
+            storage.store_downstream(&downstream).await.unwrap();
+        }
+
+        let downstream = storage.query_hashrate(1, 6000, 6300).await.unwrap();
+        let aggregate = storage.query_aggregate_hashrate(6000, 6300).await.unwrap();
+        assert_eq!(downstream.len(), 2);
+        assert_eq!(downstream.len(), aggregate.len());
+        for (a, b) in downstream.iter().zip(&aggregate) {
+            assert_eq!(a.timestamp, b.timestamp);
+            assert!((a.hashrate_hs - b.hashrate_hs).abs() < 1e-3);
+        }
+        assert!((downstream[0].hashrate_hs - derive_hashrate(1000.0, 60)).abs() < 1e-3);
+    }
+
+    #[tokio::test]
+    async fn test_warm_cache_serves_recent_window_without_db_query() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let now = unix_timestamp();
+
+        {
+            let storage = SqliteStorage::new(&db_path).await.unwrap();
+            for (i, downstream_id) in [(0, 1), (1, 1), (2, 2), (3, 1)] {
+                let downstream = DownstreamSnapshot {
+                    downstream_id,
+                    name: format!("miner_{}", downstream_id),
+                    address: "192.168.1.1:4444".to_string(),
+                    user_agent: "unknown".to_string(),
+                    shares_lifetime: 10,
+                    shares_in_window: 10,
+                    sum_difficulty_in_window: 1000.0 * (i + 1) as f64,
+                    window_seconds: 60,
+                    timestamp: now - 300 + i * 60,
+                };
+                storage.store_downstream(&downstream).await.unwrap();
+            }
+        }
+
+        // Reopen the database as after a restart
+        let cold = SqliteStorage::new(&db_path).await.unwrap();
+        let warm = SqliteStorage::new(&db_path)
+            .await
+            .unwrap()
+            .with_warm_cache(WarmCacheConfig {
+                window_secs: 3600,
+                max_samples: 100,
+            })
+            .await
+            .unwrap();
+
+        let from = now - 1800;
+        let expected = cold.query_hashrate(1, from, now).await.unwrap();
+        let cached = warm.query_hashrate(1, from, now).await.unwrap();
+        assert_eq!(warm.db_query_count(), 0);
+        assert_eq!(cached.len(), expected.len());
+        for (a, b) in cached.iter().zip(&expected) {
+            assert_eq!(a.timestamp, b.timestamp);
+            assert_eq!(a.hashrate_hs, b.hashrate_hs);
+        }
+
+        let expected = cold.query_aggregate_hashrate(from, now).await.unwrap();
+        let cached = warm.query_aggregate_hashrate(from, now).await.unwrap();
+        assert_eq!(warm.db_query_count(), 0);
+        assert_eq!(cached.len(), expected.len());
+        for (a, b) in cached.iter().zip(&expected) {
+            assert_eq!(a.timestamp, b.timestamp);
+            assert_eq!(a.hashrate_hs, b.hashrate_hs);
+        }
+
+        // Ranges reaching past the preloaded window still go to the database
+        warm.query_hashrate(1, now - 7200, now).await.unwrap();
+        assert_eq!(warm.db_query_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rollup_keeps_history_queryable() {
+        let temp_dir = TempDir::new().unwrap();
+        let storage = SqliteStorage::new(temp_dir.path().join("test.db"))
+            .await
+            .unwrap();
+
+        // Two hours of one-minute samples; the first hour runs at half the difficulty
+        for minute in 0..120u64 {
+            for downstream_id in 1..=2u32 {
+                let base = if minute < 60 { 600.0 } else { 1200.0 };
+                storage
+                    .store_downstream(&DownstreamSnapshot {
+                        downstream_id,
+                        name: format!("miner_{}", downstream_id),
+                        address: "192.168.1.1:4444".to_string(),
+                        user_agent: "unknown".to_string(),
+                        shares_lifetime: minute,
+                        shares_in_window: 10,
+                        sum_difficulty_in_window: base * downstream_id as f64,
+                        window_seconds: 60,
+                        timestamp: minute * 60,
+                    })
+                    .await
+                    .unwrap();
+            }
+        }
+
+        // 7199s over 60 points gives 300s query buckets, matching the rollup buckets below
+        let before = storage.query_aggregate_hashrate(0, 7199).await.unwrap();
+        let rolled_up = storage.rollup_before(3600, 300, None).await.unwrap();
+        assert_eq!(rolled_up, 120);
+        let after = storage.query_aggregate_hashrate(0, 7199).await.unwrap();
+
+        assert_eq!(after.len(), 24);
+        assert_eq!(before.len(), after.len());
+        for (a, b) in before.iter().zip(&after) {
+            assert_eq!(a.timestamp, b.timestamp);
+            assert_eq!(a.hashrate_hs, b.hashrate_hs);
+        }
+        // Old points come from the rollup table, new ones from the raw samples
+        assert_eq!(after[0].hashrate_hs, derive_hashrate(1800.0, 60));
+        assert_eq!(after[23].hashrate_hs, derive_hashrate(3600.0, 60));
+
+        // Per-downstream queries also span both tables without gaps
+        let downstream = storage.query_hashrate(1, 0, 7199).await.unwrap();
+        let timestamps: Vec<u64> = downstream.iter().map(|p| p.timestamp).collect();
+        assert_eq!(timestamps, (0..24).map(|i| i * 300).collect::<Vec<_>>());
+        assert!(downstream.iter().all(|p| p.hashrate_hs > 0.0));
+
+        let (raw,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM hashrate_samples")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+        assert_eq!(raw, 120);
+
+        // Rollup buckets past the retention are dropped
+        storage.rollup_before(3600, 300, Some(1800)).await.unwrap();
+        let (rollups,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM hashrate_rollup")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+        assert_eq!(rollups, 12);
+    }
 }
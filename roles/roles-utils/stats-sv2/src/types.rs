@@ -1,7 +1,8 @@
 //! Snapshot types for time-series metrics collection.
 
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
+
+pub use stats::clock::unix_timestamp;
 
 /// The type of service sending metrics.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -10,6 +11,13 @@ pub enum ServiceType {
     Pool,
 }
 
+/// User agent recorded for downstreams that did not report their client software.
+pub const UNKNOWN_USER_AGENT: &str = "unknown";
+
+fn unknown_user_agent() -> String {
+    UNKNOWN_USER_AGENT.to_string()
+}
+
 /// Snapshot of a single downstream (miner or translator connection).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownstreamSnapshot {
@@ -22,6 +30,10 @@ pub struct DownstreamSnapshot {
     /// Network address (IP:port)
     pub address: String,
 
+    /// Client software reported by the downstream, [`UNKNOWN_USER_AGENT`] if it sent none
+    #[serde(default = "unknown_user_agent")]
+    pub user_agent: String,
+
     /// Lifetime total shares accepted for this downstream
     pub shares_lifetime: u64,
 
@@ -61,14 +73,6 @@ pub struct HashratePoint {
     pub hashrate_hs: f64,
 }
 
-/// Get current Unix timestamp in seconds.
-pub fn unix_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
-        .as_secs()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,6 +83,7 @@ mod tests {
             downstream_id: 1,
             name: "miner_1".to_string(),
             address: "192.168.1.100:4444".to_string(),
+            user_agent: "cgminer/4.11.1".to_string(),
             shares_lifetime: 100,
             shares_in_window: 5,
             sum_difficulty_in_window: 100.5,
@@ -90,5 +95,13 @@ mod tests {
         let deserialized: DownstreamSnapshot = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized.downstream_id, 1);
         assert_eq!(deserialized.shares_lifetime, 100);
+        assert_eq!(deserialized.user_agent, "cgminer/4.11.1");
+    }
+
+    #[test]
+    fn test_missing_user_agent_defaults_to_unknown() {
+        let json = r#"{"downstream_id":1,"name":"miner_1","address":"192.168.1.100:4444","shares_lifetime":0,"shares_in_window":0,"sum_difficulty_in_window":0.0,"window_seconds":60,"timestamp":0}"#;
+        let snapshot: DownstreamSnapshot = serde_json::from_str(json).unwrap();
+        assert_eq!(snapshot.user_agent, UNKNOWN_USER_AGENT);
     }
 }
@@ -4,15 +4,10 @@
 //! to track shares with timestamps and calculate windowed difficulty sums.
 //! This ensures both services use the same window calculation logic.
 
-use std::time::{SystemTime, UNIX_EPOCH};
-
-/// Get current Unix timestamp in seconds.
-pub fn unix_timestamp() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs()
-}
+use std::fmt;
+
+pub use stats::clock::unix_timestamp;
+use stats::clock::{system_clock, Clock};
 
 /// Shared metrics collector that tracks shares within a rolling time window.
 ///
@@ -25,25 +20,41 @@ pub fn unix_timestamp() -> u64 {
 /// collector.record_share(100.0); // difficulty 100
 /// let sum = collector.sum_difficulty_in_window(); // sum of shares in last 10s
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct WindowedMetricsCollector {
     // Shares stored as (unix_timestamp_secs, difficulty)
     shares: Vec<(u64, f64)>,
     window_seconds: u64,
+    clock: Clock,
+}
+
+impl fmt::Debug for WindowedMetricsCollector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WindowedMetricsCollector")
+            .field("shares", &self.shares)
+            .field("window_seconds", &self.window_seconds)
+            .finish_non_exhaustive()
+    }
 }
 
 impl WindowedMetricsCollector {
     /// Create a new collector with the specified window size in seconds.
     pub fn new(window_seconds: u64) -> Self {
+        Self::with_clock(window_seconds, system_clock())
+    }
+
+    /// Like [`Self::new`], timestamping shares and windows with `clock`.
+    pub fn with_clock(window_seconds: u64, clock: Clock) -> Self {
         Self {
             shares: Vec::new(),
             window_seconds,
+            clock,
         }
     }
 
     /// Record a share with its difficulty. Uses current Unix timestamp.
     pub fn record_share(&mut self, difficulty: f64) {
-        let now = unix_timestamp();
+        let now = (self.clock)();
         self.shares.push((now, difficulty));
 
         // Cleanup shares outside the window to prevent unbounded growth
@@ -55,7 +66,7 @@ impl WindowedMetricsCollector {
     /// Get the sum of difficulties for shares in the current window.
     /// Only includes shares from the last `window_seconds` seconds.
     pub fn sum_difficulty_in_window(&self) -> f64 {
-        let now = unix_timestamp();
+        let now = (self.clock)();
         let cutoff = now.saturating_sub(self.window_seconds);
 
         self.shares
@@ -67,7 +78,7 @@ impl WindowedMetricsCollector {
 
     /// Get the count of shares in the current window.
     pub fn shares_in_window(&self) -> u64 {
-        let now = unix_timestamp();
+        let now = (self.clock)();
         let cutoff = now.saturating_sub(self.window_seconds);
 
         self.shares
@@ -137,6 +148,36 @@ mod tests {
         assert_eq!(collector.sum_difficulty_in_window(), 50.0);
     }
 
+    #[test]
+    fn test_window_filtering_with_pinned_clock() {
+        use std::sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        };
+
+        let now = Arc::new(AtomicU64::new(1_000));
+        let clock_now = now.clone();
+        let mut collector = WindowedMetricsCollector::with_clock(
+            10,
+            Arc::new(move || clock_now.load(Ordering::SeqCst)),
+        );
+
+        collector.record_share(100.0);
+        now.store(1_005, Ordering::SeqCst);
+        collector.record_share(50.0);
+        assert_eq!(collector.sum_difficulty_in_window(), 150.0);
+
+        // The first share leaves the window exactly `window_seconds` after it was recorded
+        now.store(1_010, Ordering::SeqCst);
+        assert_eq!(collector.shares_in_window(), 1);
+        assert_eq!(collector.sum_difficulty_in_window(), 50.0);
+
+        // Recording prunes what has left the window
+        now.store(1_020, Ordering::SeqCst);
+        collector.record_share(25.0);
+        assert_eq!(collector.recent_shares(), &[(1_020, 25.0)]);
+    }
+
     #[test]
     fn test_zero_window() {
         let mut collector = WindowedMetricsCollector::new(0);
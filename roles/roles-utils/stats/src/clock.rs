@@ -0,0 +1,25 @@
+//! Injectable source of the current unix time.
+//!
+//! Stats types that age, window or timestamp their data take a [`Clock`] so tests can pin
+//! time instead of sleeping. Everything defaults to [`system_clock`].
+
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Source of the current unix time in seconds
+pub type Clock = Arc<dyn Fn() -> u64 + Send + Sync>;
+
+/// Current unix time in seconds from the system clock, 0 if it is set before the epoch.
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// A [`Clock`] reading the system time.
+pub fn system_clock() -> Clock {
+    Arc::new(unix_timestamp)
+}
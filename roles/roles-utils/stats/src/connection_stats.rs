@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// Why a listener turned an incoming connection away
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectReason {
+    /// The listener was already serving its maximum number of connections
+    Capacity,
+    /// The Noise handshake did not complete
+    Handshake,
+    /// The connection was set up but the role failed to start serving it
+    Setup,
+}
+
+/// Why an established downstream connection ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectReason {
+    /// The client closed the connection
+    ClientQuit,
+    /// The client sent something the role could not handle
+    ProtocolError,
+    /// The role closed the connection itself, e.g. on shutdown or an upstream change
+    Shutdown,
+}
+
+/// A downstream that disconnected, as reported in snapshots after it is gone
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisconnectEvent {
+    pub downstream_id: u32,
+    pub name: String,
+    pub reason: DisconnectReason,
+    pub timestamp: u64,
+}
+
+/// Accepted and rejected connection totals since startup, as reported in snapshots
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    pub accepted: u64,
+    pub rejected: BTreeMap<RejectReason, u64>,
+}
+
+impl ConnectionStats {
+    pub fn total_rejected(&self) -> u64 {
+        self.rejected.values().sum()
+    }
+}
+
+/// Live accepted/rejected counters updated from a listener's accept loop
+#[derive(Debug, Default)]
+pub struct ConnectionCounters {
+    accepted: AtomicU64,
+    rejected: Mutex<BTreeMap<RejectReason, u64>>,
+}
+
+impl ConnectionCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_accepted(&self) {
+        self.accepted.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a rejection, returning the new total for `reason`.
+    pub fn record_rejected(&self, reason: RejectReason) -> u64 {
+        let mut rejected = self.rejected.lock().unwrap_or_else(|e| e.into_inner());
+        let count = rejected.entry(reason).or_insert(0);
+        *count += 1;
+        *count
+    }
+
+    pub fn snapshot(&self) -> ConnectionStats {
+        ConnectionStats {
+            accepted: self.accepted.load(Ordering::Relaxed),
+            rejected: self
+                .rejected
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_track_reasons_separately() {
+        let counters = ConnectionCounters::new();
+        counters.record_accepted();
+        counters.record_accepted();
+        assert_eq!(counters.record_rejected(RejectReason::Capacity), 1);
+        assert_eq!(counters.record_rejected(RejectReason::Capacity), 2);
+        assert_eq!(counters.record_rejected(RejectReason::Handshake), 1);
+
+        let stats = counters.snapshot();
+        assert_eq!(stats.accepted, 2);
+        assert_eq!(stats.rejected.get(&RejectReason::Capacity), Some(&2));
+        assert_eq!(stats.rejected.get(&RejectReason::Handshake), Some(&1));
+        assert_eq!(stats.rejected.get(&RejectReason::Setup), None);
+        assert_eq!(stats.total_rejected(), 3);
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert_eq!(
+            json,
+            r#"{"accepted":2,"rejected":{"capacity":2,"handshake":1}}"#
+        );
+    }
+}
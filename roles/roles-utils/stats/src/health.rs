@@ -0,0 +1,166 @@
+//! Overall health of a service, derived from the state of its subsystems.
+//!
+//! Each subsystem maps to a [`HealthStatus`] on its own and the overall status is the worst of
+//! them:
+//!
+//! - upstream: `Down` while disconnected
+//! - mint: `Degraded` while unavailable
+//! - pending quotes: `Degraded` from [`PENDING_QUOTES_DEGRADED`], `Down` from
+//!   [`PENDING_QUOTES_DOWN`]
+//! - stats freshness: `Degraded` past [`STATS_DEGRADED_AFTER_SECS`], `Down` past
+//!   [`STATS_DOWN_AFTER_SECS`]
+//!
+//! Services compute the status when they take a snapshot, where the stats are fresh by
+//! definition. Dashboards fold in the snapshot's age with [`HealthStatus::aged`].
+
+use serde::{Deserialize, Serialize};
+
+/// Pending quotes at which the mint is considered to be falling behind
+pub const PENDING_QUOTES_DEGRADED: usize = 100;
+/// Pending quotes at which quoting is considered stalled
+pub const PENDING_QUOTES_DOWN: usize = 1_000;
+/// Snapshot age after which the dashboards treat stats as stale
+pub const STATS_DEGRADED_AFTER_SECS: u64 = 15;
+/// Snapshot age after which the service is assumed to be gone
+pub const STATS_DOWN_AFTER_SECS: u64 = 60;
+
+/// Overall service status, ordered from best to worst
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    #[default]
+    Healthy,
+    Degraded,
+    Down,
+}
+
+impl HealthStatus {
+    /// Status of a snapshot `stats_age_secs` old: this one, or worse if the stats are stale.
+    pub fn aged(self, stats_age_secs: u64) -> Self {
+        let freshness = if stats_age_secs > STATS_DOWN_AFTER_SECS {
+            HealthStatus::Down
+        } else if stats_age_secs > STATS_DEGRADED_AFTER_SECS {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        };
+        self.max(freshness)
+    }
+}
+
+/// Subsystem states a service reports its health from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthInputs {
+    pub upstream_connected: bool,
+    /// `None` for services that do not talk to the mint
+    pub mint_available: Option<bool>,
+    pub pending_quotes: usize,
+}
+
+impl HealthInputs {
+    pub fn status(&self) -> HealthStatus {
+        let upstream = if self.upstream_connected {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Down
+        };
+        let mint = match self.mint_available {
+            Some(false) => HealthStatus::Degraded,
+            _ => HealthStatus::Healthy,
+        };
+        let backlog = if self.pending_quotes >= PENDING_QUOTES_DOWN {
+            HealthStatus::Down
+        } else if self.pending_quotes >= PENDING_QUOTES_DEGRADED {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Healthy
+        };
+        upstream.max(mint).max(backlog)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subsystem_states_map_to_overall_status() {
+        let healthy = HealthInputs {
+            upstream_connected: true,
+            mint_available: Some(true),
+            pending_quotes: 0,
+        };
+        let cases = [
+            (healthy, 0, HealthStatus::Healthy),
+            (
+                HealthInputs {
+                    mint_available: None,
+                    ..healthy
+                },
+                0,
+                HealthStatus::Healthy,
+            ),
+            (
+                HealthInputs {
+                    pending_quotes: PENDING_QUOTES_DEGRADED - 1,
+                    ..healthy
+                },
+                STATS_DEGRADED_AFTER_SECS,
+                HealthStatus::Healthy,
+            ),
+            (
+                HealthInputs {
+                    mint_available: Some(false),
+                    ..healthy
+                },
+                0,
+                HealthStatus::Degraded,
+            ),
+            (
+                HealthInputs {
+                    pending_quotes: PENDING_QUOTES_DEGRADED,
+                    ..healthy
+                },
+                0,
+                HealthStatus::Degraded,
+            ),
+            (
+                healthy,
+                STATS_DEGRADED_AFTER_SECS + 1,
+                HealthStatus::Degraded,
+            ),
+            (
+                HealthInputs {
+                    upstream_connected: false,
+                    ..healthy
+                },
+                0,
+                HealthStatus::Down,
+            ),
+            (
+                HealthInputs {
+                    mint_available: Some(false),
+                    pending_quotes: PENDING_QUOTES_DOWN,
+                    ..healthy
+                },
+                0,
+                HealthStatus::Down,
+            ),
+            (healthy, STATS_DOWN_AFTER_SECS + 1, HealthStatus::Down),
+        ];
+
+        for (inputs, age, expected) in cases {
+            assert_eq!(
+                inputs.status().aged(age),
+                expected,
+                "{inputs:?} at {age}s old"
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&HealthStatus::Degraded).unwrap(),
+            "\"degraded\""
+        );
+    }
+}
@@ -1,6 +1,15 @@
+pub mod clock;
+pub mod connection_stats;
+pub mod health;
+pub mod quote_stats;
 pub mod stats_adapter;
 pub mod stats_client;
 pub mod stats_poller;
 
 // Re-export snapshot types
 pub use stats_adapter::{TranslatorStatus, PoolStatus, ProxySnapshot, PoolSnapshot};
+pub use connection_stats::{
+    ConnectionCounters, ConnectionStats, DisconnectEvent, DisconnectReason, RejectReason,
+};
+pub use health::{HealthInputs, HealthStatus};
+pub use quote_stats::{QuotePollerCounters, QuotePollerStats};
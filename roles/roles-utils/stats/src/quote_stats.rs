@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// Quote poller outcome totals since startup, as reported in snapshots
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotePollerStats {
+    pub quotes_registered: u64,
+    /// Quotes the mint reported PAID and whose downstream was notified
+    pub quotes_paid: u64,
+    /// Quotes the mint reported as already issued, so no notification was needed
+    pub quotes_issued_elsewhere: u64,
+    /// Quotes dropped after sitting unpaid past the quote timeout
+    pub quotes_expired: u64,
+    /// Failed attempts to notify a downstream of a paid quote
+    pub notification_failures: u64,
+    /// Oldest quotes dropped unnotified to stay under the pending quote limit
+    #[serde(default)]
+    pub quotes_evicted: u64,
+}
+
+/// Live outcome counters updated from the quote poller loop
+#[derive(Debug, Default)]
+pub struct QuotePollerCounters {
+    quotes_registered: AtomicU64,
+    quotes_paid: AtomicU64,
+    quotes_issued_elsewhere: AtomicU64,
+    quotes_expired: AtomicU64,
+    notification_failures: AtomicU64,
+    quotes_evicted: AtomicU64,
+}
+
+impl QuotePollerCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_registered(&self) {
+        self.quotes_registered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_paid(&self) {
+        self.quotes_paid.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_issued_elsewhere(&self) {
+        self.quotes_issued_elsewhere.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_expired(&self, count: u64) {
+        self.quotes_expired.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn record_notification_failure(&self) {
+        self.notification_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_evicted(&self, count: u64) {
+        self.quotes_evicted.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> QuotePollerStats {
+        QuotePollerStats {
+            quotes_registered: self.quotes_registered.load(Ordering::Relaxed),
+            quotes_paid: self.quotes_paid.load(Ordering::Relaxed),
+            quotes_issued_elsewhere: self.quotes_issued_elsewhere.load(Ordering::Relaxed),
+            quotes_expired: self.quotes_expired.load(Ordering::Relaxed),
+            notification_failures: self.notification_failures.load(Ordering::Relaxed),
+            quotes_evicted: self.quotes_evicted.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -1,11 +1,27 @@
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    connection_stats::{ConnectionStats, DisconnectEvent},
+    health::HealthStatus,
+    quote_stats::QuotePollerStats,
+};
+
 /// Trait for collecting stats snapshot from hub services
 /// Implemented by Pool and Translator to expose their state
 pub trait StatsSnapshotProvider {
     type Snapshot: Serialize + for<'de> Deserialize<'de>;
+    type MetricsSnapshot: Serialize + for<'de> Deserialize<'de>;
 
+    /// Operational status shown on the dashboard
     fn get_snapshot(&self) -> Self::Snapshot;
+
+    /// Per-downstream metrics for time-series collection
+    fn get_metrics_snapshot(&self) -> Self::MetricsSnapshot;
+
+    /// Collect both snapshots in one call, e.g. while holding a single lock
+    fn get_snapshots(&self) -> (Self::Snapshot, Self::MetricsSnapshot) {
+        (self.get_snapshot(), self.get_metrics_snapshot())
+    }
 }
 
 // Translator status snapshot - operational state of translator
@@ -15,6 +31,18 @@ pub struct TranslatorStatus {
     pub upstream_pool: Option<PoolConnection>,
     pub downstream_miners: Vec<MinerInfo>,
     pub blockchain_network: String,
+    /// Miner connections refused because the translator was at `max_downstreams`
+    #[serde(default)]
+    pub rejected_connections: u64,
+    /// SV1 connections accepted and rejected by the listener, by reason
+    #[serde(default)]
+    pub connections: ConnectionStats,
+    /// Miners that disconnected most recently and why, oldest first
+    #[serde(default)]
+    pub recent_disconnects: Vec<DisconnectEvent>,
+    /// Overall status when the snapshot was taken
+    #[serde(default)]
+    pub health: HealthStatus,
     pub timestamp: u64,
 }
 
@@ -34,6 +62,13 @@ pub struct MinerInfo {
     pub hashrate: f64,
     pub shares_submitted: u64,
     pub connected_at: u64,
+    /// Client software from `mining.subscribe`, "unknown" if the miner sent none
+    #[serde(default = "unknown_user_agent")]
+    pub user_agent: String,
+}
+
+fn unknown_user_agent() -> String {
+    "unknown".to_string()
 }
 
 // Pool status snapshot - operational state of pool
@@ -42,6 +77,21 @@ pub struct PoolStatus {
     pub services: Vec<ServiceConnection>,
     pub downstream_proxies: Vec<ProxyConnection>,
     pub listen_address: String,
+    /// Accepted shares since the last block found, counted from pool startup until the first one
+    #[serde(default)]
+    pub shares_since_last_block: u64,
+    /// Unix timestamp of the last block found, `None` if none since startup
+    #[serde(default)]
+    pub last_block_found_at: Option<u64>,
+    /// Downstream connections accepted and rejected by the listener, by reason
+    #[serde(default)]
+    pub connections: ConnectionStats,
+    /// Overall status when the snapshot was taken
+    #[serde(default)]
+    pub health: HealthStatus,
+    /// Quote poller outcome totals, `None` when no mint HTTP endpoint is configured
+    #[serde(default)]
+    pub quote_poller: Option<QuotePollerStats>,
     pub timestamp: u64,
 }
 
@@ -68,9 +118,23 @@ pub struct ProxyConnection {
     pub channels: Vec<u32>,
     pub shares_submitted: u64,
     pub quotes_created: u64,
+    #[serde(default)]
+    pub quotes_failed: u64,
     pub ehash_mined: u64,
     pub last_share_at: Option<u64>,
     pub work_selection: bool,
+    /// Share of quote attempts that succeeded; `None` until the first attempt.
+    #[serde(default)]
+    pub quote_success_ratio: Option<f64>,
+    /// Lifetime share counts per log2 difficulty bucket (bucket `i` covers `[2^i, 2^(i+1))`).
+    #[serde(default)]
+    pub difficulty_histogram: Vec<u64>,
+    /// Share of submitted difficulty that was rejected; 0 before any share.
+    #[serde(default)]
+    pub reject_ratio: f64,
+    /// Shares submitted in the last minute.
+    #[serde(default)]
+    pub shares_per_minute: f64,
 }
 
 // JD Server snapshot types - just a heartbeat
@@ -80,6 +144,15 @@ pub struct JdsSnapshot {
     pub timestamp: u64,
 }
 
+// Per-share event, sent by the pool only when share events are enabled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShareSubmitted {
+    pub downstream_id: u32,
+    pub channel_id: u32,
+    pub difficulty: f64,
+    pub timestamp: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -99,8 +172,13 @@ mod tests {
                 hashrate: 100.5,
                 shares_submitted: 42,
                 connected_at: 1234567890,
+                user_agent: "bmminer/2.0.0".to_string(),
             }],
             blockchain_network: "testnet4".to_string(),
+            rejected_connections: 0,
+            connections: Default::default(),
+            recent_disconnects: Vec::new(),
+            health: Default::default(),
             timestamp: 1234567890,
         };
 
@@ -120,6 +198,11 @@ mod tests {
             }],
             downstream_proxies: vec![],
             listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: 1234567890,
         };
 
@@ -142,6 +142,10 @@ mod tests {
             upstream_pool: None,
             downstream_miners: vec![],
             blockchain_network: "testnet4".to_string(),
+            rejected_connections: 0,
+            connections: Default::default(),
+            recent_disconnects: Vec::new(),
+            health: Default::default(),
             timestamp: 123456,
         };
         client.send_snapshot(snapshot).await.unwrap();
@@ -159,6 +163,10 @@ mod tests {
             upstream_pool: None,
             downstream_miners: vec![],
             blockchain_network: "testnet4".to_string(),
+            rejected_connections: 0,
+            connections: Default::default(),
+            recent_disconnects: Vec::new(),
+            health: Default::default(),
             timestamp: 123,
         };
         let result = client.send_snapshot(snapshot).await;
@@ -1,35 +1,208 @@
 use crate::{stats_adapter::StatsSnapshotProvider, stats_client::StatsClient};
-use std::{sync::Arc, time::Duration};
-use tokio::sync::Mutex;
+use serde::Serialize;
+use std::{future::Future, time::Duration};
 use tracing::{debug, error};
 
 /// Generic polling loop that works with any StatsSnapshotProvider
-/// Polls at the specified interval and sends snapshots to the stats service
-pub async fn start_stats_polling<T>(
-    provider: Arc<Mutex<T>>,
-    client: StatsClient<T::Snapshot>,
+/// Polls at the specified interval and sends both snapshot kinds to the stats service
+pub async fn start_stats_polling<P>(provider: P, stats_addr: String, poll_interval: Duration)
+where
+    P: StatsSnapshotProvider + Send + 'static,
+    P::Snapshot: Send + 'static,
+    P::MetricsSnapshot: Send + 'static,
+{
+    run_snapshot_loop(stats_addr, poll_interval, move || {
+        std::future::ready(Some(provider.get_snapshots()))
+    })
+    .await
+}
+
+/// Like [`start_stats_polling`], but stops once `shutdown` resolves. One last pair of snapshots
+/// is sent on the way out so metrics collected since the previous tick reach the stats service.
+pub async fn start_stats_polling_until<P, F>(
+    provider: P,
+    stats_addr: String,
+    poll_interval: Duration,
+    shutdown: F,
+) where
+    P: StatsSnapshotProvider + Send + 'static,
+    P::Snapshot: Send + 'static,
+    P::MetricsSnapshot: Send + 'static,
+    F: Future<Output = ()>,
+{
+    run_snapshot_loop_until(
+        stats_addr,
+        poll_interval,
+        move || std::future::ready(Some(provider.get_snapshots())),
+        shutdown,
+    )
+    .await
+}
+
+/// Shared send loop for providers that need custom access (e.g. behind a lock)
+/// `collect` resolves to `None` to skip a tick
+pub async fn run_snapshot_loop<S, M, F, Fut>(
+    stats_addr: String,
+    poll_interval: Duration,
+    collect: F,
+) where
+    S: Serialize,
+    M: Serialize,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Option<(S, M)>>,
+{
+    run_snapshot_loop_until(stats_addr, poll_interval, collect, std::future::pending()).await
+}
+
+/// [`run_snapshot_loop`] that returns after a final send once `shutdown` resolves
+pub async fn run_snapshot_loop_until<S, M, F, Fut, Shutdown>(
+    stats_addr: String,
     poll_interval: Duration,
+    mut collect: F,
+    shutdown: Shutdown,
 ) where
-    T: StatsSnapshotProvider + Send + 'static,
-    T::Snapshot: Send + 'static,
+    S: Serialize,
+    M: Serialize,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Option<(S, M)>>,
+    Shutdown: Future<Output = ()>,
 {
+    let status_client = StatsClient::<S>::new(stats_addr.clone());
+    let metrics_client = StatsClient::<M>::new(stats_addr);
     let mut interval = tokio::time::interval(poll_interval);
+    tokio::pin!(shutdown);
 
     loop {
-        interval.tick().await;
-
-        // Get snapshot via trait - no SRI coupling here
-        let snapshot = {
-            let guard = provider.lock().await;
-            guard.get_snapshot()
+        let stopping = tokio::select! {
+            _ = interval.tick() => false,
+            _ = &mut shutdown => true,
         };
 
-        debug!("Collected stats snapshot, sending to stats service");
+        // Collect before sending so no provider lock is held across an await
+        if let Some((status, metrics)) = collect().await {
+            debug!("Collected stats snapshots, sending to stats service");
 
-        // Send to stats service
-        if let Err(e) = client.send_snapshot(snapshot).await {
-            error!("Failed to send stats snapshot: {}", e);
-            // Continue polling even if send fails
+            // Continue polling even if a send fails
+            if let Err(e) = status_client.send_snapshot(status).await {
+                error!("Failed to send stats snapshot: {}", e);
+            }
+            if let Err(e) = metrics_client.send_snapshot(metrics).await {
+                error!("Failed to send metrics snapshot: {}", e);
+            }
         }
+
+        if stopping {
+            debug!("Stats polling stopped after final snapshot");
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use tokio::{
+        io::{AsyncBufReadExt, BufReader},
+        net::{TcpListener, TcpStream},
+    };
+
+    #[derive(Serialize, Deserialize)]
+    struct Status {
+        kind: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Metrics {
+        kind: String,
+    }
+
+    struct MockProvider;
+
+    impl StatsSnapshotProvider for MockProvider {
+        type Snapshot = Status;
+        type MetricsSnapshot = Metrics;
+
+        fn get_snapshot(&self) -> Status {
+            Status { kind: "status".to_string() }
+        }
+
+        fn get_metrics_snapshot(&self) -> Metrics {
+            Metrics { kind: "metrics".to_string() }
+        }
+    }
+
+    async fn read_lines(socket: TcpStream, count: usize) -> Vec<String> {
+        let mut lines = BufReader::new(socket).lines();
+        let mut received = Vec::new();
+        while received.len() < count {
+            received.push(lines.next_line().await.unwrap().unwrap());
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn test_polling_sends_both_snapshots_each_tick() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let poller = tokio::spawn(start_stats_polling(
+            MockProvider,
+            addr.to_string(),
+            Duration::from_millis(20),
+        ));
+
+        // Each client holds its own connection; collect two ticks from each
+        let (first, _) = listener.accept().await.unwrap();
+        let (second, _) = listener.accept().await.unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(5), async {
+            let (a, b) = tokio::join!(read_lines(first, 2), read_lines(second, 2));
+            a.into_iter().chain(b).collect::<Vec<_>>()
+        })
+        .await
+        .expect("snapshots not received in time");
+        poller.abort();
+
+        let count = |kind: &str| received.iter().filter(|l| l.contains(kind)).count();
+        assert_eq!(count("\"status\""), 2);
+        assert_eq!(count("\"metrics\""), 2);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_sends_final_snapshots() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+
+        // The interval is long enough that only the first tick and the final flush send
+        let poller = tokio::spawn(start_stats_polling_until(
+            MockProvider,
+            addr.to_string(),
+            Duration::from_secs(3600),
+            async {
+                let _ = shutdown_rx.await;
+            },
+        ));
+
+        let (first, _) = listener.accept().await.unwrap();
+        let (second, _) = listener.accept().await.unwrap();
+        let readers = tokio::spawn(async move {
+            let (a, b) = tokio::join!(read_lines(first, 2), read_lines(second, 2));
+            a.into_iter().chain(b).collect::<Vec<_>>()
+        });
+
+        shutdown_tx.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), poller)
+            .await
+            .expect("poller did not stop on shutdown")
+            .unwrap();
+        let received = tokio::time::timeout(Duration::from_secs(5), readers)
+            .await
+            .expect("final snapshots not received in time")
+            .unwrap();
+
+        let count = |kind: &str| received.iter().filter(|l| l.contains(kind)).count();
+        assert_eq!(count("\"status\""), 2);
+        assert_eq!(count("\"metrics\""), 2);
     }
 }
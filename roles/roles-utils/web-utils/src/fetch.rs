@@ -0,0 +1,143 @@
+//! Size-limited JSON decoding for snapshot polling.
+//!
+//! The web dashboards poll their stats service every few seconds. Decoding a response with
+//! `Response::json` buffers the whole body first, so a misbehaving endpoint could make a
+//! poller allocate without bound. [`read_json_limited`] refuses bodies over a byte limit,
+//! both up front via `Content-Length` and while streaming chunks for responses without one.
+
+use serde::de::DeserializeOwned;
+
+/// Default cap on a snapshot response body
+pub const DEFAULT_MAX_SNAPSHOT_BYTES: usize = 8 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// The body exceeded the configured limit
+    TooLarge {
+        limit: usize,
+    },
+    Http(reqwest::Error),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::TooLarge { limit } => {
+                write!(f, "response body exceeds {} bytes", limit)
+            }
+            FetchError::Http(e) => write!(f, "HTTP error: {}", e),
+            FetchError::Json(e) => write!(f, "invalid JSON: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(e: reqwest::Error) -> Self {
+        FetchError::Http(e)
+    }
+}
+
+/// Decodes a JSON response body, failing without reading further once it passes `max_bytes`.
+pub async fn read_json_limited<T: DeserializeOwned>(
+    mut response: reqwest::Response,
+    max_bytes: usize,
+) -> Result<T, FetchError> {
+    let too_large = FetchError::TooLarge { limit: max_bytes };
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_bytes as u64)
+    {
+        return Err(too_large);
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(too_large);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    serde_json::from_slice(&body).map_err(FetchError::Json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// Serves `response` (raw HTTP) to every connection, returning the base URL
+    async fn mock_server(response: Vec<u8>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let response = response.clone();
+                tokio::spawn(async move {
+                    let mut request = [0u8; 1024];
+                    let _ = socket.read(&mut request).await;
+                    let _ = socket.write_all(&response).await;
+                });
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    fn json_response(body: &str) -> Vec<u8> {
+        format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .into_bytes()
+    }
+
+    /// A chunked response with no `Content-Length` and `chunks` chunks of 64 KiB
+    fn chunked_response(chunks: usize) -> Vec<u8> {
+        let mut response =
+            b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\nconnection: close\r\n\r\n".to_vec();
+        let chunk = vec![b' '; 64 * 1024];
+        for _ in 0..chunks {
+            response.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+            response.extend_from_slice(&chunk);
+            response.extend_from_slice(b"\r\n");
+        }
+        response.extend_from_slice(b"0\r\n\r\n");
+        response
+    }
+
+    async fn fetch(url: &str, max_bytes: usize) -> Result<serde_json::Value, FetchError> {
+        let response = reqwest::get(url).await.unwrap();
+        read_json_limited(response, max_bytes).await
+    }
+
+    #[tokio::test]
+    async fn test_small_body_is_decoded() {
+        let url = mock_server(json_response(r#"{"timestamp":1}"#)).await;
+        let value = fetch(&url, 1024).await.unwrap();
+        assert_eq!(value["timestamp"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_content_length_is_rejected() {
+        let url = mock_server(json_response(&format!("[{}]", "1,".repeat(2048) + "1"))).await;
+        assert!(matches!(
+            fetch(&url, 1024).await,
+            Err(FetchError::TooLarge { limit: 1024 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_chunked_body_is_rejected() {
+        let url = mock_server(chunked_response(64)).await;
+        assert!(matches!(
+            fetch(&url, 256 * 1024).await,
+            Err(FetchError::TooLarge { .. })
+        ));
+    }
+}
@@ -1,3 +1,7 @@
+use serde::Deserialize;
+
+pub mod fetch;
+
 /// Format elapsed time in human-readable format
 /// Used in both pool and proxy web dashboards
 pub fn format_elapsed_time(now: u64, timestamp: u64) -> String {
@@ -28,6 +32,42 @@ pub fn format_hashrate(hashrate: f64) -> String {
     }
 }
 
+/// How share difficulty is shown on the dashboards
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DifficultyDisplay {
+    /// As a power of two, e.g. `2^16`, the unit of `minimum_share_difficulty_bits`
+    #[default]
+    Bits,
+    /// As a number with a unit suffix, e.g. `65.5K`
+    Numeric,
+}
+
+/// Format a share difficulty in the chosen representation
+pub fn format_difficulty(difficulty: f64, display: DifficultyDisplay) -> String {
+    if !difficulty.is_finite() || difficulty <= 0.0 {
+        return "0".to_string();
+    }
+    match display {
+        DifficultyDisplay::Bits => {
+            let bits = difficulty.log2();
+            if (bits - bits.round()).abs() < 0.005 {
+                format!("2^{}", bits.round())
+            } else {
+                format!("2^{:.2}", bits)
+            }
+        }
+        DifficultyDisplay::Numeric => {
+            const UNITS: &[(f64, &str)] =
+                &[(1e15, "P"), (1e12, "T"), (1e9, "G"), (1e6, "M"), (1e3, "K")];
+            match UNITS.iter().find(|(scale, _)| difficulty >= *scale) {
+                Some((scale, unit)) => format!("{:.1}{}", difficulty / scale, unit),
+                None => format!("{:.1}", difficulty),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,4 +96,30 @@ mod tests {
     fn test_format_hashrate_th() {
         assert_eq!(format_hashrate(2_000_000_000_000.0), "2.0 TH/s");
     }
+
+    #[test]
+    fn test_format_difficulty_bits() {
+        assert_eq!(format_difficulty(65_536.0, DifficultyDisplay::Bits), "2^16");
+        assert_eq!(
+            format_difficulty(100_000.0, DifficultyDisplay::Bits),
+            "2^16.61"
+        );
+        assert_eq!(format_difficulty(0.0, DifficultyDisplay::Bits), "0");
+    }
+
+    #[test]
+    fn test_format_difficulty_numeric() {
+        assert_eq!(
+            format_difficulty(65_536.0, DifficultyDisplay::Numeric),
+            "65.5K"
+        );
+        assert_eq!(
+            format_difficulty(2_500_000_000.0, DifficultyDisplay::Numeric),
+            "2.5G"
+        );
+        assert_eq!(
+            format_difficulty(512.0, DifficultyDisplay::Numeric),
+            "512.0"
+        );
+    }
 }
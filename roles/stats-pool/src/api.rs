@@ -5,11 +5,12 @@ use hyper::{
 };
 use hyper_util::rt::TokioIo;
 use serde_json::json;
-use std::{convert::Infallible, sync::Arc};
+use std::{convert::Infallible, fmt::Write, str::FromStr, sync::Arc};
 use tokio::net::TcpListener;
 use tracing::{error, info};
 
-use stats_pool::db::StatsData;
+use stats_pool::{db::StatsData, prometheus};
+use stats_sv2::types::unix_timestamp;
 
 pub async fn run_http_server(
     address: String,
@@ -47,11 +48,23 @@ async fn handle_request(
     let path = req.uri().path().to_string();
     let query = req.uri().query().unwrap_or("");
 
-    let response = match (req.method(), path.as_str()) {
+    Ok(route(req.method(), &path, query, stats).await)
+}
+
+async fn route(
+    method: &Method,
+    path: &str,
+    query: &str,
+    stats: Arc<StatsData>,
+) -> Response<Full<Bytes>> {
+    match (method, path) {
         (&Method::GET, "/api/stats") => serve_stats_json(stats.clone()).await,
         (&Method::GET, "/api/services") => serve_services_json(stats.clone()).await,
         (&Method::GET, "/api/connections") => serve_connections_json(stats.clone()).await,
+        (&Method::GET, "/api/share-events") => serve_share_events_json(stats.clone()),
         (&Method::GET, "/health") => serve_health(stats).await,
+        (&Method::GET, "/metrics") => serve_metrics(stats).await,
+        (&Method::GET, "/api/hashrate.csv") => serve_hashrate_csv(stats, query).await,
         (&Method::GET, path) if path.starts_with("/api/downstream/") && path.contains("/hashrate") => {
             let downstream_id_str = path
                 .trim_start_matches("/api/downstream/")
@@ -84,9 +97,7 @@ async fn handle_request(
             *response.status_mut() = StatusCode::NOT_FOUND;
             response
         }
-    };
-
-    Ok(response)
+    }
 }
 
 async fn serve_stats_json(stats: Arc<StatsData>) -> Response<Full<Bytes>> {
@@ -145,6 +156,17 @@ async fn serve_connections_json(stats: Arc<StatsData>) -> Response<Full<Bytes>>
     }
 }
 
+/// Per-share event totals for each downstream; empty unless the pool emits share events
+fn serve_share_events_json(stats: Arc<StatsData>) -> Response<Full<Bytes>> {
+    let json =
+        serde_json::to_string(&stats.share_event_totals()).unwrap_or_else(|_| "[]".to_string());
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/json")
+        .body(Full::new(Bytes::from(json)))
+        .unwrap()
+}
+
 async fn serve_health(stats: Arc<StatsData>) -> Response<Full<Bytes>> {
     let stale = stats.is_stale(15);
     let status_code = if stale {
@@ -163,6 +185,90 @@ async fn serve_health(stats: Arc<StatsData>) -> Response<Full<Bytes>> {
         .unwrap()
 }
 
+async fn serve_metrics(stats: Arc<StatsData>) -> Response<Full<Bytes>> {
+    match stats.get_latest_snapshot() {
+        Some(snapshot) => Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", prometheus::CONTENT_TYPE)
+            .body(Full::new(Bytes::from(prometheus::render(
+                &snapshot,
+                unix_timestamp(),
+            ))))
+            .unwrap(),
+        None => Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .header("Content-Type", prometheus::CONTENT_TYPE)
+            .body(Full::new(Bytes::new()))
+            .unwrap(),
+    }
+}
+
+/// Hashrate history as `timestamp,hashrate_hs` CSV rows, for the downstream given by
+/// `downstream_id` or summed over all downstreams when it is omitted
+async fn serve_hashrate_csv(stats: Arc<StatsData>, query: &str) -> Response<Full<Bytes>> {
+    let Ok(from) = parsed_query_param::<u64>(query, "from") else {
+        return bad_request("Invalid from timestamp");
+    };
+    let Ok(to) = parsed_query_param::<u64>(query, "to") else {
+        return bad_request("Invalid to timestamp");
+    };
+    let (from, to) = (from.unwrap_or(0), to.unwrap_or_else(unix_timestamp));
+    if from >= to {
+        return bad_request("from must be before to");
+    }
+    let Ok(downstream_id) = parsed_query_param::<u32>(query, "downstream_id") else {
+        return bad_request("Invalid downstream ID");
+    };
+
+    let points = match downstream_id {
+        Some(downstream_id) => stats.query_hashrate(downstream_id, from, to).await,
+        None => stats.query_aggregate_hashrate(from, to).await,
+    };
+    match points {
+        Ok(points) => {
+            let mut csv = String::from("timestamp,hashrate_hs\n");
+            for point in points {
+                // Writing to a String cannot fail
+                let _ = writeln!(csv, "{},{}", point.timestamp, point.hashrate_hs);
+            }
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "text/csv")
+                .body(Full::new(Bytes::from(csv)))
+                .unwrap()
+        }
+        Err(e) => {
+            error!("Error exporting hashrate: {}", e);
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Full::new(Bytes::from("Failed to query hashrate")))
+                .unwrap()
+        }
+    }
+}
+
+fn bad_request(message: &'static str) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .body(Full::new(Bytes::from(message)))
+        .unwrap()
+}
+
+/// Value of `key` in a query string, `None` when it is missing or empty
+fn query_param<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty())
+}
+
+/// [`query_param`] parsed as a `T`; an error if the value is present but not a valid `T`
+fn parsed_query_param<T: FromStr>(query: &str, key: &str) -> Result<Option<T>, T::Err> {
+    query_param(query, key).map(str::parse).transpose()
+}
+
 /// Parse query parameters to extract timestamp range
 fn parse_timestamp_range(query: &str) -> (u64, u64) {
     let mut from = 0u64;
@@ -240,3 +346,121 @@ async fn query_aggregate_hashrate(stats: Arc<StatsData>, query: &str) -> String
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+    use stats::stats_adapter::ShareSubmitted;
+    use stats_sv2::types::{DownstreamSnapshot, ServiceSnapshot, ServiceType};
+
+    async fn get(stats: &Arc<StatsData>, path: &str, query: &str) -> (StatusCode, String) {
+        let response = route(&Method::GET, path, query, stats.clone()).await;
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_share_events_route() {
+        let stats = Arc::new(StatsData::new());
+        let (status, body) = get(&stats, "/api/share-events", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "[]");
+
+        stats.record_share_event(&ShareSubmitted {
+            downstream_id: 4,
+            channel_id: 40,
+            difficulty: 256.0,
+            timestamp: 1_700_000_000,
+        });
+        let (status, body) = get(&stats, "/api/share-events", "").await;
+        assert_eq!(status, StatusCode::OK);
+        let totals: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            totals,
+            json!([{
+                "downstream_id": 4,
+                "shares": 1,
+                "sum_difficulty": 256.0,
+                "last_share_at": 1_700_000_000u64,
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_hashrate_csv_export() {
+        let stats = Arc::new(StatsData::new());
+        stats
+            .init_metrics_storage(Some(":memory:"), None)
+            .await
+            .unwrap();
+
+        // Two downstreams reporting a minute apart, one bucket per minute
+        let start = 1_700_000_040;
+        for i in 0..3 {
+            let timestamp = start + i * 60;
+            let downstreams = (1..=2)
+                .map(|id| DownstreamSnapshot {
+                    downstream_id: id,
+                    name: format!("miner_{}", id),
+                    address: "127.0.0.1:3333".to_string(),
+                    user_agent: "test".to_string(),
+                    shares_lifetime: i,
+                    shares_in_window: 1,
+                    sum_difficulty_in_window: 1.0,
+                    window_seconds: 60,
+                    timestamp,
+                })
+                .collect();
+            stats
+                .store_metrics_snapshot(ServiceSnapshot {
+                    service_type: ServiceType::Pool,
+                    downstreams,
+                    timestamp,
+                })
+                .await
+                .unwrap();
+        }
+        let range = format!("from={}&to={}", start, start + 120);
+
+        let (status, csv) = get(
+            &stats,
+            "/api/hashrate.csv",
+            &format!("downstream_id=1&{range}"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("timestamp,hashrate_hs"));
+        assert_eq!(lines.count(), 3);
+
+        // An empty downstream_id asks for the aggregate
+        let (status, csv) = get(
+            &stats,
+            "/api/hashrate.csv",
+            &format!("downstream_id=&{range}"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(csv.starts_with("timestamp,hashrate_hs\n"));
+        assert_eq!(csv.lines().count(), 4);
+
+        let (status, _) = get(
+            &stats,
+            "/api/hashrate.csv",
+            &format!("from={}&to={}", start, start),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // A malformed bound is rejected rather than read as the default
+        for query in [
+            format!("from=yesterday&to={}", start + 120),
+            format!("from={}&to=-1", start),
+        ] {
+            let (status, _) = get(&stats, "/api/hashrate.csv", &query).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{query}");
+        }
+    }
+}
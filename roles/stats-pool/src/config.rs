@@ -1,14 +1,24 @@
 use serde::Deserialize;
-use std::{env, fs};
+use shared_config::RoleConfig;
+use stats_pool::stats_handler::{DEFAULT_INGEST_QUEUE_SIZE, DEFAULT_INGEST_WORKERS};
+use stats_sv2::{RollupConfig, WarmCacheConfig};
+use std::env;
 
 #[derive(Debug, Clone)]
 pub struct Config {
     pub tcp_address: String,
     pub http_address: String,
     pub staleness_threshold_secs: u64,
+    pub max_message_age_secs: Option<u64>,
+    pub max_window_skew_secs: Option<u64>,
+    pub tcp_read_timeout_secs: u64,
+    pub ingest_workers: usize,
+    pub ingest_queue_size: usize,
     pub request_timeout_secs: u64,
     pub pool_idle_timeout_secs: u64,
     pub metrics_db_path: String,
+    pub warm_cache: Option<WarmCacheConfig>,
+    pub rollup: Option<RollupConfig>,
     pub log_file: Option<String>,
 }
 
@@ -23,9 +33,16 @@ struct StatsPoolConfig {
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct ServerConfig {
     tcp_listen_address: Option<String>,
     http_listen_address: Option<String>,
+    /// Close a snapshot connection after this many seconds without data (0 disables)
+    tcp_read_timeout_secs: Option<u64>,
+    /// Tasks writing received snapshots to the database; 1 stores them in arrival order
+    ingest_workers: Option<usize>,
+    /// Snapshots queued for the writers before connection readers wait
+    ingest_queue_size: Option<usize>,
 }
 
 impl Default for ServerConfig {
@@ -33,24 +50,58 @@ impl Default for ServerConfig {
         Self {
             tcp_listen_address: Some("127.0.0.1:9083".to_string()),
             http_listen_address: Some("127.0.0.1:9084".to_string()),
+            tcp_read_timeout_secs: Some(DEFAULT_TCP_READ_TIMEOUT_SECS),
+            ingest_workers: Some(DEFAULT_INGEST_WORKERS),
+            ingest_queue_size: Some(DEFAULT_INGEST_QUEUE_SIZE),
         }
     }
 }
 
+/// Default time a snapshot connection may stay silent before it is closed
+const DEFAULT_TCP_READ_TIMEOUT_SECS: u64 = 60;
+
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct SnapshotStorageConfig {
     staleness_threshold_secs: Option<u64>,
+    /// Snapshots older than this many seconds are dropped at ingest (disabled if unset)
+    max_message_age_secs: Option<u64>,
+    /// Warn when a downstream's metrics samples are spaced more than this many seconds beyond
+    /// the window they report (disabled if unset)
+    max_window_skew_secs: Option<u64>,
+    /// Preload this many seconds of recent metrics samples into memory at startup (off if unset)
+    warm_cache_secs: Option<u64>,
+    warm_cache_max_samples: Option<usize>,
+    /// Roll hashrate samples older than this many seconds up into coarser buckets (off if unset)
+    rollup_after_secs: Option<u64>,
+    rollup_bucket_secs: Option<u64>,
+    /// Delete rolled-up buckets older than this many seconds (kept forever if unset)
+    rollup_retention_secs: Option<u64>,
 }
 
 impl Default for SnapshotStorageConfig {
     fn default() -> Self {
         Self {
             staleness_threshold_secs: Some(15),
+            max_message_age_secs: None,
+            max_window_skew_secs: None,
+            warm_cache_secs: None,
+            warm_cache_max_samples: None,
+            rollup_after_secs: None,
+            rollup_bucket_secs: None,
+            rollup_retention_secs: None,
         }
     }
 }
 
+/// Default upper bound on samples held by the warm metrics cache
+const DEFAULT_WARM_CACHE_MAX_SAMPLES: usize = 50_000;
+
+/// Default width of a rolled-up hashrate bucket
+const DEFAULT_ROLLUP_BUCKET_SECS: u64 = 3600;
+
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct HttpClientConfig {
     pool_idle_timeout_secs: Option<u64>,
     request_timeout_secs: Option<u64>,
@@ -65,6 +116,80 @@ impl Default for HttpClientConfig {
     }
 }
 
+impl RoleConfig for StatsPoolConfig {
+    const SERVICE: &'static str = "stats-pool";
+    const ENV_PREFIX: &'static str = "HASHPOOL_STATS_POOL";
+
+    fn validate(&self) -> Result<(), String> {
+        let storage = &self.snapshot_storage;
+        if storage.rollup_after_secs.is_some() && storage.rollup_bucket_secs == Some(0) {
+            return Err("snapshot_storage.rollup_bucket_secs must be greater than 0".to_string());
+        }
+        if self.server.ingest_workers == Some(0) {
+            return Err("server.ingest_workers must be greater than 0".to_string());
+        }
+        if self.server.ingest_queue_size == Some(0) {
+            return Err("server.ingest_queue_size must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    fn effective_config(&self) -> Vec<(&'static str, String)> {
+        fn opt<T: ToString>(value: Option<&T>) -> String {
+            value.map_or_else(|| "none".to_string(), |v| v.to_string())
+        }
+        let server = &self.server;
+        let storage = &self.snapshot_storage;
+        vec![
+            (
+                "server.tcp_listen_address",
+                opt(server.tcp_listen_address.as_ref()),
+            ),
+            (
+                "server.http_listen_address",
+                opt(server.http_listen_address.as_ref()),
+            ),
+            (
+                "server.tcp_read_timeout_secs",
+                opt(server.tcp_read_timeout_secs.as_ref()),
+            ),
+            ("server.ingest_workers", opt(server.ingest_workers.as_ref())),
+            (
+                "server.ingest_queue_size",
+                opt(server.ingest_queue_size.as_ref()),
+            ),
+            (
+                "snapshot_storage.staleness_threshold_secs",
+                opt(storage.staleness_threshold_secs.as_ref()),
+            ),
+            (
+                "snapshot_storage.max_message_age_secs",
+                opt(storage.max_message_age_secs.as_ref()),
+            ),
+            (
+                "snapshot_storage.max_window_skew_secs",
+                opt(storage.max_window_skew_secs.as_ref()),
+            ),
+            (
+                "snapshot_storage.warm_cache_secs",
+                opt(storage.warm_cache_secs.as_ref()),
+            ),
+            (
+                "snapshot_storage.rollup_after_secs",
+                opt(storage.rollup_after_secs.as_ref()),
+            ),
+            (
+                "snapshot_storage.rollup_retention_secs",
+                opt(storage.rollup_retention_secs.as_ref()),
+            ),
+            (
+                "http_client.request_timeout_secs",
+                opt(self.http_client.request_timeout_secs.as_ref()),
+            ),
+        ]
+    }
+}
+
 impl Config {
     pub fn from_args() -> Result<Self, Box<dyn std::error::Error>> {
         let args: Vec<String> = env::args().collect();
@@ -84,16 +209,7 @@ impl Config {
             .map(|s| s.as_str())
             .ok_or("Missing required argument: --config")?;
 
-        let stats_pool_config_str = fs::read_to_string(stats_pool_config_path).unwrap_or_default();
-        let stats_pool_config: StatsPoolConfig = if stats_pool_config_str.is_empty() {
-            StatsPoolConfig {
-                server: ServerConfig::default(),
-                snapshot_storage: SnapshotStorageConfig::default(),
-                http_client: HttpClientConfig::default(),
-            }
-        } else {
-            toml::from_str(&stats_pool_config_str)?
-        };
+        let stats_pool_config = StatsPoolConfig::from_optional_path(stats_pool_config_path)?;
 
         // TCP and HTTP addresses from config file, with CLI overrides
         let tcp_address = args
@@ -119,6 +235,34 @@ impl Config {
             .cloned()
             .ok_or("Missing required argument: --metrics-db-path")?;
 
+        let snapshot_storage = &stats_pool_config.snapshot_storage;
+        let warm_cache = snapshot_storage
+            .warm_cache_secs
+            .map(|window_secs| WarmCacheConfig {
+                window_secs,
+                max_samples: snapshot_storage
+                    .warm_cache_max_samples
+                    .unwrap_or(DEFAULT_WARM_CACHE_MAX_SAMPLES),
+            });
+        let rollup = snapshot_storage
+            .rollup_after_secs
+            .map(|rollup_after_secs| RollupConfig {
+                rollup_after_secs,
+                bucket_secs: snapshot_storage
+                    .rollup_bucket_secs
+                    .unwrap_or(DEFAULT_ROLLUP_BUCKET_SECS),
+                retention_secs: snapshot_storage.rollup_retention_secs,
+            });
+
+        let ingest_workers = stats_pool_config
+            .server
+            .ingest_workers
+            .unwrap_or(DEFAULT_INGEST_WORKERS);
+        let ingest_queue_size = stats_pool_config
+            .server
+            .ingest_queue_size
+            .unwrap_or(DEFAULT_INGEST_QUEUE_SIZE);
+
         Ok(Config {
             tcp_address,
             http_address,
@@ -126,6 +270,14 @@ impl Config {
                 .snapshot_storage
                 .staleness_threshold_secs
                 .unwrap_or(15),
+            max_message_age_secs: stats_pool_config.snapshot_storage.max_message_age_secs,
+            max_window_skew_secs: stats_pool_config.snapshot_storage.max_window_skew_secs,
+            tcp_read_timeout_secs: stats_pool_config
+                .server
+                .tcp_read_timeout_secs
+                .unwrap_or(DEFAULT_TCP_READ_TIMEOUT_SECS),
+            ingest_workers,
+            ingest_queue_size,
             request_timeout_secs: stats_pool_config
                 .http_client
                 .request_timeout_secs
@@ -135,6 +287,8 @@ impl Config {
                 .pool_idle_timeout_secs
                 .unwrap_or(300),
             metrics_db_path,
+            warm_cache,
+            rollup,
             log_file,
         })
     }
@@ -150,9 +304,19 @@ mod tests {
             [server]
             tcp_listen_address = "127.0.0.1:5555"
             http_listen_address = "127.0.0.1:6666"
+            tcp_read_timeout_secs = 30
+            ingest_workers = 2
+            ingest_queue_size = 256
 
             [snapshot_storage]
             staleness_threshold_secs = 20
+            max_message_age_secs = 60
+            max_window_skew_secs = 30
+            warm_cache_secs = 3600
+            warm_cache_max_samples = 1000
+            rollup_after_secs = 604800
+            rollup_bucket_secs = 900
+            rollup_retention_secs = 31536000
 
             [http_client]
             pool_idle_timeout_secs = 400
@@ -167,8 +331,52 @@ mod tests {
             config.server.http_listen_address,
             Some("127.0.0.1:6666".to_string())
         );
+        assert_eq!(config.server.tcp_read_timeout_secs, Some(30));
+        assert_eq!(config.server.ingest_workers, Some(2));
+        assert_eq!(config.server.ingest_queue_size, Some(256));
         assert_eq!(config.snapshot_storage.staleness_threshold_secs, Some(20));
+        assert_eq!(config.snapshot_storage.max_message_age_secs, Some(60));
+        assert_eq!(config.snapshot_storage.max_window_skew_secs, Some(30));
+        assert_eq!(config.snapshot_storage.warm_cache_secs, Some(3600));
+        assert_eq!(config.snapshot_storage.warm_cache_max_samples, Some(1000));
+        assert_eq!(config.snapshot_storage.rollup_after_secs, Some(604800));
+        assert_eq!(config.snapshot_storage.rollup_bucket_secs, Some(900));
+        assert_eq!(
+            config.snapshot_storage.rollup_retention_secs,
+            Some(31536000)
+        );
         assert_eq!(config.http_client.pool_idle_timeout_secs, Some(400));
         assert_eq!(config.http_client.request_timeout_secs, Some(80));
     }
+
+    #[test]
+    fn test_role_config_loads_with_own_env_prefix() {
+        let path = std::env::temp_dir().join(format!("stats-pool-{}.toml", std::process::id()));
+        std::fs::write(&path, "[server]\ningest_workers = 2\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        std::env::set_var(
+            "HASHPOOL_STATS_POOL_SNAPSHOT_STORAGE__MAX_MESSAGE_AGE_SECS",
+            "90",
+        );
+        std::env::set_var("HASHPOOL_SERVER__INGEST_WORKERS", "0");
+        let config = StatsPoolConfig::from_path(path);
+        std::env::set_var("HASHPOOL_STATS_POOL_SERVER__INGEST_QUEUE_SIZE", "0");
+        let invalid = StatsPoolConfig::from_optional_path("/nonexistent/stats-pool.toml");
+        std::env::remove_var("HASHPOOL_STATS_POOL_SNAPSHOT_STORAGE__MAX_MESSAGE_AGE_SECS");
+        std::env::remove_var("HASHPOOL_STATS_POOL_SERVER__INGEST_QUEUE_SIZE");
+        std::env::remove_var("HASHPOOL_SERVER__INGEST_WORKERS");
+        std::fs::remove_file(path).unwrap();
+
+        let config = config.unwrap();
+        let entries = config.effective_config();
+        let value = |key| entries.iter().find(|(k, _)| *k == key).unwrap().1.as_str();
+        // Only this service's prefix applies
+        assert_eq!(value("server.ingest_workers"), "2");
+        assert_eq!(value("snapshot_storage.max_message_age_secs"), "90");
+        assert_eq!(value("http_client.request_timeout_secs"), "60");
+
+        let err = invalid.unwrap_err().to_string();
+        assert!(err.contains("server.ingest_queue_size"), "{err}");
+    }
 }
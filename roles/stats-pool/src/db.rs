@@ -1,12 +1,25 @@
 use std::{
+    collections::HashMap,
     sync::{Arc, RwLock},
     time::{SystemTime, UNIX_EPOCH},
 };
 
-use stats::stats_adapter::{JdsSnapshot, PoolSnapshot, ServiceConnection, ServiceType};
+use serde::Serialize;
+use stats::stats_adapter::{
+    JdsSnapshot, PoolSnapshot, ServiceConnection, ServiceType, ShareSubmitted,
+};
 use stats_sv2::types::ServiceSnapshot;
 use stats_sv2::StatsStorage;
 
+/// Totals of the `ShareSubmitted` events received for one downstream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ShareEventTotals {
+    pub downstream_id: u32,
+    pub shares: u64,
+    pub sum_difficulty: f64,
+    pub last_share_at: u64,
+}
+
 /// In-memory storage for the latest pool and JDS snapshots.
 ///
 /// The pool and JDS emit complete snapshots on every heartbeat. We merge them
@@ -14,6 +27,8 @@ use stats_sv2::StatsStorage;
 pub struct StatsData {
     pool_snapshot: RwLock<Option<PoolSnapshot>>,
     jds_snapshot: RwLock<Option<JdsSnapshot>>,
+    // Per-share events received for each downstream, when the pool sends them
+    share_events: RwLock<HashMap<u32, ShareEventTotals>>,
     // Time-series metrics storage
     metrics_storage: Arc<tokio::sync::RwLock<Option<stats_sv2::storage::SqliteStorage>>>,
 }
@@ -23,14 +38,22 @@ impl StatsData {
         Self {
             pool_snapshot: RwLock::new(None),
             jds_snapshot: RwLock::new(None),
+            share_events: RwLock::new(HashMap::new()),
             metrics_storage: Arc::new(tokio::sync::RwLock::new(None)),
         }
     }
 
-    /// Initialize metrics storage with database path
-    pub async fn init_metrics_storage(&self, db_path: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    /// Initialize metrics storage with database path, optionally preloading recent samples
+    pub async fn init_metrics_storage(
+        &self,
+        db_path: Option<&str>,
+        warm_cache: Option<stats_sv2::WarmCacheConfig>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         let path = db_path.ok_or("Database path is required for metrics storage")?;
-        let storage = stats_sv2::storage::SqliteStorage::new(path).await?;
+        let mut storage = stats_sv2::storage::SqliteStorage::new(path).await?;
+        if let Some(warm_cache) = warm_cache {
+            storage = storage.with_warm_cache(warm_cache).await?;
+        }
         let mut guard = self.metrics_storage.write().await;
         *guard = Some(storage);
         Ok(())
@@ -76,12 +99,58 @@ impl StatsData {
         }
     }
 
-    /// Replace the currently stored pool snapshot with a new one.
+    /// Roll old hashrate samples up into coarser buckets, returning how many were rolled up
+    pub async fn rollup_metrics(
+        &self,
+        config: &stats_sv2::RollupConfig,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let guard = self.metrics_storage.read().await;
+        if let Some(storage) = guard.as_ref() {
+            Ok(storage.rollup(config).await?)
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Replace the currently stored pool snapshot with a new one. Share event totals of
+    /// downstreams missing from it are dropped, unless they saw a share after it was taken.
     pub fn store_snapshot(&self, snapshot: PoolSnapshot) {
+        let mut share_events = self.share_events.write().unwrap();
+        share_events.retain(|downstream_id, totals| {
+            totals.last_share_at >= snapshot.timestamp
+                || snapshot
+                    .downstream_proxies
+                    .iter()
+                    .any(|proxy| proxy.id == *downstream_id)
+        });
+        drop(share_events);
         let mut guard = self.pool_snapshot.write().unwrap();
         *guard = Some(snapshot);
     }
 
+    /// Count a per-share event towards its downstream's totals.
+    pub fn record_share_event(&self, event: &ShareSubmitted) {
+        let mut share_events = self.share_events.write().unwrap();
+        let totals = share_events
+            .entry(event.downstream_id)
+            .or_insert_with(|| ShareEventTotals {
+                downstream_id: event.downstream_id,
+                ..Default::default()
+            });
+        totals.shares += 1;
+        totals.sum_difficulty += event.difficulty;
+        totals.last_share_at = totals.last_share_at.max(event.timestamp);
+    }
+
+    /// Share event totals of every downstream, ordered by downstream id.
+    pub fn share_event_totals(&self) -> Vec<ShareEventTotals> {
+        let share_events = self.share_events.read().unwrap();
+        let mut totals: Vec<ShareEventTotals> = share_events.values().copied().collect();
+        drop(share_events);
+        totals.sort_by_key(|totals| totals.downstream_id);
+        totals
+    }
+
     /// Store the JDS snapshot.
     pub fn store_jds_snapshot(&self, snapshot: JdsSnapshot) {
         let mut guard = self.jds_snapshot.write().unwrap();
@@ -151,6 +220,11 @@ mod tests {
             }],
             downstream_proxies: vec![],
             listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: 1234567890,
         };
 
@@ -169,6 +243,11 @@ mod tests {
             services: vec![],
             downstream_proxies: vec![],
             listen_address: "first".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: unix_timestamp(),
         };
 
@@ -176,6 +255,11 @@ mod tests {
             services: vec![],
             downstream_proxies: vec![],
             listen_address: "second".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: unix_timestamp() + 5,
         };
 
@@ -201,6 +285,11 @@ mod tests {
             services: vec![],
             downstream_proxies: vec![],
             listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: unix_timestamp(),
         };
 
@@ -216,6 +305,11 @@ mod tests {
             services: vec![],
             downstream_proxies: vec![],
             listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: unix_timestamp() - 60,
         };
 
@@ -238,10 +332,21 @@ mod tests {
                 channels: vec![10, 11],
                 shares_submitted: 5,
                 quotes_created: 2,
+                quotes_failed: 0,
                 ehash_mined: 50,
                 last_share_at: Some(unix_timestamp()),
+                work_selection: false,
+                quote_success_ratio: Some(1.0),
+                difficulty_histogram: Vec::new(),
+                reject_ratio: 0.0,
+                shares_per_minute: 0.0,
             }],
             listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: unix_timestamp(),
         };
 
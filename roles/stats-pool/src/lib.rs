@@ -1,2 +1,3 @@
 pub mod db;
+pub mod prometheus;
 pub mod stats_handler;
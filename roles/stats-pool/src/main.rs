@@ -1,7 +1,8 @@
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tokio::{
-    io::AsyncReadExt,
-    net::{TcpListener, TcpStream},
+    io::{AsyncRead, AsyncReadExt},
+    net::TcpListener,
+    sync::mpsc,
 };
 use tracing::{error, info};
 
@@ -9,7 +10,10 @@ mod api;
 mod config;
 
 use config::Config;
-use stats_pool::{db::StatsData, stats_handler::StatsHandler};
+use stats_pool::{
+    db::StatsData,
+    stats_handler::{spawn_ingest_workers, IngestMessage, StatsHandler},
+};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -40,12 +44,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let stats = Arc::new(StatsData::new());
 
     // Initialize metrics storage with SQLite backend
-    if let Err(e) = stats.init_metrics_storage(Some(&config.metrics_db_path)).await {
+    let metrics_storage = stats
+        .init_metrics_storage(Some(&config.metrics_db_path), config.warm_cache)
+        .await;
+    if let Err(e) = metrics_storage {
         error!("Failed to initialize metrics storage: {}", e);
     } else {
         info!("Metrics storage initialized at {}", config.metrics_db_path);
     }
 
+    // Periodically fold old samples into coarser buckets so the database stays bounded
+    if let Some(rollup) = config.rollup {
+        let stats_for_rollup = stats.clone();
+        tokio::spawn(async move {
+            let period = std::time::Duration::from_secs(rollup.bucket_secs);
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                if let Err(e) = stats_for_rollup.rollup_metrics(&rollup).await {
+                    error!("Failed to roll up hashrate samples: {}", e);
+                }
+            }
+        });
+    }
+
     let tcp_listener = TcpListener::bind(&config.tcp_address).await?;
     info!("TCP server listening on {}", config.tcp_address);
 
@@ -58,13 +80,27 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     });
 
+    let read_timeout = (config.tcp_read_timeout_secs > 0)
+        .then(|| Duration::from_secs(config.tcp_read_timeout_secs));
+
+    // Connections only read lines; the ingest workers own every database write
+    let handler = StatsHandler::new(stats.clone())
+        .with_max_message_age(config.max_message_age_secs)
+        .with_max_window_skew(config.max_window_skew_secs);
+    let ingest = spawn_ingest_workers(handler, config.ingest_workers, config.ingest_queue_size);
+    info!(
+        "Ingesting snapshots with {} worker(s), queue size {}",
+        config.ingest_workers, config.ingest_queue_size
+    );
+
     loop {
         match tcp_listener.accept().await {
             Ok((stream, addr)) => {
                 info!("New pool connection from {}", addr);
-                let stats_clone = stats.clone();
+                let ingest = ingest.clone();
                 tokio::spawn(async move {
-                    if let Err(e) = handle_pool_connection(stream, addr, stats_clone).await {
+                    if let Err(e) = handle_pool_connection(stream, addr, ingest, read_timeout).await
+                    {
                         error!("Error handling pool connection from {}: {}", addr, e);
                     }
                 });
@@ -77,16 +113,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 async fn handle_pool_connection(
-    mut stream: TcpStream,
+    mut stream: impl AsyncRead + Unpin,
     addr: SocketAddr,
-    stats: Arc<StatsData>,
+    ingest: mpsc::Sender<IngestMessage>,
+    read_timeout: Option<Duration>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let handler = StatsHandler::new(stats);
     let mut buffer = vec![0u8; 8192];
     let mut leftover = Vec::new();
 
     loop {
-        match stream.read(&mut buffer).await {
+        // Each read gets the full timeout, so only a connection that stays silent is dropped
+        let read = match read_timeout {
+            Some(limit) => match tokio::time::timeout(limit, stream.read(&mut buffer)).await {
+                Ok(read) => read,
+                Err(_) => {
+                    info!(
+                        "Closing pool connection from {}: no data for {:?}",
+                        addr, limit
+                    );
+                    break;
+                }
+            },
+            None => stream.read(&mut buffer).await,
+        };
+
+        match read {
             Ok(0) => {
                 info!("Pool connection from {} closed", addr);
                 break;
@@ -98,8 +149,13 @@ async fn handle_pool_connection(
                     let line = &leftover[..newline_pos];
 
                     if !line.is_empty() {
-                        if let Err(e) = handler.handle_message(line).await {
-                            error!("Error processing message from {}: {}", addr, e);
+                        // Waits while the queue is full, so a fast sender can't outrun the writers
+                        let message = IngestMessage {
+                            addr,
+                            line: line.to_vec(),
+                        };
+                        if ingest.send(message).await.is_err() {
+                            return Err("ingest workers stopped".into());
                         }
                     }
 
@@ -115,3 +171,136 @@ async fn handle_pool_connection(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stats_sv2::types::{DownstreamSnapshot, ServiceSnapshot, ServiceType};
+    use tokio::{
+        io::{AsyncWriteExt, DuplexStream},
+        task::JoinHandle,
+        time::Instant,
+    };
+
+    /// Serve one end of an in-memory connection, returning the other end and whether the
+    /// handler succeeded
+    fn serve(
+        ingest: mpsc::Sender<IngestMessage>,
+        read_timeout: Option<Duration>,
+    ) -> (DuplexStream, JoinHandle<bool>) {
+        let (client, server) = tokio::io::duplex(64);
+        let addr = "127.0.0.1:34254".parse().unwrap();
+        let handle = tokio::spawn(async move {
+            handle_pool_connection(server, addr, ingest, read_timeout)
+                .await
+                .is_ok()
+        });
+        (client, handle)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_connection_is_closed_after_read_timeout() {
+        let ingest = spawn_ingest_workers(StatsHandler::new(Arc::new(StatsData::new())), 1, 16);
+        let read_timeout = Duration::from_millis(200);
+
+        let (mut idle_client, idle) = serve(ingest.clone(), Some(read_timeout));
+        let (mut active_client, active) = serve(ingest, Some(read_timeout));
+
+        // Keep writing to one connection for well past the timeout
+        for _ in 0..8 {
+            active_client.write_all(b"\n").await.unwrap();
+            tokio::time::sleep(Duration::from_millis(75)).await;
+        }
+
+        assert!(idle.is_finished());
+        assert!(!active.is_finished());
+
+        // The server dropped its end, so the idle client sees EOF
+        let mut buf = [0u8; 1];
+        assert_eq!(idle_client.read(&mut buf).await.unwrap(), 0);
+        assert!(idle.await.unwrap());
+
+        // Once the active client goes quiet it is closed a full timeout after its last write
+        let last_write = Instant::now() - Duration::from_millis(75);
+        assert!(active.await.unwrap());
+        assert_eq!(last_write.elapsed(), read_timeout);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_connections_all_persist() {
+        const CONNECTIONS: u32 = 8;
+        const SNAPSHOTS: u64 = 20;
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let db_path = std::env::temp_dir().join(format!(
+            "hashpool-stats-ingest-{}-{}.sqlite",
+            std::process::id(),
+            nanos
+        ));
+        let stats = Arc::new(StatsData::new());
+        stats
+            .init_metrics_storage(Some(db_path.to_str().unwrap()), None)
+            .await
+            .unwrap();
+
+        let ingest = spawn_ingest_workers(StatsHandler::new(stats.clone()), 1, 4);
+
+        // Snapshots a minute apart land in separate buckets, one point each
+        let start = 1_700_000_040;
+        let mut connections = Vec::new();
+        for id in 0..CONNECTIONS {
+            let (mut client, handle) = serve(ingest.clone(), None);
+            connections.push(handle);
+            tokio::spawn(async move {
+                for i in 0..SNAPSHOTS {
+                    let timestamp = start + i * 60;
+                    let snapshot = ServiceSnapshot {
+                        service_type: ServiceType::Pool,
+                        downstreams: vec![DownstreamSnapshot {
+                            downstream_id: id,
+                            name: format!("miner_{}", id),
+                            address: "127.0.0.1:3333".to_string(),
+                            user_agent: "test".to_string(),
+                            shares_lifetime: i,
+                            shares_in_window: 1,
+                            sum_difficulty_in_window: 1.0,
+                            window_seconds: 60,
+                            timestamp,
+                        }],
+                        timestamp,
+                    };
+                    let mut line = serde_json::to_vec(&snapshot).unwrap();
+                    line.push(b'\n');
+                    client.write_all(&line).await.unwrap();
+                }
+            });
+        }
+        drop(ingest);
+
+        // Every connection's reader finishes once its client is done
+        for handle in connections {
+            assert!(handle.await.unwrap());
+        }
+
+        let end = start + (SNAPSHOTS - 1) * 60;
+        for id in 0..CONNECTIONS {
+            let points = tokio::time::timeout(Duration::from_secs(5), async {
+                loop {
+                    let points = stats.query_hashrate(id, start, end).await.unwrap();
+                    if points.len() as u64 == SNAPSHOTS {
+                        break points;
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .unwrap_or_else(|_| panic!("downstream {} missed snapshots", id));
+            assert_eq!(points.len() as u64, SNAPSHOTS);
+        }
+
+        let _ = std::fs::remove_file(&db_path);
+    }
+}
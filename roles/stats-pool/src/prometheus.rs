@@ -0,0 +1,154 @@
+//! Prometheus text exposition of the latest pool snapshot
+//!
+//! Served at `GET /metrics` next to the JSON API. Values are read from the same merged
+//! snapshot as `/api/stats`, so both always agree.
+
+use std::fmt::Write;
+
+use stats::stats_adapter::PoolSnapshot;
+
+/// `Content-Type` of the Prometheus text format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Render `snapshot` as Prometheus text, with share ages measured against `now`.
+pub fn render(snapshot: &PoolSnapshot, now: u64) -> String {
+    let downstreams = &snapshot.downstream_proxies;
+    let mut out = String::new();
+
+    write_family(
+        &mut out,
+        "hashpool_pool_shares_submitted_total",
+        "counter",
+        "Shares submitted by each downstream",
+        downstreams
+            .iter()
+            .map(|d| (d.id, d.shares_submitted as f64)),
+    );
+    write_family(
+        &mut out,
+        "hashpool_pool_quotes_created_total",
+        "counter",
+        "Mint quotes created for each downstream",
+        downstreams.iter().map(|d| (d.id, d.quotes_created as f64)),
+    );
+    write_family(
+        &mut out,
+        "hashpool_pool_ehash_mined_total",
+        "counter",
+        "Ehash mined by each downstream",
+        downstreams.iter().map(|d| (d.id, d.ehash_mined as f64)),
+    );
+    // Downstreams that never submitted a share have no age to report
+    write_family(
+        &mut out,
+        "hashpool_pool_last_share_age_seconds",
+        "gauge",
+        "Seconds since each downstream's last share",
+        downstreams.iter().filter_map(|d| {
+            d.last_share_at
+                .map(|at| (d.id, now.saturating_sub(at) as f64))
+        }),
+    );
+
+    out
+}
+
+fn write_family(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    samples: impl Iterator<Item = (u32, f64)>,
+) {
+    // Writing to a String cannot fail
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    for (downstream_id, value) in samples {
+        let _ = writeln!(
+            out,
+            "{}{{downstream_id=\"{}\"}} {}",
+            name, downstream_id, value
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stats::stats_adapter::ProxyConnection;
+
+    fn downstream(id: u32, shares: u64, last_share_at: Option<u64>) -> ProxyConnection {
+        ProxyConnection {
+            id,
+            address: "127.0.0.1:34255".to_string(),
+            channels: vec![1],
+            shares_submitted: shares,
+            quotes_created: shares / 2,
+            quotes_failed: 0,
+            ehash_mined: shares * 64,
+            last_share_at,
+            work_selection: false,
+            quote_success_ratio: None,
+            difficulty_histogram: Vec::new(),
+            reject_ratio: 0.0,
+            shares_per_minute: 0.0,
+        }
+    }
+
+    /// Check a line against the exposition format: a HELP or TYPE comment, or
+    /// `name{label="value"} number`. Returns the metric name of sample lines.
+    fn parse_line(line: &str) -> Option<&str> {
+        if let Some(comment) = line.strip_prefix("# ") {
+            let mut parts = comment.splitn(3, ' ');
+            let keyword = parts.next().unwrap();
+            assert!(keyword == "HELP" || keyword == "TYPE", "{line}");
+            let name = parts.next().unwrap();
+            let rest = parts.next().unwrap();
+            assert!(name.starts_with("hashpool_pool_"), "{line}");
+            if keyword == "TYPE" {
+                assert!(rest == "counter" || rest == "gauge", "{line}");
+            }
+            return None;
+        }
+
+        let (series, value) = line.rsplit_once(' ').unwrap();
+        value.parse::<f64>().unwrap();
+        let (name, labels) = series.split_once('{').unwrap();
+        assert!(
+            name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'),
+            "{line}"
+        );
+        let labels = labels.strip_suffix('}').unwrap();
+        for label in labels.split(',') {
+            let (key, value) = label.split_once('=').unwrap();
+            assert!(!key.is_empty(), "{line}");
+            assert!(value.starts_with('"') && value.ends_with('"'), "{line}");
+        }
+        Some(name)
+    }
+
+    #[test]
+    fn test_rendered_metrics_are_valid_exposition_lines() {
+        let snapshot = PoolSnapshot {
+            services: vec![],
+            downstream_proxies: vec![downstream(1, 10, Some(990)), downstream(2, 0, None)],
+            listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
+            timestamp: 1_000,
+        };
+
+        let rendered = render(&snapshot, 1_000);
+        let samples: Vec<&str> = rendered.lines().filter_map(parse_line).collect();
+
+        // Three counters for both downstreams, an age only for the one that has shared
+        assert_eq!(samples.len(), 7);
+        assert!(rendered.contains("hashpool_pool_shares_submitted_total{downstream_id=\"1\"} 10\n"));
+        assert!(rendered.contains("hashpool_pool_ehash_mined_total{downstream_id=\"1\"} 640\n"));
+        assert!(rendered.contains("hashpool_pool_last_share_age_seconds{downstream_id=\"1\"} 10\n"));
+        assert!(!rendered.contains("hashpool_pool_last_share_age_seconds{downstream_id=\"2\"}"));
+    }
+}
@@ -1,18 +1,100 @@
-use std::sync::Arc;
-use tracing::{debug, warn};
+use std::{collections::HashMap, net::SocketAddr, sync::Arc};
+use tokio::sync::{mpsc, Mutex};
+use tracing::{debug, error, warn};
 
-use stats::stats_adapter::{JdsSnapshot, PoolSnapshot};
-use stats_sv2::types::ServiceSnapshot;
+use stats::stats_adapter::{JdsSnapshot, PoolSnapshot, ShareSubmitted};
+use stats_sv2::types::{unix_timestamp, DownstreamSnapshot, ServiceSnapshot};
 
 use crate::db::StatsData;
 
 pub struct StatsHandler {
     db: Arc<StatsData>,
+    max_message_age_secs: Option<u64>,
+    max_window_skew_secs: Option<u64>,
+    // Newest metrics sample timestamp seen for each downstream in the latest metrics snapshot
+    last_sample_at: Mutex<HashMap<u32, u64>>,
 }
 
 impl StatsHandler {
     pub fn new(db: Arc<StatsData>) -> Self {
-        Self { db }
+        Self {
+            db,
+            max_message_age_secs: None,
+            max_window_skew_secs: None,
+            last_sample_at: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Drop messages whose embedded timestamp is more than `secs` seconds old.
+    pub fn with_max_message_age(mut self, secs: Option<u64>) -> Self {
+        self.max_message_age_secs = secs;
+        self
+    }
+
+    /// Flag metrics samples that arrive more than `secs` seconds later than the previous
+    /// sample of the same downstream plus its `window_seconds`.
+    pub fn with_max_window_skew(mut self, secs: Option<u64>) -> Self {
+        self.max_window_skew_secs = secs;
+        self
+    }
+
+    /// Whether `downstream` was sampled so long after its previous sample that the window it
+    /// reports leaves part of the gap uncovered, which skews the hashrate derived from it.
+    /// Samples closer together than their window are expected since windows slide, and
+    /// samples older than the newest one seen are not checked.
+    async fn has_window_skew(&self, downstream: &DownstreamSnapshot) -> bool {
+        let Some(max_skew) = self.max_window_skew_secs else {
+            return false;
+        };
+        let previous = {
+            let mut last_sample_at = self.last_sample_at.lock().await;
+            let newest = last_sample_at
+                .entry(downstream.downstream_id)
+                .or_insert(downstream.timestamp);
+            let previous = *newest;
+            *newest = previous.max(downstream.timestamp);
+            previous
+        };
+
+        let cadence = downstream.timestamp.saturating_sub(previous);
+        let skew = cadence.saturating_sub(downstream.window_seconds);
+        if skew > max_skew {
+            warn!(
+                "Downstream {} sampled {}s after its previous sample but reports a {}s window; \
+                 {}s skew exceeds max_window_skew_secs={}",
+                downstream.downstream_id, cadence, downstream.window_seconds, skew, max_skew
+            );
+            return true;
+        }
+        false
+    }
+
+    /// Forget the samples of downstreams missing from `snapshot`. A metrics snapshot lists
+    /// every connected downstream, so those have disconnected, and a later connection reusing
+    /// the id is not measured against the old one.
+    async fn forget_absent_downstreams(&self, snapshot: &ServiceSnapshot) {
+        self.last_sample_at.lock().await.retain(|downstream_id, _| {
+            snapshot
+                .downstreams
+                .iter()
+                .any(|downstream| downstream.downstream_id == *downstream_id)
+        });
+    }
+
+    /// Whether a message timestamped `timestamp` exceeds the configured maximum age.
+    fn is_too_old(&self, kind: &str, timestamp: u64) -> bool {
+        let Some(max_age) = self.max_message_age_secs else {
+            return false;
+        };
+        let age = unix_timestamp().saturating_sub(timestamp);
+        if age > max_age {
+            warn!(
+                "Dropping {} timestamped {}: {}s old exceeds max_message_age_secs={}",
+                kind, timestamp, age, max_age
+            );
+            return true;
+        }
+        false
     }
 
     /// Accept a newline-delimited JSON payload, deserialize it into a
@@ -26,6 +108,14 @@ impl StatsHandler {
                 snapshot.downstreams.len(),
                 snapshot.timestamp
             );
+            if self.is_too_old("metrics snapshot", snapshot.timestamp) {
+                return Ok(());
+            }
+            // Flagged samples are still stored; the warning is for the producer's operator
+            for downstream in &snapshot.downstreams {
+                self.has_window_skew(downstream).await;
+            }
+            self.forget_absent_downstreams(&snapshot).await;
 
             // Store metrics in database
             self.db.store_metrics_snapshot(snapshot).await?;
@@ -41,6 +131,9 @@ impl StatsHandler {
                 snapshot.listen_address,
                 snapshot.timestamp
             );
+            if self.is_too_old("pool snapshot", snapshot.timestamp) {
+                return Ok(());
+            }
 
             self.db.store_snapshot(snapshot);
             return Ok(());
@@ -52,16 +145,75 @@ impl StatsHandler {
                 "Received JDS snapshot: listen={}, ts={}",
                 snapshot.listen_address, snapshot.timestamp
             );
+            if self.is_too_old("JDS snapshot", snapshot.timestamp) {
+                return Ok(());
+            }
 
             self.db.store_jds_snapshot(snapshot);
             return Ok(());
         }
 
+        // Per-share events are only sent when the pool enables them
+        if let Ok(event) = serde_json::from_slice::<ShareSubmitted>(data) {
+            debug!(
+                "Received share event: downstream={}, channel={}, difficulty={}, ts={}",
+                event.downstream_id, event.channel_id, event.difficulty, event.timestamp
+            );
+            if self.is_too_old("share event", event.timestamp) {
+                return Ok(());
+            }
+
+            self.db.record_share_event(&event);
+            return Ok(());
+        }
+
         warn!("Failed to parse snapshot message as ServiceSnapshot, PoolSnapshot, or JdsSnapshot");
         Err("Unknown snapshot type".into())
     }
 }
 
+/// One newline-delimited message read from a snapshot connection
+#[derive(Debug)]
+pub struct IngestMessage {
+    pub addr: SocketAddr,
+    pub line: Vec<u8>,
+}
+
+/// Default number of tasks writing ingested messages
+pub const DEFAULT_INGEST_WORKERS: usize = 1;
+/// Default number of messages queued for the ingest workers before readers wait
+pub const DEFAULT_INGEST_QUEUE_SIZE: usize = 1024;
+
+/// Spawn `workers` tasks that store messages queued on the returned sender. Connections only
+/// read and queue lines, so writes are bounded by the worker count instead of the number of
+/// connected clients; a single worker stores messages in the order they were queued. A full
+/// queue makes connection readers wait. The workers stop once every sender is dropped.
+pub fn spawn_ingest_workers(
+    handler: StatsHandler,
+    workers: usize,
+    queue_size: usize,
+) -> mpsc::Sender<IngestMessage> {
+    let (sender, receiver) = mpsc::channel::<IngestMessage>(queue_size.max(1));
+    let receiver = Arc::new(Mutex::new(receiver));
+    let handler = Arc::new(handler);
+    for _ in 0..workers.max(1) {
+        let receiver = receiver.clone();
+        let handler = handler.clone();
+        tokio::spawn(async move {
+            loop {
+                // Only the dequeue is serialized; workers store concurrently
+                let Some(message) = receiver.lock().await.recv().await else {
+                    break;
+                };
+                if let Err(e) = handler.handle_message(&message.line).await {
+                    error!("Error processing message from {}: {}", message.addr, e);
+                }
+            }
+        });
+    }
+    sender
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,10 +243,21 @@ mod tests {
                 channels: vec![10, 11],
                 shares_submitted: 5,
                 quotes_created: 2,
+                quotes_failed: 0,
                 ehash_mined: 50,
                 last_share_at: Some(unix_timestamp()),
+                work_selection: false,
+                quote_success_ratio: Some(1.0),
+                difficulty_histogram: Vec::new(),
+                reject_ratio: 0.0,
+                shares_per_minute: 0.0,
             }],
             listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: unix_timestamp(),
         };
 
@@ -106,6 +269,48 @@ mod tests {
         assert_eq!(retrieved.downstream_proxies.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_share_events_counted_per_downstream() {
+        let db = Arc::new(StatsData::new());
+        let handler = StatsHandler::new(db.clone());
+        let now = unix_timestamp();
+
+        for (downstream_id, difficulty) in [(2, 1024.0), (1, 512.0), (2, 2048.0)] {
+            let event = ShareSubmitted {
+                downstream_id,
+                channel_id: downstream_id * 10,
+                difficulty,
+                timestamp: now,
+            };
+            handler
+                .handle_message(&serde_json::to_vec(&event).unwrap())
+                .await
+                .unwrap();
+        }
+
+        let totals = db.share_event_totals();
+        assert_eq!(totals.len(), 2);
+        assert_eq!((totals[0].downstream_id, totals[0].shares), (1, 1));
+        assert_eq!((totals[1].downstream_id, totals[1].shares), (2, 2));
+        assert_eq!(totals[1].sum_difficulty, 3072.0);
+        assert_eq!(totals[1].last_share_at, now);
+
+        // A snapshot without downstream 1, taken after its share, drops its totals
+        let snapshot = PoolSnapshot {
+            services: vec![],
+            downstream_proxies: vec![],
+            listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
+            timestamp: now + 1,
+        };
+        db.store_snapshot(snapshot);
+        assert!(db.share_event_totals().is_empty());
+    }
+
     #[tokio::test]
     async fn test_handle_invalid_json() {
         let db = Arc::new(StatsData::new());
@@ -125,6 +330,11 @@ mod tests {
             services: vec![],
             downstream_proxies: vec![],
             listen_address: "first".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: unix_timestamp(),
         };
         handler
@@ -136,6 +346,11 @@ mod tests {
             services: vec![],
             downstream_proxies: vec![],
             listen_address: "second".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: unix_timestamp() + 1,
         };
         handler
@@ -146,4 +361,114 @@ mod tests {
         let retrieved = db.get_latest_snapshot().unwrap();
         assert_eq!(retrieved.listen_address, "second");
     }
+
+    #[tokio::test]
+    async fn test_window_skew_flags_gaps_beyond_the_window() {
+        let handler = StatsHandler::new(Arc::new(StatsData::new())).with_max_window_skew(Some(10));
+        let sample = |downstream_id, timestamp| DownstreamSnapshot {
+            downstream_id,
+            name: format!("miner_{}", downstream_id),
+            address: "127.0.0.1:3333".to_string(),
+            user_agent: "test".to_string(),
+            shares_lifetime: 0,
+            shares_in_window: 1,
+            sum_difficulty_in_window: 1.0,
+            window_seconds: 60,
+            timestamp,
+        };
+
+        // Sampled every 15s or every window: consistent
+        for timestamp in [1_000, 1_015, 1_030, 1_090, 1_160] {
+            assert!(!handler.has_window_skew(&sample(1, timestamp)).await);
+        }
+
+        // Five minutes between samples of a 60s window leaves most of the gap uncovered
+        assert!(!handler.has_window_skew(&sample(2, 1_000)).await);
+        assert!(handler.has_window_skew(&sample(2, 1_300)).await);
+
+        // A late, older sample isn't measured against the newer one
+        assert!(!handler.has_window_skew(&sample(2, 1_200)).await);
+        assert!(!handler.has_window_skew(&sample(2, 1_360)).await);
+    }
+
+    #[tokio::test]
+    async fn test_window_skew_forgets_disconnected_downstreams() {
+        let handler = StatsHandler::new(Arc::new(StatsData::new())).with_max_window_skew(Some(10));
+        let sample = |downstream_id, timestamp| DownstreamSnapshot {
+            downstream_id,
+            name: format!("miner_{}", downstream_id),
+            address: "127.0.0.1:3333".to_string(),
+            user_agent: "test".to_string(),
+            shares_lifetime: 0,
+            shares_in_window: 1,
+            sum_difficulty_in_window: 1.0,
+            window_seconds: 60,
+            timestamp,
+        };
+        let metrics = |downstreams: Vec<DownstreamSnapshot>, timestamp| {
+            serde_json::to_vec(&ServiceSnapshot {
+                service_type: stats_sv2::types::ServiceType::Pool,
+                downstreams,
+                timestamp,
+            })
+            .unwrap()
+        };
+
+        handler
+            .handle_message(&metrics(vec![sample(1, 1_000), sample(2, 1_000)], 1_000))
+            .await
+            .unwrap();
+        assert_eq!(handler.last_sample_at.lock().await.len(), 2);
+
+        // Downstream 2 disconnected
+        handler
+            .handle_message(&metrics(vec![sample(1, 1_060)], 1_060))
+            .await
+            .unwrap();
+        let last_sample_at = handler.last_sample_at.lock().await.clone();
+        assert_eq!(last_sample_at, HashMap::from([(1, 1_060)]));
+
+        // A new connection reusing id 2 starts afresh rather than showing a 300s gap
+        assert!(!handler.has_window_skew(&sample(2, 1_300)).await);
+    }
+
+    #[tokio::test]
+    async fn test_old_messages_dropped_when_max_age_set() {
+        let db = Arc::new(StatsData::new());
+        let handler = StatsHandler::new(db.clone()).with_max_message_age(Some(30));
+
+        let old = PoolSnapshot {
+            services: vec![],
+            downstream_proxies: vec![],
+            listen_address: "old".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
+            timestamp: unix_timestamp() - 300,
+        };
+        handler
+            .handle_message(&serde_json::to_vec(&old).unwrap())
+            .await
+            .unwrap();
+        assert!(db.get_latest_snapshot().is_none());
+
+        let fresh = PoolSnapshot {
+            services: vec![],
+            downstream_proxies: vec![],
+            listen_address: "fresh".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
+            timestamp: unix_timestamp(),
+        };
+        handler
+            .handle_message(&serde_json::to_vec(&fresh).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(db.get_latest_snapshot().unwrap().listen_address, "fresh");
+    }
 }
@@ -1,8 +1,6 @@
 use bytes::Bytes;
 use http_body_util::Full;
-use hyper::{
-    body::Incoming, server::conn::http1, service::service_fn, Method, Request, Response, StatusCode,
-};
+use hyper::{server::conn::http1, service::service_fn, Method, Request, Response, StatusCode};
 use hyper_util::rt::TokioIo;
 use serde_json::json;
 use std::{convert::Infallible, sync::Arc};
@@ -41,8 +39,8 @@ pub async fn run_http_server(
     }
 }
 
-async fn handle_request(
-    req: Request<Incoming>,
+async fn handle_request<B>(
+    req: Request<B>,
     db: Arc<StatsData>,
     redact_ip: bool,
 ) -> Result<Response<Full<Bytes>>, Infallible> {
@@ -78,6 +76,22 @@ async fn handle_request(
                     .body(Full::new(Bytes::from("Invalid downstream ID")))
             }
         }
+        (&Method::GET, path) if path.starts_with("/api/downstream/") => {
+            match path.trim_start_matches("/api/downstream/").parse::<u32>() {
+                Ok(downstream_id) => match get_downstream(db, downstream_id, redact_ip) {
+                    Some(downstream) => Response::builder()
+                        .header("content-type", "application/json")
+                        .body(Full::new(Bytes::from(downstream.to_string()))),
+                    None => Response::builder()
+                        .status(StatusCode::NOT_FOUND)
+                        .header("content-type", "application/json")
+                        .body(Full::new(Bytes::from(r#"{"error":"unknown downstream"}"#))),
+                },
+                Err(_) => Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .body(Full::new(Bytes::from("Invalid downstream ID"))),
+            }
+        }
         (&Method::GET, "/api/hashrate") => {
             let data = query_aggregate_hashrate(db.clone(), query).await;
             Response::builder()
@@ -113,7 +127,7 @@ async fn get_miner_stats(db: Arc<StatsData>, redact_ip: bool) -> serde_json::Val
                 .into_iter()
                 .map(|mut m| {
                     if redact_ip {
-                        m.address = m.address.split(':').next().unwrap_or("").to_string() + ":****";
+                        m.address = redact_address(&m.address);
                     }
                     m
                 })
@@ -124,6 +138,40 @@ async fn get_miner_stats(db: Arc<StatsData>, redact_ip: bool) -> serde_json::Val
     }
 }
 
+/// Current numbers for one downstream miner from the latest snapshot, `None` if it isn't in it.
+fn get_downstream(
+    db: Arc<StatsData>,
+    downstream_id: u32,
+    redact_ip: bool,
+) -> Option<serde_json::Value> {
+    let snapshot = db.get_latest_snapshot()?;
+    let miner = snapshot
+        .downstream_miners
+        .into_iter()
+        .find(|m| m.id == downstream_id)?;
+    let address = if redact_ip {
+        redact_address(&miner.address)
+    } else {
+        miner.address
+    };
+
+    Some(json!({
+        "id": miner.id,
+        "name": miner.name,
+        "address": address,
+        "hashrate": miner.hashrate,
+        "shares_submitted": miner.shares_submitted,
+        "connected_at": miner.connected_at,
+        "user_agent": miner.user_agent,
+        "last_seen": snapshot.timestamp,
+    }))
+}
+
+/// Keep the host of a `host:port` address and mask the port
+fn redact_address(address: &str) -> String {
+    address.split(':').next().unwrap_or("").to_string() + ":****"
+}
+
 /// Parse query parameters to extract timestamp range
 fn parse_timestamp_range(query: &str) -> (u64, u64) {
     let mut from = 0u64;
@@ -194,3 +242,64 @@ async fn query_aggregate_hashrate(db: Arc<StatsData>, query: &str) -> String {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+    use stats::stats_adapter::{MinerInfo, ProxySnapshot};
+
+    fn db_with_miners() -> Arc<StatsData> {
+        let db = Arc::new(StatsData::new());
+        let miner = |id: u32, address: &str| MinerInfo {
+            name: format!("miner{}", id),
+            id,
+            address: address.to_string(),
+            hashrate: 100.0 * id as f64,
+            shares_submitted: 10 * id as u64,
+            connected_at: 1_700_000_000,
+            user_agent: "unknown".to_string(),
+        };
+        db.store_snapshot(ProxySnapshot {
+            ehash_balance: 0,
+            upstream_pool: None,
+            downstream_miners: vec![miner(1, "10.0.0.1:4444"), miner(2, "10.0.0.2:5555")],
+            blockchain_network: "regtest".to_string(),
+            rejected_connections: 0,
+            connections: Default::default(),
+            recent_disconnects: Vec::new(),
+            health: Default::default(),
+            timestamp: 1_700_000_100,
+        });
+        db
+    }
+
+    async fn get(db: Arc<StatsData>, path: &str, redact_ip: bool) -> (StatusCode, String) {
+        let req = Request::builder().uri(path).body(()).unwrap();
+        let response = handle_request(req, db, redact_ip).await.unwrap();
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_downstream_route() {
+        let db = db_with_miners();
+
+        let (status, body) = get(db.clone(), "/api/downstream/2", true).await;
+        assert_eq!(status, StatusCode::OK);
+        let downstream: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(downstream["id"], 2);
+        assert_eq!(downstream["name"], "miner2");
+        assert_eq!(downstream["address"], "10.0.0.2:****");
+        assert_eq!(downstream["shares_submitted"], 20);
+        assert_eq!(downstream["last_seen"], 1_700_000_100);
+
+        let (status, _) = get(db.clone(), "/api/downstream/3", true).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // The hashrate route for a downstream is unaffected
+        let (status, _) = get(db, "/api/downstream/2/hashrate", true).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -1,4 +1,5 @@
 use serde::Deserialize;
+use shared_config::RoleConfig;
 use std::{env, fs, path::PathBuf};
 
 #[derive(Debug, Clone)]
@@ -12,6 +13,8 @@ pub struct Config {
     pub faucet_enabled: bool,
     pub faucet_url: Option<String>,
     pub staleness_threshold_secs: u64,
+    pub max_message_age_secs: Option<u64>,
+    pub tcp_read_timeout_secs: u64,
     pub request_timeout_secs: u64,
     pub pool_idle_timeout_secs: u64,
     pub log_file: Option<String>,
@@ -28,9 +31,12 @@ struct StatsProxyConfig {
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct ServerConfig {
     tcp_listen_address: Option<String>,
     http_listen_address: Option<String>,
+    /// Close a snapshot connection after this many seconds without data (0 disables)
+    tcp_read_timeout_secs: Option<u64>,
 }
 
 impl Default for ServerConfig {
@@ -38,14 +44,21 @@ impl Default for ServerConfig {
         Self {
             tcp_listen_address: Some("127.0.0.1:8082".to_string()),
             http_listen_address: Some("127.0.0.1:8084".to_string()),
+            tcp_read_timeout_secs: Some(DEFAULT_TCP_READ_TIMEOUT_SECS),
         }
     }
 }
 
+/// Default time a snapshot connection may stay silent before it is closed
+const DEFAULT_TCP_READ_TIMEOUT_SECS: u64 = 60;
+
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct SnapshotStorageConfig {
     db_path: Option<PathBuf>,
     staleness_threshold_secs: Option<u64>,
+    /// Snapshots older than this many seconds are dropped at ingest (disabled if unset)
+    max_message_age_secs: Option<u64>,
 }
 
 impl Default for SnapshotStorageConfig {
@@ -53,11 +66,13 @@ impl Default for SnapshotStorageConfig {
         Self {
             db_path: None,
             staleness_threshold_secs: Some(15),
+            max_message_age_secs: None,
         }
     }
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct HttpClientConfig {
     pool_idle_timeout_secs: Option<u64>,
     request_timeout_secs: Option<u64>,
@@ -72,6 +87,49 @@ impl Default for HttpClientConfig {
     }
 }
 
+impl RoleConfig for StatsProxyConfig {
+    const SERVICE: &'static str = "stats-proxy";
+    const ENV_PREFIX: &'static str = "HASHPOOL_STATS_PROXY";
+
+    fn effective_config(&self) -> Vec<(&'static str, String)> {
+        fn opt<T: ToString>(value: Option<&T>) -> String {
+            value.map_or_else(|| "none".to_string(), |v| v.to_string())
+        }
+        let server = &self.server;
+        let storage = &self.snapshot_storage;
+        vec![
+            (
+                "server.tcp_listen_address",
+                opt(server.tcp_listen_address.as_ref()),
+            ),
+            (
+                "server.http_listen_address",
+                opt(server.http_listen_address.as_ref()),
+            ),
+            (
+                "server.tcp_read_timeout_secs",
+                opt(server.tcp_read_timeout_secs.as_ref()),
+            ),
+            (
+                "snapshot_storage.db_path",
+                opt(storage.db_path.as_ref().map(|p| p.display()).as_ref()),
+            ),
+            (
+                "snapshot_storage.staleness_threshold_secs",
+                opt(storage.staleness_threshold_secs.as_ref()),
+            ),
+            (
+                "snapshot_storage.max_message_age_secs",
+                opt(storage.max_message_age_secs.as_ref()),
+            ),
+            (
+                "http_client.request_timeout_secs",
+                opt(self.http_client.request_timeout_secs.as_ref()),
+            ),
+        ]
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct TproxyConfig {
     downstream_address: String,
@@ -105,17 +163,7 @@ impl Config {
             .map(|s| s.as_str())
             .ok_or("Missing required argument: --config")?;
 
-        let stats_proxy_config_str =
-            fs::read_to_string(stats_proxy_config_path).unwrap_or_default();
-        let stats_proxy_config: StatsProxyConfig = if stats_proxy_config_str.is_empty() {
-            StatsProxyConfig {
-                server: ServerConfig::default(),
-                snapshot_storage: SnapshotStorageConfig::default(),
-                http_client: HttpClientConfig::default(),
-            }
-        } else {
-            toml::from_str(&stats_proxy_config_str)?
-        };
+        let stats_proxy_config = StatsProxyConfig::from_optional_path(stats_proxy_config_path)?;
 
         // TCP and HTTP addresses from config file, with CLI overrides
         let tcp_address = args
@@ -203,6 +251,11 @@ impl Config {
                 .snapshot_storage
                 .staleness_threshold_secs
                 .unwrap_or(15),
+            max_message_age_secs: stats_proxy_config.snapshot_storage.max_message_age_secs,
+            tcp_read_timeout_secs: stats_proxy_config
+                .server
+                .tcp_read_timeout_secs
+                .unwrap_or(DEFAULT_TCP_READ_TIMEOUT_SECS),
             request_timeout_secs: stats_proxy_config
                 .http_client
                 .request_timeout_secs
@@ -226,10 +279,12 @@ mod tests {
             [server]
             tcp_listen_address = "127.0.0.1:4444"
             http_listen_address = "127.0.0.1:4445"
+            tcp_read_timeout_secs = 30
 
             [snapshot_storage]
             db_path = "/tmp/stats.db"
             staleness_threshold_secs = 20
+            max_message_age_secs = 60
 
             [http_client]
             pool_idle_timeout_secs = 400
@@ -244,11 +299,13 @@ mod tests {
             config.server.http_listen_address,
             Some("127.0.0.1:4445".to_string())
         );
+        assert_eq!(config.server.tcp_read_timeout_secs, Some(30));
         assert_eq!(
             config.snapshot_storage.db_path,
             Some(PathBuf::from("/tmp/stats.db"))
         );
         assert_eq!(config.snapshot_storage.staleness_threshold_secs, Some(20));
+        assert_eq!(config.snapshot_storage.max_message_age_secs, Some(60));
         assert_eq!(config.http_client.pool_idle_timeout_secs, Some(400));
         assert_eq!(config.http_client.request_timeout_secs, Some(75));
     }
@@ -265,4 +322,25 @@ mod tests {
         assert_eq!(config.downstream_port, 3333);
         assert_eq!(config.redact_ip, true);
     }
+
+    #[test]
+    fn test_role_config_loads_with_own_env_prefix() {
+        std::env::set_var(
+            "HASHPOOL_STATS_PROXY_SNAPSHOT_STORAGE__DB_PATH",
+            "/tmp/proxy.db",
+        );
+        std::env::set_var("HASHPOOL_STATS_POOL_SERVER__TCP_READ_TIMEOUT_SECS", "5");
+        let config = StatsProxyConfig::from_optional_path("/nonexistent/stats-proxy.toml");
+        std::env::remove_var("HASHPOOL_STATS_PROXY_SNAPSHOT_STORAGE__DB_PATH");
+        std::env::remove_var("HASHPOOL_STATS_POOL_SERVER__TCP_READ_TIMEOUT_SECS");
+
+        let config = config.unwrap();
+        let entries = config.effective_config();
+        let value = |key| entries.iter().find(|(k, _)| *k == key).unwrap().1.as_str();
+        assert_eq!(value("snapshot_storage.db_path"), "/tmp/proxy.db");
+        // Sibling keys keep their defaults, and another service's prefix is ignored
+        assert_eq!(value("snapshot_storage.staleness_threshold_secs"), "15");
+        assert_eq!(value("server.tcp_read_timeout_secs"), "60");
+        assert_eq!(value("server.tcp_listen_address"), "127.0.0.1:8082");
+    }
 }
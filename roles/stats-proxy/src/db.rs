@@ -161,6 +161,7 @@ mod tests {
                     hashrate: 100.5,
                     shares_submitted: 42,
                     connected_at: 1234567890,
+                    user_agent: "unknown".to_string(),
                 },
                 MinerInfo {
                     name: "miner2".to_string(),
@@ -169,6 +170,7 @@ mod tests {
                     hashrate: 200.0,
                     shares_submitted: 84,
                     connected_at: 1234567891,
+                    user_agent: "unknown".to_string(),
                 },
             ],
             timestamp: unix_timestamp(),
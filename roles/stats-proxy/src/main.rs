@@ -1,7 +1,7 @@
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, sync::Arc, time::Duration};
 use tokio::{
-    io::AsyncReadExt,
-    net::{TcpListener, TcpStream},
+    io::{AsyncRead, AsyncReadExt},
+    net::TcpListener,
 };
 use tracing::{error, info};
 
@@ -60,13 +60,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     });
 
     // Accept TCP connections
+    let read_timeout = (config.tcp_read_timeout_secs > 0)
+        .then(|| Duration::from_secs(config.tcp_read_timeout_secs));
+
     loop {
         match tcp_listener.accept().await {
             Ok((stream, addr)) => {
                 info!("New pool connection from {}", addr);
                 let db_clone = db.clone();
+                let max_message_age_secs = config.max_message_age_secs;
                 tokio::spawn(async move {
-                    if let Err(e) = handle_pool_connection(stream, addr, db_clone).await {
+                    if let Err(e) = handle_pool_connection(
+                        stream,
+                        addr,
+                        db_clone,
+                        max_message_age_secs,
+                        read_timeout,
+                    )
+                    .await
+                    {
                         error!("Error handling pool connection from {}: {}", addr, e);
                     }
                 });
@@ -79,16 +91,33 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 async fn handle_pool_connection(
-    mut stream: TcpStream,
+    mut stream: impl AsyncRead + Unpin,
     addr: SocketAddr,
     db: Arc<StatsData>,
+    max_message_age_secs: Option<u64>,
+    read_timeout: Option<Duration>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let handler = StatsHandler::new(db);
+    let handler = StatsHandler::new(db).with_max_message_age(max_message_age_secs);
     let mut buffer = vec![0u8; 8192];
     let mut leftover = Vec::new();
 
     loop {
-        match stream.read(&mut buffer).await {
+        // Each read gets the full timeout, so only a connection that stays silent is dropped
+        let read = match read_timeout {
+            Some(limit) => match tokio::time::timeout(limit, stream.read(&mut buffer)).await {
+                Ok(read) => read,
+                Err(_) => {
+                    info!(
+                        "Closing pool connection from {}: no data for {:?}",
+                        addr, limit
+                    );
+                    break;
+                }
+            },
+            None => stream.read(&mut buffer).await,
+        };
+
+        match read {
             Ok(0) => {
                 info!("Pool connection from {} closed", addr);
                 break;
@@ -120,3 +149,57 @@ async fn handle_pool_connection(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        io::{AsyncWriteExt, DuplexStream},
+        task::JoinHandle,
+        time::Instant,
+    };
+
+    /// Serve one end of an in-memory connection, returning the other end and whether the
+    /// handler succeeded
+    fn serve(
+        stats: Arc<StatsData>,
+        read_timeout: Option<Duration>,
+    ) -> (DuplexStream, JoinHandle<bool>) {
+        let (client, server) = tokio::io::duplex(64);
+        let addr = "127.0.0.1:34254".parse().unwrap();
+        let handle = tokio::spawn(async move {
+            handle_pool_connection(server, addr, stats, None, read_timeout)
+                .await
+                .is_ok()
+        });
+        (client, handle)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_idle_connection_is_closed_after_read_timeout() {
+        let stats = Arc::new(StatsData::new());
+        let read_timeout = Duration::from_millis(200);
+
+        let (mut idle_client, idle) = serve(stats.clone(), Some(read_timeout));
+        let (mut active_client, active) = serve(stats, Some(read_timeout));
+
+        // Keep writing to one connection for well past the timeout
+        for _ in 0..8 {
+            active_client.write_all(b"\n").await.unwrap();
+            tokio::time::sleep(Duration::from_millis(75)).await;
+        }
+
+        assert!(idle.is_finished());
+        assert!(!active.is_finished());
+
+        // The server dropped its end, so the idle client sees EOF
+        let mut buf = [0u8; 1];
+        assert_eq!(idle_client.read(&mut buf).await.unwrap(), 0);
+        assert!(idle.await.unwrap());
+
+        // Once the active client goes quiet it is closed a full timeout after its last write
+        let last_write = Instant::now() - Duration::from_millis(75);
+        assert!(active.await.unwrap());
+        assert_eq!(last_write.elapsed(), read_timeout);
+    }
+}
@@ -1,5 +1,5 @@
 use stats::stats_adapter::ProxySnapshot;
-use stats_sv2::types::ServiceSnapshot;
+use stats_sv2::types::{unix_timestamp, ServiceSnapshot};
 use std::sync::Arc;
 use tracing::{debug, warn};
 
@@ -7,11 +7,37 @@ use crate::db::StatsData;
 
 pub struct StatsHandler {
     db: Arc<StatsData>,
+    max_message_age_secs: Option<u64>,
 }
 
 impl StatsHandler {
     pub fn new(db: Arc<StatsData>) -> Self {
-        Self { db }
+        Self {
+            db,
+            max_message_age_secs: None,
+        }
+    }
+
+    /// Drop messages whose embedded timestamp is more than `secs` seconds old.
+    pub fn with_max_message_age(mut self, secs: Option<u64>) -> Self {
+        self.max_message_age_secs = secs;
+        self
+    }
+
+    /// Whether a message timestamped `timestamp` exceeds the configured maximum age.
+    fn is_too_old(&self, kind: &str, timestamp: u64) -> bool {
+        let Some(max_age) = self.max_message_age_secs else {
+            return false;
+        };
+        let age = unix_timestamp().saturating_sub(timestamp);
+        if age > max_age {
+            warn!(
+                "Dropping {} timestamped {}: {}s old exceeds max_message_age_secs={}",
+                kind, timestamp, age, max_age
+            );
+            return true;
+        }
+        false
     }
 
     pub async fn handle_message(&self, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
@@ -23,6 +49,9 @@ impl StatsHandler {
                 snapshot.downstreams.len(),
                 snapshot.timestamp
             );
+            if self.is_too_old("metrics snapshot", snapshot.timestamp) {
+                return Ok(());
+            }
 
             // Store metrics in database
             self.db.store_metrics_snapshot(snapshot).await?;
@@ -37,6 +66,9 @@ impl StatsHandler {
                 snapshot.downstream_miners.len(),
                 snapshot.timestamp
             );
+            if self.is_too_old("proxy snapshot", snapshot.timestamp) {
+                return Ok(());
+            }
 
             // Store the snapshot in memory
             self.db.store_snapshot(snapshot);
@@ -79,6 +111,7 @@ mod tests {
                 hashrate: 100.5,
                 shares_submitted: 42,
                 connected_at: 1234567890,
+                user_agent: "unknown".to_string(),
             }],
             timestamp: unix_timestamp(),
         };
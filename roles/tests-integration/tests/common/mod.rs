@@ -429,12 +429,12 @@ pub async fn start_sv2_translator(upstream: SocketAddr) -> SocketAddr {
         downstream_difficulty_config,
     );
 
-    let wallet_config = shared_config::WalletConfig {
-        mnemonic: "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string(),
-        db_path: "/tmp/test_wallet.db".to_string(),
-        locking_pubkey: Some("02f6e1e07ed1e7b3f7c2e5d2b1b2e3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2".to_string()),
-        locking_privkey: None,
-    };
+    let wallet_config = shared_config::WalletConfig::new(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string(),
+        "/tmp/test_wallet.db".to_string(),
+        Some("02f6e1e07ed1e7b3f7c2e5d2b1b2e3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2".to_string()),
+        None,
+    );
     let config =
         translator_sv2::proxy_config::ProxyConfig::new(upstream_conf, downstream_conf, wallet_config, 2, 2, 8);
 
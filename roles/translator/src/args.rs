@@ -3,7 +3,7 @@
 //! It provides the `Args` struct to hold parsed arguments,
 //! and the `from_args` function to parse them from the command line.
 use clap::Parser;
-use ext_config::{Config, File, FileFormat};
+use shared_config::RoleConfig;
 use std::path::PathBuf;
 use tracing::error;
 use translator_sv2::{config::TranslatorConfig, error::TproxyError};
@@ -45,12 +45,8 @@ pub fn process_cli_args() -> Result<TranslatorConfig, TproxyError> {
         TproxyError::BadCliArgs
     })?;
 
-    let settings = Config::builder()
-        .add_source(File::new(config_path, FileFormat::Toml))
-        .build()?;
-
-    // Deserialize settings into TranslatorConfig
-    let mut config = settings.try_deserialize::<TranslatorConfig>()?;
+    // Load, apply environment overrides and validate
+    let mut config = TranslatorConfig::from_path(config_path)?;
 
     config.set_log_dir(args.log_file);
 
@@ -10,11 +10,21 @@
 //! - Downstream interface address and port ([`DownstreamConfig`])
 //! - Supported protocol versions
 //! - Downstream difficulty adjustment parameters ([`DownstreamDifficultyConfig`])
-use std::path::{Path, PathBuf};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+};
 
+use config_helpers_sv2::logging::REDACTED;
 use key_utils::Secp256k1PublicKey;
 use serde::Deserialize;
-use shared_config::{MintConfig, WalletConfig};
+use shared_config::{MintConfig, RoleConfig, WalletConfig};
+use stratum_common::roles_logic_sv2::{
+    mining_sv2::MAX_EXTRANONCE_LEN,
+    vardiff::{classic::VardiffState, error::VardiffError},
+};
+
+use crate::error::TproxyError;
 
 /// Configuration for the Translator.
 #[derive(Debug, Deserialize, Clone)]
@@ -39,12 +49,27 @@ pub struct TranslatorConfig {
     /// Whether to aggregate all downstream connections into a single upstream channel.
     /// If true, all miners share one channel. If false, each miner gets its own channel.
     pub aggregate_channels: bool,
+    /// Maximum number of SV1 miners served at once. Unlimited when unset.
+    #[serde(default)]
+    pub max_downstreams: Option<usize>,
+    /// Per-connection limit on `mining.submit` bursts, applied before share validation.
+    /// Disabled when unset.
+    #[serde(default)]
+    pub share_rate_limit: Option<ShareRateLimitConfig>,
+    /// Seconds a reconnected upstream must stay connected before downstreams are reset. Resets
+    /// immediately when zero.
+    #[serde(default)]
+    pub upstream_grace_period_secs: u64,
     /// Wallet configuration for managing ehash tokens
     pub wallet: WalletConfig,
     /// Mint service configuration for quote operations
     pub mint: Option<MintConfig>,
     /// The path to the log file for the Translator.
     log_file: Option<PathBuf>,
+    /// Tracing filter directives (e.g. `info,translator_sv2=debug`) used when `RUST_LOG` is
+    /// unset.
+    #[serde(default)]
+    log_filter: Option<String>,
     /// Optional address of the stats service for sending snapshots
     #[serde(default)]
     pub stats_server_address: Option<String>,
@@ -130,9 +155,13 @@ impl TranslatorConfig {
             user_identity,
             downstream_difficulty_config,
             aggregate_channels,
+            max_downstreams: None,
+            share_rate_limit: None,
+            upstream_grace_period_secs: 0,
             wallet,
             mint,
             log_file: None,
+            log_filter: None,
             stats_server_address: None,
             snapshot_poll_interval_secs: 5,
             redact_ip: true,
@@ -141,6 +170,12 @@ impl TranslatorConfig {
         }
     }
 
+    /// Starts a [`TranslatorConfigBuilder`] with the same defaults as the example config.
+    #[cfg(test)]
+    pub fn builder() -> TranslatorConfigBuilder {
+        TranslatorConfigBuilder::default()
+    }
+
     pub fn set_log_dir(&mut self, log_dir: Option<PathBuf>) {
         if let Some(dir) = log_dir {
             self.log_file = Some(dir);
@@ -149,6 +184,22 @@ impl TranslatorConfig {
     pub fn log_dir(&self) -> Option<&Path> {
         self.log_file.as_deref()
     }
+    pub fn log_filter(&self) -> Option<&str> {
+        self.log_filter.as_deref()
+    }
+
+    /// Socket address the SV1 server listens on, built from `downstream_address` and
+    /// `downstream_port`.
+    pub fn downstream_socket_addr(&self) -> Result<SocketAddr, TproxyError> {
+        let ip = self
+            .downstream_address
+            .parse()
+            .map_err(|_| TproxyError::InvalidAddress {
+                field: "downstream_address",
+                value: self.downstream_address.clone(),
+            })?;
+        Ok(SocketAddr::new(ip, self.downstream_port))
+    }
 
     /// Set the snapshot poll interval from shared config
     pub fn set_snapshot_poll_interval_secs(&mut self, interval: u64) {
@@ -156,6 +207,163 @@ impl TranslatorConfig {
     }
 }
 
+impl RoleConfig for TranslatorConfig {
+    const SERVICE: &'static str = "translator";
+
+    fn validate(&self) -> Result<(), String> {
+        check_fields(
+            &self.upstreams,
+            self.min_supported_version,
+            self.max_supported_version,
+            self.downstream_extranonce2_size,
+            &self.user_identity,
+        )
+        .map_err(|e| e.to_string())?;
+        if self.wallet.locking_pubkey.is_some() {
+            self.wallet.locking_pubkey_parsed().map_err(|reason| {
+                TproxyError::InvalidConfig {
+                    field: "wallet.locking_pubkey",
+                    reason,
+                }
+                .to_string()
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Resolved configuration as `(key, value)` pairs. The wallet mnemonic and locking private
+    /// key are redacted.
+    fn effective_config(&self) -> Vec<(&'static str, String)> {
+        fn opt<T: ToString>(value: Option<&T>) -> String {
+            value.map_or_else(|| "none".to_string(), T::to_string)
+        }
+        let upstreams = self
+            .upstreams
+            .iter()
+            .map(|u| format!("{}:{}", u.address, u.port))
+            .collect::<Vec<_>>()
+            .join(",");
+        let difficulty = &self.downstream_difficulty_config;
+        vec![
+            ("upstreams", upstreams),
+            (
+                "downstream",
+                format!("{}:{}", self.downstream_address, self.downstream_port),
+            ),
+            (
+                "supported_versions",
+                format!(
+                    "{}-{}",
+                    self.min_supported_version, self.max_supported_version
+                ),
+            ),
+            (
+                "downstream_extranonce2_size",
+                self.downstream_extranonce2_size.to_string(),
+            ),
+            ("user_identity", self.user_identity.clone()),
+            (
+                "min_individual_miner_hashrate",
+                difficulty.min_individual_miner_hashrate.to_string(),
+            ),
+            (
+                "shares_per_minute",
+                difficulty.shares_per_minute.to_string(),
+            ),
+            ("enable_vardiff", difficulty.enable_vardiff.to_string()),
+            (
+                "vardiff_min_interval_secs",
+                opt(difficulty.vardiff_min_interval_secs.as_ref()),
+            ),
+            (
+                "vardiff_max_step",
+                opt(difficulty.vardiff_max_step.as_ref()),
+            ),
+            ("aggregate_channels", self.aggregate_channels.to_string()),
+            (
+                "share_rate_limit",
+                match &self.share_rate_limit {
+                    Some(limit) => format!("{}/s burst {}", limit.shares_per_second, limit.burst),
+                    None => "none".to_string(),
+                },
+            ),
+            (
+                "upstream_grace_period_secs",
+                self.upstream_grace_period_secs.to_string(),
+            ),
+            ("wallet.mnemonic", REDACTED.to_string()),
+            ("wallet.db_path", self.wallet.db_path.clone()),
+            (
+                "wallet.locking_pubkey",
+                opt(self.wallet.locking_pubkey.as_ref()),
+            ),
+            (
+                "wallet.locking_privkey",
+                match self.wallet.locking_privkey {
+                    Some(_) => REDACTED.to_string(),
+                    None => "none".to_string(),
+                },
+            ),
+            ("mint.url", opt(self.mint.as_ref().map(|m| &m.url))),
+            (
+                "stats_server_address",
+                opt(self.stats_server_address.as_ref()),
+            ),
+            (
+                "snapshot_poll_interval_secs",
+                self.snapshot_poll_interval_secs.to_string(),
+            ),
+            ("redact_ip", self.redact_ip.to_string()),
+            ("faucet_port", self.faucet_port.to_string()),
+            ("faucet_timeout", self.faucet_timeout.to_string()),
+        ]
+    }
+}
+
+fn invalid(field: &'static str, reason: impl Into<String>) -> TproxyError {
+    TproxyError::InvalidConfig {
+        field,
+        reason: reason.into(),
+    }
+}
+
+/// Checks shared by [`TranslatorConfigBuilder::build`] and loading a config file
+fn check_fields(
+    upstreams: &[Upstream],
+    min_supported_version: u16,
+    max_supported_version: u16,
+    downstream_extranonce2_size: u16,
+    user_identity: &str,
+) -> Result<(), TproxyError> {
+    if upstreams.is_empty() {
+        return Err(invalid("upstreams", "at least one upstream is required"));
+    }
+    if min_supported_version > max_supported_version {
+        return Err(invalid(
+            "min_supported_version",
+            format!(
+                "{} is greater than max_supported_version {}",
+                min_supported_version, max_supported_version
+            ),
+        ));
+    }
+    // Miners need at least one byte to roll, and the whole extranonce is capped at
+    // MAX_EXTRANONCE_LEN bytes, of which the upstream also claims a share
+    if !(1..=MAX_EXTRANONCE_LEN).contains(&(downstream_extranonce2_size as usize)) {
+        return Err(invalid(
+            "downstream_extranonce2_size",
+            format!(
+                "must be between 1 and {} bytes, got {}",
+                MAX_EXTRANONCE_LEN, downstream_extranonce2_size
+            ),
+        ));
+    }
+    if user_identity.trim().is_empty() {
+        return Err(invalid("user_identity", "must not be empty"));
+    }
+    Ok(())
+}
+
 /// Configuration settings for managing difficulty adjustments on the downstream connection.
 #[derive(Debug, Deserialize, Clone)]
 pub struct DownstreamDifficultyConfig {
@@ -166,6 +374,13 @@ pub struct DownstreamDifficultyConfig {
     /// Whether to enable variable difficulty adjustment mechanism.
     /// If false, difficulty will be managed by upstream (useful with JDC).
     pub enable_vardiff: bool,
+    /// Seconds that must pass after a vardiff adjustment before the next one (15 if unset).
+    #[serde(default)]
+    pub vardiff_min_interval_secs: Option<u64>,
+    /// Largest factor a single vardiff adjustment may change a miner's hashrate by (unbounded
+    /// if unset).
+    #[serde(default)]
+    pub vardiff_max_step: Option<f32>,
 }
 
 impl DownstreamDifficultyConfig {
@@ -179,10 +394,160 @@ impl DownstreamDifficultyConfig {
             min_individual_miner_hashrate,
             shares_per_minute,
             enable_vardiff,
+            vardiff_min_interval_secs: None,
+            vardiff_max_step: None,
+        }
+    }
+
+    /// Fresh per-downstream vardiff state with the configured interval and step limits.
+    pub fn vardiff_state(&self) -> Result<VardiffState, VardiffError> {
+        let mut state = VardiffState::new()?;
+        if let Some(secs) = self.vardiff_min_interval_secs {
+            state = state.with_min_update_interval(secs);
+        }
+        if let Some(factor) = self.vardiff_max_step {
+            state = state.with_max_step(factor);
+        }
+        Ok(state)
+    }
+}
+
+/// Token bucket settings for share submissions on a single SV1 connection.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct ShareRateLimitConfig {
+    /// Sustained number of shares accepted per second.
+    pub shares_per_second: f64,
+    /// Number of shares that may arrive back to back before the sustained rate applies.
+    pub burst: u32,
+}
+
+/// Builds a [`TranslatorConfig`] through named setters instead of the positional arguments of
+/// [`TranslatorConfig::new`], validating the result in [`build`](Self::build).
+///
+/// Upstreams, the difficulty config, the wallet and the user identity must be set; everything
+/// else defaults to the values in `config/tproxy.config.toml`. Only used by tests.
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub struct TranslatorConfigBuilder {
+    upstreams: Vec<Upstream>,
+    downstream_address: String,
+    downstream_port: u16,
+    downstream_difficulty_config: Option<DownstreamDifficultyConfig>,
+    max_supported_version: u16,
+    min_supported_version: u16,
+    downstream_extranonce2_size: u16,
+    user_identity: String,
+    aggregate_channels: bool,
+    wallet: Option<WalletConfig>,
+    mint: Option<MintConfig>,
+}
+
+#[cfg(test)]
+impl Default for TranslatorConfigBuilder {
+    fn default() -> Self {
+        Self {
+            upstreams: Vec::new(),
+            downstream_address: "0.0.0.0".to_string(),
+            downstream_port: 34255,
+            downstream_difficulty_config: None,
+            max_supported_version: 2,
+            min_supported_version: 2,
+            downstream_extranonce2_size: 8,
+            user_identity: String::new(),
+            aggregate_channels: false,
+            wallet: None,
+            mint: None,
         }
     }
 }
 
+#[cfg(test)]
+impl TranslatorConfigBuilder {
+    /// Adds an upstream; upstreams are tried in the order they were added.
+    pub fn upstream(mut self, upstream: Upstream) -> Self {
+        self.upstreams.push(upstream);
+        self
+    }
+
+    pub fn downstream_address(mut self, address: impl Into<String>) -> Self {
+        self.downstream_address = address.into();
+        self
+    }
+
+    pub fn downstream_port(mut self, port: u16) -> Self {
+        self.downstream_port = port;
+        self
+    }
+
+    pub fn downstream_difficulty_config(mut self, config: DownstreamDifficultyConfig) -> Self {
+        self.downstream_difficulty_config = Some(config);
+        self
+    }
+
+    /// Sets the supported protocol version range, inclusive.
+    pub fn supported_versions(mut self, min: u16, max: u16) -> Self {
+        self.min_supported_version = min;
+        self.max_supported_version = max;
+        self
+    }
+
+    pub fn downstream_extranonce2_size(mut self, size: u16) -> Self {
+        self.downstream_extranonce2_size = size;
+        self
+    }
+
+    pub fn user_identity(mut self, user_identity: impl Into<String>) -> Self {
+        self.user_identity = user_identity.into();
+        self
+    }
+
+    pub fn aggregate_channels(mut self, aggregate: bool) -> Self {
+        self.aggregate_channels = aggregate;
+        self
+    }
+
+    pub fn wallet(mut self, wallet: WalletConfig) -> Self {
+        self.wallet = Some(wallet);
+        self
+    }
+
+    pub fn mint(mut self, mint: MintConfig) -> Self {
+        self.mint = Some(mint);
+        self
+    }
+
+    /// Validates the settings and builds the config.
+    pub fn build(self) -> Result<TranslatorConfig, TproxyError> {
+        check_fields(
+            &self.upstreams,
+            self.min_supported_version,
+            self.max_supported_version,
+            self.downstream_extranonce2_size,
+            &self.user_identity,
+        )?;
+        let downstream_difficulty_config = self
+            .downstream_difficulty_config
+            .ok_or_else(|| invalid("downstream_difficulty_config", "is required"))?;
+        let wallet = self
+            .wallet
+            .ok_or_else(|| invalid("wallet", "is required"))?;
+
+        Ok(TranslatorConfig::new(
+            self.upstreams,
+            self.downstream_address,
+            self.downstream_port,
+            downstream_difficulty_config,
+            self.max_supported_version,
+            self.min_supported_version,
+            self.downstream_extranonce2_size,
+            self.user_identity,
+            self.aggregate_channels,
+            wallet,
+            self.mint,
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -220,12 +585,12 @@ mod tests {
 
         let upstreams = vec![create_test_upstream()];
         let difficulty_config = create_test_difficulty_config();
-        let wallet = WalletConfig {
-            mnemonic: "test mnemonic".to_string(),
-            db_path: "/tmp/wallet.db".to_string(),
-            locking_pubkey: None,
-            locking_privkey: None,
-        };
+        let wallet = WalletConfig::new(
+            "test mnemonic".to_string(),
+            "/tmp/wallet.db".to_string(),
+            None,
+            None,
+        );
 
         let config = TranslatorConfig::new(
             upstreams,
@@ -258,12 +623,12 @@ mod tests {
 
         let upstreams = vec![create_test_upstream()];
         let difficulty_config = create_test_difficulty_config();
-        let wallet = WalletConfig {
-            mnemonic: "test mnemonic".to_string(),
-            db_path: "/tmp/wallet.db".to_string(),
-            locking_pubkey: None,
-            locking_privkey: None,
-        };
+        let wallet = WalletConfig::new(
+            "test mnemonic".to_string(),
+            "/tmp/wallet.db".to_string(),
+            None,
+            None,
+        );
 
         let mut config = TranslatorConfig::new(
             upstreams,
@@ -300,12 +665,12 @@ mod tests {
 
         let upstreams = vec![upstream1, upstream2];
         let difficulty_config = create_test_difficulty_config();
-        let wallet = WalletConfig {
-            mnemonic: "test mnemonic".to_string(),
-            db_path: "/tmp/wallet.db".to_string(),
-            locking_pubkey: None,
-            locking_privkey: None,
-        };
+        let wallet = WalletConfig::new(
+            "test mnemonic".to_string(),
+            "/tmp/wallet.db".to_string(),
+            None,
+            None,
+        );
 
         let config = TranslatorConfig::new(
             upstreams,
@@ -336,12 +701,12 @@ mod tests {
         difficulty_config.enable_vardiff = false;
 
         let upstreams = vec![create_test_upstream()];
-        let wallet = WalletConfig {
-            mnemonic: "test mnemonic".to_string(),
-            db_path: "/tmp/wallet.db".to_string(),
-            locking_pubkey: None,
-            locking_privkey: None,
-        };
+        let wallet = WalletConfig::new(
+            "test mnemonic".to_string(),
+            "/tmp/wallet.db".to_string(),
+            None,
+            None,
+        );
 
         let config = TranslatorConfig::new(
             upstreams,
@@ -360,4 +725,251 @@ mod tests {
         assert!(!config.downstream_difficulty_config.enable_vardiff);
         assert!(!config.aggregate_channels);
     }
+
+    #[test]
+    fn test_downstream_socket_addr_rejects_invalid_address() {
+        use shared_config::WalletConfig;
+
+        let wallet = WalletConfig::new(
+            "test mnemonic".to_string(),
+            "/tmp/wallet.db".to_string(),
+            None,
+            None,
+        );
+        let mut config = TranslatorConfig::new(
+            vec![create_test_upstream()],
+            "0.0.0.0".to_string(),
+            3333,
+            create_test_difficulty_config(),
+            2,
+            1,
+            4,
+            "test_user".to_string(),
+            true,
+            wallet,
+            None,
+        );
+        assert_eq!(
+            config.downstream_socket_addr().unwrap(),
+            "0.0.0.0:3333".parse().unwrap()
+        );
+
+        config.downstream_address = "not-an-ip".to_string();
+        let err = config.downstream_socket_addr().unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("downstream_address"));
+        assert!(message.contains("not-an-ip"));
+    }
+
+    #[test]
+    fn test_effective_config_redacts_wallet_secrets() {
+        use shared_config::WalletConfig;
+
+        let wallet = WalletConfig::new(
+            "abandon ability able".to_string(),
+            "/tmp/wallet.db".to_string(),
+            None,
+            Some("deadbeef".to_string()),
+        );
+        let config = TranslatorConfig::new(
+            vec![create_test_upstream()],
+            "0.0.0.0".to_string(),
+            3333,
+            create_test_difficulty_config(),
+            2,
+            1,
+            4,
+            "test_user".to_string(),
+            true,
+            wallet,
+            None,
+        );
+
+        let entries = config.effective_config();
+        let value = |key: &str| entries.iter().find(|(k, _)| *k == key).unwrap().1.clone();
+
+        assert_eq!(value("upstreams"), "127.0.0.1:4444");
+        assert_eq!(value("downstream"), "0.0.0.0:3333");
+        assert_eq!(value("wallet.db_path"), "/tmp/wallet.db");
+        assert_eq!(value("wallet.mnemonic"), REDACTED);
+        assert_eq!(value("wallet.locking_privkey"), REDACTED);
+        assert!(entries
+            .iter()
+            .all(|(_, v)| !v.contains("abandon") && !v.contains("deadbeef")));
+    }
+
+    #[test]
+    fn test_role_config_loads_validates_and_echoes() {
+        let toml = |user_identity: &str| {
+            format!(
+                r#"
+                downstream_address = "0.0.0.0"
+                downstream_port = 34255
+                max_supported_version = 2
+                min_supported_version = 2
+                downstream_extranonce2_size = 4
+                user_identity = "{user_identity}"
+                aggregate_channels = true
+
+                [downstream_difficulty_config]
+                min_individual_miner_hashrate = 10_000_000_000_000.0
+                shares_per_minute = 6.0
+                enable_vardiff = true
+
+                [[upstreams]]
+                address = "127.0.0.1"
+                port = 34254
+                authority_pubkey = "9auqWEzQDVyd2oe1JVGFLMLHZtCo2FFqZwtKA5gd9xbuEu7PH72"
+
+                [wallet]
+                mnemonic = "test mnemonic"
+                db_path = "/tmp/test_wallet.db"
+                "#
+            )
+        };
+        let dir = std::env::temp_dir();
+        let valid_path = dir.join(format!("tproxy-valid-{}.toml", std::process::id()));
+        let invalid_path = dir.join(format!("tproxy-invalid-{}.toml", std::process::id()));
+        std::fs::write(&valid_path, toml("test_user")).unwrap();
+        std::fs::write(&invalid_path, toml(" ")).unwrap();
+        let valid = TranslatorConfig::from_path(valid_path.to_str().unwrap());
+        let invalid = TranslatorConfig::from_path(invalid_path.to_str().unwrap());
+        std::fs::remove_file(&valid_path).unwrap();
+        std::fs::remove_file(&invalid_path).unwrap();
+
+        let config = valid.unwrap();
+        assert!(config.validate().is_ok());
+        let entries = config.effective_config();
+        let value = |key: &str| entries.iter().find(|(k, _)| *k == key).unwrap().1.clone();
+        assert_eq!(value("upstreams"), "127.0.0.1:34254");
+        assert_eq!(value("user_identity"), "test_user");
+
+        assert!(invalid.unwrap_err().to_string().contains("user_identity"));
+    }
+
+    #[test]
+    fn test_validate_rejects_bad_locking_pubkey() {
+        let with_pubkey = |pubkey: String| {
+            TranslatorConfig::builder()
+                .upstream(create_test_upstream())
+                .downstream_difficulty_config(create_test_difficulty_config())
+                .user_identity("test_user")
+                .wallet(WalletConfig::new(
+                    "test mnemonic".to_string(),
+                    "/tmp/test_wallet.db".to_string(),
+                    Some(pubkey),
+                    None,
+                ))
+                .build()
+                .unwrap()
+        };
+
+        let generator =
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798".to_string();
+        assert!(with_pubkey(generator).validate().is_ok());
+
+        let not_hex = with_pubkey("not-hex".to_string()).validate().unwrap_err();
+        assert!(not_hex.contains("wallet.locking_pubkey"));
+        let off_curve = with_pubkey(format!("02{}", "00".repeat(32)))
+            .validate()
+            .unwrap_err();
+        assert!(off_curve.contains("wallet.locking_pubkey"));
+    }
+
+    fn create_test_wallet() -> WalletConfig {
+        WalletConfig::new(
+            "test mnemonic".to_string(),
+            "/tmp/test_wallet.db".to_string(),
+            None,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_builder_matches_positional_constructor() {
+        let built = TranslatorConfig::builder()
+            .upstream(create_test_upstream())
+            .downstream_address("127.0.0.1")
+            .downstream_port(3333)
+            .downstream_difficulty_config(create_test_difficulty_config())
+            .supported_versions(1, 2)
+            .downstream_extranonce2_size(4)
+            .user_identity("test_user")
+            .aggregate_channels(true)
+            .wallet(create_test_wallet())
+            .build()
+            .unwrap();
+        let positional = TranslatorConfig::new(
+            vec![create_test_upstream()],
+            "127.0.0.1".to_string(),
+            3333,
+            create_test_difficulty_config(),
+            2,
+            1,
+            4,
+            "test_user".to_string(),
+            true,
+            create_test_wallet(),
+            None,
+        );
+
+        assert_eq!(built.effective_config(), positional.effective_config());
+        assert_eq!(built.min_supported_version, 1);
+        assert_eq!(built.max_supported_version, 2);
+    }
+
+    #[test]
+    fn test_extranonce2_size_must_fit_extranonce() {
+        let builder = TranslatorConfig::builder()
+            .upstream(create_test_upstream())
+            .downstream_difficulty_config(create_test_difficulty_config())
+            .user_identity("test_user")
+            .wallet(create_test_wallet());
+
+        for size in [1, 8, MAX_EXTRANONCE_LEN as u16] {
+            let config = builder
+                .clone()
+                .downstream_extranonce2_size(size)
+                .build()
+                .unwrap();
+            assert!(config.validate().is_ok());
+        }
+
+        let err = builder
+            .downstream_extranonce2_size(MAX_EXTRANONCE_LEN as u16 + 1)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid config field `downstream_extranonce2_size`: must be between 1 and 32 bytes, got 33"
+        );
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_settings() {
+        let valid = TranslatorConfig::builder()
+            .upstream(create_test_upstream())
+            .downstream_difficulty_config(create_test_difficulty_config())
+            .user_identity("test_user")
+            .wallet(create_test_wallet());
+        assert!(valid.clone().build().is_ok());
+
+        let field = |builder: TranslatorConfigBuilder| match builder.build() {
+            Err(TproxyError::InvalidConfig { field, .. }) => field,
+            other => panic!("expected InvalidConfig, got {:?}", other.map(|_| ())),
+        };
+        assert_eq!(
+            field(valid.clone().supported_versions(3, 2)),
+            "min_supported_version"
+        );
+        assert_eq!(field(valid.clone().user_identity("  ")), "user_identity");
+        assert_eq!(
+            field(valid.clone().downstream_extranonce2_size(0)),
+            "downstream_extranonce2_size"
+        );
+        assert_eq!(
+            field(TranslatorConfig::builder().user_identity("test_user")),
+            "upstreams"
+        );
+    }
 }
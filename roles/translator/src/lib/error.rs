@@ -9,6 +9,7 @@
 //!   asynchronous channels.
 
 use ext_config::ConfigError;
+use stats::DisconnectReason;
 use std::{fmt, sync::PoisonError};
 use stratum_common::roles_logic_sv2::{
     codec_sv2::{self, binary_sv2, framing_sv2},
@@ -70,19 +71,55 @@ pub enum TproxyError {
     Shutdown,
     /// Pending channel not found for the given request ID
     PendingChannelNotFound(u32),
+    /// Upstream opened a channel whose extranonce cannot hold the downstream extranonce2
+    ExtranonceSizeTooSmall {
+        channel_id: u32,
+        granted: u16,
+        required: usize,
+    },
     /// Represents a generic channel send failure, described by a string.
     General(String),
+    /// A configured host could not be resolved to a socket address
+    UnresolvableAddress(String),
+    /// A config field holds a value that is not a valid IP address
+    InvalidAddress { field: &'static str, value: String },
+    /// A config field holds a value that fails validation
+    InvalidConfig { field: &'static str, reason: String },
     /// Error bubbling up from translator-core library
     TranslatorCore(stratum_translation::error::StratumTranslationError),
 }
 
 impl std::error::Error for TproxyError {}
 
+impl TproxyError {
+    /// Why a downstream whose task failed with this error disconnected. A miner closing its
+    /// socket surfaces as a closed connection channel, while a closed broadcast channel means
+    /// the SV1 server side went away.
+    pub fn disconnect_reason(&self) -> DisconnectReason {
+        match self {
+            TproxyError::Io(_)
+            | TproxyError::ChannelErrorReceiver(_)
+            | TproxyError::ChannelErrorSender => DisconnectReason::ClientQuit,
+            TproxyError::BroadcastChannelErrorReceiver(_)
+            | TproxyError::TokioChannelErrorRecv(_)
+            | TproxyError::Shutdown => DisconnectReason::Shutdown,
+            _ => DisconnectReason::ProtocolError,
+        }
+    }
+}
+
 impl fmt::Display for TproxyError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use TproxyError::*;
         match self {
             General(e) => write!(f, "{e}"),
+            UnresolvableAddress(e) => write!(f, "Unable to resolve address {e}"),
+            InvalidAddress { field, value } => {
+                write!(f, "Invalid address in config field `{field}`: `{value}`")
+            }
+            InvalidConfig { field, reason } => {
+                write!(f, "Invalid config field `{field}`: {reason}")
+            }
             BadCliArgs => write!(f, "Bad CLI arg input"),
             BadSerdeJson(ref e) => write!(f, "Bad serde json: `{e:?}`"),
             BadConfigDeserialize(ref e) => write!(f, "Bad `config` TOML deserialize: `{e:?}`"),
@@ -114,6 +151,15 @@ impl fmt::Display for TproxyError {
             PendingChannelNotFound(request_id) => {
                 write!(f, "No pending channel found for request_id: {}", request_id)
             }
+            ExtranonceSizeTooSmall {
+                channel_id,
+                granted,
+                required,
+            } => write!(
+                f,
+                "Upstream granted channel {} a {}-byte extranonce, but downstream_extranonce2_size needs {}",
+                channel_id, granted, required
+            ),
             SV1Error => write!(f, "Sv1 error"),
             TranslatorCore(ref e) => write!(f, "Translator core error: {e:?}"),
             NetworkHelpersError(ref e) => write!(f, "Network helpers error: {e:?}"),
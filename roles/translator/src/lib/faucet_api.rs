@@ -1,4 +1,5 @@
 use std::convert::Infallible;
+use std::fmt;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -10,11 +11,90 @@ use http_body_util::Full;
 use tokio::net::TcpListener;
 use tokio::sync::Mutex;
 use tracing::{info, error, warn};
-use serde_json::json;
+use serde_json::{json, Value};
 
 use cdk::wallet::Wallet;
 use cdk::Amount;
 
+/// Ehash amount of each faucet token
+const FAUCET_AMOUNT: u64 = 32;
+
+/// Reasons a faucet request can fail, each reported with its own HTTP status
+#[derive(Debug)]
+pub enum FaucetError {
+    /// A token was handed out less than the faucet timeout ago
+    RateLimited { retry_after: Duration },
+    /// The wallet holds less ehash than one token needs
+    InsufficientBalance { available: u64, required: u64 },
+    /// The wallet or mint failed while preparing the token
+    MintUnavailable(String),
+    /// The request is not a faucet operation the API supports
+    InvalidRequest(String),
+}
+
+pub type FaucetResult<T> = Result<T, FaucetError>;
+
+impl FaucetError {
+    /// HTTP status the error is returned with
+    pub fn status(&self) -> StatusCode {
+        match self {
+            FaucetError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            FaucetError::InsufficientBalance { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            FaucetError::MintUnavailable(_) => StatusCode::BAD_GATEWAY,
+            FaucetError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    /// JSON body the error is returned with
+    pub fn body(&self) -> Value {
+        let mut body = json!({
+            "success": false,
+            "error": self.to_string(),
+        });
+        if let FaucetError::RateLimited { retry_after } = self {
+            body["retry_after_secs"] = json!(retry_after.as_secs());
+        }
+        body
+    }
+
+    fn into_response(self) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
+        json_response(self.status(), &self.body())
+    }
+}
+
+impl fmt::Display for FaucetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FaucetError::RateLimited { retry_after } => write!(
+                f,
+                "Rate limited. Try again in {} seconds",
+                retry_after.as_secs()
+            ),
+            FaucetError::InsufficientBalance {
+                available,
+                required,
+            } => write!(
+                f,
+                "Insufficient balance in wallet: {available} ehash available, {required} needed"
+            ),
+            FaucetError::MintUnavailable(e) => write!(f, "Minting failed: {e}"),
+            FaucetError::InvalidRequest(e) => write!(f, "Invalid request: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FaucetError {}
+
+fn json_response(
+    status: StatusCode,
+    body: &Value,
+) -> Result<Response<Full<Bytes>>, hyper::http::Error> {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(body.to_string())))
+}
+
 #[derive(Debug)]
 struct RateLimiter {
     last_request: Mutex<Option<Instant>>,
@@ -46,17 +126,23 @@ impl RateLimiter {
     }
 }
 
-async fn create_mint_token(wallet: Arc<Wallet>) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+async fn create_mint_token(wallet: Arc<Wallet>) -> FaucetResult<String> {
     // Create a 32 diff token (32 sat amount)
-    let amount = Amount::from(32u64);
+    let amount = Amount::from(FAUCET_AMOUNT);
 
     info!("🪙 Creating mint token for {} ehash", amount);
 
     // Check wallet balance first
-    let balance = wallet.total_balance().await?;
+    let balance = wallet
+        .total_balance()
+        .await
+        .map_err(|e| FaucetError::MintUnavailable(e.to_string()))?;
     if balance < amount {
         error!("❌ Insufficient balance in wallet: {} diff available, need {} ehash", balance, amount);
-        return Err("Insufficient balance in wallet".into());
+        return Err(FaucetError::InsufficientBalance {
+            available: u64::from(balance),
+            required: FAUCET_AMOUNT,
+        });
     }
 
     // First, swap to get exactly one proof of 32 sats
@@ -69,7 +155,10 @@ async fn create_mint_token(wallet: Arc<Wallet>) -> Result<String, Box<dyn std::e
         }
         Err(e) => {
             error!("❌ Failed to swap for exact amount: {:?}", e);
-            return Err(format!("Failed to prepare token: {}", e).into());
+            return Err(FaucetError::MintUnavailable(format!(
+                "Failed to prepare token: {}",
+                e
+            )));
         }
     };
 
@@ -86,53 +175,42 @@ async fn create_mint_token(wallet: Arc<Wallet>) -> Result<String, Box<dyn std::e
     Ok(token_string)
 }
 
+/// Hands out one token, unless the rate limit has not yet expired
+async fn mint_token(wallet: Arc<Wallet>, rate_limiter: &RateLimiter) -> FaucetResult<String> {
+    rate_limiter
+        .check_rate_limit()
+        .await
+        .map_err(|retry_after| FaucetError::RateLimited { retry_after })?;
+    info!("🪙 Mint request accepted");
+    create_mint_token(wallet).await
+}
+
 async fn handle_request(
     req: Request<hyper::body::Incoming>,
     wallet: Arc<Wallet>,
     rate_limiter: Arc<RateLimiter>,
 ) -> Result<Response<Full<Bytes>>, Infallible> {
     let response = match (req.method(), req.uri().path()) {
-        (&Method::POST, "/mint/tokens") => {
-            // Check mint rate limiting
-            match rate_limiter.check_rate_limit().await {
-                Ok(()) => {
-                    info!("🪙 Mint request accepted");
-                    match create_mint_token(wallet).await {
-                        Ok(token) => {
-                            let json_response = json!({
-                                "success": true,
-                                "token": token,
-                                "amount": 32
-                            });
-                            Response::builder()
-                                .header("content-type", "application/json")
-                                .body(Full::new(Bytes::from(json_response.to_string())))
-                        }
-                        Err(e) => {
-                            error!("Failed to create mint token: {}", e);
-                            let json_response = json!({
-                                "success": false,
-                                "error": format!("Minting failed: {}", e)
-                            });
-                            Response::builder()
-                                .status(StatusCode::INTERNAL_SERVER_ERROR)
-                                .header("content-type", "application/json")
-                                .body(Full::new(Bytes::from(json_response.to_string())))
-                        }
-                    }
-                }
-                Err(remaining) => {
-                    warn!("⏳ Mint request rate limited - {} seconds remaining", remaining.as_secs());
-                    let json_response = json!({
-                        "success": false,
-                        "error": format!("Rate limited. Try again in {} seconds", remaining.as_secs())
-                    });
-                    Response::builder()
-                        .status(StatusCode::TOO_MANY_REQUESTS)
-                        .header("content-type", "application/json")
-                        .body(Full::new(Bytes::from(json_response.to_string())))
+        (&Method::POST, "/mint/tokens") => match mint_token(wallet, &rate_limiter).await {
+            Ok(token) => json_response(
+                StatusCode::OK,
+                &json!({
+                    "success": true,
+                    "token": token,
+                    "amount": FAUCET_AMOUNT
+                }),
+            ),
+            Err(e) => {
+                match e {
+                    FaucetError::RateLimited { .. } => warn!("⏳ Mint request rejected: {}", e),
+                    _ => error!("Failed to create mint token: {}", e),
                 }
+                e.into_response()
             }
+        },
+        (method, "/mint/tokens") => {
+            FaucetError::InvalidRequest(format!("{} is not supported, use POST", method))
+                .into_response()
         }
         _ => {
             Response::builder()
@@ -187,3 +265,60 @@ pub async fn run_faucet_api(
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_faucet_errors_map_to_status_and_body() {
+        let cases = [
+            (
+                FaucetError::RateLimited {
+                    retry_after: Duration::from_secs(7),
+                },
+                StatusCode::TOO_MANY_REQUESTS,
+                json!({
+                    "success": false,
+                    "error": "Rate limited. Try again in 7 seconds",
+                    "retry_after_secs": 7
+                }),
+            ),
+            (
+                FaucetError::InsufficientBalance {
+                    available: 16,
+                    required: 32,
+                },
+                StatusCode::SERVICE_UNAVAILABLE,
+                json!({
+                    "success": false,
+                    "error": "Insufficient balance in wallet: 16 ehash available, 32 needed"
+                }),
+            ),
+            (
+                FaucetError::MintUnavailable("connection refused".to_string()),
+                StatusCode::BAD_GATEWAY,
+                json!({
+                    "success": false,
+                    "error": "Minting failed: connection refused"
+                }),
+            ),
+            (
+                FaucetError::InvalidRequest("GET is not supported, use POST".to_string()),
+                StatusCode::BAD_REQUEST,
+                json!({
+                    "success": false,
+                    "error": "Invalid request: GET is not supported, use POST"
+                }),
+            ),
+        ];
+
+        for (error, status, body) in cases {
+            assert_eq!(error.status(), status, "{error}");
+            assert_eq!(error.body(), body, "{error}");
+            let response = error.into_response().unwrap();
+            assert_eq!(response.status(), status);
+            assert_eq!(response.headers()["content-type"], "application/json");
+        }
+    }
+}
@@ -1,10 +1,17 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Instant;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
-use stats_sv2::WindowedMetricsCollector;
+use stats::{ConnectionCounters, ConnectionStats, DisconnectEvent, DisconnectReason, RejectReason};
+use stats_sv2::{types::unix_timestamp, WindowedMetricsCollector, UNKNOWN_USER_AGENT};
+
+/// Longest user agent kept per miner, in characters
+const MAX_USER_AGENT_LEN: usize = 64;
+
+/// Disconnects kept for snapshots; older ones are dropped first
+const MAX_RECENT_DISCONNECTS: usize = 32;
 
 #[derive(Debug, Clone)]
 pub struct MinerInfo {
@@ -15,11 +22,21 @@ pub struct MinerInfo {
     pub shares_submitted: u64,
     pub last_share_time: Option<Instant>,
     pub estimated_hashrate: f64, // H/s
+    /// Client software from `mining.subscribe`, `None` until the miner reports one
+    pub user_agent: Option<String>,
 
     // Shared windowed metrics collector (60-second / 1-minute window)
     pub metrics_collector: WindowedMetricsCollector,
 }
 
+impl MinerInfo {
+    pub fn user_agent_or_unknown(&self) -> String {
+        self.user_agent
+            .clone()
+            .unwrap_or_else(|| UNKNOWN_USER_AGENT.to_string())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MinerStats {
     pub total_miners: usize,
@@ -36,12 +53,15 @@ pub struct MinerApiInfo {
     pub hashrate: String,
     pub shares: u64,
     pub connected_time: String,
+    pub user_agent: String,
 }
 
 #[derive(Debug)]
 pub struct MinerTracker {
     miners: Arc<RwLock<HashMap<u32, MinerInfo>>>,
     next_id: Arc<RwLock<u32>>,
+    connections: ConnectionCounters,
+    recent_disconnects: RwLock<VecDeque<DisconnectEvent>>,
 }
 
 impl MinerTracker {
@@ -49,9 +69,31 @@ impl MinerTracker {
         Self {
             miners: Arc::new(RwLock::new(HashMap::new())),
             next_id: Arc::new(RwLock::new(1)),
+            connections: ConnectionCounters::new(),
+            recent_disconnects: RwLock::new(VecDeque::new()),
         }
     }
 
+    /// Count a miner connection let through by the listener.
+    pub fn record_accepted_connection(&self) {
+        self.connections.record_accepted();
+    }
+
+    /// Count a miner connection turned away, returning the new total for `reason`.
+    pub fn record_rejected_connection(&self, reason: RejectReason) -> u64 {
+        self.connections.record_rejected(reason)
+    }
+
+    /// Miner connections turned away since startup, for any reason.
+    pub fn rejected_connections(&self) -> u64 {
+        self.connections.snapshot().total_rejected()
+    }
+
+    /// Accepted and rejected miner connections since startup.
+    pub fn connection_stats(&self) -> ConnectionStats {
+        self.connections.snapshot()
+    }
+
     pub async fn add_miner(&self, address: SocketAddr, name: String) -> u32 {
         let mut next_id = self.next_id.write().await;
         let id = *next_id;
@@ -66,6 +108,7 @@ impl MinerTracker {
             shares_submitted: 0,
             last_share_time: None,
             estimated_hashrate: 0.0,
+            user_agent: None,
             metrics_collector: WindowedMetricsCollector::new(60), // 60-second (1-minute) window
         };
 
@@ -77,6 +120,29 @@ impl MinerTracker {
         self.miners.write().await.remove(&id);
     }
 
+    /// Remove a miner and remember why it left, so snapshots still show it after it is gone.
+    /// Does nothing if the miner was already removed.
+    pub async fn record_disconnect(&self, id: u32, reason: DisconnectReason) {
+        let Some(miner) = self.miners.write().await.remove(&id) else {
+            return;
+        };
+        let mut recent = self.recent_disconnects.write().await;
+        if recent.len() == MAX_RECENT_DISCONNECTS {
+            recent.pop_front();
+        }
+        recent.push_back(DisconnectEvent {
+            downstream_id: id,
+            name: miner.name,
+            reason,
+            timestamp: unix_timestamp(),
+        });
+    }
+
+    /// The last [`MAX_RECENT_DISCONNECTS`] disconnects, oldest first.
+    pub async fn recent_disconnects(&self) -> Vec<DisconnectEvent> {
+        Vec::from(self.recent_disconnects.read().await.clone())
+    }
+
     pub async fn increment_shares(&self, id: u32, current_hashrate: f32) {
         let mut miners = self.miners.write().await;
         if let Some(miner) = miners.get_mut(&id) {
@@ -113,6 +179,19 @@ impl MinerTracker {
         }
     }
 
+    /// Record the client software a miner reported. Blank strings are ignored and long ones
+    /// truncated, since the value comes straight from the miner.
+    pub async fn update_user_agent(&self, id: u32, user_agent: &str) {
+        let user_agent = user_agent.trim();
+        if user_agent.is_empty() {
+            return;
+        }
+        let mut miners = self.miners.write().await;
+        if let Some(miner) = miners.get_mut(&id) {
+            miner.user_agent = Some(user_agent.chars().take(MAX_USER_AGENT_LEN).collect());
+        }
+    }
+
     pub async fn get_hashrate(&self, id: u32) -> Option<f64> {
         let miners = self.miners.read().await;
         miners.get(&id).map(|m| m.estimated_hashrate)
@@ -175,6 +254,7 @@ impl MinerTracker {
                 hashrate,
                 shares: miner.shares_submitted,
                 connected_time,
+                user_agent: miner.user_agent_or_unknown(),
             }
         }).collect();
 
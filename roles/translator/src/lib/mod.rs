@@ -20,18 +20,30 @@ use cdk::{
     Amount, MiningShareBatchEntry,
 };
 use cdk_sqlite::WalletSqliteDatabase;
-use std::{collections::HashMap, net::SocketAddr, path::Path, str::FromStr, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::Path,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 use tokio::{sync::mpsc, time::Duration};
 use tracing::{debug, error, info, warn};
 
 pub use v1::server_to_client;
 
 use config::TranslatorConfig;
+use quote_keyset::WalletKeysets;
 
 use crate::{
     status::{State, Status},
     sv1::sv1_server::sv1_server::Sv1Server,
-    sv2::{channel_manager::ChannelMode, ChannelManager, Upstream},
+    sv2::{
+        channel_manager::ChannelMode, upstream::reconnect_grace::ReconnectGrace, ChannelManager,
+        Upstream,
+    },
     task_manager::TaskManager,
     utils::ShutdownMessage,
 };
@@ -40,6 +52,7 @@ pub mod config;
 pub mod error;
 pub mod faucet_api;
 pub mod miner_stats;
+mod quote_keyset;
 pub mod stats_integration;
 pub mod status;
 pub mod sv1;
@@ -53,6 +66,8 @@ pub struct TranslatorSv2 {
     config: TranslatorConfig,
     wallet: Option<Arc<Wallet>>,
     miner_tracker: Arc<miner_stats::MinerTracker>,
+    // Whether an upstream connection is currently up, for the health status
+    upstream_connected: Arc<AtomicBool>,
 }
 
 impl std::fmt::Debug for TranslatorSv2 {
@@ -75,6 +90,7 @@ impl TranslatorSv2 {
             config,
             wallet: None,
             miner_tracker: Arc::new(miner_stats::MinerTracker::new()),
+            upstream_connected: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -205,25 +221,27 @@ impl TranslatorSv2 {
         let stats_poll_interval = self.config.snapshot_poll_interval_secs;
         let translator_clone = self.clone();
         if let Some(stats_addr) = stats_addr_opt {
-            use stats::stats_adapter::StatsSnapshotProvider;
-            use stats::stats_client::StatsClient;
-
             info!("Starting stats polling loop, sending to {} every {} seconds",
                   stats_addr, stats_poll_interval);
 
-            let translator_for_stats = translator_clone.clone();
-            let stats_addr_clone = stats_addr.clone();
-            task_manager.spawn(async move {
-                let mut interval = tokio::time::interval(std::time::Duration::from_secs(stats_poll_interval));
-                let status_client = StatsClient::new(stats_addr.clone());
-                let metrics_client = StatsClient::new(stats_addr_clone);
-
+            // Stop with a final snapshot on full shutdown so the last window of metrics is
+            // delivered before the process exits
+            let mut shutdown_rx = notify_shutdown.subscribe();
+            let shutdown = async move {
                 loop {
-                    interval.tick().await;
-                    let _ = status_client.send_snapshot(translator_for_stats.get_snapshot()).await;
-                    let _ = metrics_client.send_snapshot(translator_for_stats.get_metrics_snapshot()).await;
+                    match shutdown_rx.recv().await {
+                        Ok(ShutdownMessage::ShutdownAll)
+                        | Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                        _ => {}
+                    }
                 }
-            });
+            };
+            task_manager.spawn(stats::stats_poller::start_stats_polling_until(
+                translator_clone,
+                stats_addr,
+                std::time::Duration::from_secs(stats_poll_interval),
+                shutdown,
+            ));
         }
 
         let (status_sender, status_receiver) = async_channel::unbounded::<Status>();
@@ -244,9 +262,11 @@ impl TranslatorSv2 {
             .upstreams
             .iter()
             .map(|upstream| {
-                let upstream_addr =
-                    SocketAddr::new(upstream.address.parse().unwrap(), upstream.port);
-                (upstream_addr, upstream.authority_pubkey)
+                (
+                    upstream.address.clone(),
+                    upstream.port,
+                    upstream.authority_pubkey,
+                )
             })
             .collect::<Vec<_>>();
 
@@ -282,10 +302,13 @@ impl TranslatorSv2 {
             self.wallet.clone(),
         ));
 
-        let downstream_addr = SocketAddr::new(
-            self.config.downstream_address.parse().unwrap(),
-            self.config.downstream_port,
-        );
+        let downstream_addr = match self.config.downstream_socket_addr() {
+            Ok(addr) => addr,
+            Err(e) => {
+                error!("{e}");
+                return;
+            }
+        };
 
         let sv1_server = Arc::new(Sv1Server::new(
             downstream_addr,
@@ -316,13 +339,18 @@ impl TranslatorSv2 {
             error!("Failed to start upstream listener: {e:?}");
             return;
         }
+        self.upstream_connected.store(true, Ordering::Relaxed);
 
         let notify_shutdown_clone = notify_shutdown.clone();
         let shutdown_complete_tx_clone = shutdown_complete_tx.clone();
         let status_sender_clone = status_sender.clone();
         let task_manager_clone = task_manager.clone();
+        let upstream_connected = self.upstream_connected.clone();
+        let mut reconnect_grace =
+            ReconnectGrace::new(Duration::from_secs(self.config.upstream_grace_period_secs));
         task_manager.spawn(async move {
             loop {
+                let reset_deadline = reconnect_grace.deadline();
                 tokio::select! {
                     _ = tokio::signal::ctrl_c() => {
                         info!("Ctrl+C received — initiating graceful shutdown...");
@@ -348,6 +376,10 @@ impl TranslatorSv2 {
                                 }
                                 State::UpstreamShutdown(msg) => {
                                     warn!("Upstream connection dropped: {msg:?} — attempting reconnection...");
+                                    upstream_connected.store(false, Ordering::Relaxed);
+                                    if reconnect_grace.dropped() {
+                                        warn!("Upstream dropped within the {:?} grace period — downstream reset postponed.", reconnect_grace.period());
+                                    }
 
                                     match Upstream::new(
                                         &upstream_addresses,
@@ -371,8 +403,13 @@ impl TranslatorSv2 {
                                                 break;
                                             } else {
                                                 info!("Upstream restarted successfully.");
-                                                // Reset channel manager state and shutdown downstreams in one message
-                                                let _ = notify_shutdown_clone.send(ShutdownMessage::UpstreamReconnectedResetAndShutdownDownstreams);
+                                                upstream_connected.store(true, Ordering::Relaxed);
+                                                if reconnect_grace.reconnected(tokio::time::Instant::now()) {
+                                                    // Reset channel manager state and shutdown downstreams in one message
+                                                    let _ = notify_shutdown_clone.send(ShutdownMessage::UpstreamReconnectedResetAndShutdownDownstreams);
+                                                } else {
+                                                    info!("Waiting {:?} for the upstream to stabilize before resetting downstreams.", reconnect_grace.period());
+                                                }
                                             }
                                         }
                                         Err(e) => {
@@ -385,6 +422,12 @@ impl TranslatorSv2 {
                             }
                         }
                     }
+                    _ = tokio::time::sleep_until(reset_deadline.unwrap_or_else(tokio::time::Instant::now)), if reset_deadline.is_some() => {
+                        if reconnect_grace.take_due(tokio::time::Instant::now()) {
+                            info!("Upstream stable for {:?} — resetting downstreams.", reconnect_grace.period());
+                            let _ = notify_shutdown_clone.send(ShutdownMessage::UpstreamReconnectedResetAndShutdownDownstreams);
+                        }
+                    }
                 }
             }
         });
@@ -420,11 +463,23 @@ impl TranslatorSv2 {
     }
 
     fn spawn_quote_sweeper(&self, task_manager: &Arc<TaskManager>, wallet: Arc<Wallet>) {
-        let locking_privkey = self.config.wallet.locking_privkey.clone();
-
-        if locking_privkey.is_none() {
-            warn!("Quote sweeper running without locking_privkey; minted tokens cannot be signed");
-        }
+        // `start` has already run `WalletConfig::initialize`, so the key is decoded once here
+        // rather than from hex on every sweep
+        let secret_key = match self.config.wallet.locking_privkey_parsed() {
+            Some(key) => match SecretKey::from_slice(&key.secret_bytes()) {
+                Ok(sk) => Some(sk),
+                Err(e) => {
+                    error!("Invalid secret key format: {}", e);
+                    None
+                }
+            },
+            None => {
+                warn!(
+                    "Quote sweeper running without locking_privkey; minted tokens cannot be signed"
+                );
+                None
+            }
+        };
 
         task_manager.spawn(async move {
             let mut loop_count: u64 = 0;
@@ -433,7 +488,7 @@ impl TranslatorSv2 {
                 info!("🕐 Quote sweeper loop #{} starting", loop_count);
 
                 debug!("📞 About to call process_stored_quotes");
-                match Self::process_stored_quotes(&wallet, locking_privkey.as_deref()).await {
+                match Self::process_stored_quotes(&wallet, secret_key.as_ref()).await {
                     Ok(_minted_amount) => {
                         if let Ok(balance) = wallet.total_balance().await {
                             info!("💰 Wallet balance after sweep: {} ehash", balance);
@@ -453,7 +508,7 @@ impl TranslatorSv2 {
 
     async fn process_stored_quotes(
         wallet: &Arc<Wallet>,
-        locking_privkey: Option<&str>,
+        secret_key: Option<&SecretKey>,
     ) -> Result<u64> {
         let pending_quotes = match wallet.get_unpaid_mint_quotes().await {
             Ok(quotes) => quotes,
@@ -483,7 +538,18 @@ impl TranslatorSv2 {
             return Ok(0);
         }
 
-        let mut quotes_by_keyset: HashMap<Id, Vec<MiningShareBatchEntry>> = HashMap::new();
+        let keysets = match wallet.get_mint_keysets().await {
+            Ok(keysets) => WalletKeysets::new(
+                wallet.unit.clone(),
+                keysets.into_iter().map(|keyset| (keyset.id, keyset.unit)),
+            ),
+            Err(e) => {
+                error!("Failed to fetch mint keysets: {}", e);
+                return Ok(0);
+            }
+        };
+
+        let mut mintable = Vec::new();
 
         for quote_id in quote_ids.iter() {
             debug!("🔍 Fetching quote {} from mint", quote_id);
@@ -519,14 +585,7 @@ impl TranslatorSv2 {
                         continue;
                     }
 
-                    quotes_by_keyset
-                        .entry(quote_response.keyset_id)
-                        .or_default()
-                        .push(MiningShareBatchEntry::new(
-                            quote_id.clone(),
-                            amount_to_mint,
-                            quote_response.keyset_id,
-                        ));
+                    mintable.push((quote_id.clone(), quote_response.keyset_id, amount_to_mint));
                 }
                 Err(e) => {
                     warn!("Failed to fetch quote {} details: {}", quote_id, e);
@@ -534,25 +593,14 @@ impl TranslatorSv2 {
             }
         }
 
+        let quotes_by_keyset = keysets.group_by_keyset(mintable);
         if quotes_by_keyset.is_empty() {
             warn!("😞 No tokens were minted from any quotes");
             return Ok(0);
         }
 
-        let secret_key = match locking_privkey {
-            Some(privkey_hex) => match hex::decode(privkey_hex) {
-                Ok(privkey_bytes) => match SecretKey::from_slice(&privkey_bytes) {
-                    Ok(sk) => sk,
-                    Err(e) => {
-                        error!("Invalid secret key format: {}", e);
-                        return Ok(0);
-                    }
-                },
-                Err(e) => {
-                    error!("Failed to decode secret key hex: {}", e);
-                    return Ok(0);
-                }
-            },
+        let secret_key = match secret_key {
+            Some(sk) => sk,
             None => {
                 error!("Secret key is required for mining share minting");
                 return Ok(0);
@@ -562,9 +610,13 @@ impl TranslatorSv2 {
         let mut total_minted = 0u64;
         let mut minted_quote_count = 0usize;
 
-        for (keyset_id, entries) in quotes_by_keyset.into_iter() {
+        for (keyset_id, quotes) in quotes_by_keyset.into_iter() {
+            let entries: Vec<MiningShareBatchEntry> = quotes
+                .into_iter()
+                .map(|(quote_id, amount)| MiningShareBatchEntry::new(quote_id, amount, keyset_id))
+                .collect();
             debug!(?keyset_id, quote_count = entries.len(), "Minting mining share batch");
-            match wallet.mint_mining_share_batch(&entries, &secret_key).await {
+            match wallet.mint_mining_share_batch(&entries, secret_key).await {
                 Ok(proofs) => {
                     let batch_amount: u64 = proofs.iter().map(|p| u64::from(p.amount)).sum();
                     total_minted += batch_amount;
@@ -0,0 +1,122 @@
+//! Keyset check for mining share quotes
+//!
+//! The sweeper mints each paid quote against the keyset the mint reports for it. A quote
+//! whose keyset belongs to another unit, or that the mint doesn't advertise at all, fails
+//! deep inside the mint call with an unhelpful error, so quotes are checked up front.
+
+use anyhow::{bail, Result};
+use cdk::{
+    nuts::{CurrencyUnit, Id},
+    Amount,
+};
+use std::collections::HashMap;
+use tracing::warn;
+
+/// The mint's keysets, checked against the unit the wallet mints
+#[derive(Debug, Clone)]
+pub(crate) struct WalletKeysets {
+    unit: CurrencyUnit,
+    units_by_id: HashMap<Id, CurrencyUnit>,
+}
+
+impl WalletKeysets {
+    /// `keysets` are every keyset the mint advertises, with their units
+    pub(crate) fn new(
+        unit: CurrencyUnit,
+        keysets: impl IntoIterator<Item = (Id, CurrencyUnit)>,
+    ) -> Self {
+        Self {
+            unit,
+            units_by_id: keysets.into_iter().collect(),
+        }
+    }
+
+    /// Ok if `keyset_id` is one of the mint's keysets in the wallet's unit
+    pub(crate) fn verify(&self, keyset_id: Id) -> Result<()> {
+        match self.units_by_id.get(&keyset_id) {
+            Some(unit) if *unit == self.unit => Ok(()),
+            Some(unit) => bail!(
+                "keyset {} is for unit {}, but the wallet mints {}",
+                keyset_id,
+                unit,
+                self.unit
+            ),
+            None => bail!("keyset {} is not one of the mint's keysets", keyset_id),
+        }
+    }
+
+    /// Group `(quote_id, keyset_id, amount)` quotes by keyset for batch minting. Quotes whose
+    /// keyset fails [`Self::verify`] are logged and left out, so they never reach the mint.
+    pub(crate) fn group_by_keyset(
+        &self,
+        quotes: impl IntoIterator<Item = (String, Id, Amount)>,
+    ) -> HashMap<Id, Vec<(String, Amount)>> {
+        let mut by_keyset: HashMap<Id, Vec<(String, Amount)>> = HashMap::new();
+        for (quote_id, keyset_id, amount) in quotes {
+            if let Err(e) = self.verify(keyset_id) {
+                warn!("Skipping quote {}: {}", quote_id, e);
+                continue;
+            }
+            by_keyset
+                .entry(keyset_id)
+                .or_default()
+                .push((quote_id, amount));
+        }
+        by_keyset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_mismatched_keyset_is_rejected() {
+        let hash_keyset = Id::from_str("009a1f293253e41e").unwrap();
+        let sat_keyset = Id::from_str("00ad268c4d1f5826").unwrap();
+        let hash = CurrencyUnit::Custom("HASH".to_string());
+        let keysets = WalletKeysets::new(
+            hash.clone(),
+            [(hash_keyset, hash), (sat_keyset, CurrencyUnit::Sat)],
+        );
+
+        assert!(keysets.verify(hash_keyset).is_ok());
+        let err = keysets.verify(sat_keyset).unwrap_err().to_string();
+        assert!(err.contains("but the wallet mints"), "{err}");
+
+        let unknown = Id::from_str("00456a94ab4e1c46").unwrap();
+        let err = keysets.verify(unknown).unwrap_err().to_string();
+        assert!(err.contains("not one of the mint's keysets"), "{err}");
+    }
+
+    #[test]
+    fn test_mismatched_quotes_are_left_out_of_mint_batches() {
+        let hash_keyset = Id::from_str("009a1f293253e41e").unwrap();
+        let sat_keyset = Id::from_str("00ad268c4d1f5826").unwrap();
+        let unknown = Id::from_str("00456a94ab4e1c46").unwrap();
+        let hash = CurrencyUnit::Custom("HASH".to_string());
+        let keysets = WalletKeysets::new(
+            hash.clone(),
+            [(hash_keyset, hash), (sat_keyset, CurrencyUnit::Sat)],
+        );
+
+        let batches = keysets.group_by_keyset([
+            ("hash-1".to_string(), hash_keyset, Amount::from(64)),
+            ("sat".to_string(), sat_keyset, Amount::from(8)),
+            ("hash-2".to_string(), hash_keyset, Amount::from(128)),
+            ("unknown".to_string(), unknown, Amount::from(2)),
+        ]);
+
+        assert_eq!(
+            batches,
+            HashMap::from([(
+                hash_keyset,
+                vec![
+                    ("hash-1".to_string(), Amount::from(64)),
+                    ("hash-2".to_string(), Amount::from(128)),
+                ]
+            )])
+        );
+    }
+}
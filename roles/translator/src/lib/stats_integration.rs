@@ -4,6 +4,7 @@
 //! to the stats service for web dashboard consumption.
 
 use super::TranslatorSv2;
+use stats::health::HealthInputs;
 use stats::stats_adapter::{MinerInfo, PoolConnection, TranslatorStatus, StatsSnapshotProvider};
 use stats_sv2::types::{DownstreamSnapshot, ServiceSnapshot, ServiceType, unix_timestamp};
 use stats_sv2::metrics::derive_hashrate;
@@ -19,6 +20,7 @@ fn _unix_timestamp_helper() -> u64 {
 
 impl StatsSnapshotProvider for TranslatorSv2 {
     type Snapshot = TranslatorStatus;
+    type MetricsSnapshot = ServiceSnapshot;
 
     fn get_snapshot(&self) -> TranslatorStatus {
         // Get wallet balance if wallet is available
@@ -53,6 +55,7 @@ impl StatsSnapshotProvider for TranslatorSv2 {
                 all_miners.into_iter().map(|miner| {
                     let elapsed_secs = miner.connected_time.elapsed().as_secs();
                     let connected_timestamp = now.saturating_sub(elapsed_secs);
+                    let user_agent = miner.user_agent_or_unknown();
                     let address = if self.config.redact_ip {
                         "REDACTED".to_string()
                     } else {
@@ -71,30 +74,45 @@ impl StatsSnapshotProvider for TranslatorSv2 {
                         hashrate,
                         shares_submitted: miner.shares_submitted,
                         connected_at: connected_timestamp,
+                        user_agent,
                     }
                 }).collect()
             })
         });
 
+        let recent_disconnects = tokio::task::block_in_place(|| {
+            tokio::runtime::Handle::current().block_on(self.miner_tracker.recent_disconnects())
+        });
+
         // Get blockchain network from environment variable
         let blockchain_network = std::env::var("BITCOIND_NETWORK")
             .unwrap_or_else(|_| "unknown".to_string())
             .to_lowercase();
 
+        // The translator has no mint connection or quote backlog of its own
+        let health = HealthInputs {
+            upstream_connected: self.upstream_connected.load(std::sync::atomic::Ordering::Relaxed),
+            mint_available: None,
+            pending_quotes: 0,
+        }
+        .status();
+
         TranslatorStatus {
             ehash_balance,
             upstream_pool,
             downstream_miners,
             blockchain_network,
+            rejected_connections: self.miner_tracker.rejected_connections(),
+            connections: self.miner_tracker.connection_stats(),
+            recent_disconnects,
+            health,
             timestamp: unix_timestamp(),
         }
     }
-}
 
-impl TranslatorSv2 {
     /// Get a ServiceSnapshot for time-series metrics collection.
     /// Uses the WindowedMetricsCollector to get windowed difficulty sums (shared implementation).
-    pub fn get_metrics_snapshot(&self) -> ServiceSnapshot {
+    fn get_metrics_snapshot(&self) -> ServiceSnapshot {
         let downstreams = tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(async {
                 self.miner_tracker.get_all_miners().await
@@ -102,6 +120,7 @@ impl TranslatorSv2 {
                     .map(|miner| {
                         DownstreamSnapshot {
                             downstream_id: miner.id,
+                            user_agent: miner.user_agent_or_unknown(),
                             name: miner.name,
                             address: if self.config.redact_ip {
                                 "REDACTED".to_string()
@@ -126,3 +145,106 @@ impl TranslatorSv2 {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DownstreamDifficultyConfig, TranslatorConfig, Upstream};
+    use crate::error::TproxyError;
+    use key_utils::Secp256k1PublicKey;
+    use shared_config::WalletConfig;
+    use stats::DisconnectReason;
+    use std::str::FromStr;
+
+    fn create_test_translator() -> TranslatorSv2 {
+        let pubkey =
+            Secp256k1PublicKey::from_str("9bDuixKmZqAJnrmP746n8zU1wyAQRrus7th9dxnkPg6RzQvCnan")
+                .unwrap();
+        let wallet = WalletConfig::new(
+            "test mnemonic".to_string(),
+            "/tmp/test_wallet.db".to_string(),
+            None,
+            None,
+        );
+        TranslatorSv2::new(TranslatorConfig::new(
+            vec![Upstream::new("127.0.0.1".to_string(), 4444, pubkey)],
+            "0.0.0.0".to_string(),
+            3333,
+            DownstreamDifficultyConfig::new(100.0, 5.0, true),
+            2,
+            1,
+            4,
+            "test_user".to_string(),
+            true,
+            wallet,
+            None,
+        ))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_reported_user_agent_surfaces_in_snapshots() {
+        let translator = create_test_translator();
+        let tracker = translator.miner_tracker.clone();
+        let reporting = tracker
+            .add_miner("10.0.0.1:4444".parse().unwrap(), "Miner-1".to_string())
+            .await;
+        let silent = tracker
+            .add_miner("10.0.0.2:4444".parse().unwrap(), "Miner-2".to_string())
+            .await;
+        tracker.update_user_agent(reporting, "cgminer/4.11.1").await;
+        tracker.update_user_agent(silent, "  ").await;
+
+        let metrics = translator.get_metrics_snapshot();
+        let user_agent = |id: u32| {
+            metrics
+                .downstreams
+                .iter()
+                .find(|d| d.downstream_id == id)
+                .map(|d| d.user_agent.clone())
+        };
+        assert_eq!(user_agent(reporting).as_deref(), Some("cgminer/4.11.1"));
+        assert_eq!(user_agent(silent).as_deref(), Some("unknown"));
+
+        let status = translator.get_snapshot();
+        let miner = status
+            .downstream_miners
+            .iter()
+            .find(|m| m.id == reporting)
+            .unwrap();
+        assert_eq!(miner.user_agent, "cgminer/4.11.1");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_disconnect_reason_surfaces_in_snapshot() {
+        let translator = create_test_translator();
+        let tracker = translator.miner_tracker.clone();
+        let quitter = tracker
+            .add_miner("10.0.0.1:4444".parse().unwrap(), "Miner-1".to_string())
+            .await;
+        let misbehaving = tracker
+            .add_miner("10.0.0.2:4444".parse().unwrap(), "Miner-2".to_string())
+            .await;
+
+        tracker
+            .record_disconnect(quitter, TproxyError::ChannelErrorSender.disconnect_reason())
+            .await;
+        tracker
+            .record_disconnect(misbehaving, TproxyError::SV1Error.disconnect_reason())
+            .await;
+
+        let status = translator.get_snapshot();
+        assert!(status.downstream_miners.is_empty());
+        let reasons: Vec<_> = status
+            .recent_disconnects
+            .iter()
+            .map(|event| (event.downstream_id, event.name.as_str(), event.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (quitter, "Miner-1", DisconnectReason::ClientQuit),
+                (misbehaving, "Miner-2", DisconnectReason::ProtocolError),
+            ]
+        );
+    }
+}
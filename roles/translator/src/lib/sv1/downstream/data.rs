@@ -6,7 +6,7 @@ use stratum_common::roles_logic_sv2::{mining_sv2::Target, utils::Mutex};
 use tracing::debug;
 use v1::{json_rpc, utils::HexU32Be};
 
-use super::SubmitShareWithChannelId;
+use super::{share_rate_limit::ShareRateLimiter, SubmitShareWithChannelId};
 use crate::sv1::sv1_server::data::Sv1ServerData;
 
 #[derive(Debug)]
@@ -36,6 +36,8 @@ pub struct DownstreamData {
     pub processing_queued_sv1_handshake_responses: AtomicBool,
     // Stores pending shares to be sent to the sv1_server
     pub pending_share: RefCell<Option<SubmitShareWithChannelId>>,
+    // Token bucket for share submissions, present when `share_rate_limit` is configured
+    pub share_rate_limiter: RefCell<Option<ShareRateLimiter>>,
     // Reference to shared sv1_server data for accessing valid_jobs during downstream sv1
     // validation
     pub sv1_server_data: Arc<Mutex<Sv1ServerData>>,
@@ -72,6 +74,7 @@ impl DownstreamData {
             queued_sv1_handshake_messages: Vec::new(),
             processing_queued_sv1_handshake_responses: AtomicBool::new(false),
             pending_share: RefCell::new(None),
+            share_rate_limiter: RefCell::new(None),
             sv1_server_data,
             upstream_target: None,
         }
@@ -10,6 +10,7 @@ use crate::{
     utils::ShutdownMessage,
 };
 use async_channel::{Receiver, Sender};
+use stats::DisconnectReason;
 use std::sync::Arc;
 use stratum_common::roles_logic_sv2::{mining_sv2::Target, utils::Mutex};
 use tokio::sync::{broadcast, mpsc};
@@ -101,6 +102,8 @@ impl Downstream {
         let mut shutdown_rx = notify_shutdown.subscribe();
         let downstream_id = self.downstream_data.super_safe_lock(|d| d.downstream_id);
         task_manager.spawn(async move {
+            // Anything other than a failed handler means the translator closed the connection
+            let mut disconnect_reason = DisconnectReason::Shutdown;
             loop {
                 tokio::select! {
                     msg = shutdown_rx.recv() => {
@@ -135,6 +138,7 @@ impl Downstream {
                     res = Self::handle_downstream_message(self.clone()) => {
                         if let Err(e) = res {
                             error!("Downstream {downstream_id}: error in downstream message handler: {e:?}");
+                            disconnect_reason = e.disconnect_reason();
                             handle_error(&status_sender, e).await;
                             break;
                         }
@@ -144,6 +148,7 @@ impl Downstream {
                     res = Self::handle_sv1_server_message(self.clone(),&mut sv1_server_receiver) => {
                         if let Err(e) = res {
                             error!("Downstream {downstream_id}: error in server message handler: {e:?}");
+                            disconnect_reason = e.disconnect_reason();
                             handle_error(&status_sender, e).await;
                             break;
                         }
@@ -151,12 +156,19 @@ impl Downstream {
 
                     else => {
                         warn!("Downstream {downstream_id}: all channels closed; exiting task");
+                        disconnect_reason = DisconnectReason::ClientQuit;
                         break;
                     }
                 }
             }
 
-            warn!("Downstream {downstream_id}: unified task shutting down");
+            warn!("Downstream {downstream_id}: unified task shutting down ({disconnect_reason:?})");
+            let (miner_id, miner_tracker) = self
+                .downstream_data
+                .super_safe_lock(|d| (d.miner_id, d.miner_tracker.clone()));
+            if let (Some(miner_id), Some(miner_tracker)) = (miner_id, miner_tracker) {
+                miner_tracker.record_disconnect(miner_id, disconnect_reason).await;
+            }
             self.downstream_channel_state.drop();
             drop(shutdown_complete_tx);
         });
@@ -41,6 +41,13 @@ impl IsServer<'static> for DownstreamData {
         info!("Received mining.subscribe from Sv1 downstream");
         debug!("Down: Handling mining.subscribe: {:?}", request);
 
+        if let (Some(miner_id), Some(miner_tracker)) = (self.miner_id, self.miner_tracker.clone()) {
+            let user_agent = request.agent_signature.clone();
+            tokio::spawn(async move {
+                miner_tracker.update_user_agent(miner_id, &user_agent).await;
+            });
+        }
+
         let set_difficulty_sub = (
             "mining.set_difficulty".to_string(),
             self.downstream_id.to_string(),
@@ -75,6 +82,15 @@ impl IsServer<'static> for DownstreamData {
                 "Received mining.submit from SV1 downstream for channel id: {}",
                 channel_id
             );
+            if let Some(limiter) = self.share_rate_limiter.borrow_mut().as_mut() {
+                if !limiter.try_acquire() {
+                    warn!(
+                        "Share rejected for channel id {}: rate_limited (downstream {})",
+                        channel_id, self.downstream_id
+                    );
+                    return false;
+                }
+            }
             let is_valid_share = validate_sv1_share(
                 request,
                 self.target.clone(),
@@ -2,6 +2,7 @@ pub(super) mod channel;
 pub(super) mod data;
 pub mod downstream;
 mod message_handler;
+pub mod share_rate_limit;
 
 use v1::{client_to_server::Submit, utils::HexU32Be};
 
@@ -0,0 +1,86 @@
+//! Per-connection token bucket for `mining.submit` messages.
+//!
+//! Vardiff needs a few adjustment periods before it slows down a miner that floods shares, and
+//! until then every submission is validated and forwarded upstream. The limiter caps how many
+//! shares a single connection can push through regardless of their difficulty: a connection may
+//! submit up to `burst` shares back to back, after which shares are admitted at
+//! `shares_per_second` and the rest are rejected.
+
+use std::time::Instant;
+
+use crate::config::ShareRateLimitConfig;
+
+#[derive(Debug)]
+pub struct ShareRateLimiter {
+    shares_per_second: f64,
+    burst: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl ShareRateLimiter {
+    pub fn new(config: &ShareRateLimitConfig) -> Self {
+        let burst = config.burst.max(1) as f64;
+        Self {
+            shares_per_second: config.shares_per_second.max(0.0),
+            burst,
+            tokens: burst,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes a token for one share, returning `false` if the share should be rejected.
+    pub fn try_acquire(&mut self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * self.shares_per_second).min(self.burst);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limiter() -> ShareRateLimiter {
+        ShareRateLimiter::new(&ShareRateLimitConfig {
+            shares_per_second: 2.0,
+            burst: 5,
+        })
+    }
+
+    #[test]
+    fn test_flooding_connection_is_limited() {
+        let mut limiter = limiter();
+        let start = limiter.last_refill;
+
+        // 50 shares within one second: the burst plus two refilled tokens get through
+        let accepted = (0..50)
+            .filter(|i| limiter.try_acquire_at(start + Duration::from_millis(i * 20)))
+            .count();
+        assert_eq!(accepted, 5 + 1);
+
+        // After a pause the connection can submit again
+        assert!(limiter.try_acquire_at(start + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_well_behaved_connection_is_not_limited() {
+        let mut limiter = limiter();
+        let start = limiter.last_refill;
+
+        // One share per second for ten minutes stays under the sustained rate
+        assert!((0..600).all(|i| limiter.try_acquire_at(start + Duration::from_secs(i))));
+    }
+}
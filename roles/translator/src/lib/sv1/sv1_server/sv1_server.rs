@@ -3,7 +3,9 @@ use crate::{
     error::TproxyError,
     status::{handle_error, Status, StatusSender},
     sv1::{
-        downstream::{downstream::Downstream, DownstreamMessages},
+        downstream::{
+            downstream::Downstream, share_rate_limit::ShareRateLimiter, DownstreamMessages,
+        },
         sv1_server::{
             channel::Sv1ServerChannelState, data::Sv1ServerData,
             difficulty_manager::DifficultyManager,
@@ -14,6 +16,7 @@ use crate::{
 };
 use async_channel::{Receiver, Sender};
 use network_helpers_sv2::{codec_sv2::binary_sv2::Str0255, sv1_connection::ConnectionSV1};
+use stats::RejectReason;
 use std::{
     collections::HashMap,
     net::SocketAddr,
@@ -26,7 +29,6 @@ use stratum_common::roles_logic_sv2::{
     mining_sv2::{CloseChannel, SetTarget, Target},
     parsers_sv2::Mining,
     utils::{hash_rate_to_target, Mutex},
-    vardiff::classic::VardiffState,
     Vardiff,
 };
 use stratum_translation::{
@@ -36,7 +38,8 @@ use stratum_translation::{
     sv2_to_sv1::{build_sv1_notify_from_sv2, build_sv1_set_difficulty_from_sv2_target},
 };
 use tokio::{
-    net::TcpListener,
+    io::AsyncWriteExt,
+    net::{TcpListener, TcpStream},
     sync::{broadcast, mpsc},
 };
 use tracing::{debug, error, info, warn};
@@ -193,13 +196,8 @@ impl Sv1Server {
                             if let Some(downstream) = current_downstream {
                                 info!("🔌 Downstream: {downstream_id} disconnected and removed from sv1 server downstreams");
 
-                                // Remove miner from tracking
-                                {
-                                    let miner_tracker = self.miner_tracker.clone();
-                                    tokio::spawn(async move {
-                                        miner_tracker.remove_miner(downstream_id).await;
-                                    });
-                                }
+                                // The downstream's own task removes it from the miner tracker
+                                // along with why it left, see `record_disconnect`
 
                                 // In aggregated mode, send UpdateChannel to reflect the new state (only if vardiff enabled)
                                 if self.config.downstream_difficulty_config.enable_vardiff {
@@ -271,6 +269,11 @@ impl Sv1Server {
                         Ok((stream, addr)) => {
                             info!("New SV1 downstream connection from {}", addr);
 
+                            if !self.admit_downstream(addr) {
+                                Self::reject_connection(stream).await;
+                                continue;
+                            }
+
                             let connection = ConnectionSV1::new(stream).await;
                             let downstream_id = self.sv1_server_data.super_safe_lock(|v| v.downstream_id_factory.next());
 
@@ -292,13 +295,18 @@ impl Sv1Server {
                                 Some(miner_id),
                                 Some(self.miner_tracker.clone()),
                             ));
+                            if let Some(limit) = &self.config.share_rate_limit {
+                                downstream.downstream_data.super_safe_lock(|d| {
+                                    d.share_rate_limiter.replace(Some(ShareRateLimiter::new(limit)));
+                                });
+                            }
                             // vardiff initialization (only if enabled)
                             _ = self.sv1_server_data
                                 .safe_lock(|d| {
                                     d.downstreams.insert(downstream_id, downstream.clone());
                                     // Insert vardiff state for this downstream only if vardiff is enabled
                                     if self.config.downstream_difficulty_config.enable_vardiff {
-                                        let vardiff = Arc::new(RwLock::new(VardiffState::new().expect("Failed to create vardiffstate")));
+                                        let vardiff = Arc::new(RwLock::new(self.config.downstream_difficulty_config.vardiff_state().expect("Failed to create vardiffstate")));
                                         d.vardiff.insert(downstream_id, vardiff);
                                     }
                                 });
@@ -348,6 +356,42 @@ impl Sv1Server {
         Ok(())
     }
 
+    /// Checks `max_downstreams` before serving a new miner. The outcome is counted in the miner
+    /// tracker so it shows up in stats.
+    fn admit_downstream(&self, addr: SocketAddr) -> bool {
+        if let Some(max_downstreams) = self.config.max_downstreams {
+            let connected = self
+                .sv1_server_data
+                .super_safe_lock(|d| d.downstreams.len());
+            if connected >= max_downstreams {
+                let rejected = self
+                    .miner_tracker
+                    .record_rejected_connection(RejectReason::Capacity);
+                warn!(
+                    "Rejecting SV1 connection from {}: translator is at its limit of {} miners ({} rejected so far)",
+                    addr, max_downstreams, rejected
+                );
+                return false;
+            }
+        }
+        self.miner_tracker.record_accepted_connection();
+        true
+    }
+
+    /// Tells a rejected miner why it is being disconnected, then closes the connection.
+    async fn reject_connection(mut stream: TcpStream) {
+        let message = serde_json::json!({
+            "id": null,
+            "method": "client.show_message",
+            "params": ["Translator is at capacity, try again later"],
+        });
+        let line = format!("{}\n", message);
+        if let Err(e) = stream.write_all(line.as_bytes()).await {
+            debug!("Failed to notify rejected miner: {}", e);
+        }
+        _ = stream.shutdown().await;
+    }
+
     /// Handles messages received from downstream SV1 miners.
     ///
     /// This method processes share submissions from miners by:
@@ -866,12 +910,12 @@ mod tests {
 
         let upstream = Upstream::new("127.0.0.1".to_string(), 4444, pubkey);
         let difficulty_config = DownstreamDifficultyConfig::new(100.0, 5.0, true);
-        let wallet = WalletConfig {
-            mnemonic: "test mnemonic".to_string(),
-            db_path: "/tmp/test_wallet.db".to_string(),
-            locking_pubkey: None,
-            locking_privkey: None,
-        };
+        let wallet = WalletConfig::new(
+            "test mnemonic".to_string(),
+            "/tmp/test_wallet.db".to_string(),
+            None,
+            None,
+        );
 
         TranslatorConfig::new(
             vec![upstream],
@@ -1056,4 +1100,54 @@ mod tests {
         server.clean_job.store(true, Ordering::SeqCst);
         assert!(server.clean_job.load(Ordering::SeqCst));
     }
+
+    #[tokio::test]
+    async fn test_admit_downstream_enforces_max_downstreams() {
+        let mut config = create_test_config();
+        config.max_downstreams = Some(1);
+
+        let (cm_sender, _cm_receiver) = unbounded();
+        let (_downstream_sender, cm_receiver) = unbounded();
+        let addr: SocketAddr = "127.0.0.1:3333".parse().unwrap();
+        let miner_tracker = std::sync::Arc::new(crate::miner_stats::MinerTracker::new());
+        let server = Sv1Server::new(addr, cm_receiver, cm_sender, config, miner_tracker.clone());
+
+        let miner_addr: SocketAddr = "10.0.0.1:40000".parse().unwrap();
+        assert!(server.admit_downstream(miner_addr));
+
+        let (sv1_sender, sv1_receiver) = unbounded();
+        let target: Target = hash_rate_to_target(200.0, 5.0).unwrap().into();
+        let downstream = Arc::new(Downstream::new(
+            1,
+            sv1_sender,
+            sv1_receiver,
+            server
+                .sv1_server_channel_state
+                .downstream_to_sv1_server_sender
+                .clone(),
+            server
+                .sv1_server_channel_state
+                .sv1_server_to_downstream_sender
+                .subscribe(),
+            target,
+            None,
+            server.sv1_server_data.clone(),
+            None,
+            None,
+        ));
+        server
+            .sv1_server_data
+            .super_safe_lock(|d| d.downstreams.insert(1, downstream));
+
+        assert!(!server.admit_downstream(miner_addr));
+        assert!(!server.admit_downstream(miner_addr));
+        assert_eq!(miner_tracker.rejected_connections(), 2);
+
+        let connections = miner_tracker.connection_stats();
+        assert_eq!(connections.accepted, 1);
+        assert_eq!(connections.rejected.get(&RejectReason::Capacity), Some(&2));
+        assert!(server
+            .sv1_server_data
+            .super_safe_lock(|d| d.downstreams.contains_key(&1)));
+    }
 }
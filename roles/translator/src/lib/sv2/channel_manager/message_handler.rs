@@ -63,6 +63,20 @@ impl HandleMiningMessagesFromServerAsync for ChannelManager {
                 TproxyError::PendingChannelNotFound(m.request_id)
             })?;
 
+        // The upstream may grant less room than requested; carving the downstream
+        // extranonce2 out of it would otherwise underflow further down
+        if (m.extranonce_size as usize) < downstream_extranonce_len {
+            error!(
+                "Upstream granted channel {} a {}-byte extranonce, but downstream needs {} bytes",
+                m.channel_id, m.extranonce_size, downstream_extranonce_len
+            );
+            return Err(TproxyError::ExtranonceSizeTooSmall {
+                channel_id: m.channel_id,
+                granted: m.extranonce_size,
+                required: downstream_extranonce_len,
+            });
+        }
+
         let success = self
             .channel_manager_data
             .safe_lock(|channel_manager_data| {
@@ -1,4 +1,5 @@
 pub mod message_handler;
+pub mod reconnect_grace;
 pub mod upstream;
 pub use upstream::Upstream;
 pub(super) mod channel;
@@ -0,0 +1,91 @@
+//! Grace period between an upstream reconnection and the downstream reset it triggers.
+//!
+//! After the upstream comes back, the channel manager state is reset and every downstream is
+//! disconnected. Against a flapping upstream that means a reset on every reconnect. With a grace
+//! period the reset is held back until the upstream has stayed connected that long; if it drops
+//! again first, the pending reset is cancelled and the next reconnection starts a fresh wait.
+
+use tokio::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub struct ReconnectGrace {
+    period: Duration,
+    reset_at: Option<Instant>,
+}
+
+impl ReconnectGrace {
+    pub fn new(period: Duration) -> Self {
+        Self {
+            period,
+            reset_at: None,
+        }
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Records a successful reconnection at `now`. Returns `true` if downstreams should be reset
+    /// right away, which is the case when no grace period is configured.
+    pub fn reconnected(&mut self, now: Instant) -> bool {
+        if self.period.is_zero() {
+            return true;
+        }
+        self.reset_at = Some(now + self.period);
+        false
+    }
+
+    /// Records the upstream dropping. Returns `true` if this cancelled a pending reset.
+    pub fn dropped(&mut self) -> bool {
+        self.reset_at.take().is_some()
+    }
+
+    /// When the pending reset is due, if there is one.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.reset_at
+    }
+
+    /// Returns `true` once, when the upstream has stayed connected through the grace period.
+    pub fn take_due(&mut self, now: Instant) -> bool {
+        match self.reset_at {
+            Some(reset_at) if now >= reset_at => {
+                self.reset_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drop_within_grace_period_cancels_reset() {
+        let mut grace = ReconnectGrace::new(Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert!(!grace.reconnected(start));
+        assert!(!grace.take_due(start + Duration::from_secs(5)));
+
+        // The upstream flaps before the grace period is over: no reset
+        assert!(grace.dropped());
+        assert_eq!(grace.deadline(), None);
+        assert!(!grace.take_due(start + Duration::from_secs(20)));
+
+        // The next connection holds, so downstreams are reset exactly once
+        assert!(!grace.reconnected(start + Duration::from_secs(21)));
+        assert!(!grace.take_due(start + Duration::from_secs(30)));
+        assert!(grace.take_due(start + Duration::from_secs(31)));
+        assert!(!grace.take_due(start + Duration::from_secs(40)));
+        assert!(!grace.dropped());
+    }
+
+    #[test]
+    fn test_zero_grace_period_resets_immediately() {
+        let mut grace = ReconnectGrace::new(Duration::ZERO);
+        assert!(grace.reconnected(Instant::now()));
+        assert_eq!(grace.deadline(), None);
+    }
+}
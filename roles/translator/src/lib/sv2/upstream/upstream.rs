@@ -8,8 +8,8 @@ use crate::{
 use async_channel::{Receiver, Sender};
 use const_sv2::{MESSAGE_TYPE_MINT_QUOTE_FAILURE, MESSAGE_TYPE_MINT_QUOTE_NOTIFICATION};
 use key_utils::Secp256k1PublicKey;
-use network_helpers_sv2::noise_connection::Connection;
-use std::{net::SocketAddr, sync::Arc};
+use network_helpers_sv2::{noise_connection::Connection, resolve::resolve_address};
+use std::sync::Arc;
 use stratum_common::roles_logic_sv2::{
     codec_sv2::{
         self, framing_sv2, HandshakeRole, Initiator, StandardEitherFrame, StandardSv2Frame,
@@ -58,7 +58,8 @@ impl Upstream {
     /// to connect to each server multiple times before giving up.
     ///
     /// # Arguments
-    /// * `upstreams` - List of (address, public_key) pairs for upstream servers
+    /// * `upstreams` - List of (host, port, public_key) entries for upstream servers; hosts are
+    ///   resolved on every attempt
     /// * `channel_manager_sender` - Channel to send messages to the channel manager
     /// * `channel_manager_receiver` - Channel to receive messages from the channel manager
     /// * `notify_shutdown` - Broadcast channel for shutdown coordination
@@ -68,7 +69,7 @@ impl Upstream {
     /// * `Ok(Upstream)` - Successfully connected to an upstream server
     /// * `Err(TproxyError)` - Failed to connect to any upstream server
     pub async fn new(
-        upstreams: &[(SocketAddr, Secp256k1PublicKey)],
+        upstreams: &[(String, u16, Secp256k1PublicKey)],
         channel_manager_sender: Sender<EitherFrame>,
         channel_manager_receiver: Receiver<EitherFrame>,
         notify_shutdown: broadcast::Sender<ShutdownMessage>,
@@ -77,8 +78,11 @@ impl Upstream {
         let mut shutdown_rx = notify_shutdown.subscribe();
         const RETRIES_PER_UPSTREAM: u8 = 3;
 
-        for (index, (addr, pubkey)) in upstreams.iter().enumerate() {
-            info!("Trying to connect to upstream {} at {}", index, addr);
+        for (index, (host, port, pubkey)) in upstreams.iter().enumerate() {
+            info!(
+                "Trying to connect to upstream {} at {}:{}",
+                index, host, port
+            );
 
             for attempt in 1..=RETRIES_PER_UPSTREAM {
                 if shutdown_rx.try_recv().is_ok() {
@@ -87,6 +91,16 @@ impl Upstream {
                     return Err(TproxyError::Shutdown);
                 }
 
+                let addr = match resolve_address(host, *port).await {
+                    Ok(addr) => addr,
+                    Err(e) => {
+                        let e = TproxyError::UnresolvableAddress(format!("{host}:{port}: {e}"));
+                        error!("{e}. Retry {attempt}/{RETRIES_PER_UPSTREAM}...");
+                        sleep(Duration::from_secs(5)).await;
+                        continue;
+                    }
+                };
+
                 match TcpStream::connect(addr).await {
                     Ok(socket) => {
                         info!(
@@ -122,7 +136,7 @@ impl Upstream {
                 sleep(Duration::from_secs(5)).await;
             }
 
-            warn!("Exhausted retries for upstream {index} at {addr}");
+            warn!("Exhausted retries for upstream {index} at {host}:{port}");
         }
 
         error!("Failed to connect to any configured upstream.");
@@ -1,7 +1,8 @@
 mod args;
 use std::process;
 
-use config_helpers_sv2::logging::init_logging;
+use config_helpers_sv2::logging::init_logging_with_filter;
+use shared_config::RoleConfig;
 pub use translator_sv2::{config, error, status, sv1, sv2, TranslatorSv2};
 
 use crate::args::process_cli_args;
@@ -17,7 +18,8 @@ async fn main() {
         std::process::exit(1);
     });
 
-    init_logging(proxy_config.log_dir());
+    init_logging_with_filter(proxy_config.log_dir(), proxy_config.log_filter());
+    proxy_config.log_effective_config();
 
     TranslatorSv2::new(proxy_config).start().await;
 
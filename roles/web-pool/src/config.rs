@@ -1,5 +1,7 @@
 use serde::Deserialize;
+use shared_config::RoleConfig;
 use std::{env, fs};
+use web_utils::{fetch::DEFAULT_MAX_SNAPSHOT_BYTES, DifficultyDisplay};
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -9,6 +11,8 @@ pub struct Config {
     pub client_poll_interval_secs: u64,
     pub request_timeout_secs: u64,
     pub pool_idle_timeout_secs: u64,
+    pub max_snapshot_bytes: usize,
+    pub difficulty_display: DifficultyDisplay,
     pub log_file: Option<String>,
 }
 
@@ -20,9 +24,12 @@ struct WebPoolConfig {
     stats_pool: StatsPoolConfig,
     #[serde(default)]
     http_client: HttpClientConfig,
+    #[serde(default)]
+    display: DisplayConfig,
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct ServerConfig {
     listen_address: Option<String>,
 }
@@ -36,6 +43,7 @@ impl Default for ServerConfig {
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct StatsPoolConfig {
     url: Option<String>,
 }
@@ -49,9 +57,11 @@ impl Default for StatsPoolConfig {
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct HttpClientConfig {
     pool_idle_timeout_secs: Option<u64>,
     request_timeout_secs: Option<u64>,
+    max_snapshot_bytes: Option<usize>,
 }
 
 impl Default for HttpClientConfig {
@@ -59,7 +69,49 @@ impl Default for HttpClientConfig {
         Self {
             pool_idle_timeout_secs: Some(300),
             request_timeout_secs: Some(60),
+            max_snapshot_bytes: Some(DEFAULT_MAX_SNAPSHOT_BYTES),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DisplayConfig {
+    #[serde(default)]
+    difficulty: DifficultyDisplay,
+}
+
+impl RoleConfig for WebPoolConfig {
+    const SERVICE: &'static str = "web-pool";
+    const ENV_PREFIX: &'static str = "HASHPOOL_WEB_POOL";
+
+    fn effective_config(&self) -> Vec<(&'static str, String)> {
+        fn opt<T: ToString>(value: Option<&T>) -> String {
+            value.map_or_else(|| "none".to_string(), |v| v.to_string())
         }
+        let http_client = &self.http_client;
+        vec![
+            (
+                "server.listen_address",
+                opt(self.server.listen_address.as_ref()),
+            ),
+            ("stats_pool.url", opt(self.stats_pool.url.as_ref())),
+            (
+                "http_client.pool_idle_timeout_secs",
+                opt(http_client.pool_idle_timeout_secs.as_ref()),
+            ),
+            (
+                "http_client.request_timeout_secs",
+                opt(http_client.request_timeout_secs.as_ref()),
+            ),
+            (
+                "http_client.max_snapshot_bytes",
+                opt(http_client.max_snapshot_bytes.as_ref()),
+            ),
+            (
+                "display.difficulty",
+                format!("{:?}", self.display.difficulty).to_lowercase(),
+            ),
+        ]
     }
 }
 
@@ -82,16 +134,7 @@ impl Config {
             .map(|s| s.as_str())
             .ok_or("Missing required argument: --web-pool-config")?;
 
-        let web_pool_config_str = fs::read_to_string(web_pool_config_path).unwrap_or_default();
-        let web_pool_config: WebPoolConfig = if web_pool_config_str.is_empty() {
-            WebPoolConfig {
-                server: ServerConfig::default(),
-                stats_pool: StatsPoolConfig::default(),
-                http_client: HttpClientConfig::default(),
-            }
-        } else {
-            toml::from_str(&web_pool_config_str)?
-        };
+        let web_pool_config = WebPoolConfig::from_optional_path(web_pool_config_path)?;
 
         // Parse command line arguments (with config file as fallback)
         let stats_pool_url = args
@@ -147,6 +190,11 @@ impl Config {
                 .http_client
                 .pool_idle_timeout_secs
                 .unwrap_or(300),
+            max_snapshot_bytes: web_pool_config
+                .http_client
+                .max_snapshot_bytes
+                .unwrap_or(DEFAULT_MAX_SNAPSHOT_BYTES),
+            difficulty_display: web_pool_config.display.difficulty,
             log_file,
         })
     }
@@ -168,6 +216,10 @@ mod tests {
             [http_client]
             pool_idle_timeout_secs = 500
             request_timeout_secs = 100
+            max_snapshot_bytes = 1048576
+
+            [display]
+            difficulty = "numeric"
         "#;
         let config: WebPoolConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(
@@ -180,5 +232,53 @@ mod tests {
         );
         assert_eq!(config.http_client.pool_idle_timeout_secs, Some(500));
         assert_eq!(config.http_client.request_timeout_secs, Some(100));
+        assert_eq!(config.http_client.max_snapshot_bytes, Some(1048576));
+        assert_eq!(config.display.difficulty, DifficultyDisplay::Numeric);
+    }
+
+    #[test]
+    fn test_role_config_loads_with_own_env_prefix() {
+        let path = std::env::temp_dir().join(format!("web-pool-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "[server]\nlisten_address = \"127.0.0.1:7070\"\n[display]\ndifficulty = \"numeric\"\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+
+        std::env::set_var("HASHPOOL_WEB_POOL_STATS_POOL__URL", "http://env-stats:9084");
+        std::env::set_var("HASHPOOL_SERVER__LISTEN_ADDRESS", "127.0.0.1:1");
+        let config = WebPoolConfig::from_path(path);
+        let missing = WebPoolConfig::from_optional_path("/nonexistent/web-pool.toml");
+        std::env::remove_var("HASHPOOL_WEB_POOL_STATS_POOL__URL");
+        std::env::remove_var("HASHPOOL_SERVER__LISTEN_ADDRESS");
+        std::fs::remove_file(path).unwrap();
+
+        let config = config.unwrap();
+        assert!(config.validate().is_ok());
+        let entries = config.effective_config();
+        let value = |key| entries.iter().find(|(k, _)| *k == key).unwrap().1.as_str();
+        // Only this service's prefix applies
+        assert_eq!(value("server.listen_address"), "127.0.0.1:7070");
+        assert_eq!(value("stats_pool.url"), "http://env-stats:9084");
+        assert_eq!(value("http_client.request_timeout_secs"), "60");
+        assert_eq!(value("display.difficulty"), "numeric");
+
+        // Without a file every section keeps its defaults, under the environment overrides
+        let missing = missing.unwrap();
+        assert_eq!(
+            missing.server.listen_address,
+            Some("127.0.0.1:8081".to_string())
+        );
+        assert_eq!(
+            missing.stats_pool.url,
+            Some("http://env-stats:9084".to_string())
+        );
+    }
+
+    #[test]
+    fn test_difficulty_display_defaults_to_bits() {
+        let config: WebPoolConfig = toml::from_str("[server]").unwrap();
+        assert_eq!(config.display.difficulty, DifficultyDisplay::Bits);
     }
 }
@@ -1,41 +1,93 @@
-use stats::stats_adapter::PoolSnapshot;
+use stats::{clock::system_clock, health::HealthStatus, stats_adapter::PoolSnapshot};
 use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast;
+use web_utils::fetch::{read_json_limited, FetchError};
 
 pub mod config;
 pub mod web;
 
+pub use stats::clock::Clock;
+
+/// Snapshots buffered per stream subscriber; a subscriber that falls further behind skips
+/// ahead to the newest ones
+const SNAPSHOT_UPDATES_CAPACITY: usize = 16;
+
 /// In-memory storage for pool snapshot data
 pub struct SnapshotStorage {
     snapshot: Arc<RwLock<Option<PoolSnapshot>>>,
+    clock: Clock,
+    updates: broadcast::Sender<PoolSnapshot>,
 }
 
 impl SnapshotStorage {
     pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Storage that judges snapshot age by `clock` instead of the system time
+    pub fn with_clock(clock: Clock) -> Self {
         Self {
             snapshot: Arc::new(RwLock::new(None)),
+            clock,
+            updates: broadcast::channel(SNAPSHOT_UPDATES_CAPACITY).0,
         }
     }
 
     pub fn update(&self, snapshot: PoolSnapshot) {
         if let Ok(mut guard) = self.snapshot.write() {
-            *guard = Some(snapshot);
+            *guard = Some(snapshot.clone());
         }
+        // Nobody listening is fine
+        let _ = self.updates.send(snapshot);
+    }
+
+    /// Receive every snapshot stored from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<PoolSnapshot> {
+        self.updates.subscribe()
+    }
+
+    /// Fetch a snapshot from `url` and store it. Responses over `max_bytes` are refused and the
+    /// current snapshot is kept.
+    pub async fn refresh(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        max_bytes: usize,
+    ) -> Result<(), FetchError> {
+        let response = client.get(url).send().await?;
+        let snapshot = read_json_limited::<PoolSnapshot>(response, max_bytes).await?;
+        self.update(snapshot);
+        Ok(())
     }
 
     pub fn get(&self) -> Option<PoolSnapshot> {
         self.snapshot.read().ok().and_then(|guard| guard.clone())
     }
 
+    /// The stored snapshot with its age in seconds, read together so the two agree
+    pub fn get_with_age(&self) -> Option<(PoolSnapshot, u64)> {
+        self.get().map(|snapshot| {
+            let age = (self.clock)().saturating_sub(snapshot.timestamp);
+            (snapshot, age)
+        })
+    }
+
     pub fn is_stale(&self, threshold_secs: u64) -> bool {
         match self.snapshot.read().ok().and_then(|guard| guard.clone()) {
+            Some(snapshot) => (self.clock)().saturating_sub(snapshot.timestamp) > threshold_secs,
+            None => true,
+        }
+    }
+
+    /// Health reported by the latest snapshot, degraded further by its age. `Down` before the
+    /// first snapshot arrives.
+    pub fn health(&self) -> HealthStatus {
+        match self.get() {
             Some(snapshot) => {
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
-                now - snapshot.timestamp > threshold_secs
+                let age = (self.clock)().saturating_sub(snapshot.timestamp);
+                snapshot.health.aged(age)
             }
-            None => true,
+            None => HealthStatus::Down,
         }
     }
 }
@@ -58,6 +110,11 @@ mod tests {
             services: vec![],
             downstream_proxies: vec![],
             listen_address: "test".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: 456,
         };
 
@@ -66,6 +123,29 @@ mod tests {
         assert_eq!(retrieved.timestamp, 456);
     }
 
+    #[tokio::test]
+    async fn test_subscriber_receives_updated_snapshot() {
+        let storage = SnapshotStorage::new();
+        let mut updates = storage.subscribe();
+
+        storage.update(PoolSnapshot {
+            services: vec![],
+            downstream_proxies: vec![],
+            listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 7,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
+            timestamp: 321,
+        });
+
+        let received = updates.recv().await.unwrap();
+        assert_eq!(received.timestamp, 321);
+        assert_eq!(received.shares_since_last_block, 7);
+        assert!(updates.try_recv().is_err());
+    }
+
     #[test]
     fn test_storage_returns_none_initially() {
         let storage = SnapshotStorage::new();
@@ -89,6 +169,11 @@ mod tests {
             services: vec![],
             downstream_proxies: vec![],
             listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: now,
         };
         storage.update(snapshot);
@@ -99,9 +184,91 @@ mod tests {
             services: vec![],
             downstream_proxies: vec![],
             listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
             timestamp: now - 30,
         };
         storage.update(old_snapshot);
         assert!(storage.is_stale(15));
     }
+
+    #[test]
+    fn test_staleness_boundary_with_mock_clock() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let now = Arc::new(AtomicU64::new(0));
+        let clock_now = now.clone();
+        let storage =
+            SnapshotStorage::with_clock(Arc::new(move || clock_now.load(Ordering::SeqCst)));
+
+        let snapshot = PoolSnapshot {
+            services: vec![],
+            downstream_proxies: vec![],
+            listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
+            timestamp: 1_000,
+        };
+        storage.update(snapshot);
+
+        // A clock behind the snapshot counts as fresh rather than underflowing
+        now.store(990, Ordering::SeqCst);
+        assert!(!storage.is_stale(15));
+
+        // Exactly at the threshold is still fresh; one second past it is stale
+        now.store(1_015, Ordering::SeqCst);
+        assert!(!storage.is_stale(15));
+        now.store(1_016, Ordering::SeqCst);
+        assert!(storage.is_stale(15));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_snapshot_keeps_previous() {
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::TcpListener,
+        };
+
+        let storage = SnapshotStorage::new();
+        storage.update(PoolSnapshot {
+            services: vec![],
+            downstream_proxies: vec![],
+            listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 0,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
+            timestamp: 789,
+        });
+
+        // Stats endpoint answering with a body well over the limit
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let body = format!("{{\"padding\":\"{}\"}}", "x".repeat(64 * 1024));
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+
+        let client = reqwest::Client::new();
+        let result = storage
+            .refresh(&client, &format!("http://{}/api/stats", addr), 4096)
+            .await;
+        assert!(matches!(result, Err(FetchError::TooLarge { limit: 4096 })));
+        assert_eq!(storage.get().unwrap().timestamp, 789);
+    }
 }
@@ -1,10 +1,10 @@
-use stats::stats_adapter::PoolSnapshot;
 use std::{sync::Arc, time::Duration};
 use tokio::time;
 use tracing::{error, info};
 use tracing_subscriber;
 
 use web_pool::{config::Config, SnapshotStorage};
+use web_utils::DifficultyDisplay;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -50,6 +50,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let poll_interval = config.stats_poll_interval_secs;
     let request_timeout = config.request_timeout_secs;
     let pool_idle_timeout = config.pool_idle_timeout_secs;
+    let max_snapshot_bytes = config.max_snapshot_bytes;
     tokio::spawn(async move {
         poll_stats_pool(
             storage_clone,
@@ -57,6 +58,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             poll_interval,
             request_timeout,
             pool_idle_timeout,
+            max_snapshot_bytes,
         )
         .await;
     });
@@ -67,6 +69,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         storage,
         config.client_poll_interval_secs,
         Some(config.stats_pool_url.clone()),
+        config.difficulty_display,
     )
     .await?;
 
@@ -79,6 +82,7 @@ async fn poll_stats_pool(
     poll_interval_secs: u64,
     request_timeout_secs: u64,
     pool_idle_timeout_secs: u64,
+    max_snapshot_bytes: usize,
 ) {
     let client = reqwest::Client::builder()
         .timeout(Duration::from_secs(request_timeout_secs))
@@ -88,33 +92,24 @@ async fn poll_stats_pool(
         .unwrap();
     let mut interval = time::interval(Duration::from_secs(poll_interval_secs));
     let mut last_success = false;
+    let stats_url = format!("{}/api/stats", stats_pool_url);
 
     loop {
         interval.tick().await;
 
-        match client
-            .get(format!("{}/api/stats", stats_pool_url))
-            .send()
+        match storage
+            .refresh(&client, &stats_url, max_snapshot_bytes)
             .await
         {
-            Ok(response) => match response.json::<PoolSnapshot>().await {
-                Ok(snapshot) => {
-                    if !last_success {
-                        info!("Successfully fetched snapshot from stats-pool");
-                        last_success = true;
-                    }
-                    storage.update(snapshot);
+            Ok(()) => {
+                if !last_success {
+                    info!("Successfully fetched snapshot from stats-pool");
+                    last_success = true;
                 }
-                Err(e) => {
-                    if last_success {
-                        error!("Failed to parse snapshot JSON: {}", e);
-                        last_success = false;
-                    }
-                }
-            },
+            }
             Err(e) => {
                 if last_success {
-                    error!("Failed to fetch from stats-pool: {}", e);
+                    error!("Failed to fetch snapshot from stats-pool: {}", e);
                     last_success = false;
                 }
             }
@@ -127,6 +122,14 @@ async fn start_web_server(
     storage: Arc<SnapshotStorage>,
     client_poll_interval_secs: u64,
     stats_pool_url: Option<String>,
+    difficulty_display: DifficultyDisplay,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    web_pool::web::run_http_server(address, storage, client_poll_interval_secs, stats_pool_url).await
+    web_pool::web::run_http_server(
+        address,
+        storage,
+        client_poll_interval_secs,
+        stats_pool_url,
+        difficulty_display,
+    )
+    .await
 }
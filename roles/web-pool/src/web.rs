@@ -1,25 +1,35 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    response::{Html, IntoResponse},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse,
+    },
     routing::get,
     Json, Router,
 };
+use futures::{stream, Stream, StreamExt};
 use serde::Deserialize;
 use serde_json::json;
 use std::sync::{Arc, OnceLock};
-use tracing::info;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info};
 
 use crate::SnapshotStorage;
+use stats::stats_adapter::PoolSnapshot;
 use web_assets::icons::{nav_icon_css, pickaxe_favicon_inline_svg};
-use web_utils::format_elapsed_time;
+use web_utils::{format_difficulty, format_elapsed_time, DifficultyDisplay};
 
 static DASHBOARD_PAGE_HTML: OnceLock<String> = OnceLock::new();
 static CLIENT_POLL_INTERVAL_SECS: OnceLock<u64> = OnceLock::new();
 static STATS_POOL_URL: OnceLock<String> = OnceLock::new();
+static DIFFICULTY_DISPLAY: OnceLock<DifficultyDisplay> = OnceLock::new();
 
 const DASHBOARD_PAGE_TEMPLATE: &str = include_str!("../templates/dashboard.html");
 
+/// `/api/stats` flags a snapshot as stale once it is older than this many client poll intervals
+const STALE_AFTER_POLL_INTERVALS: u64 = 3;
+
 #[derive(Deserialize)]
 pub struct TimeRangeQuery {
     pub from: u64,
@@ -31,9 +41,11 @@ pub async fn run_http_server(
     storage: Arc<SnapshotStorage>,
     client_poll_interval_secs: u64,
     stats_pool_url: Option<String>,
+    difficulty_display: DifficultyDisplay,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Store the polling interval for use in dashboard_page
     let _ = CLIENT_POLL_INTERVAL_SECS.set(client_poll_interval_secs);
+    let _ = DIFFICULTY_DISPLAY.set(difficulty_display);
 
     // Store stats pool URL for hashrate proxying
     if let Some(url) = stats_pool_url {
@@ -45,6 +57,7 @@ pub async fn run_http_server(
         .route("/favicon.svg", get(serve_favicon))
         .route("/", get(dashboard_page_handler))
         .route("/api/stats", get(api_stats_handler))
+        .route("/api/stream", get(api_stream_handler))
         .route("/api/services", get(api_services_handler))
         .route("/api/connections", get(api_connections_handler))
         .route("/api/hashrate", get(api_aggregate_hashrate_handler))
@@ -83,8 +96,48 @@ async fn dashboard_page_handler() -> impl IntoResponse {
 }
 
 async fn api_stats_handler(State(storage): State<Arc<SnapshotStorage>>) -> impl IntoResponse {
-    let stats = get_pool_stats(storage);
-    Json(stats)
+    let poll_interval_secs = CLIENT_POLL_INTERVAL_SECS.get().copied().unwrap_or(3);
+    Json(stats_envelope(
+        &storage,
+        poll_interval_secs * STALE_AFTER_POLL_INTERVALS,
+    ))
+}
+
+/// Pool stats under `data`, with the snapshot's age and whether it is older than
+/// `stale_after_secs`. Before the first snapshot the stats are empty, `age_secs` is null and
+/// the data counts as stale.
+fn stats_envelope(storage: &SnapshotStorage, stale_after_secs: u64) -> serde_json::Value {
+    let (snapshot, age_secs) = storage.get_with_age().unzip();
+    json!({
+        "stale": age_secs.is_none_or(|age| age > stale_after_secs),
+        "age_secs": age_secs,
+        "data": get_pool_stats(snapshot),
+    })
+}
+
+/// Server-sent events carrying each new snapshot as JSON, starting with the current one
+async fn api_stream_handler(
+    State(storage): State<Arc<SnapshotStorage>>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    // Subscribe before reading the current snapshot so no update falls in between
+    let updates = storage.subscribe();
+    let current = stream::iter(storage.get());
+    let next = stream::unfold(updates, |mut updates| async move {
+        loop {
+            match updates.recv().await {
+                Ok(snapshot) => return Some((snapshot, updates)),
+                // Each snapshot is complete, so a slow client only misses intermediate ones
+                Err(RecvError::Lagged(skipped)) => {
+                    debug!("Snapshot stream client skipped {} snapshots", skipped);
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    let events = current
+        .chain(next)
+        .map(|snapshot| Event::default().event("snapshot").json_data(snapshot));
+    Sse::new(events).keep_alive(KeepAlive::default())
 }
 
 async fn api_services_handler(State(storage): State<Arc<SnapshotStorage>>) -> impl IntoResponse {
@@ -93,7 +146,8 @@ async fn api_services_handler(State(storage): State<Arc<SnapshotStorage>>) -> im
 }
 
 async fn api_connections_handler(State(storage): State<Arc<SnapshotStorage>>) -> impl IntoResponse {
-    let connections = get_connections(storage);
+    let display = DIFFICULTY_DISPLAY.get().copied().unwrap_or_default();
+    let connections = get_connections(storage, display);
     Json(connections)
 }
 
@@ -106,7 +160,8 @@ async fn health_handler(State(storage): State<Arc<SnapshotStorage>>) -> impl Int
     };
     let json_response = json!({
         "healthy": !stale,
-        "stale": stale
+        "stale": stale,
+        "status": storage.health()
     });
     (status_code, Json(json_response))
 }
@@ -162,13 +217,15 @@ async fn api_downstream_hashrate_handler(
     (StatusCode::OK, Json(json!({ "data": [] }))).into_response()
 }
 
-fn get_pool_stats(storage: Arc<SnapshotStorage>) -> serde_json::Value {
-    match storage.get() {
+fn get_pool_stats(snapshot: Option<PoolSnapshot>) -> serde_json::Value {
+    match snapshot {
         Some(snapshot) => {
             json!({
                 "listen_address": snapshot.listen_address,
                 "services": snapshot.services,
                 "downstream_proxies": snapshot.downstream_proxies,
+                "shares_since_last_block": snapshot.shares_since_last_block,
+                "last_block_found_at": snapshot.last_block_found_at,
                 "timestamp": snapshot.timestamp
             })
         }
@@ -177,6 +234,8 @@ fn get_pool_stats(storage: Arc<SnapshotStorage>) -> serde_json::Value {
                 "listen_address": "",
                 "services": [],
                 "downstream_proxies": [],
+                "shares_since_last_block": 0,
+                "last_block_found_at": null,
                 "timestamp": 0
             })
         }
@@ -202,7 +261,17 @@ fn get_services(storage: Arc<SnapshotStorage>) -> serde_json::Value {
     }
 }
 
-fn get_connections(storage: Arc<SnapshotStorage>) -> serde_json::Value {
+/// Lower bound of the histogram bucket holding the most shares, `None` before any share
+fn typical_difficulty(histogram: &[u64]) -> Option<f64> {
+    histogram
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0)
+        .max_by_key(|(_, count)| **count)
+        .map(|(bucket, _)| 2f64.powi(bucket as i32))
+}
+
+fn get_connections(storage: Arc<SnapshotStorage>, display: DifficultyDisplay) -> serde_json::Value {
     match storage.get() {
         Some(snapshot) => {
             let now = std::time::SystemTime::now()
@@ -215,13 +284,21 @@ fn get_connections(storage: Arc<SnapshotStorage>) -> serde_json::Value {
                 .iter()
                 .map(|p| {
                     let last_share = p.last_share_at.map(|ts| format_elapsed_time(now, ts));
+                    let difficulty = typical_difficulty(&p.difficulty_histogram)
+                        .map(|d| format_difficulty(d, display));
 
                     json!({
                         "id": p.id,
                         "address": p.address,
                         "channels": p.channels,
                         "shares_submitted": p.shares_submitted,
+                        "shares_per_minute": p.shares_per_minute,
                         "quotes_created": p.quotes_created,
+                        "quotes_failed": p.quotes_failed,
+                        "quote_success_ratio": p.quote_success_ratio,
+                        "reject_ratio": p.reject_ratio,
+                        "difficulty_histogram": p.difficulty_histogram,
+                        "typical_difficulty": difficulty,
                         "ehash_mined": p.ehash_mined,
                         "last_share_at": last_share,
                         "work_selection": p.work_selection
@@ -233,3 +310,49 @@ fn get_connections(storage: Arc<SnapshotStorage>) -> serde_json::Value {
         None => json!({ "proxies": [] }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn snapshot(timestamp: u64) -> PoolSnapshot {
+        PoolSnapshot {
+            services: vec![],
+            downstream_proxies: vec![],
+            listen_address: "0.0.0.0:34254".to_string(),
+            shares_since_last_block: 4,
+            last_block_found_at: None,
+            connections: Default::default(),
+            health: Default::default(),
+            quote_poller: None,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_stats_envelope_flags_fresh_and_stale_snapshots() {
+        let now = Arc::new(AtomicU64::new(1_005));
+        let clock_now = now.clone();
+        let storage =
+            SnapshotStorage::with_clock(Arc::new(move || clock_now.load(Ordering::SeqCst)));
+
+        let empty = stats_envelope(&storage, 9);
+        assert_eq!(empty["stale"], true);
+        assert!(empty["age_secs"].is_null());
+        assert_eq!(empty["data"]["timestamp"], 0);
+
+        storage.update(snapshot(1_000));
+        let fresh = stats_envelope(&storage, 9);
+        assert_eq!(fresh["stale"], false);
+        assert_eq!(fresh["age_secs"], 5);
+        assert_eq!(fresh["data"]["shares_since_last_block"], 4);
+        assert_eq!(fresh["data"]["timestamp"], 1_000);
+
+        now.store(1_010, Ordering::SeqCst);
+        let stale = stats_envelope(&storage, 9);
+        assert_eq!(stale["stale"], true);
+        assert_eq!(stale["age_secs"], 10);
+        assert_eq!(stale["data"]["timestamp"], 1_000);
+    }
+}
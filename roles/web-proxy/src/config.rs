@@ -1,5 +1,7 @@
 use serde::Deserialize;
+use shared_config::RoleConfig;
 use std::{env, fs};
+use web_utils::fetch::DEFAULT_MAX_SNAPSHOT_BYTES;
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -13,6 +15,7 @@ pub struct Config {
     pub faucet_url: Option<String>,
     pub stats_poll_interval_secs: u64,
     pub client_poll_interval_secs: u64,
+    pub max_snapshot_bytes: usize,
     pub log_file: Option<String>,
 }
 
@@ -35,6 +38,7 @@ struct WebProxyConfig {
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct ServerConfig {
     listen_address: Option<String>,
 }
@@ -48,6 +52,7 @@ impl Default for ServerConfig {
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct StatsProxyConfig {
     url: Option<String>,
 }
@@ -61,9 +66,11 @@ impl Default for StatsProxyConfig {
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 struct HttpClientConfig {
     pool_idle_timeout_secs: Option<u64>,
     request_timeout_secs: Option<u64>,
+    max_snapshot_bytes: Option<usize>,
 }
 
 impl Default for HttpClientConfig {
@@ -71,10 +78,42 @@ impl Default for HttpClientConfig {
         Self {
             pool_idle_timeout_secs: Some(300),
             request_timeout_secs: Some(60),
+            max_snapshot_bytes: Some(DEFAULT_MAX_SNAPSHOT_BYTES),
         }
     }
 }
 
+impl RoleConfig for WebProxyConfig {
+    const SERVICE: &'static str = "web-proxy";
+    const ENV_PREFIX: &'static str = "HASHPOOL_WEB_PROXY";
+
+    fn effective_config(&self) -> Vec<(&'static str, String)> {
+        fn opt<T: ToString>(value: Option<&T>) -> String {
+            value.map_or_else(|| "none".to_string(), |v| v.to_string())
+        }
+        let http_client = &self.http_client;
+        vec![
+            (
+                "server.listen_address",
+                opt(self.server.listen_address.as_ref()),
+            ),
+            ("stats_proxy.url", opt(self.stats_proxy.url.as_ref())),
+            (
+                "http_client.pool_idle_timeout_secs",
+                opt(http_client.pool_idle_timeout_secs.as_ref()),
+            ),
+            (
+                "http_client.request_timeout_secs",
+                opt(http_client.request_timeout_secs.as_ref()),
+            ),
+            (
+                "http_client.max_snapshot_bytes",
+                opt(http_client.max_snapshot_bytes.as_ref()),
+            ),
+        ]
+    }
+}
+
 impl Config {
     pub fn from_args() -> Result<Self, Box<dyn std::error::Error>> {
         let args: Vec<String> = env::args().collect();
@@ -94,16 +133,7 @@ impl Config {
             .map(|s| s.as_str())
             .ok_or("Missing required argument: --web-proxy-config")?;
 
-        let web_proxy_config_str = fs::read_to_string(web_proxy_config_path).unwrap_or_default();
-        let web_proxy_config: WebProxyConfig = if web_proxy_config_str.is_empty() {
-            WebProxyConfig {
-                server: ServerConfig::default(),
-                stats_proxy: StatsProxyConfig::default(),
-                http_client: HttpClientConfig::default(),
-            }
-        } else {
-            toml::from_str(&web_proxy_config_str)?
-        };
+        let web_proxy_config = WebProxyConfig::from_optional_path(web_proxy_config_path)?;
 
         // Parse command line arguments (with config file as fallback)
         let stats_proxy_url = args
@@ -226,6 +256,10 @@ impl Config {
             faucet_url,
             stats_poll_interval_secs,
             client_poll_interval_secs,
+            max_snapshot_bytes: web_proxy_config
+                .http_client
+                .max_snapshot_bytes
+                .unwrap_or(DEFAULT_MAX_SNAPSHOT_BYTES),
             log_file,
         })
     }
@@ -247,6 +281,7 @@ mod tests {
             [http_client]
             pool_idle_timeout_secs = 400
             request_timeout_secs = 85
+            max_snapshot_bytes = 2097152
         "#;
         let config: WebProxyConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(
@@ -259,6 +294,41 @@ mod tests {
         );
         assert_eq!(config.http_client.pool_idle_timeout_secs, Some(400));
         assert_eq!(config.http_client.request_timeout_secs, Some(85));
+        assert_eq!(config.http_client.max_snapshot_bytes, Some(2097152));
+    }
+
+    #[test]
+    fn test_role_config_loads_with_own_env_prefix() {
+        let path = std::env::temp_dir().join(format!("web-proxy-{}.toml", std::process::id()));
+        std::fs::write(&path, "[server]\nlisten_address = \"127.0.0.1:4000\"\n").unwrap();
+        let path = path.to_str().unwrap();
+
+        std::env::set_var("HASHPOOL_WEB_PROXY_HTTP_CLIENT__MAX_SNAPSHOT_BYTES", "4096");
+        std::env::set_var("HASHPOOL_SERVER__LISTEN_ADDRESS", "127.0.0.1:1");
+        let config = WebProxyConfig::from_path(path);
+        let missing = WebProxyConfig::from_optional_path("/nonexistent/web-proxy.toml");
+        std::env::remove_var("HASHPOOL_WEB_PROXY_HTTP_CLIENT__MAX_SNAPSHOT_BYTES");
+        std::env::remove_var("HASHPOOL_SERVER__LISTEN_ADDRESS");
+        std::fs::remove_file(path).unwrap();
+
+        let config = config.unwrap();
+        assert!(config.validate().is_ok());
+        let entries = config.effective_config();
+        let value = |key| entries.iter().find(|(k, _)| *k == key).unwrap().1.as_str();
+        // Only this service's prefix applies
+        assert_eq!(value("server.listen_address"), "127.0.0.1:4000");
+        assert_eq!(value("stats_proxy.url"), "http://127.0.0.1:8084");
+        assert_eq!(value("http_client.max_snapshot_bytes"), "4096");
+        assert_eq!(value("http_client.request_timeout_secs"), "60");
+
+        // Without a file every section keeps its defaults, under the environment overrides
+        let missing = missing.unwrap();
+        assert_eq!(
+            missing.server.listen_address,
+            Some("127.0.0.1:3030".to_string())
+        );
+        assert_eq!(missing.http_client.max_snapshot_bytes, Some(4096));
+        assert_eq!(missing.http_client.request_timeout_secs, Some(60));
     }
 
     #[test]
@@ -1,18 +1,28 @@
-use stats::stats_adapter::ProxySnapshot;
+use stats::{clock::system_clock, health::HealthStatus, stats_adapter::ProxySnapshot};
 use std::sync::{Arc, RwLock};
+use web_utils::fetch::{read_json_limited, FetchError};
 
 pub mod config;
 pub mod web;
 
+pub use stats::clock::Clock;
+
 /// In-memory storage for proxy snapshot data
 pub struct SnapshotStorage {
     snapshot: Arc<RwLock<Option<ProxySnapshot>>>,
+    clock: Clock,
 }
 
 impl SnapshotStorage {
     pub fn new() -> Self {
+        Self::with_clock(system_clock())
+    }
+
+    /// Storage that judges snapshot age by `clock` instead of the system time
+    pub fn with_clock(clock: Clock) -> Self {
         Self {
             snapshot: Arc::new(RwLock::new(None)),
+            clock,
         }
     }
 
@@ -22,20 +32,48 @@ impl SnapshotStorage {
         }
     }
 
+    /// Fetch a snapshot from `url` and store it. Responses over `max_bytes` are refused and the
+    /// current snapshot is kept.
+    pub async fn refresh(
+        &self,
+        client: &reqwest::Client,
+        url: &str,
+        max_bytes: usize,
+    ) -> Result<(), FetchError> {
+        let response = client.get(url).send().await?;
+        let snapshot = read_json_limited::<ProxySnapshot>(response, max_bytes).await?;
+        self.update(snapshot);
+        Ok(())
+    }
+
     pub fn get(&self) -> Option<ProxySnapshot> {
         self.snapshot.read().ok().and_then(|guard| guard.clone())
     }
 
+    /// The stored snapshot with its age in seconds, read together so the two agree
+    pub fn get_with_age(&self) -> Option<(ProxySnapshot, u64)> {
+        self.get().map(|snapshot| {
+            let age = (self.clock)().saturating_sub(snapshot.timestamp);
+            (snapshot, age)
+        })
+    }
+
     pub fn is_stale(&self, threshold_secs: u64) -> bool {
         match self.snapshot.read().ok().and_then(|guard| guard.clone()) {
+            Some(snapshot) => (self.clock)().saturating_sub(snapshot.timestamp) > threshold_secs,
+            None => true,
+        }
+    }
+
+    /// Health reported by the latest snapshot, degraded further by its age. `Down` before the
+    /// first snapshot arrives.
+    pub fn health(&self) -> HealthStatus {
+        match self.get() {
             Some(snapshot) => {
-                let now = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_secs();
-                now - snapshot.timestamp > threshold_secs
+                let age = (self.clock)().saturating_sub(snapshot.timestamp);
+                snapshot.health.aged(age)
             }
-            None => true,
+            None => HealthStatus::Down,
         }
     }
 }
@@ -59,6 +97,10 @@ mod tests {
             upstream_pool: None,
             downstream_miners: vec![],
             blockchain_network: "testnet4".to_string(),
+            rejected_connections: 0,
+            connections: Default::default(),
+            recent_disconnects: Vec::new(),
+            health: Default::default(),
             timestamp: 123,
         };
 
@@ -91,6 +133,10 @@ mod tests {
             upstream_pool: None,
             downstream_miners: vec![],
             blockchain_network: "testnet4".to_string(),
+            rejected_connections: 0,
+            connections: Default::default(),
+            recent_disconnects: Vec::new(),
+            health: Default::default(),
             timestamp: now,
         };
         storage.update(snapshot);
@@ -102,9 +148,46 @@ mod tests {
             upstream_pool: None,
             downstream_miners: vec![],
             blockchain_network: "testnet4".to_string(),
+            rejected_connections: 0,
+            connections: Default::default(),
+            recent_disconnects: Vec::new(),
+            health: Default::default(),
             timestamp: now - 30,
         };
         storage.update(old_snapshot);
         assert!(storage.is_stale(15));
     }
+
+    #[test]
+    fn test_staleness_boundary_with_mock_clock() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        let now = Arc::new(AtomicU64::new(0));
+        let clock_now = now.clone();
+        let storage =
+            SnapshotStorage::with_clock(Arc::new(move || clock_now.load(Ordering::SeqCst)));
+
+        let snapshot = ProxySnapshot {
+            ehash_balance: 100,
+            upstream_pool: None,
+            downstream_miners: vec![],
+            blockchain_network: "testnet4".to_string(),
+            rejected_connections: 0,
+            connections: Default::default(),
+            recent_disconnects: Vec::new(),
+            health: Default::default(),
+            timestamp: 1_000,
+        };
+        storage.update(snapshot);
+
+        // A clock behind the snapshot counts as fresh rather than underflowing
+        now.store(990, Ordering::SeqCst);
+        assert!(!storage.is_stale(15));
+
+        // Exactly at the threshold is still fresh; one second past it is stale
+        now.store(1_015, Ordering::SeqCst);
+        assert!(!storage.is_stale(15));
+        now.store(1_016, Ordering::SeqCst);
+        assert!(storage.is_stale(15));
+    }
 }
@@ -1,4 +1,3 @@
-use stats::stats_adapter::ProxySnapshot;
 use std::{sync::Arc, time::Duration};
 use tokio::time;
 use tracing::{error, info};
@@ -45,8 +44,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let storage_clone = storage.clone();
     let stats_proxy_url = config.stats_proxy_url.clone();
     let poll_interval = config.stats_poll_interval_secs;
+    let max_snapshot_bytes = config.max_snapshot_bytes;
     tokio::spawn(async move {
-        poll_stats_proxy(storage_clone, stats_proxy_url, poll_interval).await;
+        poll_stats_proxy(
+            storage_clone,
+            stats_proxy_url,
+            poll_interval,
+            max_snapshot_bytes,
+        )
+        .await;
     });
 
     // Start HTTP server
@@ -70,6 +76,7 @@ async fn poll_stats_proxy(
     storage: Arc<SnapshotStorage>,
     stats_proxy_url: String,
     poll_interval_secs: u64,
+    max_snapshot_bytes: usize,
 ) {
     let client = reqwest::Client::builder()
         .pool_idle_timeout(Duration::from_secs(300))
@@ -78,33 +85,24 @@ async fn poll_stats_proxy(
         .unwrap();
     let mut interval = time::interval(Duration::from_secs(poll_interval_secs));
     let mut last_success = false;
+    let stats_url = format!("{}/api/stats", stats_proxy_url);
 
     loop {
         interval.tick().await;
 
-        match client
-            .get(format!("{}/api/stats", stats_proxy_url))
-            .send()
+        match storage
+            .refresh(&client, &stats_url, max_snapshot_bytes)
             .await
         {
-            Ok(response) => match response.json::<ProxySnapshot>().await {
-                Ok(snapshot) => {
-                    if !last_success {
-                        info!("Successfully fetched snapshot from stats-proxy");
-                        last_success = true;
-                    }
-                    storage.update(snapshot);
+            Ok(()) => {
+                if !last_success {
+                    info!("Successfully fetched snapshot from stats-proxy");
+                    last_success = true;
                 }
-                Err(e) => {
-                    if last_success {
-                        error!("Failed to parse snapshot JSON: {}", e);
-                        last_success = false;
-                    }
-                }
-            },
+            }
             Err(e) => {
                 if last_success {
-                    error!("Failed to fetch from stats-proxy: {}", e);
+                    error!("Failed to fetch snapshot from stats-proxy: {}", e);
                     last_success = false;
                 }
             }
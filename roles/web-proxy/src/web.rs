@@ -10,6 +10,7 @@ use std::sync::{Arc, OnceLock};
 use tracing::{error, info};
 
 use crate::SnapshotStorage;
+use stats::stats_adapter::ProxySnapshot;
 use web_assets::icons::{nav_icon_css, pickaxe_favicon_inline_svg};
 use web_utils::{format_elapsed_time, format_hashrate};
 
@@ -19,6 +20,10 @@ const WALLET_PAGE_TEMPLATE: &str = include_str!("../templates/wallet.html");
 const MINERS_PAGE_TEMPLATE: &str = include_str!("../templates/miners.html");
 const POOL_PAGE_TEMPLATE: &str = include_str!("../templates/pool.html");
 
+/// `/api/miners` and `/api/pool` flag a snapshot as stale once it is older than this many
+/// client poll intervals
+const STALE_AFTER_POLL_INTERVALS: u64 = 3;
+
 pub struct AppState {
     pub storage: Arc<SnapshotStorage>,
     pub http_client: reqwest::Client,
@@ -131,13 +136,35 @@ async fn pool_page_handler(State(state): State<Arc<AppState>>) -> impl IntoRespo
 }
 
 async fn api_miners_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let stats = get_miner_stats(&state.storage);
-    Json(stats)
+    Json(snapshot_envelope(
+        &state.storage,
+        state.client_poll_interval_secs * STALE_AFTER_POLL_INTERVALS,
+        get_miner_stats,
+    ))
 }
 
 async fn api_pool_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let pool_info = get_pool_info(&state.storage);
-    Json(pool_info)
+    Json(snapshot_envelope(
+        &state.storage,
+        state.client_poll_interval_secs * STALE_AFTER_POLL_INTERVALS,
+        get_pool_info,
+    ))
+}
+
+/// `render`'s view of the snapshot under `data`, with the snapshot's age and whether it is
+/// older than `stale_after_secs`. Before the first snapshot `age_secs` is null and the data
+/// counts as stale.
+fn snapshot_envelope(
+    storage: &SnapshotStorage,
+    stale_after_secs: u64,
+    render: fn(Option<ProxySnapshot>) -> serde_json::Value,
+) -> serde_json::Value {
+    let (snapshot, age_secs) = storage.get_with_age().unzip();
+    json!({
+        "stale": age_secs.is_none_or(|age| age > stale_after_secs),
+        "age_secs": age_secs,
+        "data": render(snapshot),
+    })
 }
 
 async fn balance_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
@@ -159,7 +186,8 @@ async fn health_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse
     };
     let json_response = json!({
         "healthy": !stale,
-        "stale": stale
+        "stale": stale,
+        "status": state.storage.health()
     });
     (status_code, Json(json_response))
 }
@@ -233,8 +261,8 @@ fn get_wallet_balance(storage: &Arc<SnapshotStorage>) -> u64 {
     }
 }
 
-fn get_pool_info(storage: &Arc<SnapshotStorage>) -> serde_json::Value {
-    match storage.get() {
+fn get_pool_info(snapshot: Option<ProxySnapshot>) -> serde_json::Value {
+    match snapshot {
         Some(snapshot) => {
             json!({
                 "blockchain_network": snapshot.blockchain_network,
@@ -252,14 +280,15 @@ fn get_pool_info(storage: &Arc<SnapshotStorage>) -> serde_json::Value {
     }
 }
 
-fn get_miner_stats(storage: &Arc<SnapshotStorage>) -> serde_json::Value {
-    let snapshot = match storage.get() {
+fn get_miner_stats(snapshot: Option<ProxySnapshot>) -> serde_json::Value {
+    let snapshot = match snapshot {
         Some(snapshot) => snapshot,
         None => {
             return json!({
                 "total_miners": 0,
                 "total_hashrate": "0 H/s",
                 "total_shares": 0,
+                "rejected_connections": 0,
                 "miners": []
             })
         }
@@ -292,7 +321,8 @@ fn get_miner_stats(storage: &Arc<SnapshotStorage>) -> serde_json::Value {
                 "address": m.address,
                 "hashrate": format_hashrate(m.hashrate),
                 "shares": m.shares_submitted,
-                "connected_time": connected_time
+                "connected_time": connected_time,
+                "user_agent": m.user_agent
             })
         })
         .collect();
@@ -301,6 +331,52 @@ fn get_miner_stats(storage: &Arc<SnapshotStorage>) -> serde_json::Value {
         "total_miners": total_miners,
         "total_hashrate": total_hashrate,
         "total_shares": total_shares,
+        "rejected_connections": snapshot.rejected_connections,
         "miners": miners
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn snapshot(timestamp: u64) -> ProxySnapshot {
+        ProxySnapshot {
+            ehash_balance: 0,
+            upstream_pool: None,
+            downstream_miners: vec![],
+            blockchain_network: "testnet4".to_string(),
+            rejected_connections: 2,
+            connections: Default::default(),
+            recent_disconnects: Vec::new(),
+            health: Default::default(),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_envelope_flags_fresh_and_stale_snapshots() {
+        let now = Arc::new(AtomicU64::new(1_005));
+        let clock_now = now.clone();
+        let storage =
+            SnapshotStorage::with_clock(Arc::new(move || clock_now.load(Ordering::SeqCst)));
+
+        let empty = snapshot_envelope(&storage, 9, get_pool_info);
+        assert_eq!(empty["stale"], true);
+        assert!(empty["age_secs"].is_null());
+        assert_eq!(empty["data"]["blockchain_network"], "unknown");
+
+        storage.update(snapshot(1_000));
+        let fresh = snapshot_envelope(&storage, 9, get_miner_stats);
+        assert_eq!(fresh["stale"], false);
+        assert_eq!(fresh["age_secs"], 5);
+        assert_eq!(fresh["data"]["rejected_connections"], 2);
+
+        now.store(1_010, Ordering::SeqCst);
+        let stale = snapshot_envelope(&storage, 9, get_pool_info);
+        assert_eq!(stale["stale"], true);
+        assert_eq!(stale["age_secs"], 10);
+        assert_eq!(stale["data"]["blockchain_network"], "testnet4");
+    }
+}
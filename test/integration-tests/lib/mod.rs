@@ -264,12 +264,12 @@ pub fn start_sv2_translator(upstream: SocketAddr) -> (TranslatorSv2, SocketAddr)
     );
     let downstream_extranonce2_size = 4;
 
-    let wallet_config = shared_config::WalletConfig {
-        mnemonic: "test mnemonic".to_string(),
-        db_path: "/tmp/test_wallet.db".to_string(),
-        locking_pubkey: None,
-        locking_privkey: None,
-    };
+    let wallet_config = shared_config::WalletConfig::new(
+        "test mnemonic".to_string(),
+        "/tmp/test_wallet.db".to_string(),
+        None,
+        None,
+    );
 
     let config = translator_sv2::config::TranslatorConfig::new(
         vec![upstream_conf],